@@ -0,0 +1,5 @@
+//! Calibration shader
+
+// Includes
+#include "fade/vertex.wgsl"
+#include "calibration/frag.wgsl"
@@ -0,0 +1,109 @@
+//! Frag shader
+#include_once
+
+// Includes
+#include "../fade/stage_io.wgsl"
+#include "../fade/uniforms.wgsl"
+
+// Frag output
+struct FragOutput {
+	@location(0)
+	color: vec4<f32>,
+};
+
+// Returns the color of the smpte color bars at `uvs`
+fn smpte_bars(uvs: vec2<f32>) -> vec3<f32> {
+	let bar = u32(uvs.x * 7.0);
+
+	// Main bars
+	if uvs.y < 2.0 / 3.0 {
+		var bars = array<vec3<f32>, 7>(
+			vec3(0.75, 0.75, 0.75),
+			vec3(0.75, 0.75, 0.0),
+			vec3(0.0, 0.75, 0.75),
+			vec3(0.0, 0.75, 0.0),
+			vec3(0.75, 0.0, 0.75),
+			vec3(0.75, 0.0, 0.0),
+			vec3(0.0, 0.0, 0.75),
+		);
+		return bars[bar];
+	}
+
+	// Castellations
+	if uvs.y < 3.0 / 4.0 {
+		var bars = array<vec3<f32>, 7>(
+			vec3(0.0, 0.0, 0.75),
+			vec3(0.0, 0.0, 0.0),
+			vec3(0.75, 0.0, 0.75),
+			vec3(0.0, 0.0, 0.0),
+			vec3(0.0, 0.75, 0.75),
+			vec3(0.0, 0.0, 0.0),
+			vec3(0.75, 0.75, 0.75),
+		);
+		return bars[bar];
+	}
+
+	// Bottom row, with the pluge on the right
+	let bottom = u32(uvs.x * 6.0);
+	var bottom_bars = array<vec3<f32>, 6>(
+		vec3(0.0, 0.13, 0.3),
+		vec3(1.0, 1.0, 1.0),
+		vec3(0.2, 0.0, 0.42),
+		vec3(0.0, 0.0, 0.0),
+		vec3(0.04, 0.04, 0.04),
+		vec3(0.0, 0.0, 0.0),
+	);
+	return bottom_bars[bottom];
+}
+
+// Returns the color of the gradient ramps at `uvs`
+fn gradient_ramps(uvs: vec2<f32>) -> vec3<f32> {
+	var ramps = array<vec3<f32>, 4>(
+		vec3(1.0, 1.0, 1.0),
+		vec3(1.0, 0.0, 0.0),
+		vec3(0.0, 1.0, 0.0),
+		vec3(0.0, 0.0, 1.0),
+	);
+	return ramps[u32(uvs.y * 4.0)] * uvs.x;
+}
+
+// Returns the color of the pixel grid at `pixel`
+fn pixel_grid(pixel: vec2<u32>, size: vec2<u32>) -> vec3<f32> {
+	// Note: The border and center lines are always drawn, so the
+	//       panel's edges and middle can be checked against the monitor's
+	let is_border = any(pixel == vec2(0u)) || any(pixel == size - 1u);
+	let is_center = any(pixel == size / 2u);
+	if is_border || is_center {
+		return vec3(1.0, 0.0, 0.0);
+	}
+
+	let is_grid = any(pixel % 16u == vec2(0u));
+	let is_checker = ((pixel.x + pixel.y) % 2u) == 0u;
+	return select(vec3(0.5 * f32(is_checker)), vec3(1.0), is_grid);
+}
+
+@fragment
+fn fs_main(in: VertexOutputFragInput) -> FragOutput {
+	var out: FragOutput;
+
+	// Note: `uvs` can be slightly outside of `[0.0, 1.0)` at the edges, so we clamp them
+	let uvs = clamp(in.uvs, vec2(0.0), vec2(0.9999));
+	let size = vec2<u32>(uniforms.size);
+	let pixel = min(vec2<u32>(uvs * uniforms.size), size - 1u);
+
+	// Note: Must be kept in sync with `CALIBRATION_PATTERNS`
+	var color: vec3<f32>;
+	switch uniforms.pattern {
+		case 0u: { color = smpte_bars(uvs); }
+		case 1u: { color = gradient_ramps(uvs); }
+		case 2u: { color = pixel_grid(pixel, size); }
+		case 3u: { color = vec3(1.0, 0.0, 0.0); }
+		case 4u: { color = vec3(0.0, 1.0, 0.0); }
+		case 5u: { color = vec3(0.0, 0.0, 1.0); }
+		case 6u: { color = vec3(1.0, 1.0, 1.0); }
+		default: { color = vec3(0.0, 0.0, 0.0); }
+	}
+
+	out.color = vec4(color * uniforms.tint, 1.0);
+	return out;
+}
@@ -0,0 +1,57 @@
+//! Cube rotation
+#include_once
+
+// Cube faces
+struct CubeFaces {
+	// Uvs of the face we're rotating from
+	from_uvs: vec2<f32>,
+
+	// Uvs of the face we're rotating to
+	to_uvs: vec2<f32>,
+
+	// Whether the face we're rotating from is visible
+	from_visible: bool,
+
+	// Whether the face we're rotating to is visible
+	to_visible: bool,
+}
+
+// Returns the uvs on `face`, as seen from `eye` along `dir`, and whether it's visible.
+//
+// `face` is given by it's center, outward normal and horizontal tangent.
+fn cube_face(eye: vec3<f32>, dir: vec3<f32>, center: vec3<f32>, normal: vec3<f32>, tangent: vec3<f32>, aspect: f32) -> vec3<f32> {
+	let facing = dot(dir, normal);
+	let hit = eye + dir * dot(center - eye, normal) / facing;
+	let uvs = vec2<f32>(dot(hit - center, tangent) + 0.5, hit.y / aspect + 0.5);
+	let visible = facing < 0.0 && all(uvs >= vec2<f32>(0.0, 0.0)) && all(uvs <= vec2<f32>(1.0, 1.0));
+
+	return vec3<f32>(uvs, f32(visible));
+}
+
+// Returns the faces of a cube seen at `uvs`, when rotated by `rotation` (from `0.0` to `1.0`).
+//
+// The cube is as wide and deep as the panel, and rotates around the vertical axis, bringing
+// the face on it's right to the front. It's seen in perspective from `distance` panel widths away.
+fn cube_faces(uvs: vec2<f32>, rotation: f32, size: vec2<f32>, distance: f32) -> CubeFaces {
+	let aspect = size.y / size.x;
+	let angle = rotation * radians(90.0);
+
+	// Note: The cube is pushed back while rotating, so it's edge never comes past the panel
+	let push = (sqrt(0.5) - 0.5) * sin(rotation * radians(180.0));
+	let center = vec3<f32>(0.0, 0.0, 0.5 + push);
+
+	let eye = vec3<f32>(0.0, 0.0, -distance);
+	let dir = vec3<f32>(uvs.x - 0.5, (uvs.y - 0.5) * aspect, 0.0) - eye;
+
+	let from_normal = vec3<f32>(-sin(angle), 0.0, -cos(angle));
+	let to_normal   = vec3<f32>( cos(angle), 0.0, -sin(angle));
+	let from_face = cube_face(eye, dir, center + 0.5 * from_normal, from_normal, to_normal, aspect);
+	let to_face   = cube_face(eye, dir, center + 0.5 * to_normal, to_normal, -from_normal, aspect);
+
+	var faces: CubeFaces;
+	faces.from_uvs = from_face.xy;
+	faces.from_visible = from_face.z != 0.0;
+	faces.to_uvs = to_face.xy;
+	faces.to_visible = to_face.z != 0.0;
+	return faces;
+}
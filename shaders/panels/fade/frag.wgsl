@@ -4,6 +4,10 @@
 // Includes
 #include "stage_io.wgsl"
 #include "uniforms.wgsl"
+#include "wipe.wgsl"
+#include "cube.wgsl"
+#include "slide.wgsl"
+#include "page_curl.wgsl"
 
 // Frag output
 struct FragOutput {
@@ -16,6 +20,8 @@ struct FragOutput {
 @group(1) @binding(1) var texture_cur: texture_2d<f32>;
 @group(1) @binding(2) var texture_next: texture_2d<f32>;
 @group(1) @binding(3) var texture_sampler: sampler;
+@group(2) @binding(0) var noise_texture: texture_2d<f32>;
+@group(2) @binding(1) var noise_sampler: sampler;
 
 struct Sampled {
 	color: vec4<f32>,
@@ -27,6 +33,13 @@ fn sample(texture: texture_2d<f32>, in_uvs: vec2<f32>, image_uniforms: ImageUnif
 	var sampled: Sampled;
 	var uvs = in_uvs;
 
+	// Mosaic the image while it's not fully shown
+	#match SHADER
+	#match_case "pixelate"
+		let block_size = max(uniforms.block_size * (1.0 - alpha), 1.0) / uniforms.size;
+		uvs = (floor(uvs / block_size) + 0.5) * block_size;
+	#match_end
+
 	// Apply parallax to the uvs first
 	{
 		let mid = vec2<f32>(0.5, 0.5);
@@ -34,15 +47,28 @@ fn sample(texture: texture_2d<f32>, in_uvs: vec2<f32>, image_uniforms: ImageUnif
 	}
 
 	// Then apply the image ratio and delta
-	let uvs_delta = (vec2<f32>(1.0, 1.0) - image_uniforms.image_ratio) * progress;
+	// Note: If the image doesn't scroll, we keep it at it's alignment
+	let uvs_offset = select(image_uniforms.alignment, vec2<f32>(progress, progress), image_uniforms.scroll != 0u);
+	let uvs_delta = (vec2<f32>(1.0, 1.0) - image_uniforms.image_ratio) * uvs_offset;
 	uvs = uvs * image_uniforms.image_ratio + uvs_delta;
 
+	// Then scroll the tiles, if tiling
+	uvs += image_uniforms.tile_scroll * progress;
+
 	// Offset it, if necessary
 	{
 	#match SHADER
 	#match_case    "none"
 	#match_case_or "fade"
 	#match_case_or "fade-white"
+	#match_case_or "dissolve"
+	#match_case_or "pixelate"
+	#match_case_or "page-curl"
+	#match_case_or "cube"
+	#match_case_or "slide"
+	#match_case_or "wipe-linear"
+	#match_case_or "wipe-iris"
+	#match_case_or "wipe-diagonal"
 		// Empty
 
 	#match_case "fade-out"
@@ -56,7 +82,11 @@ fn sample(texture: texture_2d<f32>, in_uvs: vec2<f32>, image_uniforms: ImageUnif
 	#match_end
 	}
 
-	sampled.color = textureSample(texture, texture_sampler, uvs);
+	// Note: When the image doesn't cover the whole panel (e.g. when fitting), we
+	//       leave the uncovered area black, instead of stretching the edges, unless
+	//       we're tiling it.
+	let contained = image_uniforms.tile != 0u || (uvs.x >= 0.0 && uvs.x <= 1.0 && uvs.y >= 0.0 && uvs.y <= 1.0);
+	sampled.color = textureSample(texture, texture_sampler, uvs) * f32(contained);
 	sampled.uvs = uvs;
 
 	return sampled;
@@ -79,6 +109,75 @@ fn fs_main(in: VertexOutputFragInput) -> FragOutput {
 	let sample_cur  = sample( texture_cur, in.uvs, uniforms.cur , progress_cur , alpha_cur );
 	let sample_next = sample(texture_next, in.uvs, uniforms.next, progress_next, alpha_next);
 
+	// Get the order each pixel is revealed in, if wiping or dissolving
+	#match SHADER
+	#match_case "dissolve"
+		let noise_uvs = in.uvs * uniforms.size / (uniforms.grain * vec2<f32>(textureDimensions(noise_texture)));
+		let order = textureSample(noise_texture, noise_sampler, noise_uvs).r;
+	#match_case "wipe-linear"
+		let order = wipe_order_linear((in.uvs - vec2<f32>(0.5, 0.5)) * uniforms.size, uniforms.size, uniforms.angle);
+	#match_case "wipe-iris"
+		let order = wipe_order_iris((in.uvs - vec2<f32>(0.5, 0.5)) * uniforms.size, uniforms.size);
+	#match_case "wipe-diagonal"
+		let order = wipe_order_diagonal((in.uvs - vec2<f32>(0.5, 0.5)) * uniforms.size, uniforms.size, uniforms.angle);
+	#match_end
+
+	// Get where each image is shown, for 3d transitions
+	// Note: Only one transition happens at a time, so we're either transitioning
+	//       from the previous image to the current one, or from the current one
+	//       to the next, and both images are sampled wherever they're visible.
+	let to_next = alpha_next > 0.0;
+	let transition_progress = select(1.0 - alpha_prev, alpha_next, to_next);
+	#match SHADER
+	#match_case "page-curl"
+		let curl = page_curl(in.uvs, transition_progress, uniforms.radius);
+		let from_uvs = curl.page_uvs;
+		let to_uvs = in.uvs;
+	#match_case "cube"
+		let cube = cube_faces(in.uvs, transition_progress, uniforms.size, uniforms.distance);
+		let from_uvs = cube.from_uvs;
+		let to_uvs = cube.to_uvs;
+	#match_case "slide"
+		let slide = slide(in.uvs, transition_progress, uniforms.direction);
+		let from_uvs = slide.from_uvs;
+		let to_uvs = slide.to_uvs;
+	#match_end
+
+	#match SHADER
+	#match_case    "page-curl"
+	#match_case_or "cube"
+		let sample_from = select(
+			sample(texture_prev, from_uvs, uniforms.prev, progress_prev, alpha_prev).color,
+			sample( texture_cur, from_uvs, uniforms.cur , progress_cur , alpha_cur ).color,
+			to_next
+		);
+		let sample_to = select(
+			sample( texture_cur, to_uvs, uniforms.cur , progress_cur , alpha_cur ).color,
+			sample(texture_next, to_uvs, uniforms.next, progress_next, alpha_next).color,
+			to_next
+		);
+
+	#match_case "slide"
+		// Note: Both images are blurred along the direction they move in
+		var sample_from = vec4(0.0);
+		var sample_to = vec4(0.0);
+		for (var tap = 0; tap < SLIDE_BLUR_TAPS; tap++) {
+			let offset = slide_blur_offset(tap, transition_progress, uniforms.direction, uniforms.blur);
+			sample_from += select(
+				sample(texture_prev, from_uvs + offset, uniforms.prev, progress_prev, alpha_prev).color,
+				sample( texture_cur, from_uvs + offset, uniforms.cur , progress_cur , alpha_cur ).color,
+				to_next
+			);
+			sample_to += select(
+				sample( texture_cur, to_uvs + offset, uniforms.cur , progress_cur , alpha_cur ).color,
+				sample(texture_next, to_uvs + offset, uniforms.next, progress_next, alpha_next).color,
+				to_next
+			);
+		}
+		sample_from /= f32(SLIDE_BLUR_TAPS);
+		sample_to /= f32(SLIDE_BLUR_TAPS);
+	#match_end
+
 	// Then mix the color
 	#match SHADER
 	#match_case    "none"
@@ -86,6 +185,7 @@ fn fs_main(in: VertexOutputFragInput) -> FragOutput {
 
 	#match_case    "fade"
 	#match_case_or "fade-out"
+	#match_case_or "pixelate"
 		out.color =
 			alpha_prev * sample_prev.color +
 			alpha_cur  * sample_cur .color +
@@ -114,7 +214,33 @@ fn fs_main(in: VertexOutputFragInput) -> FragOutput {
 			alpha_cur  * sample_cur .color * f32(contained_cur ) +
 			alpha_next * sample_next.color * f32(contained_next) ;
 		out.color.a = f32(contained_prev || contained_cur || contained_next);
+
+	#match_case "page-curl"
+		// Note: The back of the page is mostly white, with the image faintly showing through
+		let page_color = select(sample_from, mix(sample_from, vec4(1.0, 1.0, 1.0, 1.0), 0.7), curl.back);
+		out.color = select(sample_to, page_color, curl.on_page) * curl.shade;
+		out.color.a = 1.0;
+
+	#match_case "cube"
+		out.color = sample_from * f32(cube.from_visible) + sample_to * f32(cube.to_visible);
+		out.color.a = 1.0;
+
+	#match_case "slide"
+		out.color = sample_from * f32(slide.from_visible) + sample_to * f32(slide.to_visible);
+		out.color.a = 1.0;
+
+	#match_case    "dissolve"
+	#match_case_or "wipe-linear"
+	#match_case_or "wipe-iris"
+	#match_case_or "wipe-diagonal"
+		// Note: Instead of fading, the current image is revealed over the previous
+		//       one, and then the next one is revealed over the current one.
+		let reveal_cur  = wipe_reveal(order, 1.0 - alpha_prev, uniforms.feather);
+		let reveal_next = wipe_reveal(order, alpha_next, uniforms.feather);
+		out.color = mix(mix(sample_prev.color, sample_cur.color, reveal_cur), sample_next.color, reveal_next);
+		out.color.a = 1.0;
 	#match_end
 
+	out.color = vec4(out.color.rgb * uniforms.tint, out.color.a);
 	return out;
 }
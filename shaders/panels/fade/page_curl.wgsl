@@ -0,0 +1,59 @@
+//! Page curl
+#include_once
+
+// Page curl
+struct PageCurl {
+	// Uvs on the page
+	page_uvs: vec2<f32>,
+
+	// Whether the page is visible
+	on_page: bool,
+
+	// Whether we're seeing the back of the page
+	back: bool,
+
+	// Shading
+	shade: f32,
+}
+
+// Returns the page seen at `uvs`, when curled by `progress` (from `0.0` to `1.0`).
+//
+// The page curls from right to left around a cylinder of `radius` (in panel widths),
+// and the part curled past the cylinder lies flat, upside down, on top of the page.
+fn page_curl(uvs: vec2<f32>, progress: f32, radius: f32) -> PageCurl {
+	var curl: PageCurl;
+	curl.page_uvs = uvs;
+	curl.on_page = false;
+	curl.back = false;
+	curl.shade = 1.0;
+
+	// Note: The curl starts at the right edge, and ends once the cylinder is past the left edge
+	let curl_x = 1.0 - progress * (1.0 + radius);
+	let dx = uvs.x - curl_x;
+
+	// Before the cylinder, we see either the flat page, or the curled back of it on top
+	if dx < 0.0 {
+		let back_x = curl_x + radians(180.0) * radius - dx;
+		curl.on_page = true;
+		curl.back = back_x <= 1.0;
+		curl.page_uvs.x = select(uvs.x, back_x, curl.back);
+	}
+
+	// On the cylinder, we see either the back of it's top half, or the front of it's bottom half
+	else if dx < radius {
+		let angle = asin(dx / radius);
+		let back_x = curl_x + radius * (radians(180.0) - angle);
+		let front_x = curl_x + radius * angle;
+		curl.back = back_x <= 1.0;
+		curl.on_page = curl.back || front_x <= 1.0;
+		curl.page_uvs.x = select(front_x, back_x, curl.back);
+		curl.shade = select(mix(1.0, 0.6, dx / radius), mix(0.8, 1.0, dx / radius), curl.back);
+	}
+
+	// Past the cylinder, there's no page, but it's shadow
+	else {
+		curl.shade = mix(0.5, 1.0, clamp((dx - radius) / radius, 0.0, 1.0));
+	}
+
+	return curl;
+}
@@ -0,0 +1,51 @@
+//! Slide
+#include_once
+
+// Number of samples taken along the slide, when blurring
+const SLIDE_BLUR_TAPS: i32 = 9;
+
+// Maximum length of the blur, in uvs
+const SLIDE_BLUR_MAX_LENGTH: f32 = 0.1;
+
+// Slide
+struct Slide {
+	// Uvs of the image we're sliding from
+	from_uvs: vec2<f32>,
+
+	// Uvs of the image we're sliding to
+	to_uvs: vec2<f32>,
+
+	// Whether the image we're sliding from is visible
+	from_visible: bool,
+
+	// Whether the image we're sliding to is visible
+	to_visible: bool,
+}
+
+// Returns whether `uvs` are within the image
+fn slide_contains(uvs: vec2<f32>) -> bool {
+	return all(uvs >= vec2<f32>(0.0, 0.0)) && all(uvs <= vec2<f32>(1.0, 1.0));
+}
+
+// Returns both images seen at `uvs`, when slid by `progress` (from `0.0` to `1.0`) along `direction`.
+//
+// The image we're sliding to enters from the opposite side, pushing the
+// image we're sliding from out of the panel.
+fn slide(uvs: vec2<f32>, progress: f32, direction: vec2<f32>) -> Slide {
+	var slide: Slide;
+	slide.from_uvs = uvs - direction * progress;
+	slide.to_uvs = slide.from_uvs + direction;
+	slide.from_visible = slide_contains(slide.from_uvs);
+	slide.to_visible = slide_contains(slide.to_uvs);
+
+	return slide;
+}
+
+// Returns the offset of blur sample `tap`, when slid by `progress` along `direction` with blur `strength`.
+//
+// Note: The images only move while sliding, so they aren't blurred before or after it.
+fn slide_blur_offset(tap: i32, progress: f32, direction: vec2<f32>, strength: f32) -> vec2<f32> {
+	let sliding = progress > 0.0 && progress < 1.0;
+	let length = strength * SLIDE_BLUR_MAX_LENGTH * f32(sliding);
+	return direction * length * (f32(tap) / f32(SLIDE_BLUR_TAPS - 1) - 0.5);
+}
@@ -7,6 +7,13 @@ struct ImageUniforms {
 	parallax_ratio: vec2<f32>,
 	parallax_offset: vec2<f32>,
 	swap_dir: u32,
+	scroll: u32,
+
+	alignment: vec2<f32>,
+	tile_scroll: vec2<f32>,
+
+	// Note: Uniform structs used as members must have a size that's a multiple of 16
+	@size(16) tile: u32,
 }
 
 /// Uniforms
@@ -17,6 +24,7 @@ struct Uniforms {
 	next: ImageUniforms,
 	fade_point: f32,
 	progress: f32,
+	tint: vec3<f32>,
 
 	// Shader specific uniforms
 	#match SHADER
@@ -29,6 +37,37 @@ struct Uniforms {
 	#match_case_or "fade-in"
 		strength: f32,
 
+	#match_case "dissolve"
+		feather: f32,
+		size: vec2<f32>,
+		grain: f32,
+
+	#match_case "pixelate"
+		block_size: f32,
+		size: vec2<f32>,
+
+	#match_case "page-curl"
+		radius: f32,
+
+	#match_case "cube"
+		distance: f32,
+		size: vec2<f32>,
+
+	#match_case "slide"
+		blur: f32,
+		direction: vec2<f32>,
+
+	#match_case    "wipe-linear"
+	#match_case_or "wipe-iris"
+	#match_case_or "wipe-diagonal"
+		feather: f32,
+		size: vec2<f32>,
+		angle: f32,
+
+	#match_case "calibration"
+		pattern: u32,
+		size: vec2<f32>,
+
 	#match_end
 };
 
@@ -0,0 +1,35 @@
+//! Wipe
+#include_once
+
+// Note: All positions are in pixels, relative to the center of the panel, so the
+//       wipes aren't stretched by the panel's aspect ratio.
+
+// Returns the half-extent of a panel of size `size` along `dir`
+fn wipe_extent(dir: vec2<f32>, size: vec2<f32>) -> f32 {
+	return 0.5 * (abs(dir.x) * size.x + abs(dir.y) * size.y);
+}
+
+// Returns the order in which `pos` is revealed by a linear wipe, from `0.0` (first) to `1.0` (last)
+fn wipe_order_linear(pos: vec2<f32>, size: vec2<f32>, angle: f32) -> f32 {
+	let dir = vec2<f32>(cos(angle), sin(angle));
+	return dot(pos, dir) / (2.0 * wipe_extent(dir, size)) + 0.5;
+}
+
+// Returns the order in which `pos` is revealed by an iris wipe, from `0.0` (first) to `1.0` (last)
+fn wipe_order_iris(pos: vec2<f32>, size: vec2<f32>) -> f32 {
+	return length(pos) / (0.5 * length(size));
+}
+
+// Returns the order in which `pos` is revealed by a diagonal split, from `0.0` (first) to `1.0` (last)
+fn wipe_order_diagonal(pos: vec2<f32>, size: vec2<f32>, angle: f32) -> f32 {
+	let normal = vec2<f32>(-sin(angle), cos(angle));
+	return abs(dot(pos, normal)) / wipe_extent(normal, size);
+}
+
+// Returns how much of the new image is revealed at `order`, given the wipe's `progress`
+fn wipe_reveal(order: f32, progress: f32, feather: f32) -> f32 {
+	// Note: The edge is moved past both ends, so the feather is fully outside
+	//       the panel at the start and end of the wipe.
+	let edge = progress * (1.0 + feather);
+	return 1.0 - smoothstep(edge - feather, edge, order);
+}
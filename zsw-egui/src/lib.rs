@@ -6,10 +6,13 @@
 // Imports
 use {
 	anyhow::Context,
-	egui::epaint,
-	std::{fmt, sync::Arc},
+	egui::{accesskit, epaint},
+	std::{
+		fmt,
+		mem,
+		sync::{Arc, PoisonError},
+	},
 	tokio::sync::Mutex,
-	tracing as _,
 	winit::window::Window,
 	zsw_error::AppError,
 	zsw_wgpu::{FrameRender, WgpuRenderer, WgpuShared},
@@ -77,17 +80,48 @@ impl EguiRenderer {
 
 		Ok(())
 	}
+
+	/// Registers a texture, so it may be drawn by egui
+	pub fn register_texture(&mut self, wgpu_shared: &WgpuShared, texture_view: &wgpu::TextureView) -> egui::TextureId {
+		self.render_pass
+			.egui_texture_from_wgpu_texture(&wgpu_shared.device, texture_view, wgpu::FilterMode::Linear)
+	}
+
+	/// Updates a registered texture to draw `texture_view` instead
+	pub fn update_texture(
+		&mut self,
+		wgpu_shared: &WgpuShared,
+		texture_view: &wgpu::TextureView,
+		texture_id: egui::TextureId,
+	) -> Result<(), AppError> {
+		self.render_pass
+			.update_egui_texture_from_wgpu_texture(
+				&wgpu_shared.device,
+				texture_view,
+				wgpu::FilterMode::Linear,
+				texture_id,
+			)
+			.context("Unable to update texture")?;
+
+		Ok(())
+	}
 }
 
 /// Egui drawer
 pub struct EguiPainter {
 	/// Platform
 	platform: Arc<Mutex<egui_winit_platform::Platform>>,
+
+	/// AccessKit, if available
+	accesskit: Option<Arc<AccessKit>>,
 }
 
 impl fmt::Debug for EguiPainter {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		f.debug_struct("EguiPainter").field("platform", &"..").finish()
+		f.debug_struct("EguiPainter")
+			.field("platform", &"..")
+			.field("accesskit", &self.accesskit)
+			.finish()
 	}
 }
 
@@ -100,10 +134,27 @@ impl EguiPainter {
 	) -> Result<egui::FullOutput, E> {
 		let mut platform = self.platform.lock().await;
 
+		// Forward any accessibility actions to egui
+		if let Some(accesskit) = &self.accesskit {
+			let action_requests =
+				mem::take(&mut *accesskit.action_requests.lock().unwrap_or_else(PoisonError::into_inner));
+			platform
+				.raw_input_mut()
+				.events
+				.extend(action_requests.into_iter().map(egui::Event::AccessKitActionRequest));
+		}
+
 		// Draw the frame
 		platform.begin_frame();
 		let res = f(&platform.context());
-		let output = platform.end_frame(Some(window));
+		let mut output = platform.end_frame(Some(window));
+
+		// Then update the accessibility tree
+		if let Some(accesskit) = &self.accesskit {
+			if let Some(update) = output.platform_output.accesskit_update.take() {
+				accesskit.adapter.update(update);
+			}
+		}
 
 		res.map(|()| output)
 	}
@@ -126,21 +177,92 @@ impl EguiPainter {
 pub struct EguiEventHandler {
 	/// Platform
 	platform: Arc<Mutex<egui_winit_platform::Platform>>,
+
+	/// AccessKit, if available
+	accesskit: Option<Arc<AccessKit>>,
 }
 
 impl fmt::Debug for EguiEventHandler {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		f.debug_struct("EguiEventHandler").field("platform", &"..").finish()
+		f.debug_struct("EguiEventHandler")
+			.field("platform", &"..")
+			.field("accesskit", &self.accesskit)
+			.finish()
 	}
 }
 
 impl EguiEventHandler {
 	/// Handles an event
 	pub async fn handle_event(&self, event: &winit::event::WindowEvent) {
+		if let (Some(accesskit), winit::event::WindowEvent::Focused(is_focused)) = (&self.accesskit, event) {
+			accesskit.adapter.update_window_focus_state(*is_focused);
+		}
+
 		self.platform.lock().await.handle_event(event);
 	}
 }
 
+/// AccessKit
+///
+/// Exposes the egui widgets to screen readers.
+// TODO: Set the root window bounds, so screen readers can highlight widgets.
+struct AccessKit {
+	/// Adapter
+	adapter: accesskit_unix::Adapter,
+
+	/// Pending action requests
+	action_requests: Arc<std::sync::Mutex<Vec<accesskit::ActionRequest>>>,
+}
+
+impl AccessKit {
+	/// Creates the AccessKit adapter, enabling it on `ctx`.
+	///
+	/// Returns `None` if no accessibility bus is available.
+	fn new(ctx: &egui::Context, is_window_focused: bool) -> Option<Self> {
+		let action_requests = Arc::new(std::sync::Mutex::new(vec![]));
+		let adapter = accesskit_unix::Adapter::new(
+			{
+				let ctx = ctx.clone();
+				move || ctx.accesskit_placeholder_tree_update()
+			},
+			is_window_focused,
+			Box::new(AccessKitActionHandler {
+				action_requests: Arc::clone(&action_requests),
+			}),
+		)?;
+		ctx.enable_accesskit();
+
+		Some(Self {
+			adapter,
+			action_requests,
+		})
+	}
+}
+
+impl fmt::Debug for AccessKit {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("AccessKit")
+			.field("adapter", &"..")
+			.finish_non_exhaustive()
+	}
+}
+
+/// AccessKit action handler
+struct AccessKitActionHandler {
+	/// Pending action requests
+	action_requests: Arc<std::sync::Mutex<Vec<accesskit::ActionRequest>>>,
+}
+
+impl accesskit::ActionHandler for AccessKitActionHandler {
+	fn do_action(&mut self, request: accesskit::ActionRequest) {
+		// Note: These are handled by egui on the next frame
+		self.action_requests
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.push(request);
+	}
+}
+
 /// Creates the egui service
 pub fn create(
 	window: &Window,
@@ -156,6 +278,12 @@ pub fn create(
 		font_definitions: egui::FontDefinitions::default(),
 		style:            egui::Style::default(),
 	});
+
+	// Note: Only AT-SPI (linux) is supported currently
+	let accesskit = AccessKit::new(&platform.context(), window.has_focus()).map(Arc::new);
+	if accesskit.is_none() {
+		tracing::debug!("No accessibility bus found, disabling AccessKit");
+	}
 	let platform = Arc::new(Mutex::new(platform));
 
 	// Create the egui render pass
@@ -164,8 +292,9 @@ pub fn create(
 	(
 		EguiRenderer { render_pass },
 		EguiPainter {
-			platform: Arc::clone(&platform),
+			platform:  Arc::clone(&platform),
+			accesskit: accesskit.clone(),
 		},
-		EguiEventHandler { platform },
+		EguiEventHandler { platform, accesskit },
 	)
 }
@@ -0,0 +1,113 @@
+//! Path expansion
+
+// Imports
+use std::{
+	env,
+	path::{Path, PathBuf},
+};
+
+/// Expands a leading `~` and any environment variables within `path`.
+///
+/// Environment variables may be written as `$VAR` or `${VAR}`.
+/// Unset variables, as well as non-utf8 paths, are left as-is.
+#[must_use]
+pub fn expand_path(path: &Path) -> PathBuf {
+	self::expand_path_with(path, |var| env::var(var).ok())
+}
+
+/// Expands `path` like [`expand_path`], with `get_var` to lookup environment variables
+fn expand_path_with(path: &Path, get_var: impl Fn(&str) -> Option<String>) -> PathBuf {
+	let Some(path) = path.to_str() else {
+		return path.to_path_buf();
+	};
+
+	let mut expanded = String::with_capacity(path.len());
+
+	// Expand the home directory
+	let mut rest = path;
+	let after_home = rest
+		.strip_prefix('~')
+		.filter(|after_home| after_home.is_empty() || after_home.starts_with('/'));
+	if let Some(after_home) = after_home {
+		if let Some(home) = get_var("HOME") {
+			expanded.push_str(&home);
+			rest = after_home;
+		}
+	}
+
+	// Then expand all variables
+	while let Some(var_start) = rest.find('$') {
+		expanded.push_str(&rest[..var_start]);
+		let after_dollar = &rest[var_start + 1..];
+
+		let (var, after_var) = match after_dollar.strip_prefix('{') {
+			Some(after_brace) => match after_brace.find('}') {
+				Some(var_end) => (&after_brace[..var_end], &after_brace[var_end + 1..]),
+				None => ("", after_dollar),
+			},
+			None => {
+				let var_end = after_dollar
+					.find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+					.unwrap_or(after_dollar.len());
+				after_dollar.split_at(var_end)
+			},
+		};
+
+		match (!var.is_empty()).then(|| get_var(var)).flatten() {
+			Some(value) => expanded.push_str(&value),
+			None => expanded.push_str(&rest[var_start..rest.len() - after_var.len()]),
+		}
+		rest = after_var;
+	}
+	expanded.push_str(rest);
+
+	PathBuf::from(expanded)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn expand(path: &str) -> PathBuf {
+		expand_path_with(Path::new(path), |var| match var {
+			"HOME" => Some("/home/user".to_owned()),
+			"PICS" => Some("/mnt/pics".to_owned()),
+			_ => None,
+		})
+	}
+
+	#[test]
+	fn home() {
+		assert_eq!(expand("~"), Path::new("/home/user"), "Home wasn't expanded");
+		assert_eq!(expand("~/a"), Path::new("/home/user/a"), "Home wasn't expanded");
+		assert_eq!(expand("~a/b"), Path::new("~a/b"), "Other user's home was expanded");
+		assert_eq!(expand("a/~"), Path::new("a/~"), "Non-leading `~` was expanded");
+	}
+
+	#[test]
+	fn vars() {
+		assert_eq!(expand("$PICS/a"), Path::new("/mnt/pics/a"), "Variable wasn't expanded");
+		assert_eq!(
+			expand("${PICS}a"),
+			Path::new("/mnt/picsa"),
+			"Braced variable wasn't expanded"
+		);
+		assert_eq!(
+			expand("$HOME$PICS"),
+			Path::new("/home/user/mnt/pics"),
+			"Variables weren't expanded"
+		);
+	}
+
+	#[test]
+	fn unset() {
+		assert_eq!(expand("$UNSET/a"), Path::new("$UNSET/a"), "Unset variable was changed");
+		assert_eq!(
+			expand("${UNSET}/a"),
+			Path::new("${UNSET}/a"),
+			"Unset variable was changed"
+		);
+		assert_eq!(expand("${PICS/a"), Path::new("${PICS/a"), "Unclosed brace was changed");
+		assert_eq!(expand("a$/b"), Path::new("a$/b"), "Lone `$` was changed");
+	}
+}
@@ -19,8 +19,11 @@
 )]
 
 // Modules
+mod expand_path;
+mod log_limiter;
 pub mod meetup;
 mod rect;
+mod toml_decor;
 mod tpp;
 mod tuple_collect_res;
 pub mod unwrap_or_return;
@@ -28,7 +31,10 @@ pub mod walk_dir;
 
 // Exports
 pub use {
+	expand_path::expand_path,
+	log_limiter::LogLimiter,
 	rect::Rect,
+	toml_decor::preserve_toml_decor,
 	tpp::Tpp,
 	tuple_collect_res::{TupleCollectRes1, TupleCollectRes2, TupleCollectRes3, TupleCollectRes4, TupleCollectRes5},
 	unwrap_or_return::{UnwrapOrReturn, UnwrapOrReturnExt},
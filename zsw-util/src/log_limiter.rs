@@ -0,0 +1,119 @@
+//! Log limiter
+
+// Imports
+use std::{
+	collections::BTreeMap,
+	sync::{Mutex, PoisonError},
+	time::{Duration, Instant},
+};
+
+/// Log limiter.
+///
+/// Collapses repeated log messages (e.g. errors that happen every frame),
+/// by only allowing each message to be logged once per interval, and
+/// counting how many were suppressed in the meantime.
+#[derive(Debug)]
+pub struct LogLimiter {
+	/// Minimum interval between logging the same message
+	interval: Duration,
+
+	/// All recently logged messages, by key
+	entries: Mutex<BTreeMap<String, LogLimiterEntry>>,
+}
+
+/// Log limiter entry
+#[derive(Clone, Copy, Debug)]
+struct LogLimiterEntry {
+	/// When the message was last logged
+	last_logged: Instant,
+
+	/// Number of messages suppressed since
+	suppressed: usize,
+}
+
+impl LogLimiter {
+	/// Creates a new log limiter, logging each message at most once per `interval`
+	#[must_use]
+	pub const fn new(interval: Duration) -> Self {
+		Self {
+			interval,
+			entries: Mutex::new(BTreeMap::new()),
+		}
+	}
+
+	/// Checks if a message with `key` should be logged.
+	///
+	/// If so, returns the number of messages with `key` that were suppressed
+	/// since it was last logged, else counts it as suppressed and returns `None`.
+	pub fn check(&self, key: &str) -> Option<usize> {
+		self.check_at(key, Instant::now())
+	}
+
+	/// Checks if a message with `key` should be logged, at `now`.
+	///
+	/// See [`Self::check`] for details.
+	fn check_at(&self, key: &str, now: Instant) -> Option<usize> {
+		let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+
+		// Note: We forget messages that weren't repeated, so we don't keep every message ever logged.
+		entries.retain(|_, entry| entry.suppressed != 0 || now.duration_since(entry.last_logged) < self.interval);
+
+		let new_entry = LogLimiterEntry {
+			last_logged: now,
+			suppressed:  0,
+		};
+		match entries.get_mut(key) {
+			Some(entry) if now.duration_since(entry.last_logged) < self.interval => {
+				entry.suppressed += 1;
+				None
+			},
+			Some(entry) => {
+				let suppressed = entry.suppressed;
+				*entry = new_entry;
+				Some(suppressed)
+			},
+			None => {
+				let _ = entries.insert(key.to_owned(), new_entry);
+				Some(0)
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn limits() {
+		let limiter = LogLimiter::new(Duration::from_secs(1));
+		let start = Instant::now();
+		let at = |millis: u64| start + Duration::from_millis(millis);
+
+		assert_eq!(limiter.check_at("a", at(0)), Some(0), "First message wasn't logged");
+		assert_eq!(limiter.check_at("a", at(100)), None, "Repeated message was logged");
+		assert_eq!(limiter.check_at("a", at(200)), None, "Repeated message was logged");
+		assert_eq!(limiter.check_at("b", at(300)), Some(0), "Other message wasn't logged");
+		assert_eq!(
+			limiter.check_at("a", at(1000)),
+			Some(2),
+			"Message wasn't logged with suppressed count after the interval"
+		);
+		assert_eq!(limiter.check_at("a", at(1100)), None, "Repeated message was logged");
+	}
+
+	#[test]
+	fn forgets() {
+		let limiter = LogLimiter::new(Duration::from_secs(1));
+		let start = Instant::now();
+		let at = |millis: u64| start + Duration::from_millis(millis);
+
+		assert_eq!(limiter.check_at("a", at(0)), Some(0), "First message wasn't logged");
+		assert_eq!(limiter.check_at("b", at(2000)), Some(0), "Other message wasn't logged");
+		assert_eq!(
+			limiter.entries.lock().unwrap_or_else(PoisonError::into_inner).len(),
+			1,
+			"Message that wasn't repeated wasn't forgotten"
+		);
+	}
+}
@@ -3,7 +3,7 @@
 // Imports
 use {
 	anyhow::Context,
-	cgmath::{Point2, Vector2},
+	cgmath::{Matrix4, Point2, Vector2, Vector3},
 	serde::de,
 	std::{borrow::Cow, fmt, str::FromStr},
 };
@@ -100,6 +100,104 @@ impl Rect<i32, u32> {
 			point.y >= self.pos.y &&
 			point.y <= self.pos.y.checked_add_unsigned(self.size.y).expect("Overflow")
 	}
+
+	/// Returns the intersection of two rectangles, if they intersect
+	///
+	/// # Panics
+	/// Panics if the max point of either rectangle would overflow a `i32::MAX`
+	#[must_use]
+	pub fn intersection(self, rhs: Self) -> Option<Self> {
+		let lhs = self;
+
+		// Get the min/max of each
+		let lhs_min = lhs.min();
+		let rhs_min = rhs.min();
+		let lhs_max = lhs.max();
+		let rhs_max = rhs.max();
+
+		// Clamp them to the area enclosed by both
+		let intersection_min = Point2::new(lhs_min.x.max(rhs_min.x), lhs_min.y.max(rhs_min.y));
+		let intersection_max = Point2::new(lhs_max.x.min(rhs_max.x), lhs_max.y.min(rhs_max.y));
+
+		// Then reconstruct, if not empty
+		match intersection_min.x < intersection_max.x && intersection_min.y < intersection_max.y {
+			true => Some(Self::from_min_max(intersection_min, intersection_max)),
+			false => None,
+		}
+	}
+
+	/// Translates this rectangle by `offset`, saturating on overflow
+	#[must_use]
+	pub fn translated(self, offset: Vector2<i32>) -> Self {
+		Self {
+			pos:  Point2::new(self.pos.x.saturating_add(offset.x), self.pos.y.saturating_add(offset.y)),
+			size: self.size,
+		}
+	}
+
+	/// Creates a rectangle from a wider position and size, clamping them.
+	///
+	/// The size is clamped to be non-zero, and the position so that the max position doesn't overflow.
+	///
+	/// # Panics
+	/// Never panics, as all values are clamped to fit
+	#[must_use]
+	pub fn clamped(pos: Point2<i64>, size: Vector2<i64>) -> Self {
+		let max = i64::from(i32::MAX);
+		let size = size.map(|size| size.clamp(1, max));
+		let pos = Point2::new(
+			pos.x.clamp(i64::from(i32::MIN), max - size.x),
+			pos.y.clamp(i64::from(i32::MIN), max - size.y),
+		);
+
+		Self {
+			pos:  pos.map(|pos| i32::try_from(pos).expect("Position should be clamped")),
+			size: size.map(|size| u32::try_from(size).expect("Size should be clamped")),
+		}
+	}
+
+	/// Returns the largest size with this rectangle's aspect ratio that fits within `max_size`.
+	///
+	/// Never scales the size up.
+	#[must_use]
+	pub fn size_fit_within(self, max_size: Vector2<u32>) -> Vector2<u32> {
+		if self.size.x == 0 || self.size.y == 0 {
+			return self.size;
+		}
+
+		let scale = f64::min(
+			f64::from(max_size.x) / f64::from(self.size.x),
+			f64::from(max_size.y) / f64::from(self.size.y),
+		)
+		.min(1.0);
+
+		#[expect(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+		// The sizes and scale are positive and small
+		Vector2::new(
+			(f64::from(self.size.x) * scale).round() as u32,
+			(f64::from(self.size.y) * scale).round() as u32,
+		)
+	}
+
+	/// Calculates the position matrix of this rectangle within a surface of size `surface_size`, shifted by `shift`.
+	///
+	/// This matrix maps the `[-1.0, 1.0]` square onto this rectangle, in shader coordinates.
+	#[must_use]
+	pub fn pos_matrix(self, surface_size: Vector2<u32>, shift: Vector2<i32>) -> Matrix4<f32> {
+		let x_scale = self.size.x as f32 / surface_size.x as f32;
+		let y_scale = self.size.y as f32 / surface_size.y as f32;
+
+		let x_offset = (self.pos.x + shift.x) as f32 / surface_size.x as f32;
+		let y_offset = (self.pos.y + shift.y) as f32 / surface_size.y as f32;
+
+		let translation = Matrix4::from_translation(Vector3::new(
+			-1.0 + x_scale + 2.0 * x_offset,
+			1.0 - y_scale - 2.0 * y_offset,
+			0.0,
+		));
+		let scaling = Matrix4::from_nonuniform_scale(x_scale, -y_scale, 1.0);
+		translation * scaling
+	}
 }
 
 impl fmt::Display for Rect<i32, u32> {
@@ -165,3 +263,156 @@ impl serde::Serialize for Rect<i32, u32> {
 		serializer.serialize_str(&self.to_string())
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn rect(s: &str) -> Rect<i32, u32> {
+		s.parse().expect("Unable to parse rect")
+	}
+
+	#[test]
+	fn parse_display() {
+		assert_eq!(rect("1920x1080").to_string(), "1920x1080", "Rect wasn't round-tripped");
+		assert_eq!(
+			rect("10x20+30+40").to_string(),
+			"10x20+30+40",
+			"Rect wasn't round-tripped"
+		);
+		assert_eq!(
+			rect("10x20+-5+-6").pos,
+			Point2::new(-5, -6),
+			"Negative position wasn't parsed"
+		);
+	}
+
+	#[test]
+	fn merge() {
+		assert_eq!(
+			rect("10x10").merge(rect("10x10+20+5")),
+			rect("30x15"),
+			"Rects weren't merged"
+		);
+		assert_eq!(
+			rect("10x10+5+5").merge(rect("2x2+6+6")),
+			rect("10x10+5+5"),
+			"Contained rect changed merge"
+		);
+	}
+
+	#[test]
+	fn contains() {
+		assert!(rect("10x10+5+5").contains(Point2::new(5, 5)), "Min wasn't contained");
+		assert!(rect("10x10+5+5").contains(Point2::new(15, 15)), "Max wasn't contained");
+		assert!(
+			!rect("10x10+5+5").contains(Point2::new(4, 10)),
+			"Point outside was contained"
+		);
+	}
+
+	#[test]
+	fn intersection() {
+		assert_eq!(
+			rect("10x10").intersection(rect("10x10+5+5")),
+			Some(rect("5x5+5+5")),
+			"Overlapping rects didn't intersect"
+		);
+		assert_eq!(
+			rect("10x10").intersection(rect("2x2+3+3")),
+			Some(rect("2x2+3+3")),
+			"Contained rect didn't intersect"
+		);
+		assert_eq!(
+			rect("10x10").intersection(rect("10x10+10+0")),
+			None,
+			"Adjacent rects intersected"
+		);
+		assert_eq!(
+			rect("10x10").intersection(rect("10x10+50+50")),
+			None,
+			"Disjoint rects intersected"
+		);
+	}
+
+	#[test]
+	fn translated() {
+		assert_eq!(
+			rect("10x10+5+5").translated(Vector2::new(-10, 3)),
+			rect("10x10+-5+8"),
+			"Rect wasn't translated"
+		);
+		assert_eq!(
+			rect("10x10").translated(Vector2::new(i32::MAX, 0)).pos.x,
+			i32::MAX,
+			"Translation didn't saturate"
+		);
+	}
+
+	#[test]
+	fn clamped() {
+		assert_eq!(
+			Rect::clamped(Point2::new(5, 6), Vector2::new(7, 8)),
+			rect("7x8+5+6"),
+			"In-range rect was changed"
+		);
+		assert_eq!(
+			Rect::clamped(Point2::new(0, 0), Vector2::new(-5, 0)).size,
+			Vector2::new(1, 1),
+			"Size wasn't clamped"
+		);
+
+		let max = i64::from(i32::MAX);
+		let clamped = Rect::clamped(Point2::new(max, i64::MIN), Vector2::new(10, max * 2));
+		assert_eq!(clamped.size, Vector2::new(10, i32::MAX as u32), "Size wasn't clamped");
+		assert_eq!(
+			clamped.pos,
+			Point2::new(i32::MAX - 10, i32::MIN),
+			"Position wasn't clamped"
+		);
+	}
+
+	#[test]
+	fn size_fit_within() {
+		assert_eq!(
+			rect("3840x2160").size_fit_within(Vector2::new(1280, 720)),
+			Vector2::new(1280, 720),
+			"Same aspect wasn't fit"
+		);
+		assert_eq!(
+			rect("3840x1080").size_fit_within(Vector2::new(1280, 720)),
+			Vector2::new(1280, 360),
+			"Wider aspect wasn't fit"
+		);
+		assert_eq!(
+			rect("640x480").size_fit_within(Vector2::new(1280, 720)),
+			Vector2::new(640, 480),
+			"Smaller size was scaled up"
+		);
+	}
+
+	#[test]
+	fn pos_matrix() {
+		let surface_size = Vector2::new(100, 100);
+		let matrix = rect("100x100").pos_matrix(surface_size, Vector2::new(0, 0));
+		assert_eq!(
+			matrix,
+			Matrix4::from_nonuniform_scale(1.0, -1.0, 1.0),
+			"Full surface rect wasn't identity (flipped)"
+		);
+
+		let matrix = rect("50x50+50+0").pos_matrix(surface_size, Vector2::new(0, 0));
+		let min = matrix * cgmath::Vector4::new(-1.0, -1.0, 0.0, 1.0);
+		let max = matrix * cgmath::Vector4::new(1.0, 1.0, 0.0, 1.0);
+		assert_eq!(
+			min,
+			cgmath::Vector4::new(0.0, 1.0, 0.0, 1.0),
+			"Min corner was misplaced"
+		);
+		assert_eq!(
+			max,
+			cgmath::Vector4::new(1.0, 0.0, 0.0, 1.0),
+			"Max corner was misplaced"
+		);
+	}
+}
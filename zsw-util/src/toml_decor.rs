@@ -0,0 +1,142 @@
+//! Toml decor
+
+// Imports
+use {
+	anyhow::Context,
+	toml_edit::{ArrayOfTables, DocumentMut, Item, Table, Value},
+};
+
+/// Preserves the decor (comments and whitespace) of `prev_toml` onto `toml`.
+///
+/// Keys and tables are matched by name, while array elements are matched by index.
+/// Anything in `toml` that wasn't in `prev_toml` is left as-is.
+pub fn preserve_toml_decor(prev_toml: &str, toml: &str) -> Result<String, anyhow::Error> {
+	let prev = prev_toml
+		.parse::<DocumentMut>()
+		.context("Unable to parse previous toml")?;
+	let mut doc = toml.parse::<DocumentMut>().context("Unable to parse toml")?;
+
+	self::preserve_table(doc.as_table_mut(), prev.as_table());
+	doc.set_trailing(prev.trailing().clone());
+
+	Ok(doc.to_string())
+}
+
+/// Preserves the decor of `prev` onto `table`
+fn preserve_table(table: &mut Table, prev: &Table) {
+	*table.decor_mut() = prev.decor().clone();
+
+	for (mut key, item) in table.iter_mut() {
+		let Some((prev_key, prev_item)) = prev.get_key_value(key.get()) else {
+			continue;
+		};
+
+		*key.leaf_decor_mut() = prev_key.leaf_decor().clone();
+		self::preserve_item(item, prev_item);
+	}
+}
+
+/// Preserves the decor of `prev` onto `item`
+fn preserve_item(item: &mut Item, prev: &Item) {
+	match (item, prev) {
+		(Item::Table(table), Item::Table(prev)) => self::preserve_table(table, prev),
+		(Item::ArrayOfTables(tables), Item::ArrayOfTables(prev)) => self::preserve_array_of_tables(tables, prev),
+		(Item::Value(value), Item::Value(prev)) => self::preserve_value(value, prev),
+		_ => (),
+	}
+}
+
+/// Preserves the decor of `prev` onto `tables`
+fn preserve_array_of_tables(tables: &mut ArrayOfTables, prev: &ArrayOfTables) {
+	for (table, prev) in tables.iter_mut().zip(prev.iter()) {
+		self::preserve_table(table, prev);
+	}
+}
+
+/// Preserves the decor of `prev` onto `value`
+fn preserve_value(value: &mut Value, prev: &Value) {
+	*value.decor_mut() = prev.decor().clone();
+
+	match (value, prev) {
+		(Value::Array(array), Value::Array(prev)) => {
+			array.set_trailing(prev.trailing().clone());
+			array.set_trailing_comma(prev.trailing_comma());
+			for (value, prev) in array.iter_mut().zip(prev.iter()) {
+				self::preserve_value(value, prev);
+			}
+		},
+		(Value::InlineTable(table), Value::InlineTable(prev)) =>
+			for (mut key, value) in table.iter_mut() {
+				let Some((prev_key, Item::Value(prev_value))) = prev.get_key_value(key.get()) else {
+					continue;
+				};
+
+				*key.leaf_decor_mut() = prev_key.leaf_decor().clone();
+				self::preserve_value(value, prev_value);
+			},
+		_ => (),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn preserves_comments() {
+		let prev_toml = r#"# Playlist
+sequential = true # Play in order
+
+# Wallpapers
+[[items]]
+type = "Directory"
+path = "a"
+extensions = [
+	"png", # Screenshots
+	"jpg",
+]
+
+[[items]]
+type = "File"
+path = "b"
+"#;
+		let toml = r#"sequential = false
+
+[[items]]
+type = "Directory"
+path = "c"
+extensions = ["png", "jpg"]
+
+[[items]]
+type = "File"
+path = "d"
+
+[[items]]
+type = "File"
+path = "e"
+"#;
+		let expected_toml = r#"# Playlist
+sequential = false # Play in order
+
+# Wallpapers
+[[items]]
+type = "Directory"
+path = "c"
+extensions = [
+	"png", # Screenshots
+	"jpg",
+]
+
+[[items]]
+type = "File"
+path = "d"
+
+[[items]]
+type = "File"
+path = "e"
+"#;
+
+		let merged_toml = preserve_toml_decor(prev_toml, toml).expect("Unable to preserve decor");
+		assert_eq!(merged_toml, expected_toml, "Decor wasn't preserved");
+	}
+}
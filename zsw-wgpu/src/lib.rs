@@ -10,7 +10,17 @@ mod renderer;
 pub use renderer::{FrameRender, WgpuRenderer};
 
 // Imports
-use {anyhow::Context, winit::window::Window, zsw_error::AppError};
+use {
+	anyhow::Context,
+	std::{
+		fs,
+		path::{Path, PathBuf},
+		time::Duration,
+	},
+	winit::window::Window,
+	zsw_error::AppError,
+	zsw_util::LogLimiter,
+};
 
 /// Wgpu shared
 #[derive(Debug)]
@@ -20,29 +30,123 @@ pub struct WgpuShared {
 
 	/// Queue
 	pub queue: wgpu::Queue,
+
+	/// Pipeline cache, if supported
+	pub pipeline_cache: Option<PipelineCache>,
+
+	/// Adapter info
+	pub adapter_info: wgpu::AdapterInfo,
 }
 
-/// Creates the wgpu service
-pub async fn create(window: &'static Window) -> Result<(WgpuShared, WgpuRenderer), AppError> {
+/// Pipeline cache
+#[derive(Debug)]
+pub struct PipelineCache {
+	/// Cache
+	cache: wgpu::PipelineCache,
+
+	/// Path the cache is saved to
+	path: PathBuf,
+}
+
+impl PipelineCache {
+	/// Returns the cache
+	#[must_use]
+	pub fn cache(&self) -> &wgpu::PipelineCache {
+		&self.cache
+	}
+
+	/// Saves the cache to disk
+	pub fn save(&self) -> Result<(), AppError> {
+		let Some(data) = self.cache.get_data() else {
+			return Ok(());
+		};
+
+		let path = &self.path;
+		tracing::debug!(?path, "Saving pipeline cache");
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent).context("Unable to create pipeline cache directory")?;
+		}
+		fs::write(path, data).context("Unable to write pipeline cache")?;
+
+		Ok(())
+	}
+}
+
+/// Creates the wgpu service.
+///
+/// The pipeline cache, if supported, is loaded from / saved to `pipeline_cache_dir`.
+pub async fn create(
+	window: &'static Window,
+	pipeline_cache_dir: &Path,
+) -> Result<(WgpuShared, WgpuRenderer), AppError> {
 	// Create the surface and adapter
 	let (surface, adapter) = self::create_surface_and_adapter(window).await?;
 
 	// Then create the device and it's queue
 	let (device, queue) = self::create_device(&adapter).await?;
 
+	// And the pipeline cache
+	let pipeline_cache = self::create_pipeline_cache(&adapter, &device, pipeline_cache_dir);
+
 	// Then create the renderer
+	let adapter_info = adapter.get_info();
 	let renderer = WgpuRenderer::new(window, surface, adapter, &device).context("Unable to create renderer")?;
 
-	Ok((WgpuShared { device, queue }, renderer))
+	Ok((
+		WgpuShared {
+			device,
+			queue,
+			pipeline_cache,
+			adapter_info,
+		},
+		renderer,
+	))
 }
 
+/// Creates the pipeline cache, if supported
+fn create_pipeline_cache(
+	adapter: &wgpu::Adapter,
+	device: &wgpu::Device,
+	pipeline_cache_dir: &Path,
+) -> Option<PipelineCache> {
+	if !device.features().contains(wgpu::Features::PIPELINE_CACHE) {
+		tracing::debug!("Pipeline cache isn't supported");
+		return None;
+	}
+
+	// Note: The key is unique per adapter and driver, so we never load incompatible data
+	let key = wgpu::util::pipeline_cache_key(&adapter.get_info())?;
+	let path = pipeline_cache_dir.join(key);
+	let data = match fs::read(&path) {
+		Ok(data) => Some(data),
+		Err(err) => {
+			tracing::debug!(?path, ?err, "Unable to read pipeline cache, creating an empty one");
+			None
+		},
+	};
+
+	let descriptor = wgpu::PipelineCacheDescriptor {
+		label:    Some("[zsw] Pipeline cache"),
+		data:     data.as_deref(),
+		fallback: true,
+	};
+	// SAFETY: The data was saved by `PipelineCache::save` from a cache with the same key.
+	//         If it's invalid anyway, `fallback` creates an empty cache instead.
+	let cache = unsafe { device.create_pipeline_cache(&descriptor) };
+	tracing::debug!(?path, "Created pipeline cache");
+
+	Some(PipelineCache { cache, path })
+}
 
 /// Creates the device
 async fn create_device(adapter: &wgpu::Adapter) -> Result<(wgpu::Device, wgpu::Queue), AppError> {
-	// Request the device without any features
+	// Request the device with only the pipeline cache and timestamp query features, if supported
+	let optional_features = wgpu::Features::PIPELINE_CACHE |
+		wgpu::Features::TIMESTAMP_QUERY |
+		wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
 	let device_descriptor = wgpu::DeviceDescriptor {
 		label:             Some("[zsw] Device"),
-		required_features: wgpu::Features::default(),
+		required_features: adapter.features() & optional_features,
 		required_limits:   wgpu::Limits::default(),
 		memory_hints:      wgpu::MemoryHints::default(),
 	};
@@ -53,8 +157,13 @@ async fn create_device(adapter: &wgpu::Adapter) -> Result<(wgpu::Device, wgpu::Q
 		.context("Unable to request device")?;
 
 	// Configure the device to not panic on errors
+	// Note: Errors usually repeat each frame, so we rate-limit them
+	static LOG_LIMITER: LogLimiter = LogLimiter::new(Duration::from_secs(5));
 	device.on_uncaptured_error(Box::new(|err| {
-		tracing::error!("Wgpu error: {err}");
+		let err = err.to_string();
+		if let Some(suppressed) = LOG_LIMITER.check(&err) {
+			tracing::error!(suppressed, "Wgpu error: {err}");
+		}
 	}));
 
 	Ok((device, queue))
@@ -4,8 +4,10 @@
 use {
 	super::WgpuShared,
 	anyhow::Context,
+	std::time::Duration,
 	winit::{dpi::PhysicalSize, window::Window},
 	zsw_error::AppError,
+	zsw_util::LogLimiter,
 };
 
 /// Wgpu renderer
@@ -71,10 +73,14 @@ impl WgpuRenderer {
 		// Note: If the application goes to sleep, this can fail spuriously due to a timeout,
 		//       so we keep retrying.
 		// TODO: Use an exponential timeout, with a max duration?
+		static LOG_LIMITER: LogLimiter = LogLimiter::new(Duration::from_secs(5));
 		let surface_texture = tokio::task::block_in_place(|| loop {
 			match self.surface.get_current_texture() {
 				Ok(surface_texture) => break surface_texture,
-				Err(err) => tracing::warn!(%err, "Unable to retrieve current texture, retrying"),
+				Err(err) =>
+					if let Some(suppressed) = LOG_LIMITER.check(&err.to_string()) {
+						tracing::warn!(%err, suppressed, "Unable to retrieve current texture, retrying");
+					},
 			}
 		});
 		let surface_view_descriptor = wgpu::TextureViewDescriptor {
@@ -161,6 +167,15 @@ fn configure_window_surface(
 
 	// Set some options
 	config.present_mode = wgpu::PresentMode::AutoVsync;
+
+	// Note: We allow copying from the surface, if supported, so frames may be exported
+	if surface
+		.get_capabilities(adapter)
+		.usages
+		.contains(wgpu::TextureUsages::COPY_SRC)
+	{
+		config.usage |= wgpu::TextureUsages::COPY_SRC;
+	}
 	tracing::debug!(?config, "Updated surface configuration");
 
 	// Then configure it
@@ -1,7 +1,10 @@
 //! Arguments
 
 // Imports
-use std::path::PathBuf;
+use {
+	crate::{config::ConfigOverride, import::ImportSource},
+	std::path::PathBuf,
+};
 
 /// Arguments
 #[derive(Debug)]
@@ -13,10 +16,92 @@ pub struct Args {
 	#[clap(long = "config")]
 	pub config: Option<PathBuf>,
 
+	/// Config overrides
+	///
+	/// Overrides a config key for this run, as `key=value`.
+	/// Nested keys are separated by `.`, e.g. `privacy.poll_interval_secs=5`.
+	/// Applied after any `ZSW_*` environment variables, which override the same
+	/// keys, in uppercase, with nested keys separated by `__`
+	#[clap(long = "set", value_name = "KEY=VALUE")]
+	pub config_overrides: Vec<ConfigOverride>,
+
 	/// Log file
 	///
 	/// Specifies a file to perform verbose logging to.
 	/// You can use `RUST_LOG_FILE` to set filtering options
 	#[clap(long = "log-file")]
 	pub log_file: Option<PathBuf>,
+
+	/// Portable mode
+	///
+	/// Keeps all data next to the executable, instead of the usual directories.
+	/// Also enabled if a `portable.txt` file exists next to the executable
+	#[clap(long = "portable")]
+	pub portable: bool,
+
+	/// Command
+	#[clap(subcommand)]
+	pub command: Option<Command>,
+}
+
+/// Command
+#[derive(Debug)]
+#[derive(clap::Subcommand)]
+pub enum Command {
+	/// Shows an image, or rotates through a directory, on a single panel.
+	///
+	/// Doesn't require any panel or playlist files.
+	Show {
+		/// Image or directory
+		path: PathBuf,
+	},
+
+	/// Previews panels, scaled down, in a small normal window.
+	///
+	/// Useful for authoring panels for a different set of monitors.
+	Preview {
+		/// Panels to preview.
+		///
+		/// If empty, previews the default panels
+		panels: Vec<PathBuf>,
+	},
+
+	/// Watches the shaders, saving a numbered screenshot whenever they change.
+	///
+	/// Panels are shown in a preview window, and paused once loaded, so that
+	/// every screenshot shows the same scene.
+	ShaderWatch {
+		/// Directory to save the screenshots to
+		#[clap(long = "output")]
+		output: PathBuf,
+
+		/// Panels to show.
+		///
+		/// If empty, shows the default panels
+		panels: Vec<PathBuf>,
+	},
+
+	/// Imports a playlist and starter panel from another wallpaper manager.
+	Import {
+		/// Wallpaper manager to import from
+		source: ImportSource,
+
+		/// The wallpaper manager's config file.
+		///
+		/// If unspecified, uses the default location
+		path: Option<PathBuf>,
+	},
+
+	/// Creates a bug report to attach to issues.
+	///
+	/// Includes the version, config (with secrets redacted), the log of the
+	/// last run and all gpu adapters. Creating one from the settings menu
+	/// additionally includes the current panels, tasks and metrics.
+	#[clap(name = "bugreport")]
+	BugReport {
+		/// Output file.
+		///
+		/// If unspecified, creates it in the current directory
+		output: Option<PathBuf>,
+	},
 }
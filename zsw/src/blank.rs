@@ -0,0 +1,87 @@
+//! Blanking
+//!
+//! Instantly replaces all panels with a solid color, pausing them until
+//! they're restored. Useful when screen sharing.
+
+// Imports
+#[cfg(feature = "menu")]
+use crate::panel::Panel;
+use {
+	crate::panel::PanelId,
+	cgmath::Vector3,
+	std::{
+		collections::HashMap,
+		sync::{Mutex, PoisonError},
+	},
+};
+
+/// Blank
+#[derive(Debug)]
+pub struct Blank {
+	/// Color to blank with
+	color: Vector3<f32>,
+
+	/// Whether each panel was paused before blanking, while blanked
+	saved_paused: Mutex<Option<HashMap<PanelId, bool>>>,
+}
+
+impl Blank {
+	/// Creates a new, un-blanked, blank with `color`
+	pub fn new(color: Vector3<f32>) -> Self {
+		Self {
+			color,
+			saved_paused: Mutex::new(None),
+		}
+	}
+
+	/// Returns the color to render instead of the panels, if blanked
+	pub fn color(&self) -> Option<Vector3<f32>> {
+		self.saved_paused
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.is_some()
+			.then_some(self.color)
+	}
+
+	/// Blanks all panels, pausing them
+	#[cfg(feature = "menu")]
+	pub fn blank(&self, panels: &mut [Panel]) {
+		let mut saved_paused = self.saved_paused.lock().unwrap_or_else(PoisonError::into_inner);
+		if saved_paused.is_some() {
+			return;
+		}
+
+		tracing::info!("Blanking all panels");
+		*saved_paused = Some(
+			panels
+				.iter_mut()
+				.map(|panel| (panel.id, std::mem::replace(&mut panel.state.paused, true)))
+				.collect(),
+		);
+	}
+
+	/// Restores all panels, un-pausing any that weren't paused before blanking
+	#[cfg(feature = "menu")]
+	pub fn restore(&self, panels: &mut [Panel]) {
+		let Some(saved_paused) = self.saved_paused.lock().unwrap_or_else(PoisonError::into_inner).take() else {
+			return;
+		};
+
+		// Note: Any panels added since we blanked weren't paused by us, so we leave them be
+		tracing::info!("Restoring all panels");
+		for panel in panels {
+			if let Some(&paused) = saved_paused.get(&panel.id) {
+				panel.state.paused = paused;
+			}
+		}
+	}
+
+	/// Blanks all panels, or restores them, if already blanked
+	#[cfg(feature = "menu")]
+	pub fn toggle(&self, panels: &mut [Panel]) {
+		match self.color() {
+			Some(_) => self.restore(panels),
+			None => self.blank(panels),
+		}
+	}
+}
@@ -0,0 +1,260 @@
+//! Encrypted bundles
+//!
+//! A bundle is an `age` passphrase-encrypted tar archive of images.
+//! Bundles are only ever decrypted in memory, and their images are
+//! referred to by the bundle path joined with the entry path.
+//!
+//! Comic book archives (`cbz`) are also loaded as bundles, although
+//! they aren't encrypted.
+
+// Imports
+#[cfg(feature = "menu")]
+use {age::secrecy::SecretString, tokio::sync::oneshot};
+use {
+	anyhow::Context,
+	std::{
+		collections::HashMap,
+		fmt,
+		future::Future,
+		io::{self, Read},
+		path::{Path, PathBuf},
+		sync::Arc,
+	},
+	tokio::sync::{Mutex, OnceCell},
+	zsw_error::AppError,
+};
+
+/// Bundles
+#[derive(Debug)]
+pub struct Bundles {
+	/// Bundles and comic book archives, by path.
+	///
+	/// Each bundle has it's own cell, so loading a bundle from several playlists
+	/// only prompts once, without blocking the loading of any other bundles.
+	bundles: Mutex<HashMap<Arc<Path>, Arc<OnceCell<Bundle>>>>,
+
+	/// Pending passphrase requests
+	#[cfg(feature = "menu")]
+	passphrase_requests: Mutex<Vec<PassphraseRequest>>,
+}
+
+impl Bundles {
+	/// Creates a new, empty, set of bundles
+	pub fn new() -> Self {
+		Self {
+			bundles: Mutex::new(HashMap::new()),
+			#[cfg(feature = "menu")]
+			passphrase_requests: Mutex::new(vec![]),
+		}
+	}
+
+	/// Loads a bundle, returning the paths of all it's images.
+	///
+	/// If the bundle wasn't decrypted yet, prompts for it's passphrase
+	pub async fn load(&self, path: &Path) -> Result<Vec<Arc<Path>>, AppError> {
+		self.load_with(path, || self.decrypt(path)).await
+	}
+
+	/// Loads a comic book archive, returning the paths of all it's pages
+	pub async fn load_comic(&self, path: &Path) -> Result<Vec<Arc<Path>>, AppError> {
+		self.load_with(path, || async {
+			let contents = tokio::fs::read(path).await.context("Unable to read comic")?;
+
+			tracing::debug!(?path, "Reading comic");
+			tokio::task::spawn_blocking(move || Bundle::read_comic(&contents))
+				.await
+				.context("Unable to join comic read task")?
+		})
+		.await
+	}
+
+	/// Loads a bundle with `init`, if it wasn't loaded yet, returning the paths of all it's entries.
+	///
+	/// If the bundle is already being loaded, waits for it instead.
+	async fn load_with<F, Fut>(&self, path: &Path, init: F) -> Result<Vec<Arc<Path>>, AppError>
+	where
+		F: FnOnce() -> Fut,
+		Fut: Future<Output = Result<Bundle, AppError>>,
+	{
+		// Note: We only lock the bundles while getting the cell, since
+		//       initializing it might wait on the user for a passphrase.
+		let bundle = Arc::clone(self.bundles.lock().await.entry(path.into()).or_default());
+		let bundle = bundle.get_or_try_init(init).await?;
+
+		Ok(bundle.paths(path))
+	}
+
+	/// Gets the contents of an image within a bundle, if it exists and is decrypted
+	pub async fn get(&self, path: &Path) -> Option<Arc<[u8]>> {
+		let bundles = self.bundles.lock().await;
+		bundles.iter().find_map(|(bundle_path, bundle)| {
+			let entry_path = path.strip_prefix(bundle_path).ok()?;
+			bundle.get()?.entries.get(entry_path).map(Arc::clone)
+		})
+	}
+
+	/// Returns all pending passphrase requests
+	#[cfg(feature = "menu")]
+	pub fn passphrase_requests(&self) -> &Mutex<Vec<PassphraseRequest>> {
+		&self.passphrase_requests
+	}
+
+	/// Decrypts a bundle by prompting for it's passphrase
+	#[cfg(feature = "menu")]
+	async fn decrypt(&self, path: &Path) -> Result<Bundle, AppError> {
+		// Note: We read the whole file first, so we don't keep it open while prompting
+		let contents = tokio::fs::read(path).await.context("Unable to read bundle")?;
+
+		// TODO: Allow reading the passphrase from the system keyring too
+		let (passphrase_tx, passphrase_rx) = oneshot::channel();
+		self.passphrase_requests.lock().await.push(PassphraseRequest {
+			path: path.into(),
+			passphrase: String::new(),
+			passphrase_tx,
+		});
+		let passphrase = passphrase_rx.await.context("Passphrase prompt was dismissed")?;
+
+		tracing::debug!(?path, "Decrypting bundle");
+		tokio::task::spawn_blocking(move || Bundle::decrypt(&contents, passphrase))
+			.await
+			.context("Unable to join bundle decrypt task")?
+	}
+
+	/// Decrypts a bundle.
+	///
+	/// Returns an error immediately, since there's nowhere to prompt for it's passphrase without the menu.
+	// TODO: Allow reading the passphrase from the system keyring
+	#[cfg(not(feature = "menu"))]
+	#[expect(clippy::unused_async)] // We keep the same signature as with the menu
+	async fn decrypt(&self, path: &Path) -> Result<Bundle, AppError> {
+		Err(
+			anyhow::anyhow!("Decrypting bundle {path:?} requires the `menu` feature, to prompt for it's passphrase")
+				.into(),
+		)
+	}
+}
+
+/// Passphrase request
+#[cfg(feature = "menu")]
+pub struct PassphraseRequest {
+	/// Bundle path
+	path: Arc<Path>,
+
+	/// Passphrase being entered
+	passphrase: String,
+
+	/// Passphrase sender
+	passphrase_tx: oneshot::Sender<SecretString>,
+}
+
+#[cfg(feature = "menu")]
+impl PassphraseRequest {
+	/// Returns the bundle path
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Returns the passphrase being entered
+	pub fn passphrase_mut(&mut self) -> &mut String {
+		&mut self.passphrase
+	}
+
+	/// Submits this request's passphrase
+	pub fn submit(self) {
+		// Note: If the bundle load was cancelled, there's nobody to receive it
+		let _ = self.passphrase_tx.send(SecretString::from(self.passphrase));
+	}
+}
+
+#[cfg(feature = "menu")]
+impl fmt::Debug for PassphraseRequest {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PassphraseRequest")
+			.field("path", &self.path)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Decrypted bundle
+struct Bundle {
+	/// All entries, by path
+	entries: HashMap<PathBuf, Arc<[u8]>>,
+}
+
+impl Bundle {
+	/// Decrypts a bundle
+	#[cfg(feature = "menu")]
+	fn decrypt(contents: &[u8], passphrase: SecretString) -> Result<Self, AppError> {
+		let decryptor = age::Decryptor::new(contents).context("Unable to read bundle header")?;
+		if !decryptor.is_scrypt() {
+			return Err(anyhow::anyhow!("Bundle isn't passphrase-encrypted").into());
+		}
+		let identity = age::scrypt::Identity::new(passphrase);
+		let reader = decryptor
+			.decrypt(std::iter::once(&identity as &dyn age::Identity))
+			.context("Unable to decrypt bundle")?;
+
+		let mut archive = tar::Archive::new(reader);
+		let mut entries = HashMap::new();
+		for entry in archive.entries().context("Unable to read bundle entries")? {
+			let mut entry = entry.context("Unable to read bundle entry")?;
+			if !entry.header().entry_type().is_file() {
+				continue;
+			}
+
+			let entry_path = entry.path().context("Unable to get bundle entry path")?.into_owned();
+			let mut entry_contents = vec![];
+			let _ = entry
+				.read_to_end(&mut entry_contents)
+				.with_context(|| format!("Unable to read bundle entry {entry_path:?}"))?;
+			let _ = entries.insert(entry_path, entry_contents.into());
+		}
+
+		Ok(Self { entries })
+	}
+
+	/// Reads a comic book archive.
+	///
+	/// Any entries that aren't images, such as metadata, are skipped.
+	fn read_comic(contents: &[u8]) -> Result<Self, AppError> {
+		let mut archive = zip::ZipArchive::new(io::Cursor::new(contents)).context("Unable to read comic archive")?;
+		let mut entries = HashMap::new();
+		for entry_idx in 0..archive.len() {
+			let mut entry = archive.by_index(entry_idx).context("Unable to read comic entry")?;
+			if !entry.is_file() {
+				continue;
+			}
+
+			let Some(entry_path) = entry.enclosed_name() else {
+				tracing::warn!(name = entry.name(), "Skipping comic entry with an unsafe path");
+				continue;
+			};
+			let mut entry_contents = vec![];
+			let _ = entry
+				.read_to_end(&mut entry_contents)
+				.with_context(|| format!("Unable to read comic entry {entry_path:?}"))?;
+
+			if image::guess_format(&entry_contents).is_err() {
+				tracing::trace!(?entry_path, "Skipping non-image comic entry");
+				continue;
+			}
+			let _ = entries.insert(entry_path, entry_contents.into());
+		}
+
+		Ok(Self { entries })
+	}
+
+	/// Returns the paths of all entries, given the bundle's path
+	fn paths(&self, path: &Path) -> Vec<Arc<Path>> {
+		self.entries
+			.keys()
+			.map(|entry_path| path.join(entry_path).into())
+			.collect()
+	}
+}
+
+impl fmt::Debug for Bundle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Bundle").field("entries", &self.entries.len()).finish()
+	}
+}
@@ -0,0 +1,72 @@
+//! Burn-in protection
+//!
+//! Protects OLED monitors from burn-in by slowly shifting all panels
+//! by a few pixels, and optionally dimming them in a slow cycle.
+
+// Imports
+use {
+	crate::config::BurnInProtectionConfig,
+	cgmath::Vector2,
+	std::{f32::consts::TAU, time::Instant},
+};
+
+/// Burn-in protection
+#[derive(Debug)]
+pub struct BurnInProtection {
+	/// Config
+	config: BurnInProtectionConfig,
+
+	/// Start time
+	start: Instant,
+}
+
+impl BurnInProtection {
+	/// Creates new burn-in protection, starting now
+	pub fn new(config: BurnInProtectionConfig) -> Self {
+		Self {
+			config,
+			start: Instant::now(),
+		}
+	}
+
+	/// Returns the current shift (in pixels) of the window's contents
+	pub fn shift(&self) -> Vector2<i32> {
+		// Note: We walk back and forth through every offset within the maximum shift,
+		//       only moving a single pixel at each step, so the shift isn't noticeable.
+		let side = 2 * u64::from(self.config.max_shift) + 1;
+		let step = self.start.elapsed().as_secs() / self.config.shift_interval_secs.max(1);
+		let x = self::triangle_wave(step, side);
+		let y = self::triangle_wave(step / side, side);
+
+		let max_shift = i32::from(self.config.max_shift);
+		Vector2::new(
+			i32::try_from(x).expect("Shift didn't fit into an `i32`") - max_shift,
+			i32::try_from(y).expect("Shift didn't fit into an `i32`") - max_shift,
+		)
+	}
+
+	/// Returns the current brightness, `0.0 .. 1.0`
+	pub fn brightness(&self) -> f32 {
+		let Some(dim) = &self.config.dim else {
+			return 1.0;
+		};
+
+		let phase = self.start.elapsed().as_secs_f32() / dim.period_secs;
+		let dim_amount = 0.5 - 0.5 * (TAU * phase).cos();
+		1.0 - (1.0 - dim.min_brightness) * dim_amount
+	}
+}
+
+/// Returns a triangle wave at `step`, going from `0` up to `side - 1` and back down
+fn triangle_wave(step: u64, side: u64) -> u64 {
+	if side <= 1 {
+		return 0;
+	}
+
+	let period = 2 * (side - 1);
+	let step = step % period;
+	match step < side {
+		true => step,
+		false => period - step,
+	}
+}
@@ -2,12 +2,18 @@
 
 // Imports
 use {
+	crate::{ratings::RatingSelection, wallpaper_export::WallpaperExportBackend},
 	anyhow::Context,
 	std::{
 		collections::HashSet,
+		env,
+		fmt,
 		fs,
 		num::NonZeroUsize,
 		path::{Path, PathBuf},
+		slice,
+		str::FromStr,
+		time::Duration,
 	},
 	zsw_error::AppError,
 };
@@ -24,6 +30,14 @@ pub struct Config {
 	#[serde(default)]
 	pub rayon_worker_threads: Option<NonZeroUsize>,
 
+	/// Image decode threads.
+	///
+	/// If set, images are decoded and resized on a dedicated thread pool with
+	/// this many threads, instead of tokio's blocking pool, which is shared with
+	/// other blocking work, such as file dialogs.
+	#[serde(default)]
+	pub image_decode_threads: Option<NonZeroUsize>,
+
 	/// Default config file.
 	///
 	/// Will be overridden by command-line arguments
@@ -42,6 +56,10 @@ pub struct Config {
 	#[serde(default)]
 	pub upscale_cache_dir: Option<PathBuf>,
 
+	/// Pipeline cache directory
+	#[serde(default)]
+	pub pipeline_cache_dir: Option<PathBuf>,
+
 	/// Upscaling command, if any.
 	///
 	/// Will be called with arguments `["-i", <input-file>, "-o", <output-file>, "-s", <integer-power-of-two-scale>]`
@@ -52,16 +70,513 @@ pub struct Config {
 	#[serde(default)]
 	pub upscale_exclude: HashSet<PathBuf>,
 
+	/// Io timeout (in seconds) when loading images.
+	///
+	/// Playlist roots that time out are considered offline until they respond again.
+	#[serde(default)]
+	pub io_timeout_secs: Option<f32>,
+
+	/// Number of upcoming images to pre-decode for each panel.
+	///
+	/// These are kept in memory until they're swapped in, so higher values use more memory.
+	#[serde(default)]
+	pub image_look_ahead: Option<usize>,
+
+	/// Wallpaper export backend.
+	///
+	/// If unset, it's detected from the desktop environment
+	#[serde(default)]
+	pub wallpaper_export_backend: Option<WallpaperExportBackend>,
+
+	/// Screensaver, if any
+	#[serde(default)]
+	pub screensaver: Option<ScreensaverConfig>,
+
+	/// Burn-in protection, if any
+	#[serde(default)]
+	pub burn_in_protection: Option<BurnInProtectionConfig>,
+
+	/// Night light, if any
+	#[serde(default)]
+	pub night_light: Option<NightLightConfig>,
+
+	/// Palette of the frame statistics chart.
+	///
+	/// If unset, uses the default palette
+	#[cfg(feature = "menu")]
+	#[serde(default)]
+	pub chart_palette: Option<crate::settings_menu::ChartPalette>,
+
+	/// Rating selection, if any.
+	///
+	/// If unset, ratings don't affect which images are selected
+	#[serde(default)]
+	pub rating_selection: Option<RatingSelection>,
+
+	/// Memory thresholds, if any.
+	///
+	/// A warning is logged whenever any of these are exceeded
+	#[serde(default)]
+	pub memory_thresholds: Option<MemoryThresholdsConfig>,
+
+	/// Number of frame times to keep for frame statistics
+	#[serde(default)]
+	pub frame_times_history: Option<usize>,
+
+	/// Maximum frames per second to render at.
+	///
+	/// If unset, renders at the monitors' refresh rate.
+	/// Panels always progress at the same speed, regardless of this.
+	#[serde(default)]
+	pub max_fps: Option<f32>,
+
+	/// Reverse image search url template.
+	///
+	/// `{url}` is replaced by the image's `file://` url and `{path}` by it's path.
+	/// Since those are only reachable locally, this should point to a service that can
+	/// access them (e.g. a local one), instead of a public search engine.
+	/// If unset, reverse image search is disabled.
+	#[serde(default)]
+	pub reverse_image_search_url: Option<Secret<String>>,
+
+	/// Color all panels are replaced with when blanked, as `[r, g, b]`, from `0.0` to `1.0`.
+	///
+	/// If unset, grey is used
+	#[serde(default)]
+	pub blank_color: Option<[f32; 3]>,
+
+	/// Frame export, if any
+	#[serde(default)]
+	pub frame_export: Option<FrameExportConfig>,
+
+	/// Timelapse recording, if any
+	#[serde(default)]
+	pub timelapse: Option<TimelapseConfig>,
+
+	/// Transition sound, if any
+	#[serde(default)]
+	pub transition_sound: Option<TransitionSoundConfig>,
+
+	/// Status file, if any.
+	///
+	/// Periodically written with the state of all panels, as json
+	#[serde(default)]
+	pub status_file: Option<PathBuf>,
+
+	/// Privacy mode, if any
+	#[serde(default)]
+	pub privacy: Option<PrivacyConfig>,
+
 	/// Default panels
 	#[serde(default)]
 	pub default_panels: Vec<PathBuf>,
+
+	/// Delay (in seconds) between starting to load each default panel.
+	///
+	/// Loading many panels at once, such as one per monitor, may freeze the
+	/// desktop for a while, which staggering them avoids.
+	#[serde(default)]
+	pub startup_stagger_secs: Option<f32>,
+
+	/// Maximum number of default panels loaded at once.
+	///
+	/// If unset, all are loaded at once
+	#[serde(default)]
+	pub startup_concurrency: Option<NonZeroUsize>,
+
+	/// Whether to hold all panels while the monitors are asleep.
+	///
+	/// Any ongoing transition is restarted once they wake up.
+	/// Only supported on X11.
+	#[serde(default)]
+	pub hold_while_monitors_asleep: Option<bool>,
+
+	/// What panels do after the system resumes from a suspend
+	#[serde(default)]
+	pub resume_behavior: Option<ResumeBehavior>,
+}
+
+/// Screensaver configuration
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ScreensaverConfig {
+	/// Idle time (in seconds) until the screensaver starts
+	pub idle_secs: f32,
+
+	/// Playlist
+	pub playlist: String,
+
+	/// Duration (in frames)
+	pub duration: u64,
+
+	/// Fade point (in frames)
+	pub fade_point: u64,
+}
+
+impl ScreensaverConfig {
+	/// Validates this config
+	pub fn validate(&self) -> Result<(), AppError> {
+		self::validate_secs(self.idle_secs).context("Invalid idle time")?;
+		if self.duration == 0 {
+			return Err(anyhow::anyhow!("Duration must not be zero").into());
+		}
+		if self.fade_point > self.duration {
+			return Err(anyhow::anyhow!("Fade point must not be past the duration").into());
+		}
+
+		Ok(())
+	}
+}
+
+/// Privacy mode configuration
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PrivacyConfig {
+	/// Playlist shown instead of private playlists
+	pub fallback_playlist: String,
+
+	/// Whether to automatically enable privacy mode while the screen is being captured
+	#[serde(default)]
+	pub detect_screen_capture: Option<bool>,
+
+	/// Interval (in seconds) between screen capture checks
+	#[serde(default)]
+	pub poll_interval_secs: Option<f32>,
+}
+
+impl PrivacyConfig {
+	/// Returns whether to detect screen captures
+	pub fn detect_screen_capture(&self) -> bool {
+		self.detect_screen_capture.unwrap_or(true)
+	}
+
+	/// Returns the interval between screen capture checks
+	pub fn poll_interval(&self) -> Duration {
+		self.poll_interval_secs
+			.map_or(Duration::from_secs(2), Duration::from_secs_f32)
+	}
+
+	/// Validates this config
+	pub fn validate(&self) -> Result<(), AppError> {
+		if let Some(poll_interval_secs) = self.poll_interval_secs {
+			self::validate_secs(poll_interval_secs).context("Invalid poll interval")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Memory thresholds configuration
+#[derive(Clone, Default, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MemoryThresholdsConfig {
+	/// Maximum resident set size (in MiB)
+	#[serde(default)]
+	pub rss_mib: Option<u64>,
+
+	/// Maximum alive tasks
+	#[serde(default)]
+	pub tasks: Option<u64>,
+
+	/// Maximum loaded textures
+	#[serde(default)]
+	pub textures: Option<u64>,
+}
+
+/// Frame export configuration
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct FrameExportConfig {
+	/// Path to export the frames to.
+	///
+	/// Should usually be within `/dev/shm`, to avoid writing to disk.
+	pub path: PathBuf,
+
+	/// Maximum frames per second to export
+	#[serde(default)]
+	pub fps: Option<f32>,
+}
+
+impl FrameExportConfig {
+	/// Returns the maximum frames per second to export
+	pub fn fps(&self) -> f32 {
+		self.fps.unwrap_or(30.0)
+	}
+
+	/// Validates this config
+	pub fn validate(&self) -> Result<(), AppError> {
+		let fps = self.fps();
+		if !fps.is_finite() || fps <= 0.0 {
+			return Err(anyhow::anyhow!("Fps must be positive, found {fps}").into());
+		}
+		self::validate_secs(1.0 / fps).context("Fps is too low")?;
+
+		Ok(())
+	}
+}
+
+/// Timelapse configuration
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TimelapseConfig {
+	/// Directory to save the timelapses to
+	pub output_dir: PathBuf,
+
+	/// Interval (in seconds) between each captured frame
+	#[serde(default)]
+	pub interval_secs: Option<f32>,
+
+	/// Frames per second of the timelapse video
+	#[serde(default)]
+	pub fps: Option<f32>,
+}
+
+impl TimelapseConfig {
+	/// Returns the interval between each captured frame
+	pub fn interval(&self) -> Duration {
+		self.interval_secs
+			.map_or(Duration::from_secs(10), Duration::from_secs_f32)
+	}
+
+	/// Returns the frames per second of the timelapse video
+	pub fn fps(&self) -> f32 {
+		self.fps.unwrap_or(30.0)
+	}
+
+	/// Validates this config
+	pub fn validate(&self) -> Result<(), AppError> {
+		if let Some(interval_secs) = self.interval_secs {
+			self::validate_secs(interval_secs).context("Invalid interval")?;
+		}
+		let fps = self.fps();
+		if !fps.is_finite() || fps <= 0.0 {
+			return Err(anyhow::anyhow!("Fps must be positive, found {fps}").into());
+		}
+
+		Ok(())
+	}
+}
+
+/// Transition sound configuration
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TransitionSoundConfig {
+	/// Path of the sound file
+	pub path: PathBuf,
+
+	/// Volume, where `1.0` is the sound's original volume
+	#[serde(default)]
+	pub volume: Option<f32>,
+
+	/// Minimum interval (in seconds) between each time the sound is played
+	#[serde(default)]
+	pub min_interval_secs: Option<f32>,
+}
+
+impl TransitionSoundConfig {
+	/// Returns the volume
+	pub fn volume(&self) -> f32 {
+		self.volume.unwrap_or(0.5)
+	}
+
+	/// Returns the minimum interval between each time the sound is played
+	pub fn min_interval(&self) -> Duration {
+		self.min_interval_secs
+			.map_or(Duration::from_secs(1), Duration::from_secs_f32)
+	}
+
+	/// Validates this config
+	pub fn validate(&self) -> Result<(), AppError> {
+		let volume = self.volume();
+		if !volume.is_finite() || volume < 0.0 {
+			return Err(anyhow::anyhow!("Volume must be finite and not negative, found {volume}").into());
+		}
+		if let Some(min_interval_secs) = self.min_interval_secs {
+			self::validate_secs(min_interval_secs).context("Invalid minimum interval")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Burn-in protection configuration
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct BurnInProtectionConfig {
+	/// Maximum shift (in pixels) in each direction
+	pub max_shift: u8,
+
+	/// Interval (in seconds) between each 1 pixel shift
+	pub shift_interval_secs: u64,
+
+	/// Dim cycle, if any
+	#[serde(default)]
+	pub dim: Option<DimCycleConfig>,
+}
+
+impl BurnInProtectionConfig {
+	/// Validates this config
+	pub fn validate(&self) -> Result<(), AppError> {
+		if let Some(dim) = &self.dim {
+			dim.validate().context("Invalid dim cycle")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Dim cycle configuration
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct DimCycleConfig {
+	/// Minimum brightness, `0.0 .. 1.0`
+	pub min_brightness: f32,
+
+	/// Period (in seconds) of the cycle
+	pub period_secs: f32,
+}
+
+impl DimCycleConfig {
+	/// Validates this config
+	pub fn validate(&self) -> Result<(), AppError> {
+		if !(0.0..=1.0).contains(&self.min_brightness) {
+			return Err(
+				anyhow::anyhow!("Minimum brightness must be within 0..1, found {}", self.min_brightness).into(),
+			);
+		}
+		if !self.period_secs.is_finite() || self.period_secs <= 0.0 {
+			return Err(anyhow::anyhow!("Period must be positive, found {}", self.period_secs).into());
+		}
+
+		Ok(())
+	}
+}
+
+/// Night light configuration
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct NightLightConfig {
+	/// Latitude (in degrees, north positive)
+	pub latitude: f64,
+
+	/// Longitude (in degrees, east positive)
+	pub longitude: f64,
+
+	/// Color temperature (in kelvin) during the day
+	#[serde(default)]
+	pub day_temperature: Option<f32>,
+
+	/// Color temperature (in kelvin) during the night
+	#[serde(default)]
+	pub night_temperature: Option<f32>,
+
+	/// Duration (in minutes) of the transition, centered on sunrise / sunset
+	#[serde(default)]
+	pub transition_mins: Option<f32>,
+}
+
+impl NightLightConfig {
+	/// Maximum color temperature
+	pub const MAX_TEMPERATURE: f32 = 40000.0;
+	/// Minimum color temperature
+	pub const MIN_TEMPERATURE: f32 = 1000.0;
+
+	/// Returns the day color temperature
+	pub fn day_temperature(&self) -> f32 {
+		self.day_temperature.unwrap_or(6500.0)
+	}
+
+	/// Returns the night color temperature
+	pub fn night_temperature(&self) -> f32 {
+		self.night_temperature.unwrap_or(3400.0)
+	}
+
+	/// Returns the transition duration
+	pub fn transition(&self) -> Duration {
+		Duration::from_secs_f32(60.0 * self.transition_mins.unwrap_or(60.0))
+	}
+
+	/// Validates this config
+	pub fn validate(&self) -> Result<(), AppError> {
+		if !(-90.0..=90.0).contains(&self.latitude) {
+			return Err(anyhow::anyhow!("Latitude must be within -90..90, found {}", self.latitude).into());
+		}
+		if !(-180.0..=180.0).contains(&self.longitude) {
+			return Err(anyhow::anyhow!("Longitude must be within -180..180, found {}", self.longitude).into());
+		}
+		for temperature in [self.day_temperature(), self.night_temperature()] {
+			if !(Self::MIN_TEMPERATURE..=Self::MAX_TEMPERATURE).contains(&temperature) {
+				return Err(anyhow::anyhow!(
+					"Temperature must be within {}..{}, found {temperature}",
+					Self::MIN_TEMPERATURE,
+					Self::MAX_TEMPERATURE
+				)
+				.into());
+			}
+		}
+		if let Some(transition_mins) = self.transition_mins {
+			self::validate_secs(60.0 * transition_mins).context("Invalid transition duration")?;
+		}
+
+		Ok(())
+	}
 }
 
 impl Config {
-	/// Tries to load the config
+	/// Keys of all secrets, which are redacted from logs and bug reports
+	pub const SECRET_KEYS: &[&str] = &["reverse_image_search_url"];
+
+	/// Tries to load the config, then applies `env_overrides` and `args_overrides` to it.
+	///
+	/// If unable to load it, attempts to create a default config.
+	/// See [`Self::apply_overrides`] for how invalid overrides are handled.
+	pub fn get_or_create_default(
+		path: &Path,
+		env_overrides: &[ConfigOverride],
+		args_overrides: &[ConfigOverride],
+	) -> Result<Self, AppError> {
+		let config = Self::get_or_create_default_file(path);
+		let mut config = config.apply_overrides(env_overrides, args_overrides)?;
+
+		// Note: We only resolve paths after applying the overrides, so overridden paths are resolved too
+		let config_dir = path.parent().context("Config path has no parent directory")?;
+		config.resolve_paths(config_dir);
+
+		Ok(config)
+	}
+
+	/// Applies `env_overrides`, then `args_overrides`.
+	///
+	/// Each invalid environment override is reported and skipped on it's own, since
+	/// the environment is easy to leave stale, but any invalid argument override
+	/// is returned as an error, since the user just explicitly asked for it.
+	fn apply_overrides(
+		self,
+		env_overrides: &[ConfigOverride],
+		args_overrides: &[ConfigOverride],
+	) -> Result<Self, AppError> {
+		let mut config = self;
+		for config_override in env_overrides {
+			match config.with_overrides(slice::from_ref(config_override)) {
+				Ok(overridden_config) => config = overridden_config,
+				Err(err) => tracing::warn!(
+					?config_override,
+					"Unable to apply config override from the environment, ignoring it: {err:?}"
+				),
+			}
+		}
+
+		let config = config
+			.with_overrides(args_overrides)
+			.context("Unable to apply config overrides")?;
+
+		Ok(config)
+	}
+
+	/// Tries to load the config file
 	///
 	/// If unable to, attempts to create a default config
-	pub fn get_or_create_default(path: &Path) -> Self {
+	fn get_or_create_default_file(path: &Path) -> Self {
 		match Self::load(path) {
 			Ok(config) => config,
 			Err(err) => {
@@ -86,10 +601,167 @@ impl Config {
 		tracing::debug!(?path, "Loading config");
 
 		let config_toml = fs::read_to_string(path).context("Unable to open file")?;
-		let config = toml::from_str(&config_toml).context("Unable to parse config")?;
+		let config = toml::from_str::<Self>(&config_toml).context("Unable to parse config")?;
+
+		config.validate().context("Invalid config")?;
+
 		Ok(config)
 	}
 
+	/// Returns this config with `overrides` applied, in order.
+	///
+	/// Unknown keys are ignored, like in the config file.
+	fn with_overrides(&self, overrides: &[ConfigOverride]) -> Result<Self, AppError> {
+		let mut config = toml::Table::try_from(self).context("Unable to serialize config")?;
+		for config_override in overrides {
+			config_override
+				.apply(&mut config)
+				.with_context(|| format!("Unable to apply override of {:?}", config_override.key.join(".")))?;
+		}
+
+		let config = config.try_into::<Self>().context("Unable to parse overridden config")?;
+		config.validate().context("Invalid overridden config")?;
+
+		Ok(config)
+	}
+
+	/// Validates the config
+	pub fn validate(&self) -> Result<(), AppError> {
+		if let Some(io_timeout_secs) = self.io_timeout_secs {
+			self::validate_secs(io_timeout_secs).context("Invalid io timeout")?;
+		}
+		if let Some(max_fps) = self.max_fps {
+			if !max_fps.is_finite() || max_fps <= 0.0 {
+				return Err(anyhow::anyhow!("Maximum fps must be positive, found {max_fps}").into());
+			}
+			self::validate_secs(1.0 / max_fps).context("Maximum fps is too low")?;
+		}
+		if let Some(blank_color) = self.blank_color {
+			if !blank_color.iter().all(|component| (0.0..=1.0).contains(component)) {
+				return Err(anyhow::anyhow!(
+					"Blank color components must be within 0.0 ..= 1.0, found {blank_color:?}"
+				)
+				.into());
+			}
+		}
+		if let Some(startup_stagger_secs) = self.startup_stagger_secs {
+			self::validate_secs(startup_stagger_secs).context("Invalid startup stagger")?;
+		}
+		if let Some(privacy) = &self.privacy {
+			privacy.validate().context("Invalid privacy mode")?;
+		}
+		if let Some(screensaver) = &self.screensaver {
+			screensaver.validate().context("Invalid screensaver")?;
+		}
+		if let Some(burn_in_protection) = &self.burn_in_protection {
+			burn_in_protection.validate().context("Invalid burn-in protection")?;
+		}
+		if let Some(night_light) = &self.night_light {
+			night_light.validate().context("Invalid night light")?;
+		}
+		if let Some(frame_export) = &self.frame_export {
+			frame_export.validate().context("Invalid frame export")?;
+		}
+		if let Some(timelapse) = &self.timelapse {
+			timelapse.validate().context("Invalid timelapse")?;
+		}
+		if let Some(transition_sound) = &self.transition_sound {
+			transition_sound.validate().context("Invalid transition sound")?;
+		}
+
+		Ok(())
+	}
+
+	/// Resolves all paths, expanding `~` and environment variables.
+	///
+	/// Relative paths are resolved against `config_dir`.
+	fn resolve_paths(&mut self, config_dir: &Path) {
+		let resolve = |path: &mut PathBuf| *path = config_dir.join(zsw_util::expand_path(path));
+		self.log_file.iter_mut().for_each(resolve);
+		self.shaders_dir.iter_mut().for_each(resolve);
+		self.playlists_dir.iter_mut().for_each(resolve);
+		self.upscale_cache_dir.iter_mut().for_each(resolve);
+		self.pipeline_cache_dir.iter_mut().for_each(resolve);
+		self.default_panels.iter_mut().for_each(resolve);
+		self.status_file.iter_mut().for_each(resolve);
+		self.frame_export
+			.iter_mut()
+			.for_each(|frame_export| resolve(&mut frame_export.path));
+		self.timelapse
+			.iter_mut()
+			.for_each(|timelapse| resolve(&mut timelapse.output_dir));
+		self.transition_sound
+			.iter_mut()
+			.for_each(|transition_sound| resolve(&mut transition_sound.path));
+		self.upscale_exclude = self
+			.upscale_exclude
+			.iter()
+			.map(|path| config_dir.join(zsw_util::expand_path(path)))
+			.collect();
+
+		// Note: The upscale command isn't resolved against the config directory,
+		//       so that bare command names are still looked up in `PATH`.
+		if let Some(upscale_cmd) = &mut self.upscale_cmd {
+			*upscale_cmd = zsw_util::expand_path(upscale_cmd);
+		}
+	}
+
+	/// Returns the io timeout
+	pub fn io_timeout(&self) -> Duration {
+		self.io_timeout_secs
+			.map_or(Duration::from_secs(10), Duration::from_secs_f32)
+	}
+
+	/// Returns the number of upcoming images to pre-decode
+	pub fn image_look_ahead(&self) -> usize {
+		self.image_look_ahead.unwrap_or(1)
+	}
+
+	/// Returns the delay between starting to load each default panel
+	pub fn startup_stagger(&self) -> Duration {
+		self.startup_stagger_secs
+			.map_or(Duration::ZERO, Duration::from_secs_f32)
+	}
+
+	/// Returns whether to hold all panels while the monitors are asleep
+	pub fn hold_while_monitors_asleep(&self) -> bool {
+		self.hold_while_monitors_asleep.unwrap_or(false)
+	}
+
+	/// Returns what panels do after the system resumes from a suspend
+	pub fn resume_behavior(&self) -> ResumeBehavior {
+		self.resume_behavior.unwrap_or_default()
+	}
+
+	/// Returns the number of frame times to keep
+	pub fn frame_times_history(&self) -> usize {
+		self.frame_times_history.unwrap_or(600)
+	}
+
+	/// Returns the minimum interval between frames, if capped
+	pub fn min_frame_interval(&self) -> Option<Duration> {
+		self.max_fps.map(|max_fps| Duration::from_secs_f32(1.0 / max_fps))
+	}
+
+	/// Returns the color all panels are replaced with when blanked
+	pub fn blank_color(&self) -> [f32; 3] {
+		self.blank_color.unwrap_or([0.5, 0.5, 0.5])
+	}
+
+	/// Returns the wallpaper export backend
+	#[cfg(feature = "menu")]
+	pub fn wallpaper_export_backend(&self) -> WallpaperExportBackend {
+		self.wallpaper_export_backend
+			.clone()
+			.unwrap_or_else(WallpaperExportBackend::detect)
+	}
+
+	/// Returns the reverse image search url template, if any
+	#[cfg(feature = "menu")]
+	pub fn reverse_image_search_url(&self) -> Option<String> {
+		self.reverse_image_search_url.as_ref().map(|url| url.0.clone())
+	}
+
 	/// Writes the config
 	fn write(&self, path: &Path) -> Result<(), AppError> {
 		let config_toml = toml::to_string(self).context("Unable to serialize config")?;
@@ -99,19 +771,293 @@ impl Config {
 	}
 }
 
+/// What panels do after the system resumes from a suspend
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResumeBehavior {
+	/// Continue where they left off
+	#[default]
+	Continue,
+
+	/// Skip to their next image, since the current one was already shown before suspending
+	Skip,
+}
+
+/// Secret config value.
+///
+/// Redacted from it's debug output, so it's never logged.
+#[derive(Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Secret<T>(pub T);
+
+impl<T> fmt::Debug for Secret<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.pad("<redacted>")
+	}
+}
+
+/// Config override.
+///
+/// Overrides a single key of the config file, for a single run.
+/// Parsed from `key=value`, where `key` may be nested with `.` (e.g. `privacy.poll_interval_secs=5`),
+/// and `value` is any toml value, or a bare string.
+#[derive(Clone)]
+pub struct ConfigOverride {
+	/// Key, with each nested table
+	pub key: Vec<String>,
+
+	/// Value
+	pub value: toml::Value,
+}
+
+impl ConfigOverride {
+	/// Environment variable prefix
+	pub const ENV_PREFIX: &str = "ZSW_";
+
+	/// Returns all overrides from the environment.
+	///
+	/// Each `ZSW_*` variable overrides the lowercase key after the prefix, with
+	/// nested tables separated by `__` (e.g. `ZSW_PRIVACY__POLL_INTERVAL_SECS=5`).
+	pub fn from_env() -> Vec<Self> {
+		env::vars()
+			.filter_map(|(var, value)| {
+				let key = var.strip_prefix(Self::ENV_PREFIX)?;
+				Some(Self {
+					key:   key.to_lowercase().split("__").map(str::to_owned).collect(),
+					value: self::parse_override_value(&value),
+				})
+			})
+			.collect()
+	}
+
+	/// Applies this override to `config`
+	fn apply(&self, config: &mut toml::Table) -> Result<(), AppError> {
+		let (last, parents) = self.key.split_last().context("Key was empty")?;
+
+		let mut table = config;
+		for parent in parents {
+			table = table
+				.entry(parent)
+				.or_insert_with(|| toml::Value::Table(toml::Table::new()))
+				.as_table_mut()
+				.with_context(|| format!("Key {parent:?} wasn't a table"))?;
+		}
+		let _ = table.insert(last.clone(), self.value.clone());
+
+		Ok(())
+	}
+}
+
+impl fmt::Debug for ConfigOverride {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		// Note: Overrides may set secrets, so we redact their values
+		let value: &dyn fmt::Debug = match Config::SECRET_KEYS.contains(&self.key.join(".").as_str()) {
+			true => &Secret(()),
+			false => &self.value,
+		};
+
+		f.debug_struct("ConfigOverride")
+			.field("key", &self.key)
+			.field("value", value)
+			.finish()
+	}
+}
+
+impl FromStr for ConfigOverride {
+	type Err = AppError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (key, value) = s.split_once('=').context("Expected `key=value`")?;
+		let key = key.split('.').map(|key| key.trim().to_owned()).collect::<Vec<_>>();
+		if key.iter().any(String::is_empty) {
+			return Err(anyhow::anyhow!("Key must not be empty").into());
+		}
+
+		Ok(Self {
+			key,
+			value: self::parse_override_value(value.trim()),
+		})
+	}
+}
+
+/// Parses an override value.
+///
+/// If it isn't a valid toml value, it's used as a string, so strings don't need to be quoted.
+fn parse_override_value(value: &str) -> toml::Value {
+	format!("value = {value}")
+		.parse::<toml::Table>()
+		.ok()
+		.and_then(|mut table| table.remove("value"))
+		.unwrap_or_else(|| toml::Value::String(value.to_owned()))
+}
+
+/// Validates a duration, in seconds
+fn validate_secs(secs: f32) -> Result<(), AppError> {
+	// Note: `Duration::from_secs_f32` panics on any of these
+	if !secs.is_finite() || secs < 0.0 || secs >= u64::MAX as f32 {
+		return Err(anyhow::anyhow!("Seconds must be finite and not negative, found {secs}").into());
+	}
+
+	Ok(())
+}
+
 #[expect(clippy::derivable_impls)] // we want to be explicit with defaults
 impl Default for Config {
 	fn default() -> Self {
 		Self {
 			tokio_worker_threads: None,
 			rayon_worker_threads: None,
-			log_file:             None,
-			shaders_dir:          None,
-			playlists_dir:        None,
-			upscale_cache_dir:    None,
-			upscale_cmd:          None,
-			upscale_exclude:      HashSet::new(),
-			default_panels:       vec![],
+			image_decode_threads: None,
+			log_file: None,
+			shaders_dir: None,
+			playlists_dir: None,
+			upscale_cache_dir: None,
+			pipeline_cache_dir: None,
+			upscale_cmd: None,
+			upscale_exclude: HashSet::new(),
+			io_timeout_secs: None,
+			image_look_ahead: None,
+			wallpaper_export_backend: None,
+			screensaver: None,
+			burn_in_protection: None,
+			night_light: None,
+			rating_selection: None,
+			frame_times_history: None,
+			max_fps: None,
+			memory_thresholds: None,
+			reverse_image_search_url: None,
+			blank_color: None,
+			frame_export: None,
+			timelapse: None,
+			transition_sound: None,
+			status_file: None,
+			privacy: None,
+			default_panels: vec![],
+			startup_stagger_secs: None,
+			startup_concurrency: None,
+			hold_while_monitors_asleep: None,
+			resume_behavior: None,
+			#[cfg(feature = "menu")]
+			chart_palette: None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use {super::*, proptest::prelude::*};
+
+	proptest! {
+		#[test]
+		fn parse_never_panics(config_toml in "\\PC*") {
+			if let Ok(config) = toml::from_str::<Config>(&config_toml) {
+				let _ = config.validate();
+			}
 		}
+
+		#[test]
+		fn valid_configs_never_panic(
+			io_timeout_secs in any::<f32>(),
+			idle_secs in any::<f32>(),
+			min_brightness in any::<f32>(),
+			period_secs in any::<f32>(),
+			fps in any::<f32>(),
+			min_interval_secs in any::<f32>(),
+			latitude in any::<f64>(),
+			longitude in any::<f64>(),
+			day_temperature in any::<f32>(),
+			transition_mins in any::<f32>(),
+			max_fps in any::<f32>(),
+		) {
+			let config = Config {
+				io_timeout_secs: Some(io_timeout_secs),
+				max_fps: Some(max_fps),
+				screensaver: Some(ScreensaverConfig {
+					idle_secs,
+					playlist: String::new(),
+					duration: 1,
+					fade_point: 1,
+				}),
+				burn_in_protection: Some(BurnInProtectionConfig {
+					max_shift: 0,
+					shift_interval_secs: 0,
+					dim: Some(DimCycleConfig { min_brightness, period_secs }),
+				}),
+				frame_export: Some(FrameExportConfig {
+					path: PathBuf::new(),
+					fps: Some(fps),
+				}),
+				night_light: Some(NightLightConfig {
+					latitude,
+					longitude,
+					day_temperature: Some(day_temperature),
+					night_temperature: None,
+					transition_mins: Some(transition_mins),
+				}),
+				transition_sound: Some(TransitionSoundConfig {
+					path: PathBuf::new(),
+					volume: None,
+					min_interval_secs: Some(min_interval_secs),
+				}),
+				..Config::default()
+			};
+			if config.validate().is_err() {
+				return Ok(());
+			}
+
+			let _ = config.io_timeout();
+			let _ = config.min_frame_interval();
+			let _ = Duration::from_secs_f32(idle_secs);
+			let _ = Duration::from_secs_f32(1.0 / fps);
+			let _ = config.transition_sound.as_ref().map(TransitionSoundConfig::min_interval);
+			let _ = config.night_light.as_ref().map(NightLightConfig::transition);
+		}
+	}
+
+	#[test]
+	fn overrides_apply_in_order() {
+		let overrides = [
+			"max_fps=30",
+			"max_fps=60",
+			"privacy.fallback_playlist=safe",
+			"privacy.poll_interval_secs = 2.5",
+		]
+		.map(|config_override| {
+			config_override
+				.parse::<ConfigOverride>()
+				.expect("Unable to parse override")
+		});
+
+		let config = Config::default()
+			.with_overrides(&overrides)
+			.expect("Unable to apply overrides");
+		assert_eq!(config.max_fps, Some(60.0), "Later override didn't win");
+
+		let privacy = config.privacy.expect("Nested override wasn't applied");
+		assert_eq!(privacy.fallback_playlist, "safe", "Bare string wasn't parsed");
+		assert_eq!(privacy.poll_interval_secs, Some(2.5), "Nested value wasn't parsed");
+	}
+
+	#[test]
+	fn invalid_env_overrides_are_skipped() {
+		let [valid_override, invalid_override] = ["max_fps=30", "max_fps=-1"].map(|config_override| {
+			config_override
+				.parse::<ConfigOverride>()
+				.expect("Unable to parse override")
+		});
+
+		let config = Config::default()
+			.apply_overrides(&[invalid_override.clone(), valid_override.clone()], &[])
+			.expect("Invalid environment override wasn't skipped");
+		assert_eq!(config.max_fps, Some(30.0), "Valid environment override wasn't applied");
+
+		assert!(
+			Config::default()
+				.apply_overrides(&[valid_override], &[invalid_override])
+				.is_err(),
+			"Invalid argument override was ignored"
+		);
 	}
 }
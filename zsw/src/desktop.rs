@@ -0,0 +1,61 @@
+//! Desktop integration
+//!
+//! Helpers for interacting with the clipboard and the desktop's default applications.
+
+// Imports
+use {
+	anyhow::Context,
+	std::{env, path::Path, process::Stdio},
+	tokio::{io::AsyncWriteExt, process::Command},
+	zsw_error::AppError,
+};
+
+/// Copies `text` to the clipboard.
+///
+/// Uses `wl-copy` under wayland, and `xclip` otherwise.
+pub async fn copy_to_clipboard(text: &str) -> Result<(), AppError> {
+	let (program, args): (&str, &[&str]) = match env::var_os("WAYLAND_DISPLAY").is_some() {
+		true => ("wl-copy", &[]),
+		false => ("xclip", &["-selection", "clipboard"]),
+	};
+
+	// Note: Both commands fork into the background to serve the clipboard
+	//       once they've read all of stdin, so we can wait for them.
+	let mut child = Command::new(program)
+		.args(args)
+		.stdin(Stdio::piped())
+		.kill_on_drop(true)
+		.spawn()
+		.context("Unable to spawn clipboard command")?;
+
+	// Note: We drop stdin once written, so the command knows it read everything
+	let mut stdin = child.stdin.take().context("Clipboard command had no stdin")?;
+	stdin
+		.write_all(text.as_bytes())
+		.await
+		.context("Unable to write to clipboard command")?;
+	drop(stdin);
+
+	child
+		.wait()
+		.await
+		.context("Unable to wait for clipboard command")?
+		.exit_ok()
+		.context("Clipboard command returned error")?;
+
+	Ok(())
+}
+
+/// Opens `path` with the default application
+pub fn open(path: &Path) -> Result<(), AppError> {
+	opener::open(path).context("Unable to open path")?;
+	Ok(())
+}
+
+/// Reveals `path` in the file manager
+pub fn reveal(path: &Path) -> Result<(), AppError> {
+	// TODO: Select the file within the file manager, instead of just opening it's directory.
+	let dir = path.parent().context("Path has no parent directory")?;
+	opener::open(dir).context("Unable to open parent directory")?;
+	Ok(())
+}
@@ -0,0 +1,66 @@
+//! App directories
+//!
+//! By default, these are the platform's usual directories (e.g. XDG on linux).
+//! In portable mode, they're instead next to the executable, so that zsw may be
+//! run from a usb stick, or a folder managed alongside dotfiles.
+
+// Imports
+use {
+	anyhow::Context,
+	directories::ProjectDirs,
+	std::path::{Path, PathBuf},
+	zsw_error::AppError,
+};
+
+/// File that, when beside the executable, enables portable mode
+pub const PORTABLE_FILE_NAME: &str = "portable.txt";
+
+/// App directories
+#[derive(Clone, Debug)]
+pub struct Dirs {
+	/// Data directory
+	data_dir: PathBuf,
+
+	/// Local data directory.
+	///
+	/// Used for caches, which shouldn't roam.
+	data_local_dir: PathBuf,
+}
+
+impl Dirs {
+	/// Gets the app directories.
+	///
+	/// If `portable`, or if [`PORTABLE_FILE_NAME`] exists beside the executable,
+	/// the directories are relative to the executable's directory.
+	pub fn new(portable: bool) -> Result<Self, AppError> {
+		let exe_path = std::env::current_exe().context("Unable to get executable path")?;
+		let exe_dir = exe_path.parent().context("Executable path had no parent directory")?;
+		if portable || exe_dir.join(PORTABLE_FILE_NAME).exists() {
+			return Ok(Self::portable(exe_dir));
+		}
+
+		let dirs = ProjectDirs::from("", "", "zsw").context("Unable to create app directories")?;
+		Ok(Self {
+			data_dir:       dirs.data_dir().to_path_buf(),
+			data_local_dir: dirs.data_local_dir().to_path_buf(),
+		})
+	}
+
+	/// Creates portable app directories within `exe_dir`
+	fn portable(exe_dir: &Path) -> Self {
+		Self {
+			data_dir:       exe_dir.join("data/"),
+			data_local_dir: exe_dir.join("cache/"),
+		}
+	}
+
+	/// Returns the data directory
+	pub fn data_dir(&self) -> &Path {
+		&self.data_dir
+	}
+
+	/// Returns the local data directory
+	pub fn data_local_dir(&self) -> &Path {
+		&self.data_local_dir
+	}
+}
@@ -0,0 +1,214 @@
+//! Frame capture
+//!
+//! Copies rendered frames from the gpu back to the cpu.
+
+// Imports
+use {
+	tokio::sync::oneshot,
+	winit::dpi::PhysicalSize,
+	zsw_error::AppError,
+	zsw_wgpu::{FrameRender, WgpuShared},
+};
+
+/// Frame capture
+#[derive(Debug)]
+pub struct FrameCapture {
+	/// Buffer we copy the frames into
+	buffer: Option<wgpu::Buffer>,
+
+	/// Pending capture, if any
+	pending: Option<PendingCapture>,
+}
+
+impl FrameCapture {
+	/// Creates a new frame capture
+	pub fn new() -> Self {
+		Self {
+			buffer:  None,
+			pending: None,
+		}
+	}
+
+	/// Returns if a capture is pending
+	pub fn is_pending(&self) -> bool {
+		self.pending.is_some()
+	}
+
+	/// Records a copy of `frame` to be captured.
+	///
+	/// Must be called after everything has been rendered onto `frame`, and before it's finished.
+	/// Returns an error if the surface can't be captured.
+	pub fn record(&mut self, wgpu_shared: &WgpuShared, frame: &mut FrameRender) -> Result<(), AppError> {
+		let texture = &frame.surface_texture.texture;
+		let format = match texture.format() {
+			wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => CapturedFrameFormat::Rgba,
+			wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => CapturedFrameFormat::Bgra,
+			format => return Err(anyhow::anyhow!("Surface format {format:?} is unsupported").into()),
+		};
+		if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+			return Err(anyhow::anyhow!("Surface doesn't support being copied from").into());
+		}
+
+		// Note: Each row in the buffer must be aligned, so we strip the padding when capturing it.
+		let size = PhysicalSize::new(texture.width(), texture.height());
+		let bytes_per_row = (4 * size.width).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+		let buffer_size = u64::from(bytes_per_row) * u64::from(size.height);
+		let buffer = match &self.buffer {
+			Some(buffer) if buffer.size() == buffer_size => buffer,
+			_ => self
+				.buffer
+				.insert(wgpu_shared.device.create_buffer(&wgpu::BufferDescriptor {
+					label:              Some("[zsw::frame_capture] Buffer"),
+					size:               buffer_size,
+					usage:              wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+					mapped_at_creation: false,
+				})),
+		};
+
+		frame.encoder.copy_texture_to_buffer(
+			texture.as_image_copy(),
+			wgpu::ImageCopyBuffer {
+				buffer,
+				layout: wgpu::ImageDataLayout {
+					offset:         0,
+					bytes_per_row:  Some(bytes_per_row),
+					rows_per_image: None,
+				},
+			},
+			texture.size(),
+		);
+
+		self.pending = Some(PendingCapture {
+			size,
+			bytes_per_row,
+			format,
+			map_rx: None,
+		});
+
+		Ok(())
+	}
+
+	/// Polls the pending capture, if any.
+	///
+	/// Must be called after the frame passed to [`Self::record`] has been finished.
+	/// Returns the captured frame, once it's available.
+	pub fn poll(&mut self, wgpu_shared: &WgpuShared) -> Option<CapturedFrame> {
+		let pending = self.pending.as_mut()?;
+		let buffer = self.buffer.as_ref().expect("Pending capture should have a buffer");
+
+		// If we haven't started mapping the buffer yet, do so
+		let map_rx = pending.map_rx.get_or_insert_with(|| {
+			let (map_tx, map_rx) = oneshot::channel();
+			buffer.slice(..).map_async(wgpu::MapMode::Read, move |res| {
+				let _ = map_tx.send(res);
+			});
+			map_rx
+		});
+
+		// Then check if it's done
+		let _ = wgpu_shared.device.poll(wgpu::Maintain::Poll);
+		let res = match map_rx.try_recv() {
+			Ok(res) => res,
+			Err(oneshot::error::TryRecvError::Empty) => return None,
+			Err(oneshot::error::TryRecvError::Closed) => {
+				tracing::warn!("Frame capture buffer mapping was dropped");
+				self.pending = None;
+				return None;
+			},
+		};
+		let pending = self.pending.take().expect("Just checked");
+		if let Err(err) = res {
+			tracing::warn!(?err, "Unable to map frame capture buffer");
+			return None;
+		}
+
+		// Copy the frame out of the buffer
+		let frame = {
+			let data = buffer.slice(..).get_mapped_range();
+			pending.frame(&data)
+		};
+		buffer.unmap();
+
+		Some(frame)
+	}
+}
+
+/// Pending capture
+#[derive(Debug)]
+struct PendingCapture {
+	/// Frame size
+	size: PhysicalSize<u32>,
+
+	/// Bytes per row in the buffer
+	bytes_per_row: u32,
+
+	/// Pixel format
+	format: CapturedFrameFormat,
+
+	/// Buffer mapping receiver, once requested
+	map_rx: Option<oneshot::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+}
+
+impl PendingCapture {
+	/// Creates the captured frame from the buffer data
+	fn frame(&self, data: &[u8]) -> CapturedFrame {
+		let row_len = 4 * self.size.width as usize;
+		let mut pixels = Vec::with_capacity(row_len * self.size.height as usize);
+		for row in data.chunks_exact(self.bytes_per_row as usize) {
+			pixels.extend_from_slice(&row[..row_len]);
+		}
+
+		CapturedFrame {
+			size: self.size,
+			format: self.format,
+			pixels,
+		}
+	}
+}
+
+/// Captured frame
+#[derive(Debug)]
+pub struct CapturedFrame {
+	/// Size
+	pub size: PhysicalSize<u32>,
+
+	/// Pixel format
+	pub format: CapturedFrameFormat,
+
+	/// Pixels, tightly packed, row by row
+	pub pixels: Vec<u8>,
+}
+
+impl CapturedFrame {
+	/// Converts this frame into an rgba image
+	pub fn into_rgba(mut self) -> image::RgbaImage {
+		if self.format == CapturedFrameFormat::Bgra {
+			for pixel in self.pixels.chunks_exact_mut(4) {
+				pixel.swap(0, 2);
+			}
+		}
+
+		image::RgbaImage::from_raw(self.size.width, self.size.height, self.pixels)
+			.expect("Captured frame should have the right number of pixels")
+	}
+}
+
+/// Captured frame pixel format
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CapturedFrameFormat {
+	/// Rgba, 8 bits each
+	Rgba,
+
+	/// Bgra, 8 bits each
+	Bgra,
+}
+
+impl CapturedFrameFormat {
+	/// Returns the name of this format, as 4 ascii bytes
+	pub fn name(self) -> [u8; 4] {
+		match self {
+			Self::Rgba => *b"RGBA",
+			Self::Bgra => *b"BGRA",
+		}
+	}
+}
@@ -0,0 +1,126 @@
+//! Frame export
+//!
+//! Exports the rendered frames to a file (usually within `/dev/shm`), so
+//! other applications, such as OBS overlays, may consume them without
+//! capturing the whole screen.
+//!
+//! The file starts with a 12 byte header, with the width and height (as
+//! little-endian `u32`s) and the pixel format (either `RGBA` or `BGRA`),
+//! followed by the tightly packed pixels, row by row.
+//!
+//! Each frame is written to a temporary file and then renamed over the
+//! previous one, so readers should re-open the file for each frame.
+
+// Imports
+use {
+	crate::{
+		config::FrameExportConfig,
+		frame_capture::{CapturedFrame, FrameCapture},
+	},
+	anyhow::Context,
+	std::{
+		fs,
+		path::Path,
+		sync::Arc,
+		time::{Duration, Instant},
+	},
+	tokio::task::JoinHandle,
+	zsw_error::AppError,
+	zsw_wgpu::{FrameRender, WgpuShared},
+};
+
+/// Frame exporter
+#[derive(Debug)]
+pub struct FrameExporter {
+	/// Path to export to
+	path: Arc<Path>,
+
+	/// Minimum interval between exports
+	interval: Duration,
+
+	/// Last export
+	last_export: Option<Instant>,
+
+	/// Capture
+	capture: FrameCapture,
+
+	/// Write task, if any
+	write_task: Option<JoinHandle<()>>,
+
+	/// If we've been disabled due to an unsupported surface
+	disabled: bool,
+}
+
+impl FrameExporter {
+	/// Creates a new frame exporter
+	pub fn new(config: &FrameExportConfig) -> Self {
+		Self {
+			path:        Arc::from(config.path.as_path()),
+			interval:    Duration::from_secs_f32(1.0 / config.fps()),
+			last_export: None,
+			capture:     FrameCapture::new(),
+			write_task:  None,
+			disabled:    false,
+		}
+	}
+
+	/// Records a copy of `frame` to be exported, if it's time to export a new frame.
+	///
+	/// Must be called after everything has been rendered onto `frame`, and before it's finished.
+	pub fn record(&mut self, wgpu_shared: &WgpuShared, frame: &mut FrameRender) {
+		// Note: We only keep a single frame in flight, so if the consumer (or the disk)
+		//       is too slow, we simply skip frames.
+		if self.disabled ||
+			self.capture.is_pending() ||
+			self.write_task.as_ref().is_some_and(|task| !task.is_finished()) ||
+			self.last_export
+				.is_some_and(|last_export| last_export.elapsed() < self.interval)
+		{
+			return;
+		}
+
+		if let Err(err) = self.capture.record(wgpu_shared, frame) {
+			tracing::warn!(?err, "Unable to capture frame, disabling frame export");
+			self.disabled = true;
+			return;
+		}
+		self.last_export = Some(Instant::now());
+	}
+
+	/// Polls the pending export, if any.
+	///
+	/// Must be called after the frame passed to [`Self::record`] has been finished.
+	pub fn poll(&mut self, wgpu_shared: &WgpuShared) {
+		let Some(frame) = self.capture.poll(wgpu_shared) else {
+			return;
+		};
+
+		// Write the frame in the background
+		let path = Arc::clone(&self.path);
+		self.write_task = Some(tokio::task::spawn_blocking(move || {
+			if let Err(err) = self::write_frame(&path, &self::serialize_frame(&frame)) {
+				tracing::warn!(?path, ?err, "Unable to export frame");
+			}
+		}));
+	}
+}
+
+/// Serializes a frame, with it's header
+fn serialize_frame(frame: &CapturedFrame) -> Vec<u8> {
+	let mut contents = Vec::with_capacity(12 + frame.pixels.len());
+	contents.extend_from_slice(&frame.size.width.to_le_bytes());
+	contents.extend_from_slice(&frame.size.height.to_le_bytes());
+	contents.extend_from_slice(&frame.format.name());
+	contents.extend_from_slice(&frame.pixels);
+
+	contents
+}
+
+/// Writes a frame to `path`, replacing the previous frame
+fn write_frame(path: &Path, frame: &[u8]) -> Result<(), AppError> {
+	let tmp_path = path.with_extension("tmp");
+	fs::write(&tmp_path, frame).context("Unable to write frame")?;
+	fs::rename(&tmp_path, path).context("Unable to replace previous frame")?;
+
+	Ok(())
+}
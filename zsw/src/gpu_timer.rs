@@ -0,0 +1,201 @@
+//! Gpu timer
+//!
+//! Measures how long the gpu takes to render each part of a frame, using
+//! timestamp queries, when the device supports them.
+//!
+//! Timings are read back asynchronously, so they're usually a few frames late.
+
+// Imports
+use {
+	std::time::Duration,
+	tokio::sync::oneshot,
+	zsw_wgpu::{FrameRender, WgpuShared},
+};
+
+/// Gpu timestamp
+#[derive(Clone, Copy, Debug)]
+pub enum GpuTimestamp {
+	/// Start of the frame
+	Start,
+
+	/// After rendering the panels
+	Panels,
+
+	/// After rendering egui
+	Egui,
+}
+
+impl GpuTimestamp {
+	/// Number of timestamps
+	const COUNT: u32 = 3;
+}
+
+/// Gpu timings of a frame
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only read by the settings menu
+pub struct GpuTimings {
+	/// Duration of rendering the panels
+	pub panels: Duration,
+
+	/// Duration of rendering egui
+	pub egui: Duration,
+}
+
+impl GpuTimings {
+	/// Returns the total duration
+	#[cfg(feature = "menu")]
+	pub fn total(&self) -> Duration {
+		self.panels + self.egui
+	}
+}
+
+/// Gpu timer
+#[derive(Debug)]
+pub struct GpuTimer {
+	/// Query set
+	query_set: wgpu::QuerySet,
+
+	/// Buffer the queries are resolved into
+	resolve_buffer: wgpu::Buffer,
+
+	/// Buffer the resolved queries are copied into, to be read
+	read_buffer: wgpu::Buffer,
+
+	/// Timestamp period (in nanoseconds per tick)
+	period: f32,
+
+	/// Pending read, if any
+	pending: Option<PendingRead>,
+
+	/// Last timings read
+	timings: Option<GpuTimings>,
+}
+
+impl GpuTimer {
+	/// Device features required
+	const FEATURES: wgpu::Features =
+		wgpu::Features::TIMESTAMP_QUERY.union(wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS);
+
+	/// Creates a new gpu timer.
+	///
+	/// Returns `None` if the device doesn't support timestamp queries.
+	pub fn new(wgpu_shared: &WgpuShared) -> Option<Self> {
+		if !wgpu_shared.device.features().contains(Self::FEATURES) {
+			tracing::debug!("Device doesn't support timestamp queries, disabling gpu timings");
+			return None;
+		}
+
+		let buffer_size = u64::from(GpuTimestamp::COUNT) * u64::from(wgpu::QUERY_SIZE);
+		let query_set = wgpu_shared.device.create_query_set(&wgpu::QuerySetDescriptor {
+			label: Some("[zsw::gpu_timer] Query set"),
+			ty:    wgpu::QueryType::Timestamp,
+			count: GpuTimestamp::COUNT,
+		});
+		let resolve_buffer = wgpu_shared.device.create_buffer(&wgpu::BufferDescriptor {
+			label:              Some("[zsw::gpu_timer] Resolve buffer"),
+			size:               buffer_size,
+			usage:              wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+			mapped_at_creation: false,
+		});
+		let read_buffer = wgpu_shared.device.create_buffer(&wgpu::BufferDescriptor {
+			label:              Some("[zsw::gpu_timer] Read buffer"),
+			size:               buffer_size,
+			usage:              wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+			mapped_at_creation: false,
+		});
+
+		Some(Self {
+			query_set,
+			resolve_buffer,
+			read_buffer,
+			period: wgpu_shared.queue.get_timestamp_period(),
+			pending: None,
+			timings: None,
+		})
+	}
+
+	/// Returns the last timings read, if any
+	pub fn timings(&self) -> Option<GpuTimings> {
+		self.timings
+	}
+
+	/// Writes `timestamp` onto `frame`
+	pub fn write_timestamp(&self, frame: &mut FrameRender, timestamp: GpuTimestamp) {
+		frame.encoder.write_timestamp(&self.query_set, timestamp as u32);
+	}
+
+	/// Resolves all timestamps written onto `frame`, so they may be read.
+	///
+	/// Must be called after all timestamps have been written, and before the frame is finished.
+	pub fn resolve(&mut self, frame: &mut FrameRender) {
+		// Note: We only keep a single read in flight, so if it's not done yet, we skip this frame's timings
+		if self.pending.is_some() {
+			return;
+		}
+
+		frame
+			.encoder
+			.resolve_query_set(&self.query_set, 0..GpuTimestamp::COUNT, &self.resolve_buffer, 0);
+		frame
+			.encoder
+			.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.read_buffer, 0, self.read_buffer.size());
+		self.pending = Some(PendingRead { map_rx: None });
+	}
+
+	/// Polls the pending read, if any.
+	///
+	/// Must be called after the frame passed to [`Self::resolve`] has been finished.
+	pub fn poll(&mut self, wgpu_shared: &WgpuShared) {
+		let Some(pending) = &mut self.pending else {
+			return;
+		};
+
+		// If we haven't started mapping the buffer yet, do so
+		let map_rx = pending.map_rx.get_or_insert_with(|| {
+			let (map_tx, map_rx) = oneshot::channel();
+			self.read_buffer.slice(..).map_async(wgpu::MapMode::Read, move |res| {
+				let _ = map_tx.send(res);
+			});
+			map_rx
+		});
+
+		// Then check if it's done
+		let _ = wgpu_shared.device.poll(wgpu::Maintain::Poll);
+		let res = match map_rx.try_recv() {
+			Ok(res) => res,
+			Err(oneshot::error::TryRecvError::Empty) => return,
+			Err(oneshot::error::TryRecvError::Closed) => {
+				tracing::warn!("Gpu timer buffer mapping was dropped");
+				self.pending = None;
+				return;
+			},
+		};
+		self.pending = None;
+		if let Err(err) = res {
+			tracing::warn!(?err, "Unable to map gpu timer buffer");
+			return;
+		}
+
+		let timestamps = {
+			let data = self.read_buffer.slice(..).get_mapped_range();
+			bytemuck::pod_read_unaligned::<[u64; GpuTimestamp::COUNT as usize]>(&data)
+		};
+		self.read_buffer.unmap();
+
+		let duration = |start: GpuTimestamp, end: GpuTimestamp| {
+			let ticks = timestamps[end as usize].saturating_sub(timestamps[start as usize]);
+			Duration::from_secs_f64(ticks as f64 * f64::from(self.period) / 1e9)
+		};
+		self.timings = Some(GpuTimings {
+			panels: duration(GpuTimestamp::Start, GpuTimestamp::Panels),
+			egui:   duration(GpuTimestamp::Panels, GpuTimestamp::Egui),
+		});
+	}
+}
+
+/// Pending read
+#[derive(Debug)]
+struct PendingRead {
+	/// Buffer mapping receiver, once requested
+	map_rx: Option<oneshot::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+}
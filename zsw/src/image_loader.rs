@@ -1,31 +1,80 @@
 //! Image loader
 
+// Modules
+mod decode;
+mod error;
+mod histogram;
+#[cfg(feature = "menu")]
+mod metadata;
+mod video;
+
+// Exports
+#[cfg(feature = "menu")]
+pub use self::metadata::ImageMetadata;
+pub use self::{error::ImageLoadError, histogram::ImageHistogram, video::VideoStream};
+
 // Imports
 use {
-	crate::panel::PanelGeometry,
+	crate::{
+		bundle::Bundles,
+		panel::{PanelGeometry, PanelScaleMode},
+	},
 	anyhow::Context,
 	cgmath::Vector2,
-	futures::StreamExt,
-	image::DynamicImage,
+	futures::{
+		future::{BoxFuture, Shared},
+		FutureExt,
+		StreamExt,
+	},
+	image::{DynamicImage, RgbaImage},
 	std::{
-		collections::HashSet,
+		collections::{HashMap, HashSet},
 		fs,
+		num::NonZeroUsize,
+		panic::{self, AssertUnwindSafe},
 		path::{Path, PathBuf},
+		sync::Arc,
+		time::Duration,
 	},
-	tokio::sync::{oneshot, Semaphore},
+	tokio::sync::{oneshot, Mutex, Semaphore},
 	tracing::Instrument,
 	zsw_error::AppError,
-	zsw_util::Rect,
+	zsw_util::{LogLimiter, Rect},
 };
 
 /// Image
 #[derive(Debug)]
+#[expect(clippy::struct_field_names)] // It's the image data
 pub struct Image {
 	/// Path
 	pub path: PathBuf,
 
 	/// Image
 	pub image: DynamicImage,
+
+	/// Thumbnail, at most [`THUMBNAIL_SIZE`] on each side
+	pub thumbnail: RgbaImage,
+
+	/// Histogram, computed from the thumbnail
+	pub histogram: ImageHistogram,
+
+	/// Whether this is a video.
+	///
+	/// If so, `image` is it's first frame.
+	pub is_video: bool,
+
+	/// Whether `path` isn't a file on disk.
+	///
+	/// Images within bundles or rendered from text are virtual.
+	pub is_virtual: bool,
+}
+
+/// Maximum thumbnail size
+pub const THUMBNAIL_SIZE: u32 = 128;
+
+/// Creates the thumbnail of `image`
+pub fn thumbnail(image: &DynamicImage) -> RgbaImage {
+	image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).into_rgba8()
 }
 
 /// Request
@@ -34,11 +83,19 @@ pub struct ImageRequest {
 	/// Path
 	pub path: PathBuf,
 
+	/// Playlist root the image was found in, if any.
+	///
+	/// Used to detect when the root goes offline
+	pub root: Option<Arc<Path>>,
+
 	/// Geometries
 	///
 	/// Image must fit within these geometries
 	pub geometries: Vec<Rect<i32, u32>>,
 
+	/// Scale mode of the panel the image is for
+	pub scale_mode: PanelScaleMode,
+
 	/// Max image size
 	pub max_image_size: u32,
 }
@@ -50,7 +107,7 @@ pub struct ImageResponse {
 	pub request: ImageRequest,
 
 	/// Image result
-	pub image_res: Result<Image, AppError>,
+	pub image_res: Result<Image, ImageLoadError>,
 }
 
 
@@ -59,9 +116,27 @@ pub struct ImageResponse {
 pub struct ImageRequester {
 	/// Request sender
 	req_tx: async_channel::Sender<(ImageRequest, oneshot::Sender<ImageResponse>)>,
+
+	/// Offline roots
+	#[cfg(feature = "menu")]
+	offline_roots: Arc<Mutex<HashSet<Arc<Path>>>>,
+
+	/// Number of upcoming images to request ahead of time
+	look_ahead: usize,
 }
 
 impl ImageRequester {
+	/// Returns the number of upcoming images to request ahead of time
+	pub fn look_ahead(&self) -> usize {
+		self.look_ahead
+	}
+
+	/// Returns all playlist roots currently offline
+	#[cfg(feature = "menu")]
+	pub async fn offline_roots(&self) -> Vec<Arc<Path>> {
+		self.offline_roots.lock().await.iter().cloned().collect()
+	}
+
 	/// Sends a request
 	pub fn request(&self, request: ImageRequest) -> ImageReceiver {
 		let (ret_tx, ret_rx) = oneshot::channel();
@@ -111,95 +186,270 @@ pub struct ImageLoader {
 
 	/// Upscale semaphore
 	upscale_semaphore: Semaphore,
+
+	/// Io timeout
+	io_timeout: Duration,
+
+	/// Offline roots
+	///
+	/// Any requests within these roots are paused until the root is back online
+	offline_roots: Arc<Mutex<HashSet<Arc<Path>>>>,
+
+	/// Probes of whether each root is online, while running.
+	///
+	/// Each probe is shared by everyone checking the root, so that an unreachable
+	/// root never has more than a single probe hanging on it.
+	root_probes: Mutex<HashMap<Arc<Path>, Shared<BoxFuture<'static, bool>>>>,
+
+	/// Bundles
+	bundles: Arc<Bundles>,
+
+	/// Decode pool.
+	///
+	/// If `None`, images are decoded on tokio's blocking pool
+	decode_pool: Option<rayon::ThreadPool>,
 }
 
 impl ImageLoader {
 	/// Runs the image loader.
 	pub async fn run(self) -> Result<(), AppError> {
 		// Accept all requests in parallel
+		// Note: We clone the receiver, since we need `self` to process each request
 		self.req_rx
-			.then(|(request, response_tx)| async {
+			.clone()
+			.for_each_concurrent(None, |(request, response_tx)| async {
 				// Load the image, then send it
-				let image_res = Self::load(
-					&self.upscale_cache_dir,
-					self.upscale_cmd.as_deref(),
-					&self.upscale_exclude,
-					&self.upscale_semaphore,
-					&request,
-				)
-				.await;
-				if let Err(err) = &image_res {
-					tracing::warn!(?request, ?err, "Unable to load image");
+				let image_res = self.load_checked(&request).await;
+				// Note: Panels may keep requesting the same broken image, so we rate-limit it
+				// Note: Offline roots are already logged once when they go offline
+				static LOG_LIMITER: LogLimiter = LogLimiter::new(Duration::from_secs(30));
+				match &image_res {
+					Ok(_) | Err(ImageLoadError::RootOffline { .. }) => (),
+					Err(err) =>
+						if let Some(suppressed) = LOG_LIMITER.check(&request.path.to_string_lossy()) {
+							tracing::warn!(?request, ?err, suppressed, "Unable to load image");
+						},
 				}
 
 				if let Err(err) = response_tx.send(ImageResponse { request, image_res }) {
 					tracing::warn!(?err, "Unable to response to image request");
 				}
 			})
-			.collect::<()>()
 			.await;
 
 		Ok(())
 	}
 
-	/// Loads an image by request
-	async fn load(
-		upscale_cache_dir: &Path,
-		upscale_cmd: Option<&Path>,
-		upscale_exclude: &HashSet<PathBuf>,
-		upscale_semaphore: &Semaphore,
-		request: &ImageRequest,
-	) -> Result<Image, AppError> {
-		// Default image path
-		let mut image_path = request.path.clone();
+	/// Loads an image by request, while checking if it's root is online.
+	///
+	/// If the root is, or goes, offline, returns [`ImageLoadError::RootOffline`],
+	/// so the panel may retry later, instead of us waiting on it.
+	async fn load_checked(&self, request: &ImageRequest) -> Result<Image, ImageLoadError> {
+		if let Some(root) = &request.root {
+			self.check_root_online(root).await?;
+		}
 
-		// Check if we should upscale the image
-		match Self::check_upscale(
-			request,
-			upscale_exclude,
-			upscale_cache_dir,
-			upscale_cmd,
-			upscale_semaphore,
-		)
-		.await
-		{
-			Ok(Some(upscaled_image_path)) => image_path = upscaled_image_path,
-			Ok(None) => (),
-			Err(err) => tracing::warn!(path = ?request.path, ?err, "Unable to upscale image"),
+		let err = match self.load(request).await {
+			Ok(image) => return Ok(image),
+			Err(err) => err,
+		};
+
+		// If we failed, check if it was because the root went offline
+		match &request.root {
+			Some(root) if !self.is_root_online(root).await => {
+				tracing::warn!(?root, path = ?request.path, ?err, "Playlist root went offline while loading image");
+				let _ = self.offline_roots.lock().await.insert(Arc::clone(root));
+				Err(ImageLoadError::RootOffline { root: Arc::clone(root) })
+			},
+			_ => Err(err),
 		}
+	}
 
+	/// Checks if `root`, if offline, is back online
+	async fn check_root_online(&self, root: &Arc<Path>) -> Result<(), ImageLoadError> {
+		if !self.offline_roots.lock().await.contains(root) {
+			return Ok(());
+		}
 
-		// Load the image
-		tracing::trace!(path = ?request.path, "Loading image");
-		let mut image = tokio::task::spawn_blocking(move || image::open(image_path))
-			.instrument(tracing::trace_span!("Loading image"))
+		if !self.is_root_online(root).await {
+			return Err(ImageLoadError::RootOffline { root: Arc::clone(root) });
+		}
+
+		tracing::info!(?root, "Playlist root is back online");
+		let _ = self.offline_roots.lock().await.remove(root);
+
+		Ok(())
+	}
+
+	/// Checks if `root` is online.
+	///
+	/// If `root` is already being probed, waits on that probe instead of starting another.
+	async fn is_root_online(&self, root: &Arc<Path>) -> bool {
+		let probe = {
+			let mut root_probes = self.root_probes.lock().await;
+			root_probes
+				.entry(Arc::clone(root))
+				.or_insert_with(|| {
+					tokio::fs::metadata(Arc::clone(root))
+						.map(|res| res.is_ok())
+						.boxed()
+						.shared()
+				})
+				.clone()
+		};
+
+		// Note: If the root is unreachable, the probe may keep a blocking thread hanging,
+		//       so we stop waiting on it after a timeout, but keep it around for the next check.
+		let is_online = tokio::time::timeout(self.io_timeout, probe.clone())
 			.await
-			.context("Unable to join image load task")?
-			.context("Unable to open image")?;
+			.unwrap_or(false);
+
+		// If the probe finished, remove it, so the next check probes again
+		if probe.peek().is_some() {
+			let mut root_probes = self.root_probes.lock().await;
+			if root_probes.get(root).is_some_and(|cur_probe| cur_probe.ptr_eq(&probe)) {
+				let _ = root_probes.remove(root);
+			}
+		}
+
+		is_online
+	}
+
+	/// Loads an image by request
+	async fn load(&self, request: &ImageRequest) -> Result<Image, ImageLoadError> {
+		let decode_pool = self.decode_pool.as_ref();
+
+		// Load the image
+		// Note: Images within bundles are never upscaled, since the upscaler
+		//       would require them to be written to disk decrypted.
+		// Note: Videos within bundles aren't supported, since they'd need to be
+		//       streamed from disk by the decoder.
+		let bundle_contents = self.bundles.get(&request.path).await;
+		let is_virtual = bundle_contents.is_some();
+		let (mut image, is_video) = match bundle_contents {
+			Some(contents) => {
+				tracing::trace!(path = ?request.path, "Loading image from bundle");
+				let image = self::spawn_decode(decode_pool, "load", move || decode::decode(&contents))
+					.instrument(tracing::trace_span!("Loading image"))
+					.await?
+					.map_err(ImageLoadError::Decode)?;
+				(image, false)
+			},
+			None =>
+				Self::load_file(
+					&self.upscale_cache_dir,
+					self.upscale_cmd.as_deref(),
+					&self.upscale_exclude,
+					&self.upscale_semaphore,
+					self.io_timeout,
+					decode_pool,
+					request,
+				)
+				.await?,
+		};
 		tracing::trace!(path = ?request.path, image_width = ?image.width(), image_height = ?image.height(), "Loaded image");
 
 		// TODO: Use `request.geometries?` for upscaling?
 
 		// If the image is too big, resize it
-		if image.width() >= request.max_image_size || image.height() >= request.max_image_size {
+		// Note: Some drivers have a low texture size limit (such as 2048), so we use a
+		//       high quality filter, to avoid the image looking too blurry when downscaled.
+		// TODO: Split very large images across multiple textures instead of downscaling them?
+		if image.width() > request.max_image_size || image.height() > request.max_image_size {
 			let max_image_size = request.max_image_size;
 
 			tracing::trace!(path = ?request.path, image_width = ?image.width(), image_height = ?image.height(), ?max_image_size, "Resizing image");
-			image = tokio::task::spawn_blocking(move || {
-				image.resize(max_image_size, max_image_size, image::imageops::FilterType::Nearest)
+			image = self::spawn_decode(decode_pool, "resize", move || {
+				image.resize(max_image_size, max_image_size, image::imageops::FilterType::Lanczos3)
 			})
 			.instrument(tracing::trace_span!("Resizing image"))
-			.await
-			.context("Failed to join image resize task")?;
+			.await?;
 			tracing::trace!(path = ?request.path, image_width = ?image.width(), image_height = ?image.height(), "Resized image");
 		}
 
+		// Then create it's thumbnail and histogram
+		let (image, thumbnail, histogram) = self::spawn_decode(decode_pool, "thumbnail", move || {
+			let thumbnail = self::thumbnail(&image);
+			let histogram = ImageHistogram::new(&thumbnail);
+			(image, thumbnail, histogram)
+		})
+		.instrument(tracing::trace_span!("Creating thumbnail"))
+		.await?;
+
 		Ok(Image {
 			path: request.path.clone(),
 			image,
+			thumbnail,
+			histogram,
+			is_video,
+			is_virtual,
 		})
 	}
 
+	/// Loads an image file by request, upscaling it if necessary.
+	///
+	/// If the file is a video, loads it's first frame instead, and returns `true`.
+	async fn load_file(
+		upscale_cache_dir: &Path,
+		upscale_cmd: Option<&Path>,
+		upscale_exclude: &HashSet<PathBuf>,
+		upscale_semaphore: &Semaphore,
+		io_timeout: Duration,
+		decode_pool: Option<&rayon::ThreadPool>,
+		request: &ImageRequest,
+	) -> Result<(DynamicImage, bool), ImageLoadError> {
+		// Default image path
+		let mut image_path = request.path.clone();
+
+		// Check if it's a video
+		let is_video_task = tokio::task::spawn_blocking({
+			let image_path = request.path.clone();
+			move || video::is_video_file(&image_path)
+		});
+		let is_video = tokio::time::timeout(io_timeout, is_video_task)
+			.await
+			.map_err(ImageLoadError::Timeout)?
+			.map_err(|err| ImageLoadError::Join {
+				task: "check video",
+				err,
+			})?
+			.map_err(ImageLoadError::Open)?;
+
+		// Check if we should upscale the image
+		// Note: Videos are never upscaled, since the upscaler only handles images
+		if !is_video {
+			match Self::check_upscale(
+				request,
+				upscale_exclude,
+				upscale_cache_dir,
+				upscale_cmd,
+				upscale_semaphore,
+				io_timeout,
+			)
+			.await
+			{
+				Ok(Some(upscaled_image_path)) => image_path = upscaled_image_path,
+				Ok(None) => (),
+				Err(err) => tracing::warn!(path = ?request.path, ?err, "Unable to upscale image"),
+			}
+		}
+
+
+		// Load the image
+		tracing::trace!(path = ?request.path, ?is_video, "Loading image");
+		let image_task = self::spawn_decode(decode_pool, "load", move || match is_video {
+			true => video::first_frame(&image_path),
+			false => decode::decode_file(&image_path),
+		});
+		let image = tokio::time::timeout(io_timeout, image_task)
+			.instrument(tracing::trace_span!("Loading image"))
+			.await
+			.map_err(ImageLoadError::Timeout)??
+			.map_err(ImageLoadError::Open)?;
+
+		Ok((image, is_video))
+	}
+
 	/// Checks if an upscale is required and performs it, if so.
 	///
 	/// Returns the path of the upscaled image, if any
@@ -209,15 +459,18 @@ impl ImageLoader {
 		upscale_cache_dir: &Path,
 		upscale_cmd: Option<&Path>,
 		upscale_semaphore: &Semaphore,
+		io_timeout: Duration,
 	) -> Result<Option<PathBuf>, AppError> {
 		// Get the image size
-		let (image_width, image_height) = tokio::task::spawn_blocking({
+		let image_size_task = tokio::task::spawn_blocking({
 			let image_path = request.path.clone();
-			move || image::image_dimensions(image_path)
-		})
-		.await
-		.context("Unable to join image check size task")?
-		.context("Unable to get image size")?;
+			move || decode::file_dimensions(&image_path)
+		});
+		let (image_width, image_height) = tokio::time::timeout(io_timeout, image_size_task)
+			.await
+			.context("Timed out getting image size")?
+			.context("Unable to join image check size task")?
+			.context("Unable to get image size")?;
 		tracing::trace!(path = ?request.path, ?image_width, ?image_height, "Image size");
 
 		// Then compute the minimum size required.
@@ -226,7 +479,7 @@ impl ImageLoader {
 		let minimum_size = request
 			.geometries
 			.iter()
-			.map(|geometry| Self::minimum_image_size_for_panel(Vector2::new(image_width, image_height), geometry.size))
+			.filter_map(|geometry| Self::minimum_image_size_for_panel(image_size, geometry.size, request.scale_mode))
 			.reduce(|lhs, rhs| Vector2::new(lhs.x.max(rhs.x), lhs.y.max(rhs.y)));
 		tracing::trace!(?request, ?minimum_size, "Minimum image size");
 		let Some(minimum_size) = minimum_size else {
@@ -324,15 +577,24 @@ impl ImageLoader {
 		Ok(upscaled_image_path)
 	}
 
-	/// Determines the minimum size for an image for a panel
-	fn minimum_image_size_for_panel(image_size: Vector2<u32>, panel_size: Vector2<u32>) -> Vector2<u32> {
-		let ratio = PanelGeometry::image_ratio(panel_size, image_size);
+	/// Determines the minimum size for an image for a panel.
+	///
+	/// Returns `None` if the scale mode doesn't stretch the image, so any size is enough.
+	fn minimum_image_size_for_panel(
+		image_size: Vector2<u32>,
+		panel_size: Vector2<u32>,
+		scale_mode: PanelScaleMode,
+	) -> Option<Vector2<u32>> {
+		if !scale_mode.stretches() {
+			return None;
+		}
+		let ratio = PanelGeometry::image_ratio(panel_size, image_size, scale_mode);
 
 		#[expect(clippy::cast_sign_loss)] // The sizes and ratio are positive
-		Vector2::new(
+		Some(Vector2::new(
 			(panel_size.x as f32 / ratio.x).ceil() as u32,
 			(panel_size.y as f32 / ratio.y).ceil() as u32,
-		)
+		))
 	}
 }
 
@@ -341,14 +603,30 @@ pub async fn create(
 	upscale_cache_dir: PathBuf,
 	upscale_cmd: Option<PathBuf>,
 	upscale_exclude: HashSet<PathBuf>,
+	io_timeout: Duration,
+	look_ahead: usize,
+	decode_threads: Option<NonZeroUsize>,
+	bundles: Arc<Bundles>,
 ) -> Result<(ImageLoader, ImageRequester), AppError> {
 	// Create the upscale cache directory
 	tokio::fs::create_dir_all(&upscale_cache_dir)
 		.await
 		.context("Unable to create upscale cache directory")?;
 
+	// Create the decode pool, if requested
+	let decode_pool = decode_threads
+		.map(|decode_threads| {
+			rayon::ThreadPoolBuilder::new()
+				.thread_name(|idx| format!("decode${idx}"))
+				.num_threads(decode_threads.get())
+				.build()
+				.context("Unable to build decode thread pool")
+		})
+		.transpose()?;
+
 
 	let (req_tx, req_rx) = async_channel::unbounded();
+	let offline_roots = Arc::new(Mutex::new(HashSet::new()));
 	Ok((
 		ImageLoader {
 			req_rx,
@@ -356,7 +634,46 @@ pub async fn create(
 			upscale_cmd,
 			upscale_exclude,
 			upscale_semaphore: Semaphore::new(1),
+			io_timeout,
+			offline_roots: Arc::clone(&offline_roots),
+			root_probes: Mutex::new(HashMap::new()),
+			bundles,
+			decode_pool,
+		},
+		ImageRequester {
+			req_tx,
+			#[cfg(feature = "menu")]
+			offline_roots,
+			look_ahead,
 		},
-		ImageRequester { req_tx },
 	))
 }
+
+/// Spawns `f` on the decode pool, if any, else on tokio's blocking pool
+async fn spawn_decode<T, F>(
+	decode_pool: Option<&rayon::ThreadPool>,
+	task: &'static str,
+	f: F,
+) -> Result<T, ImageLoadError>
+where
+	T: Send + 'static,
+	F: FnOnce() -> T + Send + 'static,
+{
+	match decode_pool {
+		Some(decode_pool) => {
+			let (res_tx, res_rx) = oneshot::channel();
+			decode_pool.spawn(move || {
+				// Note: If the receiver was dropped (e.g. due to a timeout), we don't care about the result
+				let _ = res_tx.send(panic::catch_unwind(AssertUnwindSafe(f)));
+			});
+
+			match res_rx.await {
+				Ok(Ok(value)) => Ok(value),
+				Ok(Err(_)) | Err(_) => Err(ImageLoadError::Panicked { task }),
+			}
+		},
+		None => tokio::task::spawn_blocking(f)
+			.await
+			.map_err(|err| ImageLoadError::Join { task, err }),
+	}
+}
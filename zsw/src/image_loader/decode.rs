@@ -0,0 +1,45 @@
+//! Image decoding
+//!
+//! Formats are always sniffed from the contents, never from the file extension.
+
+// Modules
+#[cfg(feature = "heif")]
+mod heif;
+
+// Imports
+use {
+	image::{DynamicImage, ImageReader, ImageResult},
+	std::{io::Cursor, path::Path},
+};
+
+/// Decodes an image file
+pub fn decode_file(path: &Path) -> ImageResult<DynamicImage> {
+	#[cfg(feature = "heif")]
+	if heif::is_heif_file(path)? {
+		let contents = std::fs::read(path)?;
+		return heif::decode(&contents);
+	}
+
+	ImageReader::open(path)?.with_guessed_format()?.decode()
+}
+
+/// Decodes an image from memory
+pub fn decode(contents: &[u8]) -> ImageResult<DynamicImage> {
+	#[cfg(feature = "heif")]
+	if heif::is_heif(contents) {
+		return heif::decode(contents);
+	}
+
+	ImageReader::new(Cursor::new(contents)).with_guessed_format()?.decode()
+}
+
+/// Returns the dimensions of an image file, without decoding it
+pub fn file_dimensions(path: &Path) -> ImageResult<(u32, u32)> {
+	#[cfg(feature = "heif")]
+	if heif::is_heif_file(path)? {
+		let contents = std::fs::read(path)?;
+		return heif::dimensions(&contents);
+	}
+
+	ImageReader::open(path)?.with_guessed_format()?.into_dimensions()
+}
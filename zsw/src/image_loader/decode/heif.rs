@@ -0,0 +1,78 @@
+//! HEIF decoding
+//!
+//! Also decodes AVIF, since it's a HEIF container.
+
+// Imports
+use {
+	image::{
+		error::{DecodingError, ImageFormatHint},
+		DynamicImage,
+		ImageError,
+		ImageResult,
+		RgbaImage,
+	},
+	libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma},
+	std::{fs, io::Read, path::Path},
+};
+
+/// All brands we decode
+const BRANDS: [&[u8; 4]; 10] = [
+	b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"mif1", b"msf1", b"avif", b"avis",
+];
+
+/// Returns if `contents` is a HEIF image, by it's `ftyp` box
+pub fn is_heif(contents: &[u8]) -> bool {
+	match contents.get(4..12) {
+		Some([b'f', b't', b'y', b'p', brand @ ..]) => BRANDS.iter().any(|known| known[..] == *brand),
+		_ => false,
+	}
+}
+
+/// Returns if the file at `path` is a HEIF image
+pub fn is_heif_file(path: &Path) -> ImageResult<bool> {
+	let mut header = Vec::with_capacity(12);
+	let _ = fs::File::open(path)?.take(12).read_to_end(&mut header)?;
+
+	Ok(self::is_heif(&header))
+}
+
+/// Decodes a HEIF image
+pub fn decode(contents: &[u8]) -> ImageResult<DynamicImage> {
+	let context = HeifContext::read_from_bytes(contents).map_err(self::decoding_error)?;
+	let handle = context.primary_image_handle().map_err(self::decoding_error)?;
+	let image = LibHeif::new()
+		.decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+		.map_err(self::decoding_error)?;
+
+	let plane = image
+		.planes()
+		.interleaved
+		.ok_or_else(|| self::decoding_error("Decoded image had no interleaved plane"))?;
+
+	// Note: Rows may be padded, so we can't just copy the whole plane
+	let row_len = plane.width as usize * 4;
+	let data = plane
+		.data
+		.chunks(plane.stride)
+		.take(plane.height as usize)
+		.flat_map(|row| &row[..row_len])
+		.copied()
+		.collect();
+	let image = RgbaImage::from_raw(plane.width, plane.height, data)
+		.ok_or_else(|| self::decoding_error("Decoded image plane was too small"))?;
+
+	Ok(DynamicImage::ImageRgba8(image))
+}
+
+/// Returns the dimensions of a HEIF image, without decoding it
+pub fn dimensions(contents: &[u8]) -> ImageResult<(u32, u32)> {
+	let context = HeifContext::read_from_bytes(contents).map_err(self::decoding_error)?;
+	let handle = context.primary_image_handle().map_err(self::decoding_error)?;
+
+	Ok((handle.width(), handle.height()))
+}
+
+/// Creates a decoding error
+fn decoding_error(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> ImageError {
+	ImageError::Decoding(DecodingError::new(ImageFormatHint::Name("heif".to_owned()), err))
+}
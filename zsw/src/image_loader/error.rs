@@ -0,0 +1,61 @@
+//! Image loader errors
+
+// Imports
+use {
+	std::{path::Path, sync::Arc},
+	zsw_error::AppError,
+};
+
+/// Image load error
+#[derive(Debug, thiserror::Error)]
+pub enum ImageLoadError {
+	/// Timed out opening image
+	#[error("Timed out opening image")]
+	Timeout(#[source] tokio::time::error::Elapsed),
+
+	/// Unable to open image
+	#[error("Unable to open image")]
+	Open(#[source] image::ImageError),
+
+	/// The image's playlist root is offline
+	#[error("Playlist root {root:?} is offline")]
+	RootOffline {
+		/// Root
+		root: Arc<Path>,
+	},
+
+	/// Unable to decode image from a bundle
+	#[error("Unable to decode image")]
+	Decode(#[source] image::ImageError),
+
+	/// Unable to join a blocking task
+	#[error("Unable to join image {task} task")]
+	Join {
+		/// Task
+		task: &'static str,
+
+		/// Error
+		#[source]
+		err: tokio::task::JoinError,
+	},
+
+	/// A decode pool task panicked
+	#[error("Image {task} task panicked")]
+	Panicked {
+		/// Task
+		task: &'static str,
+	},
+}
+
+impl ImageLoadError {
+	/// Returns if this error is transient, i.e. if loading the image again might succeed
+	pub fn is_transient(&self) -> bool {
+		matches!(self, Self::Timeout(_) | Self::RootOffline { .. })
+	}
+}
+
+impl From<ImageLoadError> for AppError {
+	fn from(err: ImageLoadError) -> Self {
+		Self::Other(anyhow::Error::new(err))
+	}
+}
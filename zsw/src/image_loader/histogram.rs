@@ -0,0 +1,112 @@
+//! Image histogram
+
+// Imports
+use image::RgbaImage;
+
+/// Image histogram.
+///
+/// Computed from the thumbnail, so it's cheap enough to compute for every image.
+#[derive(Clone, Debug)]
+pub struct ImageHistogram {
+	/// Number of pixels in each luminance bin, from darkest to brightest
+	pub luminance: [u32; Self::BINS],
+
+	/// Number of pixels clipped to black (all channels at 0)
+	pub clipped_black: u32,
+
+	/// Number of pixels clipped to white (any channel at 255)
+	pub clipped_white: u32,
+
+	/// Total number of pixels counted
+	pub pixels: u32,
+}
+
+impl ImageHistogram {
+	/// Number of luminance bins
+	pub const BINS: usize = 64;
+
+	/// Computes the histogram of `image`.
+	///
+	/// Fully transparent pixels are ignored.
+	pub fn new(image: &RgbaImage) -> Self {
+		let mut histogram = Self {
+			luminance:     [0; Self::BINS],
+			clipped_black: 0,
+			clipped_white: 0,
+			pixels:        0,
+		};
+
+		for &image::Rgba([r, g, b, a]) in image.pixels() {
+			if a == 0 {
+				continue;
+			}
+
+			// Note: We use the Rec. 709 luma coefficients on the encoded values,
+			//       which is close enough for a histogram.
+			let luma = (2126 * usize::from(r) + 7152 * usize::from(g) + 722 * usize::from(b)) / 10000;
+			histogram.luminance[luma * Self::BINS / 256] += 1;
+
+			if r == 0 && g == 0 && b == 0 {
+				histogram.clipped_black += 1;
+			}
+			if r == 255 || g == 255 || b == 255 {
+				histogram.clipped_white += 1;
+			}
+			histogram.pixels += 1;
+		}
+
+		histogram
+	}
+
+	/// Returns the fraction of pixels clipped to black
+	#[cfg(feature = "menu")]
+	pub fn clipped_black_ratio(&self) -> f32 {
+		self.ratio(self.clipped_black)
+	}
+
+	/// Returns the fraction of pixels clipped to white
+	#[cfg(feature = "menu")]
+	pub fn clipped_white_ratio(&self) -> f32 {
+		self.ratio(self.clipped_white)
+	}
+
+	/// Returns `count` as a fraction of all pixels
+	#[cfg(feature = "menu")]
+	fn ratio(&self, count: u32) -> f32 {
+		match self.pixels {
+			0 => 0.0,
+			pixels => count as f32 / pixels as f32,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn bins_and_clipping() {
+		let image = RgbaImage::from_fn(4, 1, |x, _| match x {
+			0 => image::Rgba([0, 0, 0, 255]),
+			1 => image::Rgba([255, 255, 255, 255]),
+			2 => image::Rgba([128, 128, 128, 255]),
+			_ => image::Rgba([255, 0, 0, 0]),
+		});
+		let histogram = ImageHistogram::new(&image);
+
+		assert_eq!(histogram.pixels, 3, "Transparent pixel wasn't ignored");
+		assert_eq!(histogram.luminance[0], 1, "Black pixel wasn't in the first bin");
+		assert_eq!(
+			histogram.luminance[ImageHistogram::BINS - 1],
+			1,
+			"White pixel wasn't in the last bin"
+		);
+		assert_eq!(
+			histogram.luminance[ImageHistogram::BINS / 2],
+			1,
+			"Gray pixel wasn't in the middle bin"
+		);
+		assert_eq!(histogram.clipped_black, 1, "Black pixel wasn't clipped");
+		assert_eq!(histogram.clipped_white, 1, "White pixel wasn't clipped");
+	}
+}
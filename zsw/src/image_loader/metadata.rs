@@ -0,0 +1,87 @@
+//! Image metadata
+
+// Imports
+use {
+	super::decode,
+	percent_encoding::NON_ALPHANUMERIC,
+	std::{
+		fs,
+		io::BufReader,
+		path::{Path, PathBuf},
+	},
+};
+
+/// Image metadata
+#[derive(Clone, Debug)]
+pub struct ImageMetadata {
+	/// Path
+	pub path: PathBuf,
+
+	/// Resolution, if available
+	pub resolution: Option<(u32, u32)>,
+
+	/// File size (in bytes), if available
+	pub file_size: Option<u64>,
+
+	/// Exif fields, as `(tag, value)`
+	pub exif: Vec<(String, String)>,
+}
+
+impl ImageMetadata {
+	/// Reads the metadata of the image at `path`.
+	///
+	/// Any metadata we're unable to read is left empty.
+	pub fn read(path: &Path) -> Self {
+		let resolution = decode::file_dimensions(path)
+			.inspect_err(|err| tracing::debug!(?path, ?err, "Unable to get image resolution"))
+			.ok();
+		let file_size = fs::metadata(path)
+			.inspect_err(|err| tracing::debug!(?path, ?err, "Unable to get image file metadata"))
+			.ok()
+			.map(|metadata| metadata.len());
+		let exif = self::read_exif(path)
+			.inspect_err(|err| tracing::debug!(?path, ?err, "Unable to read image exif"))
+			.unwrap_or_default();
+
+		Self {
+			path: path.to_path_buf(),
+			resolution,
+			file_size,
+			exif,
+		}
+	}
+
+	/// Returns the reverse image search url of this image, from the url template `url`.
+	///
+	/// Within the template, `{url}` is replaced by the image's `file://` url and `{path}`
+	/// by it's path, both percent-encoded.
+	pub fn reverse_search_url(&self, url: &str) -> String {
+		// TODO: Not use lossy conversion to encode it?
+		let path = self.path.to_string_lossy();
+		let file_url = format!("file://{path}");
+		url.replace(
+			"{url}",
+			&percent_encoding::utf8_percent_encode(&file_url, NON_ALPHANUMERIC).to_string(),
+		)
+		.replace(
+			"{path}",
+			&percent_encoding::utf8_percent_encode(&path, NON_ALPHANUMERIC).to_string(),
+		)
+	}
+}
+
+/// Reads all exif fields of the primary image at `path`
+fn read_exif(path: &Path) -> Result<Vec<(String, String)>, exif::Error> {
+	let file = fs::File::open(path)?;
+	let exif = exif::Reader::new().read_from_container(&mut BufReader::new(file))?;
+	let fields = exif
+		.fields()
+		.filter(|field| field.ifd_num == exif::In::PRIMARY)
+		.map(|field| {
+			let value = field.display_value().with_unit(&exif).to_string();
+			(field.tag.to_string(), value)
+		})
+		.collect();
+
+	Ok(fields)
+}
@@ -0,0 +1,174 @@
+//! Video decoding
+//!
+//! Videos are decoded by running `ffmpeg`, which must be installed.
+//! When loading, only the first frame is decoded, to be used as the image.
+//! Once shown, the video is streamed at it's native frame rate, in a loop.
+
+// Imports
+use {
+	anyhow::Context,
+	cgmath::Vector2,
+	image::{
+		error::{DecodingError, ImageFormatHint},
+		DynamicImage,
+		ImageError,
+		ImageFormat,
+		ImageResult,
+	},
+	std::{
+		fs,
+		io::Read,
+		path::Path,
+		process::{self, Command, Stdio},
+		sync::{Arc, Mutex, PoisonError},
+	},
+	zsw_error::AppError,
+};
+
+/// All mp4 / quicktime brands we decode
+const BRANDS: [&[u8; 4]; 12] = [
+	b"isom", b"iso2", b"iso4", b"iso5", b"iso6", b"mp41", b"mp42", b"avc1", b"M4V ", b"dash", b"qt  ", b"mmp4",
+];
+
+/// Ebml magic, used by webm and matroska
+const EBML_MAGIC: [u8; 4] = [0x1a, 0x45, 0xdf, 0xa3];
+
+/// Returns if `contents` is a video, by it's header
+pub fn is_video(contents: &[u8]) -> bool {
+	if contents.starts_with(&EBML_MAGIC) {
+		return true;
+	}
+
+	match contents.get(4..12) {
+		Some([b'f', b't', b'y', b'p', brand @ ..]) => BRANDS.iter().any(|known| known[..] == *brand),
+		_ => false,
+	}
+}
+
+/// Returns if the file at `path` is a video
+pub fn is_video_file(path: &Path) -> ImageResult<bool> {
+	let mut header = Vec::with_capacity(12);
+	let _ = fs::File::open(path)?.take(12).read_to_end(&mut header)?;
+
+	Ok(self::is_video(&header))
+}
+
+/// Decodes the first frame of the video at `path`
+pub fn first_frame(path: &Path) -> ImageResult<DynamicImage> {
+	let output = Command::new("ffmpeg")
+		.args(["-nostdin", "-loglevel", "error", "-i"])
+		.arg(path)
+		.args([
+			"-an",
+			"-frames:v",
+			"1",
+			"-f",
+			"image2pipe",
+			"-c:v",
+			"png",
+			"-pix_fmt",
+			"rgba",
+			"-",
+		])
+		.stdin(Stdio::null())
+		.output()?;
+	if !output.status.success() {
+		return Err(self::decoding_error(String::from_utf8_lossy(&output.stderr).trim()));
+	}
+
+	// Note: We always want `rgba8`, so the frames may be copied straight into the texture
+	let image = image::load_from_memory_with_format(&output.stdout, ImageFormat::Png)?;
+	Ok(DynamicImage::ImageRgba8(image.into_rgba8()))
+}
+
+/// Video stream.
+///
+/// Decodes a video on a separate process, keeping only the latest frame.
+/// The process is killed once this is dropped.
+#[derive(Debug)]
+pub struct VideoStream {
+	/// Decoder process
+	decoder: process::Child,
+
+	/// Latest frame, if not yet taken.
+	///
+	/// Tightly packed `rgba8` pixels.
+	frame: Arc<Mutex<Option<Vec<u8>>>>,
+}
+
+impl VideoStream {
+	/// Starts streaming the video at `path`, scaled to `size`
+	pub fn start(path: &Path, size: Vector2<u32>) -> Result<Self, AppError> {
+		let mut decoder = Command::new("ffmpeg")
+			.args(["-nostdin", "-loglevel", "error", "-re", "-stream_loop", "-1", "-i"])
+			.arg(path)
+			.args(["-an", "-vf"])
+			.arg(format!("scale={}:{}", size.x, size.y))
+			.args(["-f", "rawvideo", "-pix_fmt", "rgba", "-"])
+			.stdin(Stdio::null())
+			.stdout(Stdio::piped())
+			.spawn()
+			.context("Unable to run `ffmpeg`")?;
+		let mut stdout = decoder.stdout.take().expect("Decoder stdout should be piped");
+
+		// Note: We create the stream before spawning the reader, so the decoder
+		//       is killed if we're unable to spawn it.
+		let stream = Self {
+			decoder,
+			frame: Arc::new(Mutex::new(None)),
+		};
+
+		let frame = Arc::clone(&stream.frame);
+		let frame_len = 4 * size.x as usize * size.y as usize;
+		let _ = std::thread::Builder::new()
+			.name("video-reader".to_owned())
+			.spawn(move || loop {
+				// Note: Once the decoder is killed, reading fails, so we quit
+				let mut buffer = vec![0; frame_len];
+				if stdout.read_exact(&mut buffer).is_err() {
+					break;
+				}
+
+				*frame.lock().unwrap_or_else(PoisonError::into_inner) = Some(buffer);
+			})
+			.context("Unable to spawn video reader thread")?;
+
+		Ok(stream)
+	}
+
+	/// Takes the latest frame, if a new one was decoded since the last call
+	pub fn take_frame(&self) -> Option<Vec<u8>> {
+		self.frame.lock().unwrap_or_else(PoisonError::into_inner).take()
+	}
+}
+
+impl Drop for VideoStream {
+	fn drop(&mut self) {
+		if let Err(err) = self.decoder.kill() {
+			tracing::warn!(?err, "Unable to kill video decoder");
+		}
+		let _ = self.decoder.wait();
+	}
+}
+
+/// Creates a decoding error
+fn decoding_error(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> ImageError {
+	ImageError::Decoding(DecodingError::new(ImageFormatHint::Name("video".to_owned()), err))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn detects_videos() {
+		assert!(
+			is_video(b"\x00\x00\x00\x20ftypisom\x00\x00\x02\x00"),
+			"Mp4 wasn't detected"
+		);
+		assert!(is_video(b"\x1a\x45\xdf\xa3\x9f\x42\x86\x81"), "Webm wasn't detected");
+		assert!(!is_video(b"\x00\x00\x00\x18ftypheic"), "Heif was detected as a video");
+		assert!(!is_video(b"\x89PNG\r\n\x1a\n"), "Png was detected as a video");
+		assert!(!is_video(b"ftyp"), "Truncated header was detected as a video");
+	}
+}
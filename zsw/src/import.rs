@@ -0,0 +1,206 @@
+//! Importing from other wallpaper managers
+//!
+//! Generates a playlist and a starter panel from another
+//! wallpaper manager's configuration.
+
+// Modules
+mod ini;
+mod nitrogen;
+mod plasma;
+mod variety;
+
+// Imports
+use {
+	crate::{
+		panel::{ser, PanelAlignment, PanelSampler, PanelScaleMode, PanelTile},
+		playlist::{Playlist, PlaylistItem, PlaylistItemKind, Playlists},
+	},
+	anyhow::Context,
+	cgmath::{Point2, Vector2},
+	directories::BaseDirs,
+	std::{
+		path::{Path, PathBuf},
+		sync::Arc,
+		time::Duration,
+	},
+	zsw_error::AppError,
+	zsw_util::Rect,
+};
+
+/// Import source
+#[derive(Clone, Copy, Debug)]
+#[derive(clap::ValueEnum)]
+pub enum ImportSource {
+	/// Variety
+	Variety,
+
+	/// Nitrogen
+	Nitrogen,
+
+	/// KDE Plasma
+	Plasma,
+}
+
+impl ImportSource {
+	/// Returns the name of this source
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Variety => "variety",
+			Self::Nitrogen => "nitrogen",
+			Self::Plasma => "plasma",
+		}
+	}
+
+	/// Returns the default config file path of this source
+	fn default_path(self) -> Result<PathBuf, AppError> {
+		let base_dirs = BaseDirs::new().context("Unable to get base directories")?;
+		let config_dir = base_dirs.config_dir();
+		let path = match self {
+			Self::Variety => config_dir.join("variety/variety.conf"),
+			Self::Nitrogen => config_dir.join("nitrogen/nitrogen.cfg"),
+			Self::Plasma => config_dir.join("plasma-org.kde.plasma.desktop-appletsrc"),
+		};
+
+		Ok(path)
+	}
+}
+
+/// Imported configuration
+#[derive(Default, Debug)]
+struct Imported {
+	/// Playlist items
+	items: Vec<PlaylistItemKind>,
+
+	/// Interval between wallpapers
+	interval: Option<Duration>,
+}
+
+impl Imported {
+	/// Adds a path as an item, if it wasn't already added
+	async fn add_path(&mut self, path: &Path) {
+		let path = zsw_util::expand_path(path);
+		if self.items.iter().any(|item| **item.path() == *path) {
+			return;
+		}
+
+		// Note: If we can't get the metadata, we assume it's a file, since
+		//       it might just be on a drive that isn't mounted currently.
+		let is_dir = tokio::fs::metadata(&path).await.is_ok_and(|metadata| metadata.is_dir());
+		let path = Arc::<Path>::from(path);
+		let item = match is_dir {
+			true => PlaylistItemKind::Directory {
+				path,
+				recursive: true,
+				extensions: None,
+			},
+			false => PlaylistItemKind::File { path },
+		};
+		self.items.push(item);
+	}
+}
+
+/// Imports a playlist and starter panel from `source`.
+///
+/// If `path` isn't specified, the source's default config file is used.
+pub async fn run(
+	source: ImportSource,
+	path: Option<&Path>,
+	playlists_dir: PathBuf,
+	panels_dir: &Path,
+) -> Result<(), AppError> {
+	let path = match path {
+		Some(path) => path.to_path_buf(),
+		None => source.default_path()?,
+	};
+	tracing::info!(?source, ?path, "Importing");
+
+	let imported = match source {
+		ImportSource::Variety => variety::load(&path).await,
+		ImportSource::Nitrogen => nitrogen::load(&path).await,
+		ImportSource::Plasma => plasma::load(&path).await,
+	}
+	.with_context(|| format!("Unable to import from {path:?}"))?;
+	tracing::debug!(?imported, "Imported");
+	if imported.items.is_empty() {
+		return Err(anyhow::anyhow!("No wallpapers found to import in {path:?}").into());
+	}
+
+	// Create the playlist
+	let mut playlists = Playlists::load(playlists_dir)
+		.await
+		.context("Unable to load playlists")?;
+	let playlist = Playlist::new(
+		imported
+			.items
+			.into_iter()
+			.map(|kind| PlaylistItem {
+				enabled: true,
+				kind,
+				ser_path: None,
+			})
+			.collect(),
+	);
+	let playlist_name = playlists
+		.create(source.name(), playlist)
+		.await
+		.context("Unable to create playlist")?;
+	let playlist_path = playlists.playlist_path(&playlist_name);
+	tracing::info!(?playlist_name, ?playlist_path, "Created playlist");
+
+	// Then the starter panel
+	let duration_secs = imported.interval.map_or(60.0, |interval| interval.as_secs_f64());
+	let panel = ser::Panel {
+		// TODO: Detect the monitor geometries?
+		geometries:     vec![ser::PanelGeometry {
+			geometry: Rect {
+				pos:  Point2::new(0, 0),
+				size: Vector2::new(1920, 1080),
+			},
+		}],
+		state:          ser::PanelState {
+			duration_secs:    Some(duration_secs),
+			fade_point_secs:  Some(duration_secs / 6.0 * 5.0),
+			duration:         None,
+			fade_point:       None,
+			parallax_ratio:   ser::default_panel_parallax_ratio(),
+			parallax_exp:     ser::default_panel_parallax_exp(),
+			reverse_parallax: ser::default_panel_parallax_reverse(),
+			scale_mode:       PanelScaleMode::default(),
+			alignment:        PanelAlignment::default(),
+			sampler:          PanelSampler::default(),
+			tile:             PanelTile::default(),
+			transition_sound: ser::default_panel_transition_sound(),
+			transition_blur:  0.0,
+			distinct_images:  false,
+			monitor:          None,
+			quiet_hours:      vec![],
+			fps:              None,
+		},
+		playlist:       playlist_name.to_string(),
+		kind:           ser::PanelKind::Images,
+		quotes:         None,
+		ticker:         None,
+		coordinate:     false,
+		cascade_offset: None,
+	};
+	let panel_toml = toml::to_string(&panel).context("Unable to serialize panel")?;
+	let panel_path = panels_dir.join(format!("{playlist_name}.toml"));
+	tokio::fs::create_dir_all(panels_dir)
+		.await
+		.context("Unable to create panels directory")?;
+	if tokio::fs::try_exists(&panel_path)
+		.await
+		.context("Unable to check if panel exists")?
+	{
+		return Err(anyhow::anyhow!("Panel {panel_path:?} already exists").into());
+	}
+	tokio::fs::write(&panel_path, panel_toml)
+		.await
+		.context("Unable to write panel")?;
+	tracing::info!(
+		?panel_path,
+		"Created starter panel. Adjust it's geometry and add it to `default_panels` in the config to use it"
+	);
+
+	Ok(())
+}
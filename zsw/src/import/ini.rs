@@ -0,0 +1,50 @@
+//! Ini parsing
+//!
+//! Only supports the subset of ini used by the wallpaper managers we import from.
+
+/// Ini entry
+#[derive(Clone, Copy, Debug)]
+pub struct IniEntry<'a> {
+	/// Section, without the outer brackets.
+	///
+	/// Empty for entries before any section.
+	pub section: &'a str,
+
+	/// Key
+	pub key: &'a str,
+
+	/// Value, with any surrounding quotes removed
+	pub value: &'a str,
+}
+
+/// Parses all entries of an ini file
+pub fn parse(contents: &str) -> Vec<IniEntry<'_>> {
+	let mut section = "";
+	contents
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with(['#', ';']))
+		.filter_map(|line| {
+			if let Some(line_section) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+				section = line_section;
+				return None;
+			}
+
+			let Some((key, value)) = line.split_once('=') else {
+				tracing::warn!(?line, "Ignoring malformed ini line");
+				return None;
+			};
+			let value = value.trim();
+			let value = value
+				.strip_prefix('"')
+				.and_then(|value| value.strip_suffix('"'))
+				.unwrap_or(value);
+
+			Some(IniEntry {
+				section,
+				key: key.trim(),
+				value,
+			})
+		})
+		.collect()
+}
@@ -0,0 +1,40 @@
+//! Nitrogen importing
+//!
+//! Reads `nitrogen.cfg` for the browsed directories, and
+//! `bg-saved.cfg`, next to it, for the current wallpapers.
+
+// Imports
+use {
+	super::{ini, Imported},
+	anyhow::Context,
+	std::path::Path,
+	zsw_error::AppError,
+};
+
+/// Loads nitrogen's config
+pub async fn load(path: &Path) -> Result<Imported, AppError> {
+	let contents = tokio::fs::read_to_string(path).await.context("Unable to read config")?;
+	let entries = ini::parse(&contents);
+
+	let mut imported = Imported::default();
+	let dirs = entries
+		.iter()
+		.filter(|entry| entry.section == "nitrogen" && entry.key == "dirs")
+		.flat_map(|entry| entry.value.split(';'))
+		.filter(|dir| !dir.is_empty());
+	for dir in dirs {
+		imported.add_path(Path::new(dir)).await;
+	}
+
+	// Note: Nitrogen only creates the saved wallpapers once one is set
+	let saved_path = path.with_file_name("bg-saved.cfg");
+	match tokio::fs::read_to_string(&saved_path).await {
+		Ok(saved_contents) =>
+			for entry in ini::parse(&saved_contents).iter().filter(|entry| entry.key == "file") {
+				imported.add_path(Path::new(entry.value)).await;
+			},
+		Err(err) => tracing::warn!(?saved_path, ?err, "Unable to read saved wallpapers"),
+	}
+
+	Ok(imported)
+}
@@ -0,0 +1,47 @@
+//! Plasma importing
+//!
+//! Reads the desktop applets config, importing the wallpaper of
+//! each containment's active wallpaper plugin.
+
+// Imports
+use {
+	super::{ini, Imported},
+	anyhow::Context,
+	std::{path::Path, time::Duration},
+	zsw_error::AppError,
+};
+
+/// Loads plasma's desktop applets config
+pub async fn load(path: &Path) -> Result<Imported, AppError> {
+	let contents = tokio::fs::read_to_string(path).await.context("Unable to read config")?;
+	let entries = ini::parse(&contents);
+
+	let mut imported = Imported::default();
+	let containment_plugins = entries
+		.iter()
+		.filter(|entry| entry.key == "wallpaperplugin")
+		.filter_map(|entry| Some((entry.section.strip_prefix("Containments][")?, entry.value)));
+	for (containment, plugin) in containment_plugins {
+		let plugin_section = format!("Containments][{containment}][Wallpaper][{plugin}][General");
+		for entry in entries.iter().filter(|entry| entry.section == plugin_section) {
+			match entry.key {
+				"Image" => {
+					let image = entry.value.strip_prefix("file://").unwrap_or(entry.value);
+					imported.add_path(Path::new(image)).await;
+				},
+				"SlidePaths" =>
+					for slide_path in entry.value.split(',').filter(|slide_path| !slide_path.is_empty()) {
+						let slide_path = slide_path.strip_prefix("file://").unwrap_or(slide_path);
+						imported.add_path(Path::new(slide_path)).await;
+					},
+				"SlideInterval" =>
+					if let Ok(interval) = entry.value.parse() {
+						imported.interval = Some(Duration::from_secs(interval));
+					},
+				_ => (),
+			}
+		}
+	}
+
+	Ok(imported)
+}
@@ -0,0 +1,55 @@
+//! Variety importing
+//!
+//! Reads `variety.conf`, importing all enabled local sources.
+
+// Imports
+use {
+	super::{ini, Imported},
+	anyhow::Context,
+	std::{path::Path, time::Duration},
+	zsw_error::AppError,
+};
+
+/// Loads variety's config
+pub async fn load(path: &Path) -> Result<Imported, AppError> {
+	let contents = tokio::fs::read_to_string(path).await.context("Unable to read config")?;
+	let entries = ini::parse(&contents);
+	let variety_dir = path.parent().context("Config path has no parent directory")?;
+
+	// Gets a folder, with `default` referring to it's default directory
+	let get_folder = |key: &str, default_dir: &str| match entries
+		.iter()
+		.find(|entry| entry.section.is_empty() && entry.key == key)
+	{
+		Some(entry) if entry.value != "default" => Path::new(entry.value).to_path_buf(),
+		_ => variety_dir.join(default_dir),
+	};
+
+	let mut imported = Imported::default();
+	for entry in entries.iter().filter(|entry| entry.section == "sources") {
+		// Note: Sources are in the format `<enabled>|<type>|<location>`
+		let mut source = entry.value.splitn(3, '|');
+		let (Some(enabled), Some(ty), location) = (source.next(), source.next(), source.next()) else {
+			tracing::warn!(?entry, "Ignoring malformed source");
+			continue;
+		};
+		if !enabled.eq_ignore_ascii_case("true") {
+			continue;
+		}
+
+		match (ty, location) {
+			("folder" | "image", Some(location)) => imported.add_path(Path::new(location)).await,
+			("favorites", _) => imported.add_path(&get_folder("favorites_folder", "Favorites")).await,
+			("fetched", _) => imported.add_path(&get_folder("fetched_folder", "Fetched")).await,
+			_ => tracing::warn!(?ty, ?location, "Ignoring unsupported source"),
+		}
+	}
+
+	imported.interval = entries
+		.iter()
+		.find(|entry| entry.section.is_empty() && entry.key == "change_interval")
+		.and_then(|entry| entry.value.parse().ok())
+		.map(Duration::from_secs);
+
+	Ok(imported)
+}
@@ -25,35 +25,91 @@
 
 // Modules
 mod args;
+mod blank;
+mod bug_report;
+mod bundle;
+mod burn_in;
 mod config;
+#[cfg(feature = "menu")]
+mod desktop;
+mod dirs;
+mod frame_capture;
+mod frame_export;
+mod gpu_timer;
 mod image_loader;
+mod import;
 mod init;
+mod metrics;
+mod monitor_power;
+mod night_light;
 mod panel;
 mod playlist;
+mod privacy;
+mod ratings;
+mod sandbox;
+mod screensaver;
+#[cfg(feature = "menu")]
 mod settings_menu;
+mod shader_watch;
 mod shared;
+#[cfg(feature = "menu")]
+mod snapshots;
+mod sound;
+mod status;
+mod tasks;
+mod timelapse;
+mod ui;
+mod wallpaper_export;
 mod window;
 
 // Imports
+#[cfg(feature = "menu")]
+use self::snapshots::Snapshots;
 use {
 	self::{
-		config::Config,
-		panel::{Panel, PanelShader, PanelsManager, PanelsRenderer},
+		blank::Blank,
+		bug_report::BugReport,
+		bundle::Bundles,
+		burn_in::BurnInProtection,
+		config::{Config, ConfigOverride, ResumeBehavior},
+		dirs::Dirs,
+		frame_export::FrameExporter,
+		gpu_timer::{GpuTimer, GpuTimestamp},
+		metrics::{FrameTime, FrameTimes, MemoryMetrics},
+		night_light::NightLight,
+		panel::{
+			PanelCommand,
+			PanelCommands,
+			PanelShader,
+			PanelsManager,
+			PanelsRenderer,
+			RenderError,
+			RgbConverter,
+			PANEL_FRAME_DURATION,
+		},
 		playlist::Playlists,
-		settings_menu::SettingsMenu,
+		privacy::Privacy,
+		ratings::Ratings,
+		sandbox::Sandbox,
+		shader_watch::ShaderScreenshotter,
 		shared::Shared,
+		sound::TransitionSound,
+		tasks::{TaskStatus, TASKS},
+		timelapse::{Timelapse, TimelapseRecorder},
+		ui::UiRenderer,
 	},
 	anyhow::Context,
-	args::Args,
-	cgmath::Point2,
+	args::{Args, Command},
+	cgmath::{Vector2, Vector3},
 	clap::Parser,
 	crossbeam::atomic::AtomicCell,
-	directories::ProjectDirs,
-	futures::{stream::FuturesUnordered, Future, StreamExt},
+	futures::{Future, StreamExt},
 	std::{
 		fs,
+		num::NonZeroUsize,
 		path::{Path, PathBuf},
-		sync::Arc,
+		sync::{Arc, PoisonError},
+		time::{Duration, Instant, SystemTime},
 	},
 	tokio::sync::{mpsc, Mutex, RwLock},
 	winit::{
@@ -62,9 +118,8 @@ use {
 		event_loop::EventLoop,
 		window::WindowId,
 	},
-	zsw_egui::{EguiPainter, EguiRenderer},
 	zsw_error::AppError,
-	zsw_util::{meetup, TokioTaskBlockOn},
+	zsw_util::meetup,
 	zsw_wgpu::WgpuRenderer,
 };
 
@@ -75,14 +130,29 @@ fn main() -> Result<(), AppError> {
 	init::logger::pre_init::debug(format!("args: {args:?}"));
 
 	// Create the configuration then load the config
-	let dirs = ProjectDirs::from("", "", "zsw").context("Unable to create app directories")?;
+	let dirs = Dirs::new(args.portable).context("Unable to get app directories")?;
 	fs::create_dir_all(dirs.data_dir()).context("Unable to create data directory")?;
 	let config_path = args.config.unwrap_or_else(|| dirs.data_dir().join("config.toml"));
-	let config = Config::get_or_create_default(&config_path);
+	let config = Config::get_or_create_default(&config_path, &ConfigOverride::from_env(), &args.config_overrides)
+		.context("Unable to load config")?;
 	init::logger::pre_init::debug(format!("config_path: {config_path:?}, config: {config:?}"));
 
+	// If we're creating a bug report, we don't need to create a window
+	// Note: We gather it before initializing the logger, since it truncates the log file,
+	//       and then only log to stderr
+	let log_file = args.log_file.or_else(|| config.log_file.clone());
+	if let Some(Command::BugReport { output }) = &args.command {
+		let bug_report = BugReport::new(&config_path, log_file.as_deref());
+		init::logger::init(None);
+
+		let output = output.clone().unwrap_or_else(bug_report::default_file_name);
+		bug_report.write(&output).context("Unable to write bug report")?;
+		tracing::info!(?output, "Created bug report");
+		return Ok(());
+	}
+
 	// Initialize the logger properly now
-	init::logger::init(args.log_file.as_deref().or(config.log_file.as_deref()));
+	init::logger::init(log_file.as_deref());
 
 	// Initialize and create everything
 	init::rayon_pool::init(config.rayon_worker_threads).context("Unable to initialize rayon")?;
@@ -92,6 +162,18 @@ fn main() -> Result<(), AppError> {
 	// Enter the tokio runtime
 	let _runtime_enter = tokio_runtime.enter();
 
+	// If we're importing, we don't need to create a window
+	if let Some(Command::Import { source, path }) = &args.command {
+		let playlists_dir = config
+			.playlists_dir
+			.unwrap_or_else(|| dirs.data_dir().join("playlists/"));
+		let panels_dir = config_path
+			.parent()
+			.context("Config path had no parent directory")?
+			.join("panels/");
+		return tokio_runtime.block_on(import::run(*source, path.as_deref(), playlists_dir, &panels_dir));
+	}
+
 	// Create the event loop
 	let event_loop = EventLoop::builder()
 		.build()
@@ -103,7 +185,9 @@ fn main() -> Result<(), AppError> {
 		.run_app(&mut WinitApp {
 			dirs,
 			config_path,
+			log_file,
 			config,
+			command: args.command,
 			event_rx: Some(event_rx),
 			event_tx,
 		})
@@ -113,9 +197,11 @@ fn main() -> Result<(), AppError> {
 }
 
 struct WinitApp {
-	dirs:        ProjectDirs,
+	dirs:        Dirs,
 	config_path: PathBuf,
+	log_file:    Option<PathBuf>,
 	config:      Config,
+	command:     Option<Command>,
 	event_rx:    Option<mpsc::UnboundedReceiver<(WindowId, WindowEvent)>>,
 	event_tx:    mpsc::UnboundedSender<(WindowId, WindowEvent)>,
 }
@@ -126,7 +212,9 @@ impl winit::application::ApplicationHandler for WinitApp {
 		let Err(err) = futures::executor::block_on(self::run(
 			&self.dirs,
 			&self.config_path,
+			self.log_file.as_deref(),
 			&self.config,
+			self.command.as_ref(),
 			event_loop,
 			self.event_rx.take().expect("Already resumed"),
 		)) else {
@@ -148,17 +236,39 @@ impl winit::application::ApplicationHandler for WinitApp {
 	}
 }
 
+#[expect(clippy::too_many_lines)] // TODO: Refactor
 async fn run(
-	dirs: &ProjectDirs,
+	dirs: &Dirs,
 	config_path: &Path,
+	#[cfg_attr(not(feature = "menu"), expect(unused_variables))] // Only used by the settings menu
+	log_file: Option<&Path>,
 	config: &Config,
+	command: Option<&Command>,
 	event_loop: &winit::event_loop::ActiveEventLoop,
 	mut event_rx: mpsc::UnboundedReceiver<(WindowId, WindowEvent)>,
 ) -> Result<(), AppError> {
+	let start_time = Instant::now();
+
 	// TODO: Not leak the window?
-	let window = window::create(event_loop).context("Unable to create winit event loop and window")?;
+	let (window, preview_desktop_size) = match command {
+		Some(Command::Preview { .. } | Command::ShaderWatch { .. }) => {
+			let window = window::create_preview(event_loop).context("Unable to create preview window")?;
+			let desktop_size = window::desktop_geometry(event_loop)?.size;
+			(window, Some(PhysicalSize::new(desktop_size.x, desktop_size.y)))
+		},
+		_ => (
+			window::create(event_loop).context("Unable to create winit event loop and window")?,
+			None,
+		),
+	};
+	let monitors = window::monitors(event_loop).context("Unable to get monitors")?;
 	let window = Box::leak(Box::new(window));
-	let (wgpu_shared, wgpu_renderer) = zsw_wgpu::create(window)
+	// Note: Caches are kept in the local data directory, so they don't roam on windows
+	let pipeline_cache_dir = config
+		.pipeline_cache_dir
+		.clone()
+		.unwrap_or_else(|| dirs.data_local_dir().join("pipeline_cache/"));
+	let (wgpu_shared, wgpu_renderer) = zsw_wgpu::create(window, &pipeline_cache_dir)
 		.await
 		.context("Unable to create wgpu renderer")?;
 
@@ -177,11 +287,26 @@ async fn run(
 		return Err(anyhow::anyhow!("Shaders directory doesn't exist: {shaders_path:?}").into());
 	}
 
-	let (panels_renderer, panels_renderer_layout, panels_renderer_shader) =
-		PanelsRenderer::new(&wgpu_renderer, &wgpu_shared, shaders_path.join("panels/fade.wgsl"))
-			.context("Unable to create panels renderer")?;
-	let (egui_renderer, egui_painter, egui_event_handler) = zsw_egui::create(window, &wgpu_renderer, &wgpu_shared);
-	let settings_menu = SettingsMenu::new();
+	let (panels_renderer, panels_renderer_layout, panels_renderer_shader) = PanelsRenderer::new(
+		&wgpu_shared,
+		shaders_path.join("panels/fade.wgsl"),
+		shaders_path.join("panels/calibration.wgsl"),
+	);
+	let rgb_converter = RgbConverter::new(&wgpu_shared);
+	let (ui_renderer, ui_painter, ui_event_handler) = ui::create(window, &wgpu_renderer, &wgpu_shared, config);
+	let frame_exporter = config.frame_export.as_ref().map(FrameExporter::new);
+	let timelapse_recorder = config.timelapse.as_ref().map(TimelapseRecorder::new);
+	let shader_screenshotter = match command {
+		Some(Command::ShaderWatch { output, .. }) =>
+			Some(ShaderScreenshotter::new(output.clone()).context("Unable to create shader screenshotter")?),
+		_ => None,
+	};
+	let gpu_timer = GpuTimer::new(&wgpu_shared);
+	let transition_sound = config.transition_sound.as_ref().and_then(|transition_sound| {
+		TransitionSound::new(transition_sound)
+			.inspect_err(|err| tracing::warn!(?err, "Unable to create transition sound"))
+			.ok()
+	});
 
 	let playlists = Playlists::load(playlists_path)
 		.await
@@ -192,66 +317,191 @@ async fn run(
 	let upscale_cache_dir = config
 		.upscale_cache_dir
 		.clone()
-		.unwrap_or_else(|| dirs.data_dir().join("upscale_cache/"));
+		.unwrap_or_else(|| dirs.data_local_dir().join("upscale_cache/"));
+	let bundles = Arc::new(Bundles::new());
 	let (image_loader, image_requester) = image_loader::create(
 		upscale_cache_dir,
 		config.upscale_cmd.clone(),
 		config.upscale_exclude.clone(),
+		config.io_timeout(),
+		config.image_look_ahead(),
+		config.image_decode_threads,
+		Arc::clone(&bundles),
 	)
 	.await
 	.context("Unable to create image loader")?;
 
+	let ratings = Ratings::load(dirs.data_dir().join("ratings.json")).context("Unable to load ratings")?;
+	let sandbox = Sandbox::load(dirs.data_dir().join("sandbox.json")).context("Unable to load sandbox")?;
+
 	// Shared state
 	let shared = Shared {
 		window,
 		wgpu: wgpu_shared,
 		panels_renderer_layout,
+		rgb_converter,
 		last_resize: AtomicCell::new(None),
 		// TODO: Not have a default of (0,0)?
 		cursor_pos: AtomicCell::new(PhysicalPosition::new(0.0, 0.0)),
+		occluded: AtomicCell::new(false),
+		minimized: AtomicCell::new(false),
+		monitors_asleep: AtomicCell::new(false),
+		preview_desktop_size,
+		monitors,
 		panels_manager,
 		image_requester,
+		bundles,
+		ratings: Arc::new(ratings),
+		rating_selection: config.rating_selection,
+		#[cfg(feature = "menu")]
+		snapshots: Snapshots::new(dirs.data_dir().join("snapshots/")),
+		sandbox,
+		#[cfg(feature = "menu")]
+		wallpaper_export_backend: config.wallpaper_export_backend(),
+		burn_in_protection: config.burn_in_protection.clone().map(BurnInProtection::new),
+		night_light: config.night_light.clone().map(NightLight::new),
+		blank: Blank::new(config.blank_color().into()),
+		privacy: config.privacy.clone().map(Privacy::new),
+		timelapse: config.timelapse.is_some().then(Timelapse::new),
+		#[cfg(feature = "menu")]
+		reverse_image_search_url: config.reverse_image_search_url(),
+		transition_sound,
+		frame_times: std::sync::Mutex::new(FrameTimes::new(config.frame_times_history())),
+		#[cfg(feature = "menu")]
+		panel_thumbnails: std::sync::Mutex::new(panel::PanelThumbnails::new()),
+		memory_metrics: AtomicCell::new(MemoryMetrics::default()),
 		cur_panels: Mutex::new(vec![]),
 		panels_renderer_shader: RwLock::new(panels_renderer_shader),
 		playlists: RwLock::new(playlists),
+		panel_commands: PanelCommands::new(),
+		#[cfg(feature = "menu")]
+		config_path: config_path.to_path_buf(),
+		#[cfg(feature = "menu")]
+		log_file: log_file.map(Path::to_path_buf),
+		panels_loading: AtomicCell::new(None),
 	};
 	let shared = Arc::new(shared);
 
-	let (egui_painter_output_tx, egui_painter_output_rx) = meetup::channel();
 	let (panels_updater_output_tx, panels_updater_output_rx) = meetup::channel();
-
-
-	self::spawn_task("Load default panels", {
-		let shared = Arc::clone(&shared);
-		let config_path = config_path.to_path_buf();
-		let default_panels = config.default_panels.clone();
-		|| async move { self::load_default_panels(&config_path, default_panels, shared).await }
-	});
+	let (frame_times_tx, frame_times_rx) = mpsc::unbounded_channel();
+
+
+	match command {
+		Some(Command::Show { path }) => self::spawn_task(format!("Show {path:?}"), {
+			let shared = Arc::clone(&shared);
+			let path = path.clone();
+			|| async move { self::show(&path, shared).await }
+		}),
+		Some(Command::Preview { panels } | Command::ShaderWatch { panels, .. }) if !panels.is_empty() =>
+			self::spawn_task("Load preview panels", {
+				let shared = Arc::clone(&shared);
+				let config_path = config_path.to_path_buf();
+				// Note: Panels given on the command line are relative to the current directory, not the config
+				let panels = panels
+					.iter()
+					.map(std::path::absolute)
+					.collect::<Result<Vec<_>, _>>()
+					.context("Unable to get absolute panel paths")?;
+				let paused = shader_screenshotter.is_some();
+				let stagger = config.startup_stagger();
+				let concurrency = config.startup_concurrency;
+				move || async move {
+					self::load_default_panels(&config_path, panels, paused, stagger, concurrency, shared).await
+				}
+			}),
+		Some(Command::Preview { .. } | Command::ShaderWatch { .. }) | None =>
+			self::spawn_task("Load default panels", {
+				let shared = Arc::clone(&shared);
+				let config_path = config_path.to_path_buf();
+				let default_panels = config.default_panels.clone();
+				let paused = shader_screenshotter.is_some();
+				let stagger = config.startup_stagger();
+				let concurrency = config.startup_concurrency;
+				move || async move {
+					self::load_default_panels(&config_path, default_panels, paused, stagger, concurrency, shared).await
+				}
+			}),
+		Some(Command::Import { .. }) => unreachable!("Imports are handled before creating the window"),
+		Some(Command::BugReport { .. }) => unreachable!("Bug reports are handled before creating the window"),
+	}
 
 	self::spawn_task("Renderer", {
 		let shared = Arc::clone(&shared);
-		|| {
+		let min_frame_interval = config.min_frame_interval();
+		move || {
 			self::renderer(
 				shared,
+				start_time,
 				wgpu_renderer,
 				panels_renderer,
-				egui_renderer,
-				egui_painter_output_rx,
+				ui_renderer,
+				frame_exporter,
+				timelapse_recorder,
+				shader_screenshotter,
+				gpu_timer,
 				panels_updater_output_rx,
+				frame_times_tx,
+				min_frame_interval,
 			)
 		}
 	});
 
+	self::spawn_task("Shader watcher", {
+		let shared = Arc::clone(&shared);
+		|| async move { shader_watch::watch_shaders(&shaders_path, &shared.panels_renderer_shader).await }
+	});
+
+	self::spawn_task("Frame times aggregator", {
+		let shared = Arc::clone(&shared);
+		|| metrics::run_frame_times_aggregator(shared, frame_times_rx)
+	});
+
 	self::spawn_task("Panels updater", {
 		let shared = Arc::clone(&shared);
-		|| self::panels_updater(shared, panels_updater_output_tx)
+		let resume_behavior = config.resume_behavior();
+		move || self::panels_updater(shared, panels_updater_output_tx, resume_behavior)
 	});
 
 	self::spawn_task("Image loader", || image_loader.run());
 
-	self::spawn_task("Egui painter", {
+	if let Some(screensaver_config) = config.screensaver.clone() {
+		self::spawn_task("Screensaver", {
+			let shared = Arc::clone(&shared);
+			|| screensaver::run(shared, screensaver_config)
+		});
+	}
+
+	if config.hold_while_monitors_asleep() {
+		self::spawn_task("Monitor power watcher", {
+			let shared = Arc::clone(&shared);
+			|| monitor_power::run(shared)
+		});
+	}
+
+	if shared.privacy.is_some() {
+		self::spawn_task("Privacy mode", {
+			let shared = Arc::clone(&shared);
+			|| privacy::run(shared)
+		});
+	}
+
+	// Note: We always sample the memory metrics, to display them, even without any thresholds
+	self::spawn_task("Memory sampler", {
+		let shared = Arc::clone(&shared);
+		let thresholds = config.memory_thresholds.clone().unwrap_or_default();
+		|| metrics::run_memory_sampler(shared, thresholds)
+	});
+
+	if let Some(status_file) = config.status_file.clone() {
+		self::spawn_task("Status writer", {
+			let shared = Arc::clone(&shared);
+			|| status::run_writer(shared, status_file)
+		});
+	}
+
+	self::spawn_task("Ui painter", {
 		let shared = Arc::clone(&shared);
-		|| self::egui_painter(shared, egui_painter, settings_menu, egui_painter_output_tx)
+		|| ui_painter.run(shared)
 	});
 
 	self::spawn_task("Event receiver", {
@@ -259,12 +509,19 @@ async fn run(
 		|| async move {
 			while let Some((_, event)) = event_rx.recv().await {
 				match event {
-					winit::event::WindowEvent::Resized(size) => shared.last_resize.store(Some(Resize { size })),
+					winit::event::WindowEvent::Resized(size) => {
+						shared.last_resize.store(Some(Resize { size }));
+						shared.minimized.store(size.width == 0 || size.height == 0);
+					},
 					winit::event::WindowEvent::CursorMoved { position, .. } => shared.cursor_pos.store(position),
+					winit::event::WindowEvent::Occluded(occluded) => {
+						tracing::debug!(?occluded, "Window occlusion changed");
+						shared.occluded.store(occluded);
+					},
 					_ => (),
 				}
 
-				egui_event_handler.handle_event(&event).await;
+				ui_event_handler.handle_event(&event).await;
 			}
 
 			Ok(())
@@ -274,17 +531,40 @@ async fn run(
 	Ok(())
 }
 
-/// Loads the default panels
+/// Loads the default panels.
+///
+/// Panels start loading `stagger` apart, with at most `concurrency` loading at once,
+/// and are added as soon as they're loaded.
 async fn load_default_panels(
 	config_path: &Path,
 	default_panels: Vec<PathBuf>,
+	paused: bool,
+	stagger: Duration,
+	concurrency: Option<NonZeroUsize>,
 	shared: Arc<Shared>,
 ) -> Result<(), AppError> {
-	// Load the panels
+	// Set the shader, if any panels will be loaded
+	// Note: The renderer doesn't render anything until the first panel is added.
+	if !default_panels.is_empty() {
+		let mut panels_renderer_shader = shared.panels_renderer_shader.write().await;
+		panels_renderer_shader.shader = PanelShader::FadeOut { strength: 1.5 };
+	}
+
+	// Then load the panels
 	let shared = &shared;
-	let loaded_panels = default_panels
+	let total = default_panels.len();
+	shared.panels_loading.store(Some((0, total)));
+	let concurrency = concurrency.map_or(total, NonZeroUsize::get);
+	let mut loaded = 0;
+	let start = tokio::time::Instant::now();
+	let load_panels = default_panels
 		.iter()
-		.map(|default_panel| async move {
+		.enumerate()
+		.map(|(panel_idx, default_panel)| async move {
+			// Note: We wait relative to the start, so that waiting on the concurrency
+			//       limit doesn't add up with the stagger.
+			tokio::time::sleep_until(start + stagger * u32::try_from(panel_idx).unwrap_or(u32::MAX)).await;
+
 			let default_panel_path = config_path
 				.parent()
 				.expect("Config path had no parent directory")
@@ -297,22 +577,34 @@ async fn load_default_panels(
 				.inspect_err(|err| tracing::warn!("Unable to load default panel {default_panel_path:?}: {err:?}"))
 				.ok()
 		})
-		.collect::<FuturesUnordered<_>>()
-		.filter_map(async move |opt| opt)
-		.collect::<Vec<Panel>>()
-		.await;
-
-	// Add the default panels to the current panels
-	{
-		let mut cur_panels = shared.cur_panels.lock().await;
-		cur_panels.extend(loaded_panels);
+		.collect::<Vec<_>>();
+	let mut loaded_panels = futures::stream::iter(load_panels).buffer_unordered(concurrency.max(1));
+	while let Some(panel) = loaded_panels.next().await {
+		loaded += 1;
+		shared.panels_loading.store(Some((loaded, total)));
+
+		// Add the panel to the current panels
+		if let Some(mut panel) = panel {
+			panel.state.paused = paused;
+			shared.cur_panels.lock().await.push(panel);
+		}
 	}
+	shared.panels_loading.store(None);
 
-	// Finally at the end set the shader, if any panels were loaded
-	if !default_panels.is_empty() {
-		let mut panels_renderer_shader = shared.panels_renderer_shader.write().await;
-		panels_renderer_shader.shader = PanelShader::FadeOut { strength: 1.5 };
-	}
+	Ok(())
+}
+
+/// Shows an image or directory on a single panel
+async fn show(path: &Path, shared: Arc<Shared>) -> Result<(), AppError> {
+	let panel = shared
+		.panels_manager
+		.create_show(path, &shared)
+		.await
+		.context("Unable to create panel")?;
+	shared.cur_panels.lock().await.push(panel);
+
+	let mut panels_renderer_shader = shared.panels_renderer_shader.write().await;
+	panels_renderer_shader.shader = PanelShader::FadeOut { strength: 1.5 };
 
 	Ok(())
 }
@@ -326,195 +618,305 @@ where
 {
 	let name = name.into();
 
+	// Note: If the task never runs, or is dropped mid-way, the guard marks it as cancelled
+	let task = TASKS.register(name.clone());
 	let _ = tokio::task::Builder::new().name(&name.clone()).spawn(async move {
 		let fut = f();
 
 		let id = tokio::task::id();
 		tracing::debug!(?name, ?id, "Spawning task");
 		match fut.await {
-			Ok(_) => tracing::debug!(?name, "Task finished"),
-			Err(err) => tracing::warn!(?name, ?err, "Task returned error"),
+			Ok(_) => {
+				tracing::debug!(?name, "Task finished");
+				task.finish(TaskStatus::Finished);
+			},
+			Err(err) => {
+				let status = TaskStatus::Failed {
+					err: format!("{err:#}"),
+				};
+				tracing::warn!(?name, ?err, "Task returned error");
+				task.finish(status);
+			},
 		}
 	});
 }
 
 /// Renderer task
+#[expect(clippy::too_many_arguments, clippy::too_many_lines)] // TODO: Refactor
 async fn renderer(
 	shared: Arc<Shared>,
+	start_time: Instant,
 	mut wgpu_renderer: WgpuRenderer,
 	mut panels_renderer: PanelsRenderer,
-	mut egui_renderer: EguiRenderer,
-	egui_painter_output_rx: meetup::Receiver<(Vec<egui::ClippedPrimitive>, egui::TexturesDelta)>,
+	mut ui_renderer: UiRenderer,
+	mut frame_exporter: Option<FrameExporter>,
+	mut timelapse_recorder: Option<TimelapseRecorder>,
+	mut shader_screenshotter: Option<ShaderScreenshotter>,
+	mut gpu_timer: Option<GpuTimer>,
 	panels_updater_output_rx: meetup::Receiver<()>,
+	frame_times_tx: mpsc::UnboundedSender<FrameTime>,
+	min_frame_interval: Option<Duration>,
 ) -> Result<!, AppError> {
-	let mut egui_paint_jobs = vec![];
-	let mut egui_textures_delta = None;
+	let mut last_frame_time = Instant::now();
+	let mut rendered_first_frame = false;
+	let mut failed_shader = None;
 	loop {
 		// Meetup with the panels updater
 		let _ = panels_updater_output_rx.try_recv();
 
-		// Update egui, if available
-		if let Some((paint_jobs, textures_delta)) = egui_painter_output_rx.try_recv() {
-			egui_paint_jobs = paint_jobs;
-			egui_textures_delta = Some(textures_delta);
+		// If we're occluded or minimized, don't render anything, but keep the panels updating
+		// Note: We don't meetup with the ui painter, so it doesn't draw for nothing.
+		if shared.occluded.load() || shared.minimized.load() {
+			tokio::time::sleep(PANEL_FRAME_DURATION).await;
+			continue;
 		}
 
+		// Update the ui, if available
+		ui_renderer.update();
+
 		// Start rendering
 		let mut frame = wgpu_renderer
 			.start_render(&shared.wgpu)
 			.context("Unable to start frame")?;
+		if let Some(gpu_timer) = &gpu_timer {
+			gpu_timer.write_timestamp(&mut frame, GpuTimestamp::Start);
+		}
 		// Render the panels
-		{
+		let (panels, draws, shader_generation, frame_interval_hint) = {
 			let cur_panels = shared.cur_panels.lock().await;
-			let cursor_pos = shared.cursor_pos.load();
-
 			let panels_renderer_shader = shared.panels_renderer_shader.read().await;
-			panels_renderer
-				.render(
+			let panels_surface_size = shared.panels_surface_size(frame.surface_size());
+			let (shift, brightness) = shared
+				.burn_in_protection
+				.as_ref()
+				.map_or((Vector2::new(0, 0), 1.0), |burn_in_protection| {
+					(burn_in_protection.shift(), burn_in_protection.brightness())
+				});
+			let tint = shared
+				.night_light
+				.as_ref()
+				.map_or(Vector3::new(1.0, 1.0, 1.0), |night_light| {
+					night_light.tint(chrono::Utc::now())
+				});
+
+			// Note: If the shader failed, we don't retry it until it's changed, to avoid spamming errors.
+			let shader = (panels_renderer_shader.shader, panels_renderer_shader.generation);
+			let draws = match (shared.blank.color(), failed_shader == Some(shader)) {
+				(Some(color), _) => {
+					PanelsRenderer::render_blank(&mut frame, color);
+					0
+				},
+				(None, true) => 0,
+				(None, false) => match panels_renderer.render(
 					&mut frame,
 					&wgpu_renderer,
 					&shared.wgpu,
 					&shared.panels_renderer_layout,
-					Point2::new(cursor_pos.x as i32, cursor_pos.y as i32),
-					&*cur_panels,
+					panels_surface_size,
+					shared.panels_cursor_pos(),
+					shift,
+					tint * brightness,
+					&cur_panels,
+					&shared.monitors,
 					&panels_renderer_shader,
-				)
-				.context("Unable to render panels")?;
+				) {
+					Ok(draws) => {
+						failed_shader = None;
+						draws
+					},
+					Err(err @ RenderError::Shader { .. }) => {
+						tracing::warn!(?err, shader = ?panels_renderer_shader.shader, "Unable to render panels, skipping them until the shader is changed");
+						failed_shader = Some(shader);
+						0
+					},
+				},
+			};
+
+			// Update the thumbnails while we have the panels
+			ui_renderer.update_thumbnails(&shared, &cur_panels)?;
+
+			let frame_interval_hint = PanelsRenderer::frame_interval_hint(&cur_panels);
+
+			(
+				cur_panels.len(),
+				draws,
+				panels_renderer_shader.generation,
+				frame_interval_hint,
+			)
+		};
+		if let Some(gpu_timer) = &gpu_timer {
+			gpu_timer.write_timestamp(&mut frame, GpuTimestamp::Panels);
 		}
 
-		// Render egui
-		egui_renderer
-			.render_egui(
-				&mut frame,
-				shared.window,
-				&shared.wgpu,
-				&egui_paint_jobs,
-				egui_textures_delta.take(),
-			)
-			.context("Unable to render egui")?;
+		// Render the ui
+		ui_renderer.render(&mut frame, &shared)?;
+		if let Some(gpu_timer) = &mut gpu_timer {
+			gpu_timer.write_timestamp(&mut frame, GpuTimestamp::Egui);
+			gpu_timer.resolve(&mut frame);
+		}
+
+		// Export the frame, if we're exporting
+		if let Some(frame_exporter) = &mut frame_exporter {
+			frame_exporter.record(&shared.wgpu, &mut frame);
+		}
+		if let (Some(timelapse_recorder), Some(timelapse)) = (&mut timelapse_recorder, &shared.timelapse) {
+			timelapse_recorder.record(timelapse, &shared.wgpu, &mut frame);
+		}
+		if let Some(shader_screenshotter) = &mut shader_screenshotter {
+			shader_screenshotter.record(&shared.wgpu, &mut frame, shader_generation);
+		}
 
 		// Finish the frame
 		frame.finish(&shared.wgpu);
+		if let Some(frame_exporter) = &mut frame_exporter {
+			frame_exporter.poll(&shared.wgpu);
+		}
+		if let (Some(timelapse_recorder), Some(timelapse)) = (&mut timelapse_recorder, &shared.timelapse) {
+			timelapse_recorder.poll(timelapse, &shared.wgpu);
+		}
+		if let Some(shader_screenshotter) = &mut shader_screenshotter {
+			shader_screenshotter.poll(&shared.wgpu);
+		}
+		if let Some(gpu_timer) = &mut gpu_timer {
+			gpu_timer.poll(&shared.wgpu);
+		}
+
+		// And record it
+		// Note: Only the startup duration is set directly, since it's only set once.
+		let frame_time = Instant::now();
+		if !rendered_first_frame {
+			let startup_duration = frame_time.duration_since(start_time);
+			tracing::info!(?startup_duration, "Rendered first frame");
+			shared
+				.frame_times
+				.lock()
+				.unwrap_or_else(PoisonError::into_inner)
+				.set_startup_duration(startup_duration);
+			rendered_first_frame = true;
+		}
+		let frame = FrameTime {
+			time: SystemTime::now(),
+			duration: frame_time.duration_since(last_frame_time),
+			panels,
+			draws,
+			gpu: gpu_timer.as_ref().and_then(GpuTimer::timings),
+		};
+		if frame_times_tx.send(frame).is_err() {
+			tracing::warn!("Frame times aggregator quit");
+		}
+		last_frame_time = frame_time;
 
 		// Resize if we need to
 		if let Some(resize) = shared.last_resize.swap(None) {
 			wgpu_renderer
 				.resize(&shared.wgpu, resize.size)
 				.context("Unable to resize wgpu")?;
-			panels_renderer.resize(&wgpu_renderer, &shared.wgpu, resize.size);
+			panels_renderer.resize(resize.size);
+		}
+
+		// Finally, if we're capping the frame rate, wait until the next frame
+		// Note: Otherwise, we're already paced by vsync to the monitors' refresh rate.
+		//       While the ui is drawing anything, we ignore the panels' hints, so it stays responsive.
+		let frame_interval_hint = frame_interval_hint.filter(|_| !ui_renderer.is_drawing());
+		if let Some(min_frame_interval) = Option::max(min_frame_interval, frame_interval_hint) {
+			tokio::time::sleep_until((frame_time + min_frame_interval).into()).await;
 		}
 	}
 }
 
 /// Panel updater task
 #[expect(clippy::infinite_loop, reason = "We need this type signature for `spawn_task`")]
-async fn panels_updater(shared: Arc<Shared>, panels_updater_output_tx: meetup::Sender<()>) -> Result<!, AppError> {
-	loop {
-		{
-			let mut cur_panels = shared.cur_panels.lock().await;
+async fn panels_updater(
+	shared: Arc<Shared>,
+	panels_updater_output_tx: meetup::Sender<()>,
+	resume_behavior: ResumeBehavior,
+) -> Result<!, AppError> {
+	/// Maximum duration to advance panels by on each update
+	// Note: Otherwise, after a stall (e.g. the system sleeping), we'd skip through images
+	const MAX_UPDATE_DURATION: Duration = Duration::from_secs(1);
 
-			for panel in &mut *cur_panels {
-				panel
-					.update(&shared.wgpu, &shared.panels_renderer_layout, &shared.image_requester)
-					.await;
-			}
+	/// Minimum gap between updates considered a suspend
+	const MIN_SUSPEND_DURATION: Duration = Duration::from_secs(30);
+
+	let mut last_update = Instant::now();
+	let mut last_update_time = SystemTime::now();
+	loop {
+		// Note: We update once per rendered frame, which depends on the refresh rate,
+		//       so we advance panels by the time elapsed instead of by a single frame.
+		let now = Instant::now();
+		let elapsed = now.duration_since(last_update).min(MAX_UPDATE_DURATION);
+		let frames = elapsed.as_secs_f64() / PANEL_FRAME_DURATION.as_secs_f64();
+
+		// Note: On some platforms, `Instant` doesn't advance while suspended, so we also check the wall clock
+		let now_time = SystemTime::now();
+		let gap = Duration::max(
+			now.duration_since(last_update),
+			now_time.duration_since(last_update_time).unwrap_or_default(),
+		);
+		let resumed = gap >= MIN_SUSPEND_DURATION;
+		if resumed {
+			tracing::info!(?gap, ?resume_behavior, "Resumed after a suspend");
 		}
+		last_update = now;
+		last_update_time = now_time;
 
-		panels_updater_output_tx.send(()).await;
-	}
-}
+		// Note: While the monitors are asleep, we hold all panels, so nothing happens unseen
+		let frames = match shared.monitors_asleep.load() {
+			true => 0.0,
+			false => frames,
+		};
 
-/// Egui painter task
-async fn egui_painter(
-	shared: Arc<Shared>,
-	egui_painter: EguiPainter,
-	mut settings_menu: SettingsMenu,
-	egui_painter_output_tx: meetup::Sender<(Vec<egui::ClippedPrimitive>, egui::TexturesDelta)>,
-) -> Result<!, AppError> {
-	loop {
-		let full_output_fut = egui_painter.draw(shared.window, |ctx| {
-			// Draw the settings menu
-			tokio::task::block_in_place(|| settings_menu.draw(ctx, &shared));
-
-			// Pause any double-clicked panels
-			if !ctx.is_pointer_over_area() &&
-				ctx.input(|input| input.pointer.button_double_clicked(egui::PointerButton::Primary))
-			{
-				let cursor_pos = shared.cursor_pos.load();
-				let cursor_pos = Point2::new(cursor_pos.x as i32, cursor_pos.y as i32);
-				let mut cur_panels = shared.cur_panels.lock().block_on();
-				for panel in &mut *cur_panels {
-					for geometry in &panel.geometries {
-						if geometry.geometry.contains(cursor_pos) {
-							panel.state.paused ^= true;
-							break;
-						}
-					}
+		{
+			let mut cur_panels = shared.cur_panels.lock().await;
+
+			if resumed && resume_behavior == ResumeBehavior::Skip {
+				for panel in &*cur_panels {
+					shared.panel_commands.push(panel.id, PanelCommand::Skip);
 				}
 			}
 
-			// Skip any ctrl-clicked panels
-			// TODO: Deduplicate this with the above and settings menu.
-			if !ctx.is_pointer_over_area() &&
-				ctx.input(|input| input.pointer.button_clicked(egui::PointerButton::Primary) && input.modifiers.ctrl)
-			{
-				let cursor_pos = shared.cursor_pos.load();
-				let cursor_pos = Point2::new(cursor_pos.x as i32, cursor_pos.y as i32);
-				let mut cur_panels = shared.cur_panels.lock().block_on();
-				for panel in &mut *cur_panels {
-					if !panel
-						.geometries
-						.iter()
-						.any(|geometry| geometry.geometry.contains(cursor_pos))
-					{
-						continue;
-					}
+			// Run any queued commands first
+			for (panel_id, command) in shared.panel_commands.take() {
+				// Note: The panel might have been removed since the command was queued
+				let Some(panel) = cur_panels.iter_mut().find(|panel| panel.id == panel_id) else {
+					tracing::debug!(?panel_id, ?command, "Ignoring command for missing panel");
+					continue;
+				};
 
-					panel
-						.skip(&shared.wgpu, &shared.panels_renderer_layout, &shared.image_requester)
-						.block_on();
-				}
+				panel
+					.run_command(
+						command,
+						&shared.wgpu,
+						&shared.panels_renderer_layout,
+						&shared.rgb_converter,
+						&shared.image_requester,
+					)
+					.await;
 			}
 
-			// Scroll panels
-			// TODO: Deduplicate this with the above and settings menu.
-			if !ctx.is_pointer_over_area() && ctx.input(|input| input.smooth_scroll_delta.y != 0.0) {
-				let delta = ctx.input(|input| input.smooth_scroll_delta.y);
-				let cursor_pos = shared.cursor_pos.load();
-				let cursor_pos = Point2::new(cursor_pos.x as i32, cursor_pos.y as i32);
-				let mut cur_panels = shared.cur_panels.lock().block_on();
-				for panel in &mut *cur_panels {
-					if !panel
-						.geometries
-						.iter()
-						.any(|geometry| geometry.geometry.contains(cursor_pos))
-					{
-						continue;
-					}
+			// Note: We check the quiet hours every update, so panels freeze / unfreeze right on time
+			let now = chrono::Local::now().naive_local();
+			for panel in &mut *cur_panels {
+				panel.state.update_quiet(now);
+				let transition_started = panel
+					.update(
+						&shared.wgpu,
+						&shared.panels_renderer_layout,
+						&shared.rgb_converter,
+						&shared.image_requester,
+						frames,
+					)
+					.await;
 
-					// TODO: Make this "speed" configurable
-					let speed = (panel.state.duration as f32) / 1000.0;
-					let frames = (-delta * speed) as i64;
-					panel
-						.step(
-							&shared.wgpu,
-							&shared.panels_renderer_layout,
-							&shared.image_requester,
-							frames,
-						)
-						.block_on();
+				if transition_started && panel.state.transition_sound {
+					if let Some(transition_sound) = &shared.transition_sound {
+						transition_sound.play();
+					}
 				}
 			}
+		}
 
-			Ok::<_, !>(())
-		});
-		let full_output = full_output_fut.await?;
-		let paint_jobs = egui_painter
-			.tessellate_shapes(full_output.shapes, full_output.pixels_per_point)
-			.await;
-		let textures_delta = full_output.textures_delta;
-
-		egui_painter_output_tx.send((paint_jobs, textures_delta)).await;
+		panels_updater_output_tx.send(()).await;
 	}
 }
 
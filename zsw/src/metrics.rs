@@ -0,0 +1,291 @@
+//! Metrics
+
+// Imports
+#[cfg(feature = "menu")]
+use std::{fmt::Write, path::Path};
+use {
+	crate::{config::MemoryThresholdsConfig, gpu_timer::GpuTimings, panel::Panel, shared::Shared},
+	anyhow::Context,
+	std::{
+		collections::VecDeque,
+		fs,
+		sync::{Arc, PoisonError},
+		time::{Duration, SystemTime},
+	},
+	tokio::sync::mpsc,
+	zsw_error::AppError,
+};
+
+/// Frame times
+#[derive(Debug)]
+pub struct FrameTimes {
+	/// Frames
+	///
+	/// Last frame is the newest
+	frames: VecDeque<FrameTime>,
+
+	/// Maximum number of frames
+	max_frames: usize,
+
+	/// Duration from startup until the first frame, if rendered yet
+	startup_duration: Option<Duration>,
+}
+
+impl FrameTimes {
+	/// Creates new, empty, frame times
+	pub fn new(max_frames: usize) -> Self {
+		Self {
+			frames: VecDeque::with_capacity(max_frames),
+			max_frames,
+			startup_duration: None,
+		}
+	}
+
+	/// Returns the duration from startup until the first frame, if rendered yet
+	#[cfg(feature = "menu")]
+	pub fn startup_duration(&self) -> Option<Duration> {
+		self.startup_duration
+	}
+
+	/// Sets the duration from startup until the first frame
+	pub fn set_startup_duration(&mut self, startup_duration: Duration) {
+		self.startup_duration = Some(startup_duration);
+	}
+
+	/// Adds a frame, removing the oldest one if full
+	pub fn push(&mut self, frame: FrameTime) {
+		if self.frames.len() >= self.max_frames {
+			let _ = self.frames.pop_front();
+		}
+		self.frames.push_back(frame);
+	}
+
+	/// Returns an iterator over all frames.
+	///
+	/// They are ordered from oldest to newest
+	#[cfg(feature = "menu")]
+	pub fn frames(&self) -> impl DoubleEndedIterator<Item = &FrameTime> + ExactSizeIterator {
+		self.frames.iter()
+	}
+
+	/// Returns the average fps over the last `frames` frames
+	#[cfg(feature = "menu")]
+	pub fn fps(&self, frames: usize) -> f32 {
+		let (count, total) = self
+			.frames
+			.iter()
+			.rev()
+			.take(frames)
+			.fold((0, Duration::ZERO), |(count, total), frame| {
+				(count + 1, total + frame.duration)
+			});
+
+		match total.is_zero() {
+			true => 0.0,
+			false => count as f32 / total.as_secs_f32(),
+		}
+	}
+
+	/// Exports all frames to `path`.
+	///
+	/// Exports as json if `path` has a `json` extension, else as csv.
+	#[cfg(feature = "menu")]
+	pub fn export(&self, path: &Path) -> Result<(), AppError> {
+		match path.extension().is_some_and(|ext| ext == "json") {
+			true => zsw_util::serialize_json_to_file(path, &self.serialize()?).context("Unable to write json")?,
+			false => fs::write(path, self.to_csv()?).context("Unable to write csv")?,
+		}
+
+		Ok(())
+	}
+
+	/// Returns all frames as csv
+	#[cfg(feature = "menu")]
+	pub fn to_csv(&self) -> Result<String, AppError> {
+		let mut csv = String::from("timestamp_ms,duration_ms,panels,draws,gpu_panels_ms,gpu_egui_ms\n");
+		for frame in self.serialize()? {
+			// Note: Missing gpu timings are left empty
+			let gpu_ms = |ms: Option<f64>| ms.map(|ms| format!("{ms:.3}")).unwrap_or_default();
+			writeln!(
+				csv,
+				"{:.3},{:.3},{},{},{},{}",
+				frame.timestamp_ms,
+				frame.duration_ms,
+				frame.panels,
+				frame.draws,
+				gpu_ms(frame.gpu_panels_ms),
+				gpu_ms(frame.gpu_egui_ms)
+			)
+			.expect("Writing to a string can't fail");
+		}
+
+		Ok(csv)
+	}
+
+	/// Serializes all frames
+	#[cfg(feature = "menu")]
+	fn serialize(&self) -> Result<Vec<ser::FrameTime>, AppError> {
+		self.frames
+			.iter()
+			.map(|frame| {
+				let time = frame
+					.time
+					.duration_since(SystemTime::UNIX_EPOCH)
+					.context("Frame time was before the unix epoch")?;
+				Ok(ser::FrameTime {
+					timestamp_ms:  time.as_secs_f64() * 1000.0,
+					duration_ms:   frame.duration.as_secs_f64() * 1000.0,
+					panels:        frame.panels,
+					draws:         frame.draws,
+					gpu_panels_ms: frame.gpu.map(|gpu| gpu.panels.as_secs_f64() * 1000.0),
+					gpu_egui_ms:   frame.gpu.map(|gpu| gpu.egui.as_secs_f64() * 1000.0),
+				})
+			})
+			.collect()
+	}
+}
+
+/// Aggregates all frame times sent by the renderer into the shared frame times.
+///
+/// Exists so the renderer never has to wait on the frame times lock.
+pub async fn run_frame_times_aggregator(
+	shared: Arc<Shared>,
+	mut frame_times_rx: mpsc::UnboundedReceiver<FrameTime>,
+) -> Result<(), AppError> {
+	/// Maximum number of frames to receive at once
+	const MAX_BATCH_FRAMES: usize = 64;
+
+	// Note: We receive all pending frames at once, so we only lock once per batch
+	let mut frames = Vec::with_capacity(MAX_BATCH_FRAMES);
+	while frame_times_rx.recv_many(&mut frames, MAX_BATCH_FRAMES).await != 0 {
+		let mut frame_times = shared.frame_times.lock().unwrap_or_else(PoisonError::into_inner);
+		for &frame in &frames {
+			frame_times.push(frame);
+		}
+		frames.clear();
+	}
+
+	Ok(())
+}
+
+/// Memory metrics
+#[derive(Clone, Copy, Default, Debug)]
+pub struct MemoryMetrics {
+	/// Resident set size (in bytes), if available
+	pub rss: Option<u64>,
+
+	/// Alive tokio tasks
+	pub tasks: usize,
+
+	/// Loaded panel image textures
+	pub textures: usize,
+}
+
+impl MemoryMetrics {
+	/// Samples the current memory metrics
+	async fn sample(shared: &Shared) -> Self {
+		let rss = self::process_rss()
+			.inspect_err(|err| tracing::trace!(?err, "Unable to get process rss"))
+			.ok();
+		let tasks = tokio::runtime::Handle::current().metrics().num_alive_tasks();
+
+		let cur_panels = shared.cur_panels.lock().await;
+		let textures = cur_panels
+			.iter()
+			.flat_map(Panel::all_images)
+			.flat_map(|images| [images.prev(), images.cur(), images.next()])
+			.filter(|image| image.is_loaded())
+			.count();
+
+		Self { rss, tasks, textures }
+	}
+}
+
+/// Samples the memory metrics periodically, warning when any exceed `thresholds`
+#[expect(clippy::infinite_loop, reason = "We need this type signature for `spawn_task`")]
+pub async fn run_memory_sampler(shared: Arc<Shared>, thresholds: MemoryThresholdsConfig) -> Result<!, AppError> {
+	/// Interval between samples
+	const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+	// Note: We only warn once each time a threshold is crossed, so we don't spam the log
+	let mut exceeded = [false; 3];
+	loop {
+		let metrics = MemoryMetrics::sample(&shared).await;
+		shared.memory_metrics.store(metrics);
+
+		let checks = [
+			(
+				"rss (MiB)",
+				metrics.rss.map_or(0, |rss| rss / (1024 * 1024)),
+				thresholds.rss_mib,
+			),
+			("tasks", metrics.tasks as u64, thresholds.tasks),
+			("textures", metrics.textures as u64, thresholds.textures),
+		];
+		for ((name, value, max), exceeded) in checks.into_iter().zip(&mut exceeded) {
+			let Some(max) = max else { continue };
+			match (value > max, *exceeded) {
+				(true, false) => tracing::warn!(?metrics, "Memory metric {name} exceeded threshold: {value} > {max}"),
+				(false, true) => tracing::info!(?metrics, "Memory metric {name} is back within threshold"),
+				_ => (),
+			}
+			*exceeded = value > max;
+		}
+
+		tokio::time::sleep(SAMPLE_INTERVAL).await;
+	}
+}
+
+/// Returns the resident set size (in bytes) of this process
+fn process_rss() -> Result<u64, AppError> {
+	// Note: This is only available on linux
+	let status = fs::read_to_string("/proc/self/status").context("Unable to read process status")?;
+	let rss_kib = status
+		.lines()
+		.find_map(|line| line.strip_prefix("VmRSS:"))
+		.context("Process status had no rss")?
+		.trim()
+		.strip_suffix("kB")
+		.context("Process rss wasn't in kB")?
+		.trim()
+		.parse::<u64>()
+		.context("Unable to parse process rss")?;
+
+	Ok(rss_kib * 1024)
+}
+
+/// Serialized types
+#[cfg(feature = "menu")]
+mod ser {
+	/// Serialized frame time
+	#[derive(Debug)]
+	#[derive(serde::Serialize)]
+	pub struct FrameTime {
+		pub timestamp_ms:  f64,
+		pub duration_ms:   f64,
+		pub panels:        usize,
+		pub draws:         usize,
+		pub gpu_panels_ms: Option<f64>,
+		pub gpu_egui_ms:   Option<f64>,
+	}
+}
+
+/// Frame time
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only read by the settings menu
+pub struct FrameTime {
+	/// Time the frame finished
+	pub time: SystemTime,
+
+	/// Duration since the previous frame
+	pub duration: Duration,
+
+	/// Number of panels
+	pub panels: usize,
+
+	/// Number of draws
+	pub draws: usize,
+
+	/// Gpu timings, if available
+	pub gpu: Option<GpuTimings>,
+}
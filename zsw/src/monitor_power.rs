@@ -0,0 +1,103 @@
+//! Monitor power
+//!
+//! Holds all panels while the monitors are asleep, and restarts any
+//! transition that was ongoing once they wake up, so transitions
+//! are always seen from the beginning.
+
+// Imports
+#[cfg(target_os = "linux")]
+use x11rb::{
+	protocol::dpms::{ConnectionExt, DPMSMode},
+	rust_connection::RustConnection,
+};
+use {
+	crate::shared::Shared,
+	anyhow::Context,
+	std::{sync::Arc, time::Duration},
+	zsw_error::AppError,
+};
+
+/// Monitor power detector.
+///
+/// Uses the X11 DPMS extension.
+// TODO: Support wayland, where there's no protocol for this yet.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct PowerDetector {
+	/// Connection
+	conn: RustConnection,
+}
+
+#[cfg(target_os = "linux")]
+impl PowerDetector {
+	/// Creates a new power detector
+	fn new() -> Result<Self, AppError> {
+		let (conn, _) = x11rb::connect(None).context("Unable to connect to X11 server")?;
+
+		Ok(Self { conn })
+	}
+
+	/// Returns whether the monitors are asleep
+	fn is_asleep(&self) -> Result<bool, AppError> {
+		let info = self
+			.conn
+			.dpms_info()
+			.context("Unable to query DPMS info")?
+			.reply()
+			.context("Unable to get DPMS info reply")?;
+
+		// Note: If DPMS is disabled, the power level is meaningless
+		Ok(info.state && info.power_level != DPMSMode::ON)
+	}
+}
+
+/// Monitor power detector.
+///
+/// Unsupported outside of X11.
+// TODO: Support windows' `GUID_CONSOLE_DISPLAY_STATE` notifications.
+#[cfg(not(target_os = "linux"))]
+#[derive(Debug)]
+enum PowerDetector {}
+
+#[cfg(not(target_os = "linux"))]
+impl PowerDetector {
+	/// Creates a new power detector
+	fn new() -> Result<Self, AppError> {
+		Err(anyhow::anyhow!("Monitor power detection is only supported on X11").into())
+	}
+
+	/// Returns whether the monitors are asleep
+	fn is_asleep(&self) -> Result<bool, AppError> {
+		match *self {}
+	}
+}
+
+/// Runs the monitor power watcher
+pub async fn run(shared: Arc<Shared>) -> Result<!, AppError> {
+	/// Interval between power checks
+	const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+	let power_detector = Arc::new(PowerDetector::new().context("Unable to create monitor power detector")?);
+	loop {
+		tokio::time::sleep(POLL_INTERVAL).await;
+
+		let asleep = tokio::task::spawn_blocking({
+			let power_detector = Arc::clone(&power_detector);
+			move || power_detector.is_asleep()
+		})
+		.await
+		.context("Unable to join monitor power detector task")??;
+
+		match (asleep, shared.monitors_asleep.swap(asleep)) {
+			(true, false) => tracing::info!("Monitors went to sleep, holding panels"),
+			(false, true) => {
+				tracing::info!("Monitors woke up, restarting transitions");
+				let mut cur_panels = shared.cur_panels.lock().await;
+				for panel in &mut *cur_panels {
+					panel.state.fade.restart_fade();
+				}
+			},
+			_ => (),
+		}
+	}
+}
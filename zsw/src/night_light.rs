@@ -0,0 +1,229 @@
+//! Night light
+//!
+//! Shifts the color temperature of all panels towards warmer colors at
+//! night, transitioning around the sunrise and sunset at the configured
+//! location.
+
+// Imports
+use {
+	crate::config::NightLightConfig,
+	cgmath::Vector3,
+	chrono::{DateTime, NaiveDate, TimeDelta, Utc},
+	std::f64::consts::PI,
+};
+
+/// Night light
+#[derive(Debug)]
+pub struct NightLight {
+	/// Config
+	config: NightLightConfig,
+}
+
+impl NightLight {
+	/// Creates a new night light
+	pub fn new(config: NightLightConfig) -> Self {
+		Self { config }
+	}
+
+	/// Returns the color temperature (in kelvin) at `now`
+	pub fn temperature(&self, now: DateTime<Utc>) -> f32 {
+		let day_amount = self.day_amount(now);
+		let day_temperature = self.config.day_temperature();
+		let night_temperature = self.config.night_temperature();
+		night_temperature + (day_temperature - night_temperature) * day_amount
+	}
+
+	/// Returns the color tint at `now`
+	pub fn tint(&self, now: DateTime<Utc>) -> Vector3<f32> {
+		self::temperature_color(self.temperature(now))
+	}
+
+	/// Returns how much it's day at `now`, `0.0 .. 1.0`
+	fn day_amount(&self, now: DateTime<Utc>) -> f32 {
+		// Note: We use the solar date at our longitude, so that the sunrise and sunset
+		//       we calculate are always the ones around `now`.
+		let solar_offset = TimeDelta::seconds((self.config.longitude / 360.0 * 86400.0) as i64);
+		let date = (now + solar_offset).date_naive();
+		let (sunrise, sunset) = match self::sun_times(date, self.config.latitude, self.config.longitude) {
+			SunTimes::Day => return 1.0,
+			SunTimes::Night => return 0.0,
+			SunTimes::Times { sunrise, sunset } => (sunrise, sunset),
+		};
+
+		let transition = self.config.transition().as_secs_f32();
+		let ramp = |time: DateTime<Utc>| match transition > 0.0 {
+			true => ((now - time).as_seconds_f32() / transition + 0.5).clamp(0.0, 1.0),
+			false => f32::from(u8::from(now >= time)),
+		};
+
+		ramp(sunrise).min(1.0 - ramp(sunset))
+	}
+}
+
+/// Sun times of a day
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum SunTimes {
+	/// The sun never sets
+	Day,
+
+	/// The sun never rises
+	Night,
+
+	/// The sun rises and sets
+	Times {
+		sunrise: DateTime<Utc>,
+		sunset:  DateTime<Utc>,
+	},
+}
+
+/// Calculates the sunrise and sunset on `date`, at `latitude` and `longitude` (in degrees).
+///
+/// See <https://en.wikipedia.org/wiki/Sunrise_equation>
+fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> SunTimes {
+	/// Julian date of the unix epoch
+	const UNIX_EPOCH_JULIAN_DATE: f64 = 2_440_587.5;
+
+	/// Julian date of the J2000 epoch
+	const J2000_JULIAN_DATE: f64 = 2_451_545.0;
+
+	let sin = |degrees: f64| degrees.to_radians().sin();
+	let cos = |degrees: f64| degrees.to_radians().cos();
+
+	// Current julian day, since the J2000 epoch
+	let days = date.signed_duration_since(DateTime::UNIX_EPOCH.date_naive()).num_days() as f64;
+	let day = (days + UNIX_EPOCH_JULIAN_DATE - J2000_JULIAN_DATE + 0.0008).ceil();
+
+	// Mean solar time, solar mean anomaly, equation of the center and ecliptic longitude
+	let mean_solar_time = day - longitude / 360.0;
+	let mean_anomaly = (357.5291 + 0.985_600_28 * mean_solar_time).rem_euclid(360.0);
+	let center = 1.9148 * sin(mean_anomaly) + 0.02 * sin(2.0 * mean_anomaly) + 0.0003 * sin(3.0 * mean_anomaly);
+	let ecliptic_longitude = (mean_anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+
+	// Solar transit
+	let transit =
+		J2000_JULIAN_DATE + mean_solar_time + 0.0053 * sin(mean_anomaly) - 0.0069 * sin(2.0 * ecliptic_longitude);
+
+	// Declination of the sun and hour angle
+	let declination_sin = sin(ecliptic_longitude) * sin(23.4397);
+	let declination_cos = declination_sin.asin().cos();
+	let hour_angle_cos = (sin(-0.833) - sin(latitude) * declination_sin) / (cos(latitude) * declination_cos);
+	if hour_angle_cos < -1.0 {
+		return SunTimes::Day;
+	}
+	if hour_angle_cos > 1.0 || hour_angle_cos.is_nan() {
+		return SunTimes::Night;
+	}
+	let hour_angle = hour_angle_cos.acos() / (2.0 * PI);
+
+	let to_time = |julian_date: f64| {
+		let secs = (julian_date - UNIX_EPOCH_JULIAN_DATE) * 86400.0;
+		DateTime::from_timestamp(secs as i64, 0).unwrap_or_default()
+	};
+	SunTimes::Times {
+		sunrise: to_time(transit - hour_angle),
+		sunset:  to_time(transit + hour_angle),
+	}
+}
+
+/// Returns the color of a color temperature (in kelvin), as a tint.
+///
+/// Temperatures around 6600K are white, with lower temperatures being warmer.
+///
+/// See <https://tannerhelland.com/2012/09/18/convert-temperature-rgb-algorithm-code.html>
+fn temperature_color(temperature: f32) -> Vector3<f32> {
+	let temp = temperature / 100.0;
+	let red = match temp <= 66.0 {
+		true => 255.0,
+		false => 329.698_73 * (temp - 60.0).powf(-0.133_204_76),
+	};
+	let green = match temp <= 66.0 {
+		true => 99.470_8 * temp.ln() - 161.119_57,
+		false => 288.122_16 * (temp - 60.0).powf(-0.075_514_85),
+	};
+	let blue = match temp {
+		_ if temp >= 66.0 => 255.0,
+		_ if temp <= 19.0 => 0.0,
+		_ => 138.517_73 * (temp - 10.0).ln() - 305.044_8,
+	};
+
+	Vector3::new(red, green, blue).map(|value| (value / 255.0).clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod test {
+	use {super::*, chrono::NaiveTime};
+
+	#[test]
+	fn sun_times_equator() {
+		// Note: On the equinox, at the equator, the sun rises and sets at about 6:00 and 18:00
+		let date = NaiveDate::from_ymd_opt(2024, 3, 20).expect("Invalid date");
+		let SunTimes::Times { sunrise, sunset } = self::sun_times(date, 0.0, 0.0) else {
+			panic!("Sun should rise and set at the equator");
+		};
+
+		let around = |time: DateTime<Utc>, hour: u32| {
+			let expected = date.and_time(NaiveTime::from_hms_opt(hour, 0, 0).expect("Invalid time"));
+			(time.naive_utc() - expected).num_minutes().abs() < 15
+		};
+		assert!(around(sunrise, 6), "Wrong sunrise: {sunrise}");
+		assert!(around(sunset, 18), "Wrong sunset: {sunset}");
+	}
+
+	#[test]
+	fn sun_times_polar() {
+		let summer = NaiveDate::from_ymd_opt(2024, 6, 21).expect("Invalid date");
+		assert_eq!(
+			self::sun_times(summer, 80.0, 0.0),
+			SunTimes::Day,
+			"Sun set during polar day"
+		);
+		assert_eq!(
+			self::sun_times(summer, -80.0, 0.0),
+			SunTimes::Night,
+			"Sun rose during polar night"
+		);
+	}
+
+	#[test]
+	fn temperature() {
+		let night_light = NightLight::new(NightLightConfig {
+			latitude:          0.0,
+			longitude:         0.0,
+			day_temperature:   Some(6500.0),
+			night_temperature: Some(3000.0),
+			transition_mins:   Some(60.0),
+		});
+
+		let time = |hour: u32| {
+			NaiveDate::from_ymd_opt(2024, 3, 20)
+				.and_then(|date| date.and_hms_opt(hour, 0, 0))
+				.expect("Invalid time")
+				.and_utc()
+		};
+		let night = night_light.temperature(time(0));
+		assert!((night - 3000.0).abs() < 1.0, "Wrong temperature at night: {night}");
+		let day = night_light.temperature(time(12));
+		assert!((day - 6500.0).abs() < 1.0, "Wrong temperature at day: {day}");
+
+		let evening = night_light.temperature(time(18));
+		assert!(
+			3000.0 < evening && evening < 6500.0,
+			"Not transitioning at sunset: {evening}"
+		);
+	}
+
+	#[test]
+	fn temperature_colors() {
+		assert_eq!(
+			self::temperature_color(6600.0),
+			Vector3::new(1.0, 1.0, 1.0),
+			"6600K should be white"
+		);
+
+		let warm = self::temperature_color(3000.0);
+		assert!(
+			warm.x > warm.y && warm.y > warm.z,
+			"Warm temperatures should be reddish: {warm:?}"
+		);
+	}
+}
@@ -1,33 +1,64 @@
 //! Panel
 
 // Modules
+mod commands;
+mod fade;
 mod geometry;
 mod image;
+mod quotes;
 mod renderer;
-mod ser;
+pub mod ser;
 mod state;
+mod text;
+#[cfg(feature = "menu")]
+mod thumbnails;
+mod ticker;
 
 // Exports
+#[cfg(any(feature = "menu", test))]
+pub use self::ticker::redact_feed;
 pub use self::{
+	commands::{PanelCommand, PanelCommands},
+	fade::{PanelFade, PanelFadeCommand, PanelFadeImages, PanelImageSlot},
 	geometry::PanelGeometry,
-	image::{PanelImage, PanelImages},
-	renderer::{PanelShader, PanelsRenderer, PanelsRendererLayouts, PanelsRendererShader},
-	state::{PanelParallaxState, PanelState},
+	image::{PanelImage, PanelImages, PanelSamplers, RgbConverter},
+	quotes::PanelQuotes,
+	renderer::{PanelShader, PanelsRenderer, PanelsRendererLayouts, PanelsRendererShader, RenderError},
+	state::{PanelAlignment, PanelParallaxState, PanelQuietHours, PanelSampler, PanelScaleMode, PanelState, PanelTile},
+	ticker::PanelTicker,
+};
+#[cfg(feature = "menu")]
+pub use self::{
+	state::{PanelSamplerAddressMode, PanelSamplerFilter},
+	thumbnails::PanelThumbnails,
 };
 
 // Imports
 use {
 	crate::{
-		image_loader::ImageRequester,
-		playlist::{PlaylistItemKind, PlaylistName, PlaylistPlayer},
+		image_loader::{self, Image, ImageHistogram, ImageRequester},
+		playlist::{self, Playlist, PlaylistItem, PlaylistItemKind, PlaylistName, PlaylistPlayer, PlaylistWatcher},
+		sandbox::SandboxPathKind,
 		shared::Shared,
 		AppError,
 	},
 	anyhow::Context,
+	cgmath::{Point2, Vector2},
 	futures::{stream::FuturesUnordered, StreamExt},
 	std::{
+		collections::HashMap,
+		iter,
+		mem,
 		path::{Path, PathBuf},
-		sync::Arc,
+		slice,
+		sync::{
+			atomic::{self, AtomicU64},
+			Arc,
+			Mutex,
+			PoisonError,
+			Weak,
+		},
+		time::Duration,
 	},
 	tokio::{fs, sync::RwLock},
 	zsw_util::{Rect, UnwrapOrReturnExt, WalkDir},
@@ -36,12 +67,39 @@ use {
 
 /// Panels manager
 #[derive(Debug)]
-pub struct PanelsManager {}
+pub struct PanelsManager {
+	/// Playlist players shared between panels, by playlist and kind of sharing
+	shared_players: Mutex<HashMap<SharedPlayerKey, Weak<RwLock<PlaylistPlayer>>>>,
+}
 
 impl PanelsManager {
 	/// Creates a new panels manager
 	pub fn new() -> Self {
-		Self {}
+		Self {
+			shared_players: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Returns the playlist player shared by all panels of `playlist_name` with the same kind of `sharing`.
+	///
+	/// Also returns whether it was just created, and so still needs to be loaded.
+	fn shared_player(
+		&self,
+		playlist_name: &PlaylistName,
+		sharing: PanelPlayerSharing,
+	) -> (Arc<RwLock<PlaylistPlayer>>, bool) {
+		let key = (playlist_name.clone(), mem::discriminant(&sharing));
+		let mut shared_players = self.shared_players.lock().unwrap_or_else(PoisonError::into_inner);
+		if let Some(playlist_player) = shared_players.get(&key).and_then(Weak::upgrade) {
+			return (playlist_player, false);
+		}
+
+		let mut playlist_player = PlaylistPlayer::new();
+		playlist_player.set_coordinated(sharing == PanelPlayerSharing::Coordinated);
+		let playlist_player = Arc::new(RwLock::new(playlist_player));
+		let _ = shared_players.insert(key, Arc::downgrade(&playlist_player));
+
+		(playlist_player, true)
 	}
 
 	/// Loads a panel from a path
@@ -53,62 +111,351 @@ impl PanelsManager {
 		// Then parse it
 		let panel = toml::from_str::<ser::Panel>(&panel_toml).context("Unable to parse panel")?;
 
-		// Finally convert it
+		// Finally create it
+		Self::create(panel, shared)
+	}
+
+	/// Creates a panel showing a single image, or rotating through a directory.
+	///
+	/// The panel covers the whole window, and uses a transient playlist.
+	pub async fn create_show(&self, path: &Path, shared: &Arc<Shared>) -> Result<Panel, AppError> {
+		let path = tokio::fs::canonicalize(path)
+			.await
+			.context("Unable to canonicalize path")?;
+		let is_dir = tokio::fs::metadata(&path)
+			.await
+			.context("Unable to get path metadata")?
+			.is_dir();
+		let kind = match is_dir {
+			true => PlaylistItemKind::Directory {
+				path:       path.clone().into(),
+				recursive:  true,
+				extensions: None,
+			},
+			false => PlaylistItemKind::File {
+				path: path.clone().into(),
+			},
+		};
+
+		let playlist_name = PlaylistName::show(&path);
+		let _ = shared.playlists.write().await.add_transient(
+			playlist_name.clone(),
+			Playlist::new(vec![PlaylistItem {
+				enabled: true,
+				kind,
+				ser_path: None,
+			}]),
+		);
+
+		let window_size = shared.window.inner_size();
+		let panel = ser::Panel {
+			geometries:     vec![ser::PanelGeometry {
+				geometry: Rect {
+					pos:  Point2::new(0, 0),
+					size: Vector2::new(window_size.width, window_size.height),
+				},
+			}],
+			state:          ser::PanelState {
+				duration_secs:    Some(60.0),
+				fade_point_secs:  Some(50.0),
+				duration:         None,
+				fade_point:       None,
+				parallax_ratio:   ser::default_panel_parallax_ratio(),
+				parallax_exp:     ser::default_panel_parallax_exp(),
+				reverse_parallax: ser::default_panel_parallax_reverse(),
+				scale_mode:       PanelScaleMode::default(),
+				alignment:        PanelAlignment::default(),
+				sampler:          PanelSampler::default(),
+				tile:             PanelTile::default(),
+				transition_sound: ser::default_panel_transition_sound(),
+				transition_blur:  0.0,
+				distinct_images:  false,
+				monitor:          None,
+				quiet_hours:      vec![],
+				fps:              None,
+			},
+			playlist:       playlist_name.to_string(),
+			kind:           ser::PanelKind::Images,
+			quotes:         None,
+			ticker:         None,
+			coordinate:     false,
+			cascade_offset: None,
+		};
+
+		Self::create(panel, shared)
+	}
+
+	/// Duplicates a panel.
+	///
+	/// The duplicate has the same configuration and playlist, with it's geometries slightly offset.
+	/// Panels showing a path can't be duplicated, since their playlist is transient.
+	#[cfg(feature = "menu")]
+	pub fn duplicate(panel: &Panel, shared: &Arc<Shared>) -> Result<Panel, AppError> {
+		/// Offset of the duplicate's geometries
+		const OFFSET: i32 = 32;
+
+		if panel.base_playlist().0.is_show() {
+			return Err(anyhow::anyhow!("Panels showing a path can't be duplicated").into());
+		}
+
+		let mut panel = Self::serialize(panel);
+		for geometry in &mut panel.geometries {
+			geometry.geometry = geometry.geometry.translated(Vector2::new(OFFSET, OFFSET));
+		}
+
+		Self::create(panel, shared)
+	}
+
+	/// Serializes a panel, with it's current configuration
+	#[cfg(feature = "menu")]
+	pub fn serialize(panel: &Panel) -> ser::Panel {
+		// Note: Overrides are temporary, so we save the playlist they'll restore
+		let (playlist_name, player_sharing) = panel.base_playlist();
+		ser::Panel {
+			geometries:     panel
+				.geometries
+				.iter()
+				.map(|geometry| ser::PanelGeometry {
+					geometry: geometry.geometry,
+				})
+				.collect(),
+			state:          ser::PanelState {
+				duration_secs:    Some(ser::frames_to_secs(panel.state.fade.duration)),
+				fade_point_secs:  Some(ser::frames_to_secs(panel.state.fade.fade_point)),
+				duration:         None,
+				fade_point:       None,
+				parallax_ratio:   panel.state.parallax.ratio,
+				parallax_exp:     panel.state.parallax.exp,
+				reverse_parallax: panel.state.parallax.reverse,
+				scale_mode:       panel.state.scale_mode,
+				alignment:        panel.state.alignment,
+				sampler:          panel.state.sampler,
+				tile:             panel.state.tile,
+				transition_sound: panel.state.transition_sound,
+				transition_blur:  panel.state.transition_blur,
+				distinct_images:  panel.state.distinct_images,
+				monitor:          panel.state.monitor.clone(),
+				quiet_hours:      panel.state.quiet_hours.clone(),
+				fps:              panel.state.fps,
+			},
+			playlist:       playlist_name.to_string(),
+			kind:           match panel.kind {
+				PanelKind::Images => ser::PanelKind::Images,
+				PanelKind::Calibration { .. } => ser::PanelKind::Calibration,
+				PanelKind::Quotes => ser::PanelKind::Quotes,
+				PanelKind::Ticker => ser::PanelKind::Ticker,
+			},
+			quotes:         match &panel.text_source {
+				Some(PanelTextSource::Quotes(quotes)) => Some(quotes.config()),
+				_ => None,
+			},
+			ticker:         match &panel.text_source {
+				Some(PanelTextSource::Ticker(ticker)) => Some(ticker.config()),
+				_ => None,
+			},
+			coordinate:     player_sharing == Some(PanelPlayerSharing::Coordinated),
+			cascade_offset: match player_sharing {
+				Some(PanelPlayerSharing::Cascade { offset }) => Some(offset),
+				_ => None,
+			},
+		}
+	}
+
+	/// Creates a panel from it's serialized form
+	pub fn create(panel: ser::Panel, shared: &Arc<Shared>) -> Result<Panel, AppError> {
+		panel.validate().context("Invalid panel")?;
+
 		let geometries = panel.geometries.into_iter().map(|geometry| geometry.geometry).collect();
 		let state = PanelState {
-			paused:     false,
-			progress:   0,
-			duration:   panel.state.duration,
-			fade_point: panel.state.fade_point,
-			parallax:   PanelParallaxState {
+			paused:           false,
+			muted:            false,
+			soloed:           false,
+			fade:             PanelFade::new(panel.state.duration_frames()?, panel.state.fade_point_frames()?),
+			parallax:         PanelParallaxState {
 				ratio:   panel.state.parallax_ratio,
 				exp:     panel.state.parallax_exp,
 				reverse: panel.state.reverse_parallax,
 			},
+			scale_mode:       panel.state.scale_mode,
+			alignment:        panel.state.alignment,
+			sampler:          panel.state.sampler,
+			tile:             panel.state.tile,
+			transition_sound: panel.state.transition_sound,
+			transition_blur:  panel.state.transition_blur,
+			distinct_images:  panel.state.distinct_images,
+			monitor:          panel.state.monitor,
+			quiet_hours:      panel.state.quiet_hours,
+			quiet:            false,
+			fps:              panel.state.fps,
+		};
+		let kind = match panel.kind {
+			ser::PanelKind::Images => PanelKind::Images,
+			ser::PanelKind::Calibration => PanelKind::Calibration { pattern: 0 },
+			ser::PanelKind::Quotes => PanelKind::Quotes,
+			ser::PanelKind::Ticker => PanelKind::Ticker,
+		};
+		let text_source = match kind {
+			PanelKind::Images | PanelKind::Calibration { .. } => None,
+			PanelKind::Quotes => {
+				let quotes = panel.quotes.context("Quotes panel had no quotes")?;
+				let quotes = PanelQuotes::load(quotes).context("Unable to load panel quotes")?;
+				Some(PanelTextSource::Quotes(quotes))
+			},
+			PanelKind::Ticker => {
+				let ticker = panel.ticker.context("Ticker panel had no ticker")?;
+				let ticker = PanelTicker::new(ticker).context("Unable to create panel ticker")?;
+
+				crate::spawn_task("Ticker feeds fetcher", {
+					let feeds = ticker.feeds().to_vec();
+					let interval = ticker.interval();
+					let headlines = Arc::downgrade(ticker.headlines());
+					move || ticker::run_fetcher(feeds, interval, headlines)
+				});
+
+				Some(PanelTextSource::Ticker(ticker))
+			},
 		};
 		let playlist_name = PlaylistName::from(panel.playlist);
+		let player_sharing = match (panel.coordinate, panel.cascade_offset) {
+			(false, None) => None,
+			(true, None) => Some(PanelPlayerSharing::Coordinated),
+			(false, Some(offset)) => Some(PanelPlayerSharing::Cascade { offset }),
+			(true, Some(_)) => return Err(anyhow::anyhow!("Panel can't both coordinate and cascade").into()),
+		};
 
-		let panel = Panel::new(&shared.wgpu, &shared.panels_renderer_layout, geometries, state)
-			.context("Unable to create panel")?;
+		let mut panel = Panel::new(
+			&shared.wgpu,
+			&shared.panels_renderer_layout,
+			geometries,
+			state,
+			kind,
+			text_source,
+			playlist_name.clone(),
+		)
+		.context("Unable to create panel")?;
 
-		crate::spawn_task(format!("Load panel playlist {path:?}: {playlist_name:?}"), {
-			let playlist_player = Arc::clone(&panel.playlist_player);
-			let shared = Arc::clone(shared);
-			|| async move {
-				Self::load_playlist_into(&playlist_player, &playlist_name, &shared)
-					.await
-					.context("Unable to load playlist")?;
+		// Note: Only image panels display playlist images, so the others don't need the playlist
+		if kind != PanelKind::Images {
+			return Ok(panel);
+		}
 
-				Ok(())
-			}
-		});
+		// Panels sharing a player with other panels of the same playlist only need to load it once.
+		// Note: Cascading panels each view the player through their own cursor
+		let is_new = match player_sharing {
+			Some(player_sharing) => {
+				let (playlist_player, is_new) = shared.panels_manager.shared_player(&playlist_name, player_sharing);
+				if let PanelPlayerSharing::Cascade { offset } = player_sharing {
+					panel.images.set_cascade_offset(Some(offset));
+				}
+				panel.playlist_player = playlist_player;
+				panel.player_sharing = Some(player_sharing);
+				is_new
+			},
+			None => true,
+		};
+
+		if is_new {
+			crate::spawn_task(format!("Load panel playlist {playlist_name:?}"), {
+				let playlist_player = Arc::clone(&panel.playlist_player);
+				let shared = Arc::clone(shared);
+				|| async move {
+					Self::load_playlist_into(&playlist_player, &playlist_name, &shared)
+						.await
+						.context("Unable to load playlist")?;
+
+					Ok(())
+				}
+			});
+		}
+
+		if let Some(privacy) = &shared.privacy {
+			privacy.apply(&mut panel, shared);
+		}
 
 		Ok(panel)
 	}
 
+	/// Reloads all playlists, and then re-fills the playlist players of all panels.
+	///
+	/// The panels themselves, and their current images, are kept, so any playlist
+	/// edits apply without interrupting them.
+	#[cfg(feature = "menu")]
+	pub async fn reload_playlists(shared: &Arc<Shared>) -> Result<(), AppError> {
+		shared
+			.playlists
+			.write()
+			.await
+			.reload_all()
+			.await
+			.context("Unable to reload playlists")?;
+
+		// Note: Players may be shared by several panels, so we only reload each once.
+		//       We also don't keep the panels locked while loading them.
+		// Note: Players saved by overrides (such as privacy mode) are reloaded with their own
+		//       playlist, so they're up to date once restored, and the override's aren't mixed with them.
+		let playlist_players = {
+			let cur_panels = shared.cur_panels.lock().await;
+			let mut playlist_players = Vec::<(Arc<RwLock<PlaylistPlayer>>, PlaylistName)>::new();
+			for (panel_playlist_player, panel_playlist_name) in cur_panels.iter().flat_map(Panel::playlist_players) {
+				if !playlist_players
+					.iter()
+					.any(|(playlist_player, _)| Arc::ptr_eq(playlist_player, panel_playlist_player))
+				{
+					playlist_players.push((Arc::clone(panel_playlist_player), panel_playlist_name.clone()));
+				}
+			}
+			playlist_players
+		};
+
+		playlist_players
+			.iter()
+			.map(|(playlist_player, playlist_name)| async move {
+				playlist_player.write().await.remove_all();
+				if let Err(err) = Self::load_playlist_into(playlist_player, playlist_name, shared).await {
+					tracing::warn!(?playlist_name, ?err, "Unable to reload playlist");
+				}
+			})
+			.collect::<FuturesUnordered<_>>()
+			.collect::<()>()
+			.await;
+
+		Ok(())
+	}
+
 	/// Loads `playlist` into `playlist_player`.
 	// TODO: Not make `pub`?
 	pub async fn load_playlist_into(
-		playlist_player: &RwLock<PlaylistPlayer>,
+		playlist_player: &Arc<RwLock<PlaylistPlayer>>,
 		playlist_name: &PlaylistName,
 		shared: &Shared,
 	) -> Result<(), AppError> {
-		/// Attempts to canonicalize `path`. If unable to, logs a warning and returns `None`
-		async fn try_canonicalize_path(path: &Path) -> Option<PathBuf> {
-			tokio::fs::canonicalize(path)
-				.await
-				.inspect_err(|err| tracing::warn!(?path, ?err, "Unable to canonicalize path"))
-				.ok()
-		}
-
-		let playlist_items = {
+		let (playlist_items, sequential) = {
 			let playlists = shared.playlists.read().await;
 			let playlist = playlists
 				.get(playlist_name)
 				.with_context(|| format!("Unknown playlist: {playlist_name:?}"))?;
 			let playlist = playlist.read().await;
-			playlist.items()
+			(playlist.items(), playlist.sequential())
 		};
+		playlist_player.write().await.set_sequential(sequential);
+
+		if let Some(rating_selection) = shared.rating_selection {
+			let mut playlist_player = playlist_player.write().await;
+			playlist_player.set_rating_selection(Arc::clone(&shared.ratings), rating_selection);
+		}
+
+		// Start watching the playlist before scanning, so we don't miss any changes
+		// Note: Any items added twice are ignored by the player
+		let playlist_watcher = PlaylistWatcher::new(&playlist_items)
+			.await
+			.context("Unable to create playlist watcher")?;
+		crate::spawn_task(format!("Watch playlist {playlist_name:?}"), {
+			let playlist_player = Arc::downgrade(playlist_player);
+			|| async move {
+				playlist_watcher.run(playlist_player).await;
+				Ok(())
+			}
+		});
 
 		playlist_items
 			.into_iter()
@@ -124,60 +471,61 @@ impl PanelsManager {
 					return;
 				}
 
+				// If we can't access the item, skip it until the user grants us access
+				let path_kind = match item.kind {
+					PlaylistItemKind::Directory { .. } => SandboxPathKind::Directory,
+					_ => SandboxPathKind::File,
+				};
+				if !shared.sandbox.check_access(item.kind.path(), path_kind).await {
+					tracing::debug!(?playlist_name, ?item, "Skipping inaccessible playlist item");
+					return;
+				}
+				let path = &shared.sandbox.resolve(item.kind.path());
+
+				// Register the item as a root, for offline detection
+				if let Some(root) = self::try_canonicalize_path(path).await {
+					let mut playlist_player = playlist_player.write().await;
+					playlist_player.add_root(root.into());
+				}
+
 				// Else check the kind of item
 				match item.kind {
 					PlaylistItemKind::Directory {
-						path: ref dir_path,
 						recursive,
+						ref extensions,
+						..
 					} =>
-						WalkDir::builder()
-							.max_depth(match recursive {
-								true => None,
-								false => Some(0),
-							})
-							.recurse_symlink(true)
-							.build(dir_path.to_path_buf())
-							.map(|entry: Result<fs::DirEntry, _>| async move {
-								let entry = entry
-									.map_err(|err| {
-										tracing::warn!(
-											?playlist_name,
-											?dir_path,
-											?err,
-											"Unable to read directory entry"
-										);
-									})
-									.unwrap_or_return()?;
-
-								let path = entry.path();
-								if fs::metadata(&path)
-									.await
-									.map_err(|err| {
-										tracing::warn!(?playlist_name, ?path, ?err, "Unable to get entry metadata");
-									})
-									.unwrap_or_return()?
-									.is_dir()
-								{
-									// If it's a directory, skip it
-									return;
-								}
-
-								let Some(path) = try_canonicalize_path(&path).await else {
-									return;
-								};
-
-								let mut playlist_player = playlist_player.write().await;
-								playlist_player.add(path.into());
-							})
-							.collect::<FuturesUnordered<_>>()
-							.await
-							.collect::<()>()
+						Self::load_dir_into(playlist_player, playlist_name, path, recursive, extensions.as_deref())
 							.await,
-					PlaylistItemKind::File { ref path } =>
-						if let Some(path) = try_canonicalize_path(path).await {
+					PlaylistItemKind::File { .. } =>
+						if let Some(path) = self::try_canonicalize_path(path).await {
 							let mut playlist_player = playlist_player.write().await;
 							playlist_player.add(path.into());
 						},
+					PlaylistItemKind::Bundle { .. } =>
+						if let Some(path) = self::try_canonicalize_path(path).await {
+							match shared.bundles.load(&path).await {
+								Ok(paths) => {
+									let mut playlist_player = playlist_player.write().await;
+									for path in paths {
+										playlist_player.add(path);
+									}
+								},
+								Err(err) => tracing::warn!(?playlist_name, ?path, ?err, "Unable to load bundle"),
+							}
+						},
+					PlaylistItemKind::Comic { .. } =>
+						if let Some(path) = self::try_canonicalize_path(path).await {
+							match shared.bundles.load_comic(&path).await {
+								Ok(paths) => {
+									let mut playlist_player = playlist_player.write().await;
+									for path in paths {
+										playlist_player.add(path);
+									}
+								},
+								Err(err) => tracing::warn!(?playlist_name, ?path, ?err, "Unable to load comic"),
+							}
+						},
 				}
 			})
 			.collect::<FuturesUnordered<_>>()
@@ -194,11 +542,85 @@ impl PanelsManager {
 
 		Ok(())
 	}
+
+	/// Loads all files in directory `dir_path` into `playlist_player`
+	async fn load_dir_into(
+		playlist_player: &RwLock<PlaylistPlayer>,
+		playlist_name: &PlaylistName,
+		dir_path: &Path,
+		recursive: bool,
+		extensions: Option<&[String]>,
+	) {
+		WalkDir::builder()
+			.max_depth(match recursive {
+				true => None,
+				false => Some(0),
+			})
+			.recurse_symlink(true)
+			.build(dir_path.to_path_buf())
+			.map(|entry: Result<fs::DirEntry, _>| async move {
+				let entry = entry
+					.map_err(|err| {
+						tracing::warn!(?playlist_name, ?dir_path, ?err, "Unable to read directory entry");
+					})
+					.unwrap_or_return()?;
+
+				let path = entry.path();
+				if fs::metadata(&path)
+					.await
+					.map_err(|err| {
+						tracing::warn!(?playlist_name, ?path, ?err, "Unable to get entry metadata");
+					})
+					.unwrap_or_return()?
+					.is_dir()
+				{
+					// If it's a directory, skip it
+					return;
+				}
+
+				// If it doesn't have one of the extensions, skip it
+				if !playlist::has_extension(&path, extensions) {
+					return;
+				}
+
+				let Some(path) = self::try_canonicalize_path(&path).await else {
+					return;
+				};
+
+				let mut playlist_player = playlist_player.write().await;
+				playlist_player.add(path.into());
+			})
+			.collect::<FuturesUnordered<_>>()
+			.await
+			.collect::<()>()
+			.await;
+	}
+}
+
+/// Panel id.
+///
+/// Unlike a panel's index, it never changes, even when panels are reordered or removed.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub struct PanelId(u64);
+
+impl PanelId {
+	/// Creates a new, unique, panel id
+	fn new() -> Self {
+		static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+		Self(NEXT_ID.fetch_add(1, atomic::Ordering::AcqRel))
+	}
 }
 
 /// Panel
 #[derive(Debug)]
+#[expect(
+	clippy::partial_pub_fields,
+	reason = "The saved playlists may only be changed by overriding and restoring the playlist"
+)]
 pub struct Panel {
+	/// Id
+	pub id: PanelId,
+
 	/// Geometries
 	pub geometries: Vec<PanelGeometry>,
 
@@ -210,6 +632,26 @@ pub struct Panel {
 
 	/// Images
 	pub images: PanelImages,
+
+	/// Images of each geometry past the first, if showing distinct images.
+	///
+	/// Otherwise, all geometries show `images`.
+	pub geometry_images: Vec<PanelImages>,
+
+	/// Kind
+	pub kind: PanelKind,
+
+	/// Text source, if a text panel
+	pub text_source: Option<PanelTextSource>,
+
+	/// Playlist name
+	pub playlist_name: PlaylistName,
+
+	/// How this panel's playlist player is shared with other panels, if it is
+	pub player_sharing: Option<PanelPlayerSharing>,
+
+	/// Playlists saved by each override of this panel's playlist, in the order they were overridden
+	saved_playlists: Vec<PanelSavedPlaylist>,
 }
 
 impl Panel {
@@ -219,8 +661,12 @@ impl Panel {
 		renderer_layouts: &PanelsRendererLayouts,
 		geometries: Vec<Rect<i32, u32>>,
 		state: PanelState,
+		kind: PanelKind,
+		text_source: Option<PanelTextSource>,
+		playlist_name: PlaylistName,
 	) -> Result<Self, AppError> {
 		Ok(Self {
+			id: PanelId::new(),
 			geometries: geometries
 				.into_iter()
 				.map(|geometry| PanelGeometry::new(wgpu_shared, renderer_layouts, geometry))
@@ -228,29 +674,252 @@ impl Panel {
 			state,
 			playlist_player: Arc::new(RwLock::new(PlaylistPlayer::new())),
 			images: PanelImages::new(wgpu_shared, renderer_layouts),
+			geometry_images: vec![],
+			kind,
+			text_source,
+			playlist_name,
+			player_sharing: None,
+			saved_playlists: vec![],
 		})
 	}
 
+	/// Overrides this panel's playlist with `playlist_name`, played by `playlist_player`.
+	///
+	/// The current playlist is saved until restored by [`Self::restore_playlist`].
+	pub fn override_playlist(
+		&mut self,
+		playlist_override: PanelPlaylistOverride,
+		playlist_name: PlaylistName,
+		playlist_player: Arc<RwLock<PlaylistPlayer>>,
+	) {
+		self.saved_playlists.push(PanelSavedPlaylist {
+			playlist_override,
+			playlist_name: mem::replace(&mut self.playlist_name, playlist_name),
+			playlist_player: mem::replace(&mut self.playlist_player, playlist_player),
+			player_sharing: self.player_sharing.take(),
+		});
+		self.images.set_cascade_offset(None);
+		self.clear_scheduled();
+	}
+
+	/// Restores the playlist saved by `playlist_override`.
+	///
+	/// Returns whether the playlist was overridden by it.
+	pub fn restore_playlist(&mut self, playlist_override: PanelPlaylistOverride) -> bool {
+		let Some(saved_idx) = self
+			.saved_playlists
+			.iter()
+			.position(|saved| saved.playlist_override == playlist_override)
+		else {
+			return false;
+		};
+		let saved = self.saved_playlists.remove(saved_idx);
+
+		// Note: If overridden again since, we restore into the override after us, so it
+		//       restores our saved playlist once it's over, instead of the one we replaced.
+		if let Some(next_saved) = self.saved_playlists.get_mut(saved_idx) {
+			next_saved.playlist_name = saved.playlist_name;
+			next_saved.playlist_player = saved.playlist_player;
+			next_saved.player_sharing = saved.player_sharing;
+			return true;
+		}
+
+		self.playlist_name = saved.playlist_name;
+		self.playlist_player = saved.playlist_player;
+		self.player_sharing = saved.player_sharing;
+		self.images.set_cascade_offset(match self.player_sharing {
+			Some(PanelPlayerSharing::Cascade { offset }) => Some(offset),
+			_ => None,
+		});
+		self.clear_scheduled();
+
+		true
+	}
+
+	/// Returns whether this panel's playlist is overridden by `playlist_override`
+	pub fn is_overridden(&self, playlist_override: PanelPlaylistOverride) -> bool {
+		self.saved_playlists
+			.iter()
+			.any(|saved| saved.playlist_override == playlist_override)
+	}
+
+	/// Discards all overrides of this panel's playlist, keeping the current one
+	#[cfg(feature = "menu")]
+	pub fn clear_overrides(&mut self) {
+		self.saved_playlists.clear();
+	}
+
+	/// Returns all playlist players of this panel, with their playlist, including those saved by overrides
+	#[cfg(feature = "menu")]
+	pub fn playlist_players(&self) -> impl Iterator<Item = (&Arc<RwLock<PlaylistPlayer>>, &PlaylistName)> {
+		iter::once((&self.playlist_player, &self.playlist_name)).chain(
+			self.saved_playlists
+				.iter()
+				.map(|saved| (&saved.playlist_player, &saved.playlist_name)),
+		)
+	}
+
+	/// Returns the playlist name and player sharing of this panel, ignoring any overrides
+	#[cfg(feature = "menu")]
+	pub fn base_playlist(&self) -> (&PlaylistName, Option<PanelPlayerSharing>) {
+		match self.saved_playlists.first() {
+			Some(saved) => (&saved.playlist_name, saved.player_sharing),
+			None => (&self.playlist_name, self.player_sharing),
+		}
+	}
+
+	/// Returns the images shown on the geometry at `geometry_idx`
+	pub fn images_of(&self, geometry_idx: usize) -> &PanelImages {
+		geometry_idx
+			.checked_sub(1)
+			.and_then(|idx| self.geometry_images.get(idx))
+			.unwrap_or(&self.images)
+	}
+
+	/// Returns the minimum interval between frames this panel needs.
+	///
+	/// Returns `None` if it needs the maximum frame rate.
+	pub fn frame_interval_hint(&self) -> Option<Duration> {
+		// Note: Transitions always need the maximum frame rate, to look smooth
+		let is_transitioning = self.state.fade.is_fading() && !self.state.paused && !self.state.quiet;
+		if is_transitioning {
+			return None;
+		}
+
+		self.state.fps.map(|fps| Duration::from_secs_f32(1.0 / fps))
+	}
+
+	/// Returns all images of this panel
+	pub fn all_images(&self) -> impl Iterator<Item = &PanelImages> {
+		iter::once(&self.images).chain(&self.geometry_images)
+	}
+
+	/// Returns all images of this panel, mutably
+	fn all_images_mut(&mut self) -> impl Iterator<Item = &mut PanelImages> {
+		iter::once(&mut self.images).chain(&mut self.geometry_images)
+	}
+
+	/// Returns which images are loaded, across all geometries.
+	///
+	/// An image is only considered loaded once it's loaded for every geometry,
+	/// so all geometries transition at the same time.
+	fn fade_images(&self) -> PanelFadeImages {
+		self.all_images()
+			.map(PanelImages::fade_images)
+			.fold(PanelFadeImages { cur: true, next: true }, |lhs, rhs| PanelFadeImages {
+				cur:  lhs.cur && rhs.cur,
+				next: lhs.next && rhs.next,
+			})
+	}
+
+	/// Executes a fade command on all images
+	fn execute(
+		&mut self,
+		wgpu_shared: &WgpuShared,
+		renderer_layouts: &PanelsRendererLayouts,
+		command: PanelFadeCommand,
+	) {
+		for images in self.all_images_mut() {
+			images.execute(wgpu_shared, renderer_layouts, command);
+		}
+	}
+
+	/// Clears all scheduled images.
+	///
+	/// See [`PanelImages::clear_scheduled`].
+	pub fn clear_scheduled(&mut self) {
+		for images in self.all_images_mut() {
+			images.clear_scheduled();
+		}
+	}
+
+	/// Creates or removes the images of each geometry, depending on whether we're showing distinct images
+	fn sync_geometry_images(&mut self, wgpu_shared: &WgpuShared, renderer_layouts: &PanelsRendererLayouts) {
+		// Note: Only image panels have more than one image to show
+		let len = match self.state.distinct_images && self.kind == PanelKind::Images {
+			true => self.geometries.len() - 1,
+			false => 0,
+		};
+
+		// Note: The new images load straight from the playlist player, even when
+		//       cascading, since they'd all show the same images otherwise.
+		self.geometry_images
+			.resize_with(len, || PanelImages::new(wgpu_shared, renderer_layouts));
+	}
+
+	/// Runs `command` on this panel
+	pub async fn run_command(
+		&mut self,
+		command: PanelCommand,
+		wgpu_shared: &WgpuShared,
+		renderer_layouts: &PanelsRendererLayouts,
+		rgb_converter: &RgbConverter,
+		image_requester: &ImageRequester,
+	) {
+		match command {
+			PanelCommand::Skip =>
+				self.skip(wgpu_shared, renderer_layouts, rgb_converter, image_requester)
+					.await,
+			PanelCommand::SwitchInstant =>
+				self.switch_instant(wgpu_shared, renderer_layouts, rgb_converter, image_requester)
+					.await,
+			#[cfg(feature = "menu")]
+			PanelCommand::Step { frames } =>
+				self.step(wgpu_shared, renderer_layouts, rgb_converter, image_requester, frames)
+					.await,
+		}
+	}
+
 	/// Skips to the next image
 	pub async fn skip(
 		&mut self,
 		wgpu_shared: &WgpuShared,
 		renderer_layouts: &PanelsRendererLayouts,
+		rgb_converter: &RgbConverter,
 		image_requester: &ImageRequester,
 	) {
-		self.images.step_next(wgpu_shared, renderer_layouts);
-		self.state.progress = self.state.duration.saturating_sub(self.state.fade_point);
+		if let PanelKind::Calibration { pattern } = &mut self.kind {
+			*pattern = (*pattern + 1) % CALIBRATION_PATTERNS;
+			self.state.fade.progress = 0;
+			return;
+		}
+
+		let command = self.state.fade.skip();
+		self.execute(wgpu_shared, renderer_layouts, command);
 
 		// Then try to load the next image
 		// Note: If we already have a next one, this will simply return.
-		self.images
-			.load_next(
-				&self.playlist_player,
-				wgpu_shared,
-				renderer_layouts,
-				image_requester,
-				&self.geometries,
-			)
+		self.load_next(wgpu_shared, renderer_layouts, rgb_converter, image_requester)
+			.await;
+	}
+
+	/// Switches to the next image instantly, without any transition.
+	///
+	/// If the next image isn't loaded yet, does nothing.
+	pub async fn switch_instant(
+		&mut self,
+		wgpu_shared: &WgpuShared,
+		renderer_layouts: &PanelsRendererLayouts,
+		rgb_converter: &RgbConverter,
+		image_requester: &ImageRequester,
+	) {
+		if let PanelKind::Calibration { pattern } = &mut self.kind {
+			*pattern = (*pattern + 1) % CALIBRATION_PATTERNS;
+			self.state.fade.progress = 0;
+			return;
+		}
+
+		// Note: Unlike skipping, we never switch to an image that isn't loaded yet
+		self.load_next(wgpu_shared, renderer_layouts, rgb_converter, image_requester)
+			.await;
+		let Some(command) = self.state.fade.switch_instant(self.fade_images()) else {
+			tracing::debug!("Next image isn't loaded yet, not switching");
+			return;
+		};
+		self.execute(wgpu_shared, renderer_layouts, command);
+
+		// Then start loading the image after it
+		self.load_next(wgpu_shared, renderer_layouts, rgb_converter, image_requester)
 			.await;
 	}
 
@@ -259,63 +928,243 @@ impl Panel {
 		&mut self,
 		wgpu_shared: &WgpuShared,
 		renderer_layouts: &PanelsRendererLayouts,
+		rgb_converter: &RgbConverter,
 		image_requester: &ImageRequester,
 		frames: i64,
 	) {
+		// Calibration panels just roll over to the next pattern
+		if let PanelKind::Calibration { pattern } = &mut self.kind {
+			if self.state.fade.step_looping(frames) {
+				*pattern = (*pattern + 1) % CALIBRATION_PATTERNS;
+			}
+			return;
+		}
+
 		// Update the progress, potentially rolling over to the next image
-		let next_progress = self.state.progress.saturating_add_signed(frames);
-		match next_progress >= self.state.duration {
-			true => {
-				self.images.step_next(wgpu_shared, renderer_layouts);
-				self.state.progress = next_progress.saturating_sub(self.state.fade_point);
-			},
-			false => {
-				let max_progress = match (self.images.cur().is_loaded(), self.images.next().is_loaded()) {
-					(false, false) => 0,
-					(true, false) => self.state.fade_point,
-					(_, true) => self.state.duration,
-				};
-				self.state.progress = self.state.progress.saturating_add_signed(frames).clamp(0, max_progress);
-			},
+		if let Some(command) = self.state.fade.step(frames, self.fade_images()) {
+			self.execute(wgpu_shared, renderer_layouts, command);
+		}
+
+		// Then show the latest frame of any videos
+		for images in self.all_images() {
+			images.update_videos(wgpu_shared);
 		}
 
 		// Then try to load the next image
 		// Note: If we already have a next one, this will simply return.
-		self.images
-			.load_next(
-				&self.playlist_player,
-				wgpu_shared,
-				renderer_layouts,
-				image_requester,
-				&self.geometries,
-			)
+		self.load_next(wgpu_shared, renderer_layouts, rgb_converter, image_requester)
 			.await;
 	}
 
-	/// Updates this panel's state
+	/// Updates this panel's state, advancing it by `frames` (potentially fractional) frames.
+	///
+	/// Returns whether a transition into the next image started.
 	pub async fn update(
 		&mut self,
 		wgpu_shared: &WgpuShared,
 		renderer_layouts: &PanelsRendererLayouts,
+		rgb_converter: &RgbConverter,
 		image_requester: &ImageRequester,
-	) {
+		frames: f64,
+	) -> bool {
+		// Keep the images and their sampler in sync with our state
+		self.sync_geometry_images(wgpu_shared, renderer_layouts);
+		let sampler = self.state.sampler();
+		for images in self.all_images_mut() {
+			images.set_sampler(wgpu_shared, renderer_layouts, sampler);
+		}
+
 		// Then try to load the next image
 		// Note: If we already have a next one, this will simply return.
-		self.images
-			.load_next(
-				&self.playlist_player,
-				wgpu_shared,
-				renderer_layouts,
-				image_requester,
-				&self.geometries,
-			)
+		self.load_next(wgpu_shared, renderer_layouts, rgb_converter, image_requester)
+			.await;
+
+		// If we're paused, or within quiet hours, don't update anything
+		if self.state.paused || self.state.quiet {
+			return false;
+		}
+
+		let was_fading = self.state.fade.is_fading();
+		let frames = self.state.fade.add_sub_progress(frames);
+		self.step(wgpu_shared, renderer_layouts, rgb_converter, image_requester, frames)
 			.await;
 
-		// If we're paused, don't update anything
-		if self.state.paused {
+		// Note: Calibration panels don't transition, they just switch patterns
+		!matches!(self.kind, PanelKind::Calibration { .. }) && !was_fading && self.state.fade.is_fading()
+	}
+
+	/// Loads the next (or current) images.
+	///
+	/// For text panels, renders the next text instead.
+	async fn load_next(
+		&mut self,
+		wgpu_shared: &WgpuShared,
+		renderer_layouts: &PanelsRendererLayouts,
+		rgb_converter: &RgbConverter,
+		image_requester: &ImageRequester,
+	) {
+		let Some(text_source) = &mut self.text_source else {
+			// Note: When showing distinct images, each image is only loaded for it's own geometry
+			let geometries = match self.geometry_images.is_empty() {
+				true => &*self.geometries,
+				false => &self.geometries[..1],
+			};
+			self.images
+				.load_next(
+					&self.playlist_player,
+					wgpu_shared,
+					renderer_layouts,
+					rgb_converter,
+					image_requester,
+					geometries,
+					self.state.scale_mode,
+				)
+				.await;
+			for (images, geometry) in self.geometry_images.iter_mut().zip(&self.geometries[1..]) {
+				images
+					.load_next(
+						&self.playlist_player,
+						wgpu_shared,
+						renderer_layouts,
+						rgb_converter,
+						image_requester,
+						slice::from_ref(geometry),
+						self.state.scale_mode,
+					)
+					.await;
+			}
+			return;
+		};
+
+		if !self.images.fade_images().needs_load() {
 			return;
 		}
 
-		self.step(wgpu_shared, renderer_layouts, image_requester, 1).await;
+		// Note: We render the text at the largest geometry size, so it's never upscaled
+		let max_image_size = wgpu_shared.device.limits().max_texture_dimension_2d;
+		let size = self
+			.geometries
+			.iter()
+			.map(|geometry| geometry.geometry.size)
+			.reduce(|lhs, rhs| Vector2::new(lhs.x.max(rhs.x), lhs.y.max(rhs.y)))
+			.unwrap_or(Vector2::new(1, 1))
+			.map(|len| len.clamp(1, max_image_size));
+		let (path, image) = match text_source {
+			PanelTextSource::Quotes(quotes) => (quotes.path().to_path_buf(), quotes.render_next(size)),
+			PanelTextSource::Ticker(ticker) => {
+				// Note: If we don't have any headlines yet, we'll try again next update
+				let Some(image) = ticker.render(size, max_image_size) else {
+					return;
+				};
+				let path = ticker
+					.feeds()
+					.first()
+					.map(|feed| PathBuf::from(ticker::redact_feed(feed)));
+				(path.unwrap_or_default(), image)
+			},
+		};
+		let thumbnail = image_loader::thumbnail(&image);
+		let image = Image {
+			path,
+			histogram: ImageHistogram::new(&thumbnail),
+			thumbnail,
+			image,
+			is_video: false,
+			is_virtual: true,
+		};
+		self.images
+			.push(wgpu_shared, renderer_layouts, rgb_converter, image, None);
 	}
 }
+
+/// Panel kind
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PanelKind {
+	/// Images from the playlist
+	Images,
+
+	/// Calibration test patterns
+	Calibration {
+		/// Current pattern
+		pattern: u32,
+	},
+
+	/// Quotes from a file
+	Quotes,
+
+	/// Headlines from news feeds
+	Ticker,
+}
+
+/// Panel playlist player sharing
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PanelPlayerSharing {
+	/// Shared with all coordinated panels of the playlist, never showing the same image at once
+	Coordinated,
+
+	/// Shared with all cascading panels of the playlist, showing what
+	/// the panel `offset` steps ahead showed
+	Cascade {
+		/// Steps behind the panel with offset `0`
+		offset: usize,
+	},
+}
+
+/// Panel playlist override
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PanelPlaylistOverride {
+	/// Privacy mode
+	Privacy,
+
+	/// Screensaver
+	Screensaver,
+}
+
+/// Panel playlist, saved while overridden
+#[derive(Debug)]
+struct PanelSavedPlaylist {
+	/// Override that saved it
+	playlist_override: PanelPlaylistOverride,
+
+	/// Playlist name
+	playlist_name: PlaylistName,
+
+	/// Playlist player
+	playlist_player: Arc<RwLock<PlaylistPlayer>>,
+
+	/// Player sharing
+	player_sharing: Option<PanelPlayerSharing>,
+}
+
+/// Key of a playlist player shared between panels
+type SharedPlayerKey = (PlaylistName, mem::Discriminant<PanelPlayerSharing>);
+
+/// Panel text source
+#[derive(Debug)]
+pub enum PanelTextSource {
+	/// Quotes
+	Quotes(PanelQuotes),
+
+	/// Ticker
+	Ticker(PanelTicker),
+}
+
+/// Number of calibration patterns
+// Note: Must be kept in sync with the calibration shader
+const CALIBRATION_PATTERNS: u32 = 8;
+
+/// Panel frame rate.
+///
+/// Panel durations are in frames at this rate, regardless of the rate we render at.
+pub const PANEL_FRAME_RATE: u32 = 60;
+
+/// Duration of each panel frame
+pub const PANEL_FRAME_DURATION: Duration = Duration::from_nanos(1_000_000_000 / PANEL_FRAME_RATE as u64);
+
+/// Attempts to canonicalize `path`. If unable to, logs a warning and returns `None`
+async fn try_canonicalize_path(path: &Path) -> Option<PathBuf> {
+	tokio::fs::canonicalize(path)
+		.await
+		.inspect_err(|err| tracing::warn!(?path, ?err, "Unable to canonicalize path"))
+		.ok()
+}
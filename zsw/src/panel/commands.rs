@@ -0,0 +1,62 @@
+//! Panel commands
+
+// Imports
+use {
+	super::PanelId,
+	std::{
+		mem,
+		sync::{Mutex, PoisonError},
+	},
+};
+
+/// Panel commands.
+///
+/// Queue of commands for the panels updater to run on panels.
+///
+/// Exists so that the settings menu and input handling don't have to wait
+/// on panel operations (such as loading the next image) while holding the
+/// panels lock, which would stall the renderer.
+///
+/// Commands are addressed by panel id, so they still reach the right panel
+/// if the panels are reordered before they're run.
+#[derive(Debug)]
+pub struct PanelCommands {
+	/// Pending commands, by panel id
+	commands: Mutex<Vec<(PanelId, PanelCommand)>>,
+}
+
+impl PanelCommands {
+	/// Creates a new, empty, queue
+	pub fn new() -> Self {
+		Self {
+			commands: Mutex::new(vec![]),
+		}
+	}
+
+	/// Queues `command` to run on the panel with id `panel_id`
+	pub fn push(&self, panel_id: PanelId, command: PanelCommand) {
+		self.commands
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.push((panel_id, command));
+	}
+
+	/// Takes all pending commands, in the order they were queued
+	pub fn take(&self) -> Vec<(PanelId, PanelCommand)> {
+		mem::take(&mut *self.commands.lock().unwrap_or_else(PoisonError::into_inner))
+	}
+}
+
+/// Panel command
+#[derive(Clone, Copy, Debug)]
+pub enum PanelCommand {
+	/// Skip to the next image
+	Skip,
+
+	/// Switch to the next image instantly
+	SwitchInstant,
+
+	/// Step by a number of frames (potentially negative)
+	#[cfg(feature = "menu")]
+	Step { frames: i64 },
+}
@@ -0,0 +1,545 @@
+//! Panel fade
+//!
+//! Decides how a panel's fade progresses and when it's images should
+//! change, without touching the images themselves. The caller executes
+//! the returned commands on the panel images.
+
+// Imports
+use std::time::Duration;
+
+/// Panel fade
+#[derive(Clone, Copy, Debug)]
+pub struct PanelFade {
+	/// Current progress (in frames)
+	pub progress: u64,
+
+	/// Duration (in frames)
+	pub duration: u64,
+
+	/// Fade point (in frames)
+	pub fade_point: u64,
+
+	/// Progress within the current frame, `0.0 .. 1.0`.
+	///
+	/// Only used for rendering, so fades stay smooth when
+	/// rendering faster than the panel frame rate.
+	pub sub_progress: f32,
+}
+
+impl PanelFade {
+	/// Creates a new fade, at the start
+	pub fn new(duration: u64, fade_point: u64) -> Self {
+		Self {
+			progress: 0,
+			duration,
+			fade_point,
+			sub_progress: 0.0,
+		}
+	}
+
+	/// Adds `frames` (potentially fractional) to the sub-frame progress.
+	///
+	/// Returns the number of whole frames to step by.
+	pub fn add_sub_progress(&mut self, frames: f64) -> i64 {
+		let frames = f64::from(self.sub_progress) + frames;
+		let whole_frames = frames.floor();
+		self.sub_progress = (frames - whole_frames) as f32;
+
+		whole_frames as i64
+	}
+
+	/// Returns the maximum progress we may currently reach, given the loaded images.
+	///
+	/// We can't start fading out of the current image until we have the next.
+	pub fn max_progress(&self, images: PanelFadeImages) -> u64 {
+		match (images.cur, images.next) {
+			(false, false) => 0,
+			(true, false) => self.fade_point,
+			(_, true) => self.duration,
+		}
+	}
+
+	/// Steps the fade by a certain number of frames (potentially negative).
+	///
+	/// Returns the command to execute, if any.
+	pub fn step(&mut self, frames: i64, images: PanelFadeImages) -> Option<PanelFadeCommand> {
+		let next_progress = self.progress.saturating_add_signed(frames);
+		match next_progress >= self.duration {
+			// Note: After stepping, the next image takes the place of the current one, which
+			//       has already been faded into, so we start after the fade point.
+			true => {
+				self.progress = next_progress.saturating_sub(self.fade_point);
+				Some(PanelFadeCommand::StepNext)
+			},
+			false => {
+				// Note: If we're held back (e.g. waiting for the next image), don't render past it
+				let max_progress = self.max_progress(images);
+				if next_progress >= max_progress {
+					self.sub_progress = 0.0;
+				}
+
+				self.progress = next_progress.min(max_progress);
+				None
+			},
+		}
+	}
+
+	/// Steps the fade by a certain number of frames (potentially negative), looping around at the end.
+	///
+	/// Returns whether we looped around.
+	pub fn step_looping(&mut self, frames: i64) -> bool {
+		let next_progress = self.progress.saturating_add_signed(frames);
+		self.progress = next_progress.checked_rem(self.duration).unwrap_or(0);
+		next_progress >= self.duration
+	}
+
+	/// Skips to the next image.
+	///
+	/// Returns the command to execute.
+	pub fn skip(&mut self) -> PanelFadeCommand {
+		self.complete_fade();
+		PanelFadeCommand::StepNext
+	}
+
+	/// Switches to the next image instantly, without any transition.
+	///
+	/// Returns the command to execute, if the next image is loaded.
+	pub fn switch_instant(&mut self, images: PanelFadeImages) -> Option<PanelFadeCommand> {
+		if !images.next {
+			return None;
+		}
+
+		Some(self.skip())
+	}
+
+	/// Completes the fade into the current image.
+	pub fn complete_fade(&mut self) {
+		// Note: At this progress, the previous image has fully faded out, while the next
+		//       one hasn't started fading in yet, since the fade point is past half the duration.
+		self.progress = self.duration.saturating_sub(self.fade_point);
+	}
+
+	/// Restarts the fade into the next image, if we're fading
+	pub fn restart_fade(&mut self) {
+		if self.is_fading() {
+			self.progress = self.fade_point;
+			self.sub_progress = 0.0;
+		}
+	}
+
+	/// Returns if we're fading into the next image
+	pub fn is_fading(&self) -> bool {
+		self.progress >= self.fade_point
+	}
+
+	/// Returns the elapsed time of the current cycle, given the duration of each frame
+	pub fn elapsed(&self, frame_duration: Duration) -> Duration {
+		self::frames_duration(self.progress, frame_duration)
+	}
+
+	/// Returns the remaining time until the next image, given the duration of each frame
+	pub fn remaining(&self, frame_duration: Duration) -> Duration {
+		self::frames_duration(self.duration.saturating_sub(self.progress), frame_duration)
+	}
+
+	/// Returns the normalized image progress
+	#[must_use]
+	pub fn progress_norm(&self) -> f32 {
+		// Note: Image progress is linear throughout the full cycle
+		(self.progress as f32 + self.sub_progress) / self.duration as f32
+	}
+
+	/// Returns the fade point normalized
+	pub fn fade_point_norm(&self) -> f32 {
+		// Note: Image progress is linear throughout the full cycle
+		self.fade_point as f32 / self.duration as f32
+	}
+}
+
+/// Returns the duration of `frames` frames, saturating on overflow
+fn frames_duration(frames: u64, frame_duration: Duration) -> Duration {
+	Duration::try_from_secs_f64(frames as f64 * frame_duration.as_secs_f64()).unwrap_or(Duration::MAX)
+}
+
+/// Which panel images are loaded
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct PanelFadeImages {
+	/// If the current image is loaded
+	pub cur: bool,
+
+	/// If the next image is loaded
+	pub next: bool,
+}
+
+impl PanelFadeImages {
+	/// Returns if a new image should be loaded
+	pub fn needs_load(self) -> bool {
+		!self.next
+	}
+
+	/// Returns the slot a newly loaded image should be uploaded to
+	pub fn load_slot(self) -> PanelImageSlot {
+		match self.cur {
+			true => PanelImageSlot::Next,
+			false => PanelImageSlot::Cur,
+		}
+	}
+
+	/// Returns the loaded images after executing `command`
+	#[must_use]
+	pub fn after(self, command: PanelFadeCommand) -> Self {
+		match command {
+			PanelFadeCommand::StepNext => Self {
+				cur:  self.next,
+				next: false,
+			},
+		}
+	}
+}
+
+/// Panel image slot
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PanelImageSlot {
+	/// Current image
+	Cur,
+
+	/// Next image
+	Next,
+}
+
+/// Panel fade command
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PanelFadeCommand {
+	/// Step to the next image.
+	///
+	/// The previous image is dropped, the current becomes the previous
+	/// and the next becomes the current.
+	StepNext,
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// All possible loaded images
+	const ALL_IMAGES: [PanelFadeImages; 4] = [
+		PanelFadeImages {
+			cur:  false,
+			next: false,
+		},
+		PanelFadeImages {
+			cur:  true,
+			next: false,
+		},
+		PanelFadeImages {
+			cur:  false,
+			next: true,
+		},
+		PanelFadeImages { cur: true, next: true },
+	];
+
+	fn fade(progress: u64) -> PanelFade {
+		PanelFade {
+			progress,
+			duration: 100,
+			fade_point: 80,
+			sub_progress: 0.0,
+		}
+	}
+
+	#[test]
+	fn sub_progress() {
+		let mut fade = self::fade(0);
+		assert_eq!(fade.add_sub_progress(0.5), 0, "Stepped a whole frame early");
+		assert_eq!(fade.add_sub_progress(0.75), 1, "Didn't step a whole frame");
+		assert!(
+			(fade.sub_progress - 0.25).abs() < 1e-6,
+			"Wrong sub-frame progress: {}",
+			fade.sub_progress
+		);
+		assert_eq!(fade.add_sub_progress(2.0), 2, "Didn't step multiple frames");
+	}
+
+	#[test]
+	fn step_clamped_resets_sub_progress() {
+		let mut fade = self::fade(79);
+		fade.sub_progress = 0.5;
+		let images = PanelFadeImages {
+			cur:  true,
+			next: false,
+		};
+		assert_eq!(fade.step(1, images), None, "Stepped next within the duration");
+		assert!(
+			fade.sub_progress.abs() < 1e-6,
+			"Sub-frame progress wasn't reset at the max: {}",
+			fade.sub_progress
+		);
+	}
+
+	#[test]
+	fn max_progress() {
+		let fade = self::fade(0);
+		let max_progresses = ALL_IMAGES.map(|images| fade.max_progress(images));
+		assert_eq!(max_progresses, [0, 80, 100, 100], "Wrong max progress");
+	}
+
+	#[test]
+	fn step_within() {
+		for images in ALL_IMAGES {
+			for progress in 0..99 {
+				let mut fade = self::fade(progress);
+				let max_progress = fade.max_progress(images);
+				let command = fade.step(1, images);
+				assert_eq!(command, None, "Stepped next within the duration");
+				assert_eq!(
+					fade.progress,
+					(progress + 1).min(max_progress),
+					"Wrong progress after stepping from {progress} with {images:?}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn step_clamps_to_max() {
+		// Note: If we were past the max (e.g. the next image was unloaded), we're clamped back down to it
+		let mut fade = self::fade(90);
+		let images = PanelFadeImages {
+			cur:  true,
+			next: false,
+		};
+		assert_eq!(fade.step(1, images), None, "Stepped next within the duration");
+		assert_eq!(fade.progress, 80, "Progress wasn't clamped to the fade point");
+	}
+
+	#[test]
+	fn step_next() {
+		for images in ALL_IMAGES {
+			let mut fade = self::fade(99);
+			assert_eq!(
+				fade.step(1, images),
+				Some(PanelFadeCommand::StepNext),
+				"Didn't step next at the end with {images:?}"
+			);
+			assert_eq!(fade.progress, 20, "Didn't resume after the fade point");
+
+			let mut fade = self::fade(99);
+			assert_eq!(
+				fade.step(31, images),
+				Some(PanelFadeCommand::StepNext),
+				"Didn't step next past the end with {images:?}"
+			);
+			assert_eq!(fade.progress, 50, "Overflowing progress was lost");
+		}
+	}
+
+	#[test]
+	fn step_backwards() {
+		let images = PanelFadeImages { cur: true, next: true };
+
+		let mut fade = self::fade(50);
+		assert_eq!(fade.step(-20, images), None, "Stepped next backwards");
+		assert_eq!(fade.progress, 30, "Wrong progress stepping backwards");
+
+		let mut fade = self::fade(10);
+		assert_eq!(fade.step(-20, images), None, "Stepped next backwards");
+		assert_eq!(fade.progress, 0, "Progress went below 0");
+	}
+
+	#[test]
+	fn step_looping() {
+		let mut fade = self::fade(98);
+		assert!(!fade.step_looping(1), "Looped within the duration");
+		assert_eq!(fade.progress, 99, "Wrong progress");
+		assert!(fade.step_looping(1), "Didn't loop at the end");
+		assert_eq!(fade.progress, 0, "Didn't loop to the start");
+		assert!(fade.step_looping(150), "Didn't loop past the end");
+		assert_eq!(fade.progress, 50, "Overflowing progress was lost");
+
+		let mut fade = PanelFade::new(0, 0);
+		assert!(fade.step_looping(1), "Didn't loop with no duration");
+		assert_eq!(fade.progress, 0, "Progress wasn't reset with no duration");
+	}
+
+	#[test]
+	fn skip() {
+		for progress in [0, 50, 99] {
+			let mut fade = self::fade(progress);
+			assert_eq!(fade.skip(), PanelFadeCommand::StepNext, "Skip didn't step next");
+			assert_eq!(fade.progress, 20, "Skip didn't complete the fade");
+		}
+	}
+
+	#[test]
+	fn switch_instant() {
+		for images in ALL_IMAGES {
+			let mut fade = self::fade(50);
+			match images.next {
+				true => {
+					assert_eq!(
+						fade.switch_instant(images),
+						Some(PanelFadeCommand::StepNext),
+						"Didn't switch with {images:?}"
+					);
+					assert_eq!(fade.progress, 20, "Switch didn't complete the fade");
+				},
+				false => {
+					assert_eq!(fade.switch_instant(images), None, "Switched with {images:?}");
+					assert_eq!(fade.progress, 50, "Progress changed without switching");
+				},
+			}
+		}
+	}
+
+	#[test]
+	fn complete_fade() {
+		let mut fade = self::fade(0);
+		fade.complete_fade();
+		assert_eq!(fade.progress, 20, "Wrong progress after completing fade");
+
+		let mut fade = PanelFade::new(10, 20);
+		fade.complete_fade();
+		assert_eq!(fade.progress, 0, "Progress underflowed");
+	}
+
+	#[test]
+	fn restart_fade() {
+		let mut fade = self::fade(90);
+		fade.restart_fade();
+		assert_eq!(fade.progress, 80, "Fade wasn't restarted");
+
+		let mut fade = self::fade(50);
+		fade.restart_fade();
+		assert_eq!(fade.progress, 50, "Progress changed while not fading");
+	}
+
+	#[test]
+	fn is_fading() {
+		assert!(!self::fade(79).is_fading(), "Fading before the fade point");
+		assert!(self::fade(80).is_fading(), "Not fading at the fade point");
+
+		// Note: After stepping next, we should be back within the current image
+		let mut fade = self::fade(99);
+		let _ = fade.step(1, PanelFadeImages { cur: true, next: true });
+		assert!(!fade.is_fading(), "Fading after stepping next");
+	}
+
+	#[test]
+	fn elapsed_remaining() {
+		let frame_duration = Duration::from_millis(10);
+		let fade = self::fade(30);
+		assert_eq!(
+			fade.elapsed(frame_duration),
+			Duration::from_millis(300),
+			"Wrong elapsed"
+		);
+		assert_eq!(
+			fade.remaining(frame_duration),
+			Duration::from_millis(700),
+			"Wrong remaining"
+		);
+
+		let fade = self::fade(150);
+		assert_eq!(fade.remaining(frame_duration), Duration::ZERO, "Remaining underflowed");
+
+		let fade = PanelFade::new(u64::MAX, 0);
+		assert_eq!(
+			fade.remaining(Duration::MAX),
+			Duration::MAX,
+			"Remaining didn't saturate"
+		);
+	}
+
+	#[test]
+	fn images_load() {
+		let needs_load = ALL_IMAGES.map(PanelFadeImages::needs_load);
+		assert_eq!(needs_load, [true, true, false, false], "Wrong images needing load");
+
+		let load_slots = ALL_IMAGES.map(PanelFadeImages::load_slot);
+		assert_eq!(
+			load_slots,
+			[
+				PanelImageSlot::Cur,
+				PanelImageSlot::Next,
+				PanelImageSlot::Cur,
+				PanelImageSlot::Next
+			],
+			"Wrong load slots"
+		);
+	}
+
+	#[test]
+	fn images_after_step_next() {
+		let after = ALL_IMAGES.map(|images| images.after(PanelFadeCommand::StepNext));
+		assert_eq!(
+			after,
+			[
+				PanelFadeImages {
+					cur:  false,
+					next: false,
+				},
+				PanelFadeImages {
+					cur:  false,
+					next: false,
+				},
+				PanelFadeImages {
+					cur:  true,
+					next: false,
+				},
+				PanelFadeImages {
+					cur:  true,
+					next: false,
+				},
+			],
+			"Wrong images after stepping next"
+		);
+	}
+
+	#[test]
+	fn load_then_fade() {
+		// Simulates a panel loading both images, then fading through them
+		let mut fade = self::fade(0);
+		let mut images = PanelFadeImages {
+			cur:  false,
+			next: false,
+		};
+
+		assert_eq!(fade.step(1, images), None, "Stepped next with no images");
+		assert_eq!(fade.progress, 0, "Progressed with no images");
+
+		assert!(images.needs_load(), "Didn't need to load with no images");
+		assert_eq!(
+			images.load_slot(),
+			PanelImageSlot::Cur,
+			"Didn't load into the current image"
+		);
+		images.cur = true;
+
+		for _ in 0..200 {
+			assert_eq!(fade.step(1, images), None, "Stepped next with no next image");
+		}
+		assert_eq!(fade.progress, 80, "Didn't stop at the fade point");
+
+		assert!(images.needs_load(), "Didn't need to load with no next image");
+		assert_eq!(
+			images.load_slot(),
+			PanelImageSlot::Next,
+			"Didn't load into the next image"
+		);
+		images.next = true;
+		assert!(!images.needs_load(), "Needed to load with all images");
+
+		let command = (0..20).find_map(|_| fade.step(1, images));
+		assert_eq!(command, Some(PanelFadeCommand::StepNext), "Didn't step next");
+		images = images.after(PanelFadeCommand::StepNext);
+		assert_eq!(
+			images,
+			PanelFadeImages {
+				cur:  true,
+				next: false,
+			},
+			"Wrong images after step"
+		);
+		assert_eq!(fade.progress, 20, "Didn't resume after the fade point");
+	}
+}
@@ -2,11 +2,10 @@
 
 // Imports
 use {
-	super::PanelsRendererLayouts,
-	cgmath::{Matrix4, Point2, Vector2, Vector3},
+	super::{renderer::MAX_UNIFORMS_SIZE, PanelScaleMode, PanelsRendererLayouts},
+	cgmath::{Point2, Vector2},
 	num_rational::Rational32,
 	wgpu::util::DeviceExt,
-	winit::dpi::PhysicalSize,
 	zsw_util::Rect,
 	zsw_wgpu::WgpuShared,
 };
@@ -31,8 +30,7 @@ impl PanelGeometry {
 		// Note: Initial value doesn't matter
 		let uniforms_descriptor = wgpu::util::BufferInitDescriptor {
 			label:    None,
-			// TODO: Resize buffer as we go?
-			contents: &[0; 0x100],
+			contents: &[0; MAX_UNIFORMS_SIZE],
 			usage:    wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
 		};
 		let uniforms = wgpu_shared.device.create_buffer_init(&uniforms_descriptor);
@@ -55,26 +53,6 @@ impl PanelGeometry {
 		}
 	}
 
-	/// Calculates this panel's position matrix
-	// Note: This matrix simply goes from a geometry in physical units
-	//       onto shader coordinates.
-	#[must_use]
-	pub fn pos_matrix(&self, surface_size: PhysicalSize<u32>) -> Matrix4<f32> {
-		let x_scale = self.geometry.size[0] as f32 / surface_size.width as f32;
-		let y_scale = self.geometry.size[1] as f32 / surface_size.height as f32;
-
-		let x_offset = self.geometry.pos[0] as f32 / surface_size.width as f32;
-		let y_offset = self.geometry.pos[1] as f32 / surface_size.height as f32;
-
-		let translation = Matrix4::from_translation(Vector3::new(
-			-1.0 + x_scale + 2.0 * x_offset,
-			1.0 - y_scale - 2.0 * y_offset,
-			0.0,
-		));
-		let scaling = Matrix4::from_nonuniform_scale(x_scale, -y_scale, 1.0);
-		translation * scaling
-	}
-
 	/// Returns the parallax ratio and offset
 	pub fn parallax_ratio_offset(
 		&self,
@@ -129,7 +107,7 @@ impl PanelGeometry {
 	///
 	/// This ratio is multiplied by the base uvs to fix the stretching
 	/// that comes from having a square coordinate system [0.0 .. 1.0] x [0.0 .. 1.0]
-	pub fn image_ratio(panel_size: Vector2<u32>, image_size: Vector2<u32>) -> Vector2<f32> {
+	pub fn image_ratio(panel_size: Vector2<u32>, image_size: Vector2<u32>, scale_mode: PanelScaleMode) -> Vector2<f32> {
 		let image_size = image_size.cast().expect("Image size didn't fit into an `i32`");
 		let panel_size = panel_size.cast().expect("Panel size didn't fit into an `i32`");
 
@@ -153,9 +131,22 @@ impl PanelGeometry {
 		// Y-axis ratio, if image scrolls vertically
 		let y_ratio = self::ratio_as_f32(height_ratio / width_ratio);
 
-		match image_ratio >= panel_ratio {
-			true => Vector2::new(x_ratio, 1.0),
-			false => Vector2::new(1.0, y_ratio),
+		match scale_mode {
+			PanelScaleMode::Fill => match image_ratio >= panel_ratio {
+				true => Vector2::new(x_ratio, 1.0),
+				false => Vector2::new(1.0, y_ratio),
+			},
+
+			// Note: When fitting, the axis that would be cropped when filling is
+			//       the one that fits exactly, and the other one is letterboxed.
+			PanelScaleMode::Fit => match image_ratio >= panel_ratio {
+				true => Vector2::new(1.0, y_ratio),
+				false => Vector2::new(x_ratio, 1.0),
+			},
+			PanelScaleMode::Stretch => Vector2::new(1.0, 1.0),
+			// Note: Tiles are scaled by the tile scale afterwards
+			PanelScaleMode::Center | PanelScaleMode::Tile =>
+				Vector2::new(self::ratio_as_f32(width_ratio), self::ratio_as_f32(height_ratio)),
 		}
 	}
 }
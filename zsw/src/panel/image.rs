@@ -1,14 +1,43 @@
 //! Panel images
 
+// Modules
+mod rgb_converter;
+
+// Exports
+pub use self::rgb_converter::RgbConverter;
+
 // Imports
 use {
-	super::{PanelGeometry, PanelsRendererLayouts, PlaylistPlayer},
-	crate::image_loader::{Image, ImageReceiver, ImageRequest, ImageRequester},
+	super::{
+		PanelFadeCommand,
+		PanelFadeImages,
+		PanelGeometry,
+		PanelImageSlot,
+		PanelSampler,
+		PanelScaleMode,
+		PanelsRendererLayouts,
+		PlaylistPlayer,
+	},
+	crate::{
+		image_loader::{
+			Image,
+			ImageHistogram,
+			ImageLoadError,
+			ImageReceiver,
+			ImageRequest,
+			ImageRequester,
+			VideoStream,
+		},
+		playlist::{PlaylistCursor, PlaylistLease},
+	},
 	cgmath::Vector2,
-	image::DynamicImage,
+	image::{DynamicImage, RgbaImage},
 	std::{
+		collections::{HashMap, VecDeque},
 		mem,
 		path::{Path, PathBuf},
+		sync::{Arc, Mutex, PoisonError},
+		time::{Duration, Instant},
 	},
 	tokio::sync::RwLock,
 	wgpu::util::DeviceExt,
@@ -27,17 +56,36 @@ pub struct PanelImages {
 	/// Next image
 	next: PanelImage,
 
+	/// Sampler settings
+	sampler: PanelSampler,
+
 	/// Texture sampler
-	texture_sampler: wgpu::Sampler,
+	texture_sampler: Arc<wgpu::Sampler>,
 
 	/// Texture bind group
 	image_bind_group: wgpu::BindGroup,
 
-	/// Scheduled image receiver.
-	scheduled_image_receiver: Option<ImageReceiver>,
+	/// Scheduled image receivers.
+	///
+	/// Front is the next image to be loaded. Any others are the
+	/// upcoming images, being pre-decoded ahead of time.
+	/// Each keeps it's image leased until it's no longer shown.
+	scheduled_image_receivers: VecDeque<(ImageReceiver, PlaylistLease)>,
+
+	/// Offset to cascade at, if the playlist player is shared with other panels at an offset
+	cascade_offset: Option<usize>,
+
+	/// Playlist cursor, if cascading
+	playlist_cursor: Option<PlaylistCursor>,
+
+	/// Instant to retry scheduling images at, if waiting on an offline playlist root
+	retry_at: Option<Instant>,
 }
 
 impl PanelImages {
+	/// Interval to retry scheduling images at, while waiting on an offline playlist root
+	const ROOT_OFFLINE_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
 	/// Creates a new panel
 	#[must_use]
 	pub fn new(wgpu_shared: &WgpuShared, renderer_layouts: &PanelsRendererLayouts) -> Self {
@@ -45,7 +93,8 @@ impl PanelImages {
 		let image_prev = PanelImage::new(wgpu_shared);
 		let image_cur = PanelImage::new(wgpu_shared);
 		let image_next = PanelImage::new(wgpu_shared);
-		let texture_sampler = self::create_texture_sampler(wgpu_shared);
+		let sampler = PanelSampler::default();
+		let texture_sampler = renderer_layouts.samplers.get(wgpu_shared, sampler);
 		let image_bind_group = self::create_image_bind_group(
 			wgpu_shared,
 			&renderer_layouts.image_bind_group_layout,
@@ -59,9 +108,13 @@ impl PanelImages {
 			prev: image_prev,
 			cur: image_cur,
 			next: image_next,
+			sampler,
 			texture_sampler,
 			image_bind_group,
-			scheduled_image_receiver: None,
+			scheduled_image_receivers: VecDeque::new(),
+			cascade_offset: None,
+			playlist_cursor: None,
+			retry_at: None,
 		}
 	}
 
@@ -70,47 +123,98 @@ impl PanelImages {
 		&self.image_bind_group
 	}
 
+	/// Returns which images are loaded
+	pub fn fade_images(&self) -> PanelFadeImages {
+		PanelFadeImages {
+			cur:  self.cur.is_loaded,
+			next: self.next.is_loaded,
+		}
+	}
+
+	/// Executes a fade command
+	pub fn execute(
+		&mut self,
+		wgpu_shared: &WgpuShared,
+		renderer_layouts: &PanelsRendererLayouts,
+		command: PanelFadeCommand,
+	) {
+		let images = self.fade_images();
+		match command {
+			PanelFadeCommand::StepNext => self.step_next(wgpu_shared, renderer_layouts),
+		}
+		debug_assert_eq!(self.fade_images(), images.after(command), "Images diverged from fade");
+	}
+
 	/// Steps to the next image
-	pub fn step_next(&mut self, wgpu_shared: &WgpuShared, renderer_layouts: &PanelsRendererLayouts) {
+	fn step_next(&mut self, wgpu_shared: &WgpuShared, renderer_layouts: &PanelsRendererLayouts) {
 		mem::swap(&mut self.prev, &mut self.cur);
 		mem::swap(&mut self.cur, &mut self.next);
 		self.next.is_loaded = false;
+
+		// Note: The previous image is no longer shown, so we release it
+		self.prev.lease = None;
+		self.prev.video = None;
 		self.update_image_bind_group(wgpu_shared, renderer_layouts);
 	}
 
 	/// Loads the next (or current) images.
 	///
 	/// Requests images if missing any.
+	#[expect(clippy::too_many_arguments)] // TODO: Refactor
 	pub async fn load_next(
 		&mut self,
 		playlist_player: &RwLock<PlaylistPlayer>,
 		wgpu_shared: &WgpuShared,
 		renderer_layouts: &PanelsRendererLayouts,
+		rgb_converter: &RgbConverter,
 		image_requester: &ImageRequester,
 		geometries: &[PanelGeometry],
+		scale_mode: PanelScaleMode,
 	) {
-		// Schedule the next image.
-		self.schedule_load_image(wgpu_shared, playlist_player, image_requester, geometries)
+		// Schedule the next images.
+		self.schedule_load_images(wgpu_shared, playlist_player, image_requester, geometries, scale_mode)
 			.await;
 
 		// If we have both images, don't advance
-		if self.next.is_loaded {
+		if !self.fade_images().needs_load() {
 			return;
 		}
 
 		// Otherwise, try to load the image.
-		if let Some(image) = self
-			.load_img(wgpu_shared, playlist_player, image_requester, geometries)
+		if let Some((image, lease)) = self
+			.load_img(wgpu_shared, playlist_player, image_requester, geometries, scale_mode)
 			.await
 		{
-			match self.cur.is_loaded {
-				true => self.next.update(wgpu_shared, image),
-				false => self.cur.update(wgpu_shared, image),
-			}
-			self.update_image_bind_group(wgpu_shared, renderer_layouts);
+			self.push(wgpu_shared, renderer_layouts, rgb_converter, image, Some(lease));
 		}
 	}
 
+	/// Uploads the latest frame of all videos being shown
+	pub fn update_videos(&self, wgpu_shared: &WgpuShared) {
+		self.cur.update_video(wgpu_shared);
+		self.next.update_video(wgpu_shared);
+	}
+
+	/// Pushes an image as the next image, or the current, if we don't have one.
+	///
+	/// If the image is from a playlist, `lease` should be it's lease, so it's kept
+	/// leased while it's shown.
+	pub fn push(
+		&mut self,
+		wgpu_shared: &WgpuShared,
+		renderer_layouts: &PanelsRendererLayouts,
+		rgb_converter: &RgbConverter,
+		image: Image,
+		lease: Option<PlaylistLease>,
+	) {
+		let image_slot = match self.fade_images().load_slot() {
+			PanelImageSlot::Cur => &mut self.cur,
+			PanelImageSlot::Next => &mut self.next,
+		};
+		image_slot.update(wgpu_shared, rgb_converter, image, lease);
+		self.update_image_bind_group(wgpu_shared, renderer_layouts);
+	}
+
 	/// Tries to load the scheduled image.
 	///
 	/// If unavailable, schedules it, and returns None.
@@ -120,10 +224,11 @@ impl PanelImages {
 		playlist_player: &RwLock<PlaylistPlayer>,
 		image_requester: &ImageRequester,
 		geometries: &[PanelGeometry],
-	) -> Option<Image> {
+		scale_mode: PanelScaleMode,
+	) -> Option<(Image, PlaylistLease)> {
 		// Get the image receiver, or schedule it.
-		let Some(image_receiver) = self.scheduled_image_receiver.as_mut() else {
-			self.schedule_load_image(wgpu_shared, playlist_player, image_requester, geometries)
+		let Some((image_receiver, _)) = self.scheduled_image_receivers.front_mut() else {
+			self.schedule_load_images(wgpu_shared, playlist_player, image_requester, geometries, scale_mode)
 				.await;
 			return None;
 		};
@@ -132,58 +237,135 @@ impl PanelImages {
 		let response = image_receiver.try_recv()?;
 
 		// Remove the exhausted receiver
-		self.scheduled_image_receiver = None;
+		let (_, lease) = self
+			.scheduled_image_receivers
+			.pop_front()
+			.expect("Scheduled image receiver should exist");
 
 		// Then check if we got the image
 		match response.image_res {
 			// If so, return it
-			Ok(image) => Some(image),
+			Ok(image) => Some((image, lease)),
 
 			// Else, log an error, remove the image and re-schedule it
+			// Note: If the error was transient, we keep the image, so it may be loaded later
+			// Note: If it's root is offline, we wait a bit before retrying, so we don't
+			//       keep requesting images while the whole playlist is offline.
 			Err(err) => {
-				{
-					tracing::warn!(image_path = ?response.request.path, ?err, "Unable to load image, removing it from player");
-					let mut playlist_player = playlist_player.write().await;
-					playlist_player.remove(&response.request.path);
+				match &err {
+					ImageLoadError::RootOffline { root } => {
+						tracing::debug!(image_path = ?response.request.path, ?root, "Playlist root is offline, retrying later");
+						self.retry_at = Some(Instant::now() + Self::ROOT_OFFLINE_RETRY_INTERVAL);
+					},
+					_ if err.is_transient() =>
+						tracing::warn!(image_path = ?response.request.path, ?err, "Unable to load image, skipping it"),
+					_ => {
+						tracing::warn!(image_path = ?response.request.path, ?err, "Unable to load image, removing it from player");
+						let mut playlist_player = playlist_player.write().await;
+						playlist_player.remove(&response.request.path);
+					},
 				}
 
-				self.schedule_load_image(wgpu_shared, playlist_player, image_requester, geometries)
+				self.schedule_load_images(wgpu_shared, playlist_player, image_requester, geometries, scale_mode)
 					.await;
 				None
 			},
 		}
 	}
 
-	/// Schedules a new image.
+	/// Schedules new images.
 	///
+	/// Schedules the next image, along with the image requester's look-ahead
+	/// of upcoming images, so they're decoded by the time we swap to them.
 	/// If the playlist player is empty, does not schedule.
 	/// If already scheduled, returns
-	async fn schedule_load_image(
+	async fn schedule_load_images(
 		&mut self,
 		wgpu_shared: &WgpuShared,
 		playlist_player: &RwLock<PlaylistPlayer>,
 		image_requester: &ImageRequester,
 		geometries: &[PanelGeometry],
+		scale_mode: PanelScaleMode,
 	) {
-		if self.scheduled_image_receiver.is_some() {
+		let max_scheduled = 1 + image_requester.look_ahead();
+		if self.scheduled_image_receivers.len() >= max_scheduled {
+			return;
+		}
+		if self.retry_at.is_some_and(|retry_at| Instant::now() < retry_at) {
 			return;
 		}
+		self.retry_at = None;
 
 		let mut playlist_player = playlist_player.write().await;
-		let image_path = match playlist_player.next() {
-			Some(path) => path.to_path_buf(),
-			None => {
+		let wgpu_limits = wgpu_shared.device.limits();
+
+		// Note: We only add our cursor once we first need it, so we don't need to lock the player before
+		if self.playlist_cursor.is_none() {
+			if let Some(offset) = self.cascade_offset {
+				self.playlist_cursor = Some(playlist_player.add_cursor(offset));
+			}
+		}
+
+		while self.scheduled_image_receivers.len() < max_scheduled {
+			let lease = match &self.playlist_cursor {
+				Some(playlist_cursor) => playlist_player.next_at(playlist_cursor),
+				None => playlist_player.next(),
+			};
+			let Some(lease) = lease else {
 				tracing::trace!("No images left");
 				return;
-			},
-		};
+			};
+
+			let root = playlist_player.root_of(lease.path());
+			let image_receiver = image_requester.request(ImageRequest {
+				path: lease.path().to_path_buf(),
+				root,
+				geometries: geometries.iter().map(|geometry| geometry.geometry).collect(),
+				scale_mode,
+				max_image_size: wgpu_limits.max_texture_dimension_2d,
+			});
+			self.scheduled_image_receivers.push_back((image_receiver, lease));
+		}
+	}
 
-		let wgpu_limits = wgpu_shared.device.limits();
-		self.scheduled_image_receiver = Some(image_requester.request(ImageRequest {
-			path:           image_path,
-			geometries:     geometries.iter().map(|geometry| geometry.geometry).collect(),
-			max_image_size: wgpu_limits.max_texture_dimension_2d,
-		}));
+	/// Clears all scheduled images.
+	///
+	/// Any images already being loaded are discarded once loaded.
+	/// Should be called when the playlist player is replaced, so
+	/// we don't show images pre-decoded from the previous one.
+	///
+	/// Also drops our cursor, if any, since it belongs to the previous
+	/// playlist player. It's re-added to the new one once needed.
+	pub fn clear_scheduled(&mut self) {
+		self.scheduled_image_receivers.clear();
+		self.playlist_cursor = None;
+	}
+
+	/// Sets the offset to cascade at.
+	///
+	/// If `Some`, images are loaded through a cursor of the playlist player
+	/// at that offset, else they're loaded from the playlist player directly.
+	pub fn set_cascade_offset(&mut self, cascade_offset: Option<usize>) {
+		self.cascade_offset = cascade_offset;
+		self.playlist_cursor = None;
+	}
+
+	/// Sets the sampler settings.
+	///
+	/// Re-creates the image bind group if they changed.
+	pub fn set_sampler(
+		&mut self,
+		wgpu_shared: &WgpuShared,
+		renderer_layouts: &PanelsRendererLayouts,
+		sampler: PanelSampler,
+	) {
+		if self.sampler == sampler {
+			return;
+		}
+
+		self.sampler = sampler;
+		self.texture_sampler = renderer_layouts.samplers.get(wgpu_shared, sampler);
+		self.update_image_bind_group(wgpu_shared, renderer_layouts);
 	}
 
 	/// Updates the image bind group
@@ -204,6 +386,7 @@ impl PanelImages {
 	}
 
 	/// Returns the previous image mutably
+	#[cfg(feature = "menu")]
 	pub fn prev_mut(&mut self) -> &mut PanelImage {
 		&mut self.prev
 	}
@@ -214,6 +397,7 @@ impl PanelImages {
 	}
 
 	/// Returns the current image mutably
+	#[cfg(feature = "menu")]
 	pub fn cur_mut(&mut self) -> &mut PanelImage {
 		&mut self.cur
 	}
@@ -224,6 +408,7 @@ impl PanelImages {
 	}
 
 	/// Returns the next image mutably
+	#[cfg(feature = "menu")]
 	pub fn next_mut(&mut self) -> &mut PanelImage {
 		&mut self.next
 	}
@@ -251,6 +436,27 @@ pub struct PanelImage {
 
 	/// Image path
 	image_path: Option<PathBuf>,
+
+	/// Whether the image path isn't a file on disk
+	is_virtual: bool,
+
+	/// Thumbnail texture
+	thumbnail_texture: wgpu::Texture,
+
+	/// Thumbnail texture view
+	thumbnail_texture_view: wgpu::TextureView,
+
+	/// Thumbnail size
+	thumbnail_size: Vector2<u32>,
+
+	/// Histogram, if loaded
+	histogram: Option<ImageHistogram>,
+
+	/// Playlist lease, if any
+	lease: Option<PlaylistLease>,
+
+	/// Video stream, if this image is a video
+	video: Option<VideoStream>,
 }
 
 impl PanelImage {
@@ -259,6 +465,7 @@ impl PanelImage {
 	pub fn new(wgpu_shared: &WgpuShared) -> Self {
 		// Create the texture and sampler
 		let (texture, texture_view) = self::create_empty_image_texture(wgpu_shared);
+		let (thumbnail_texture, thumbnail_texture_view) = self::create_empty_image_texture(wgpu_shared);
 
 		Self {
 			texture,
@@ -267,6 +474,13 @@ impl PanelImage {
 			size: Vector2::new(0, 0),
 			swap_dir: false,
 			image_path: None,
+			is_virtual: false,
+			thumbnail_texture,
+			thumbnail_texture_view,
+			thumbnail_size: Vector2::new(0, 0),
+			histogram: None,
+			lease: None,
+			video: None,
 		}
 	}
 
@@ -286,6 +500,7 @@ impl PanelImage {
 	}
 
 	/// Returns the swap direction of this image mutably
+	#[cfg(feature = "menu")]
 	pub fn swap_dir_mut(&mut self) -> &mut bool {
 		&mut self.swap_dir
 	}
@@ -295,18 +510,95 @@ impl PanelImage {
 		self.image_path.as_deref()
 	}
 
+	/// Returns if the image path isn't a file on disk, such as images within bundles
+	#[cfg(feature = "menu")]
+	pub fn is_virtual(&self) -> bool {
+		self.is_virtual
+	}
+
+	/// Returns the thumbnail texture view
+	#[cfg(feature = "menu")]
+	pub fn thumbnail_texture_view(&self) -> &wgpu::TextureView {
+		&self.thumbnail_texture_view
+	}
+
+	/// Returns the thumbnail size
+	#[cfg(feature = "menu")]
+	pub fn thumbnail_size(&self) -> Vector2<u32> {
+		self.thumbnail_size
+	}
+
+	/// Returns the histogram, if loaded
+	#[cfg(feature = "menu")]
+	pub fn histogram(&self) -> Option<&ImageHistogram> {
+		self.histogram.as_ref()
+	}
+
 	/// Updates this image
-	pub fn update(&mut self, wgpu_shared: &WgpuShared, image: Image) {
+	pub fn update(
+		&mut self,
+		wgpu_shared: &WgpuShared,
+		rgb_converter: &RgbConverter,
+		image: Image,
+		lease: Option<PlaylistLease>,
+	) {
 		// Update our texture
 		let size = Vector2::new(image.image.width(), image.image.height());
-		(self.texture, self.texture_view) = self::create_image_texture(wgpu_shared, image.image);
+		(self.texture, self.texture_view) = self::create_image_texture(wgpu_shared, rgb_converter, image.image);
+		self.lease = lease;
+
+		// If it's a video, start streaming it into the texture
+		// Note: If we can't, we just keep showing the first frame
+		self.video = match image.is_video {
+			true => match VideoStream::start(&image.path, size) {
+				Ok(video) => Some(video),
+				Err(err) => {
+					tracing::warn!(path = ?image.path, ?err, "Unable to stream video");
+					None
+				},
+			},
+			false => None,
+		};
 		self.image_path = Some(image.path);
+		self.is_virtual = image.is_virtual;
+
+		// And the thumbnail
+		self.thumbnail_size = Vector2::new(image.thumbnail.width(), image.thumbnail.height());
+		(self.thumbnail_texture, self.thumbnail_texture_view) =
+			self::create_thumbnail_texture(wgpu_shared, &image.thumbnail);
+		self.histogram = Some(image.histogram);
 
 		// Then update the image size and swap direction
 		self.size = size;
 		self.swap_dir = rand::random();
 		self.is_loaded = true;
 	}
+
+	/// Uploads the latest frame of the video, if any.
+	///
+	/// Does nothing if no new frame was decoded since the last call.
+	pub fn update_video(&self, wgpu_shared: &WgpuShared) {
+		let Some(frame) = self.video.as_ref().and_then(VideoStream::take_frame) else {
+			return;
+		};
+
+		// Note: Frames are decoded to the image size, and the image is always `rgba8`, so we can
+		//       copy the frame straight into the texture.
+		wgpu_shared.queue.write_texture(
+			self.texture.as_image_copy(),
+			&frame,
+			wgpu::ImageDataLayout {
+				offset:         0,
+				bytes_per_row:  Some(4 * self.size.x),
+				rows_per_image: None,
+			},
+			wgpu::Extent3d {
+				width:                 self.size.x,
+				height:                self.size.y,
+				depth_or_array_layers: 1,
+			},
+		);
+	}
 }
 
 
@@ -323,13 +615,43 @@ fn create_empty_image_texture(wgpu_shared: &WgpuShared) -> (wgpu::Texture, wgpu:
 }
 
 /// Creates the image texture and view
-fn create_image_texture(wgpu_shared: &WgpuShared, image: DynamicImage) -> (wgpu::Texture, wgpu::TextureView) {
+fn create_image_texture(
+	wgpu_shared: &WgpuShared,
+	rgb_converter: &RgbConverter,
+	image: DynamicImage,
+) -> (wgpu::Texture, wgpu::TextureView) {
+	// Note: The image loader should ensure the image is the right size.
+	let limits = wgpu_shared.device.limits();
+	let max_image_size = limits.max_texture_dimension_2d;
+	let image_width = image.width();
+	let image_height = image.height();
+	assert!(
+		image_width <= max_image_size && image_height <= max_image_size,
+		"Loaded image was too big {image_width}x{image_height} (max: {max_image_size})",
+	);
+
 	// Get the image's format, converting if necessary.
 	let (image, format) = match image {
 		// With `rgba8` we can simply use the image
 		image @ DynamicImage::ImageRgba8(_) => (image, wgpu::TextureFormat::Rgba8UnormSrgb),
 
-		// TODO: Convert more common formats (such as rgb8) if possible.
+		// With `rgb8`, convert it on the gpu, to avoid converting it on the cpu.
+		// Note: If it's too big for the gpu, we still convert it on the cpu
+		DynamicImage::ImageRgb8(image) => match rgb_converter.convert(wgpu_shared, image, "[zsw::panel_img] Image") {
+			Ok(texture) => {
+				let texture_view = texture.create_view(&wgpu::TextureViewDescriptor {
+					format: Some(wgpu::TextureFormat::Rgba8UnormSrgb),
+					..wgpu::TextureViewDescriptor::default()
+				});
+				return (texture, texture_view);
+			},
+			Err(image) => {
+				let image = DynamicImage::ImageRgb8(image).to_rgba8();
+				(DynamicImage::ImageRgba8(image), wgpu::TextureFormat::Rgba8UnormSrgb)
+			},
+		},
+
+		// TODO: Convert more common formats if possible.
 
 		// Else simply convert to rgba8
 		image => {
@@ -338,16 +660,6 @@ fn create_image_texture(wgpu_shared: &WgpuShared, image: DynamicImage) -> (wgpu:
 		},
 	};
 
-	// Note: The image loader should ensure the image is the right size.
-	let limits = wgpu_shared.device.limits();
-	let max_image_size = limits.max_texture_dimension_2d;
-	let image_width = image.width();
-	let image_height = image.height();
-	assert!(
-		image_width <= max_image_size && image_height <= max_image_size,
-		"Loaded image was too big {image_width}x{image_height} (max: {max_image_size})",
-	);
-
 	// TODO: Pass some view formats?
 	let texture_descriptor =
 		self::texture_descriptor("[zsw::panel_img] Image", image.width(), image.height(), format, &[]);
@@ -362,16 +674,66 @@ fn create_image_texture(wgpu_shared: &WgpuShared, image: DynamicImage) -> (wgpu:
 	(texture, texture_view)
 }
 
+/// Creates the thumbnail texture and view
+fn create_thumbnail_texture(wgpu_shared: &WgpuShared, thumbnail: &RgbaImage) -> (wgpu::Texture, wgpu::TextureView) {
+	let texture_descriptor = self::texture_descriptor(
+		"[zsw::panel_img] Thumbnail",
+		thumbnail.width(),
+		thumbnail.height(),
+		wgpu::TextureFormat::Rgba8UnormSrgb,
+		&[],
+	);
+	let texture = wgpu_shared.device.create_texture_with_data(
+		&wgpu_shared.queue,
+		&texture_descriptor,
+		wgpu::util::TextureDataOrder::LayerMajor,
+		thumbnail.as_raw(),
+	);
+	let texture_view_descriptor = wgpu::TextureViewDescriptor::default();
+	let texture_view = texture.create_view(&texture_view_descriptor);
+	(texture, texture_view)
+}
+
+/// Panel samplers
+///
+/// Caches the texture samplers, by their settings, so panels with the same settings share them.
+#[derive(Debug)]
+pub struct PanelSamplers {
+	/// Samplers
+	samplers: Mutex<HashMap<PanelSampler, Arc<wgpu::Sampler>>>,
+}
+
+impl PanelSamplers {
+	/// Creates new, empty, panel samplers
+	pub fn new() -> Self {
+		Self {
+			samplers: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Gets the texture sampler for `sampler`, creating it if it doesn't exist
+	pub fn get(&self, wgpu_shared: &WgpuShared, sampler: PanelSampler) -> Arc<wgpu::Sampler> {
+		let mut samplers = self.samplers.lock().unwrap_or_else(PoisonError::into_inner);
+		let texture_sampler = samplers
+			.entry(sampler)
+			.or_insert_with(|| Arc::new(self::create_texture_sampler(wgpu_shared, sampler)));
+		Arc::clone(texture_sampler)
+	}
+}
+
 /// Creates the texture sampler
-fn create_texture_sampler(wgpu_shared: &WgpuShared) -> wgpu::Sampler {
+fn create_texture_sampler(wgpu_shared: &WgpuShared, sampler: PanelSampler) -> wgpu::Sampler {
+	let filter_mode = sampler.filter.filter_mode();
+	let address_mode = sampler.address_mode.address_mode();
 	let descriptor = wgpu::SamplerDescriptor {
 		label: Some("[zsw::panel] Texture sampler"),
-		address_mode_u: wgpu::AddressMode::ClampToEdge,
-		address_mode_v: wgpu::AddressMode::ClampToEdge,
-		address_mode_w: wgpu::AddressMode::ClampToEdge,
-		mag_filter: wgpu::FilterMode::Linear,
-		min_filter: wgpu::FilterMode::Linear,
-		mipmap_filter: wgpu::FilterMode::Linear,
+		address_mode_u: address_mode,
+		address_mode_v: address_mode,
+		address_mode_w: address_mode,
+		mag_filter: filter_mode,
+		min_filter: filter_mode,
+		mipmap_filter: filter_mode,
+		anisotropy_clamp: sampler.anisotropy_clamp(),
 		..wgpu::SamplerDescriptor::default()
 	};
 	wgpu_shared.device.create_sampler(&descriptor)
@@ -0,0 +1,174 @@
+//! Rgb to rgba converter
+
+// Imports
+use {image::RgbImage, wgpu::util::DeviceExt, zsw_wgpu::WgpuShared};
+
+/// Workgroup size of the conversion shader, in each dimension
+const WORKGROUP_SIZE: u32 = 8;
+
+/// Rgb to rgba converter
+///
+/// Converts rgb images to rgba textures on the gpu, since
+/// there are no rgb texture formats to upload them to directly.
+#[derive(Debug)]
+pub struct RgbConverter {
+	/// Bind group layout
+	bind_group_layout: wgpu::BindGroupLayout,
+
+	/// Compute pipeline
+	pipeline: wgpu::ComputePipeline,
+}
+
+impl RgbConverter {
+	/// Creates a new converter
+	pub fn new(wgpu_shared: &WgpuShared) -> Self {
+		let bind_group_layout = self::create_bind_group_layout(wgpu_shared);
+		let pipeline = self::create_pipeline(wgpu_shared, &bind_group_layout);
+
+		Self {
+			bind_group_layout,
+			pipeline,
+		}
+	}
+
+	/// Converts `image` into an rgba texture.
+	///
+	/// The texture is created with the `Rgba8Unorm` format, but may be viewed as `Rgba8UnormSrgb`.
+	/// Returns the image back if it's too large to be converted on the gpu.
+	pub fn convert(&self, wgpu_shared: &WgpuShared, image: RgbImage, label: &str) -> Result<wgpu::Texture, RgbImage> {
+		// Note: Storage buffers must be a multiple of 4 bytes, so we pad the data
+		let data_len = image.as_raw().len().next_multiple_of(4);
+		let max_buffer_size = wgpu_shared.device.limits().max_storage_buffer_binding_size;
+		if u64::try_from(data_len).map_or(true, |len| len > u64::from(max_buffer_size)) {
+			return Err(image);
+		}
+
+		let (width, height) = image.dimensions();
+		let mut data = image.into_raw();
+		data.resize(data_len, 0);
+
+		let buffer = wgpu_shared
+			.device
+			.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+				label:    Some("[zsw::rgb_converter] Rgb buffer"),
+				contents: &data,
+				usage:    wgpu::BufferUsages::STORAGE,
+			});
+
+		let texture = wgpu_shared.device.create_texture(&wgpu::TextureDescriptor {
+			label:           Some(label),
+			size:            wgpu::Extent3d {
+				width,
+				height,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count:    1,
+			dimension:       wgpu::TextureDimension::D2,
+			format:          wgpu::TextureFormat::Rgba8Unorm,
+			usage:           wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
+			view_formats:    &[wgpu::TextureFormat::Rgba8UnormSrgb],
+		});
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+		let bind_group = wgpu_shared.device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label:   Some("[zsw::rgb_converter] Bind group"),
+			layout:  &self.bind_group_layout,
+			entries: &[
+				wgpu::BindGroupEntry {
+					binding:  0,
+					resource: buffer.as_entire_binding(),
+				},
+				wgpu::BindGroupEntry {
+					binding:  1,
+					resource: wgpu::BindingResource::TextureView(&texture_view),
+				},
+			],
+		});
+
+		let mut encoder = wgpu_shared
+			.device
+			.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+				label: Some("[zsw::rgb_converter] Command encoder"),
+			});
+		{
+			let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+				label:            Some("[zsw::rgb_converter] Compute pass"),
+				timestamp_writes: None,
+			});
+			compute_pass.set_pipeline(&self.pipeline);
+			compute_pass.set_bind_group(0, &bind_group, &[]);
+			compute_pass.dispatch_workgroups(width.div_ceil(WORKGROUP_SIZE), height.div_ceil(WORKGROUP_SIZE), 1);
+		}
+		let _ = wgpu_shared.queue.submit([encoder.finish()]);
+
+		Ok(texture)
+	}
+}
+
+/// Creates the bind group layout
+fn create_bind_group_layout(wgpu_shared: &WgpuShared) -> wgpu::BindGroupLayout {
+	let descriptor = wgpu::BindGroupLayoutDescriptor {
+		label:   Some("[zsw::rgb_converter] Bind group layout"),
+		entries: &[
+			wgpu::BindGroupLayoutEntry {
+				binding:    0,
+				visibility: wgpu::ShaderStages::COMPUTE,
+				ty:         wgpu::BindingType::Buffer {
+					ty:                 wgpu::BufferBindingType::Storage { read_only: true },
+					has_dynamic_offset: false,
+					min_binding_size:   None,
+				},
+				count:      None,
+			},
+			wgpu::BindGroupLayoutEntry {
+				binding:    1,
+				visibility: wgpu::ShaderStages::COMPUTE,
+				ty:         wgpu::BindingType::StorageTexture {
+					access:         wgpu::StorageTextureAccess::WriteOnly,
+					format:         wgpu::TextureFormat::Rgba8Unorm,
+					view_dimension: wgpu::TextureViewDimension::D2,
+				},
+				count:      None,
+			},
+		],
+	};
+
+	wgpu_shared.device.create_bind_group_layout(&descriptor)
+}
+
+/// Creates the compute pipeline
+fn create_pipeline(wgpu_shared: &WgpuShared, bind_group_layout: &wgpu::BindGroupLayout) -> wgpu::ComputePipeline {
+	// Note: This shader isn't meant to be customized, so we include it, instead of loading it from the shaders directory
+	let shader = wgpu_shared
+		.device
+		.create_shader_module(wgpu::include_wgsl!("rgb_to_rgba.wgsl"));
+
+	let pipeline_layout = wgpu_shared
+		.device
+		.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+			label:                Some("[zsw::rgb_converter] Pipeline layout"),
+			bind_group_layouts:   &[bind_group_layout],
+			push_constant_ranges: &[],
+		});
+
+	let pipeline = wgpu_shared
+		.device
+		.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+			label:               Some("[zsw::rgb_converter] Compute pipeline"),
+			layout:              Some(&pipeline_layout),
+			module:              &shader,
+			entry_point:         "cs_main",
+			compilation_options: wgpu::PipelineCompilationOptions::default(),
+			cache:               wgpu_shared.pipeline_cache.as_ref().map(zsw_wgpu::PipelineCache::cache),
+		});
+
+	// Save the pipeline cache, so the next run can reuse this pipeline
+	if let Some(pipeline_cache) = &wgpu_shared.pipeline_cache {
+		if let Err(err) = pipeline_cache.save() {
+			tracing::warn!(?err, "Unable to save pipeline cache");
+		}
+	}
+
+	pipeline
+}
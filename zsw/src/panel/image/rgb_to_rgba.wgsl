@@ -0,0 +1,27 @@
+//! Rgb to rgba conversion
+
+/// Rgb image data, with 3 bytes per pixel, packed into words
+@group(0) @binding(0)
+var<storage, read> rgb: array<u32>;
+
+/// Output rgba texture
+@group(0) @binding(1)
+var rgba: texture_storage_2d<rgba8unorm, write>;
+
+/// Reads the byte at `idx` of the rgb image data
+fn rgb_byte(idx: u32) -> f32 {
+	let word = rgb[idx / 4u];
+	let byte = (word >> ((idx % 4u) * 8u)) & 0xFFu;
+	return f32(byte) / 255.0;
+}
+
+@compute @workgroup_size(8, 8)
+fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+	let size = textureDimensions(rgba);
+	if id.x >= size.x || id.y >= size.y {
+		return;
+	}
+
+	let idx = (id.y * size.x + id.x) * 3u;
+	textureStore(rgba, id.xy, vec4<f32>(rgb_byte(idx), rgb_byte(idx + 1u), rgb_byte(idx + 2u), 1.0));
+}
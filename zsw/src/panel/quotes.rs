@@ -0,0 +1,170 @@
+//! Panel quotes
+//!
+//! Quotes are rendered into images, so they can be displayed (and
+//! transitioned between) just like any other panel image.
+
+// Imports
+use {
+	super::{ser, text::TextRenderer},
+	anyhow::Context,
+	cgmath::Vector2,
+	image::DynamicImage,
+	std::{fs, path::Path},
+	zsw_error::AppError,
+};
+
+/// Panel quotes
+#[derive(Debug)]
+pub struct PanelQuotes {
+	/// Quotes file
+	path: Box<Path>,
+
+	/// All quotes
+	quotes: Vec<Quote>,
+
+	/// Text renderer
+	text_renderer: TextRenderer,
+
+	/// Index of the next quote
+	next_idx: usize,
+}
+
+impl PanelQuotes {
+	/// Loads the quotes and font of `config`
+	pub fn load(config: ser::PanelQuotes) -> Result<Self, AppError> {
+		let quotes = self::load_quotes(&config.path).context("Unable to load quotes")?;
+		if quotes.is_empty() {
+			return Err(anyhow::anyhow!("Quotes file {:?} had no quotes", config.path).into());
+		}
+
+		let text_renderer = TextRenderer::new(config.style).context("Unable to create text renderer")?;
+
+		Ok(Self {
+			path: config.path.into(),
+			quotes,
+			text_renderer,
+			next_idx: 0,
+		})
+	}
+
+	/// Returns the configuration
+	#[cfg(feature = "menu")]
+	pub fn config(&self) -> ser::PanelQuotes {
+		ser::PanelQuotes {
+			path:  self.path.to_path_buf(),
+			style: self.text_renderer.style().clone(),
+		}
+	}
+
+	/// Returns the path of the quotes file
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Renders the next quote into an image of `size`
+	pub fn render_next(&mut self, size: Vector2<u32>) -> DynamicImage {
+		let quote = &self.quotes[self.next_idx];
+		self.next_idx = (self.next_idx + 1) % self.quotes.len();
+
+		// Note: We leave a margin of one font size around the text
+		let margin = self.text_renderer.style().font_size;
+		let max_width = (size.x as f32 - 2.0 * margin).max(0.0);
+		let mut lines = self.text_renderer.wrap(&quote.text, max_width);
+		if let Some(author) = &quote.author {
+			lines.push(String::new());
+			lines.extend(self.text_renderer.wrap(&format!("— {author}"), max_width));
+		}
+
+		// Then draw each line, centered
+		let mut image = self.text_renderer.background(size);
+		let line_height = self.text_renderer.line_height();
+		let text_height = line_height * lines.len() as f32;
+		let mut y = (size.y as f32 - text_height) / 2.0;
+		for line in &lines {
+			let x = (size.x as f32 - self.text_renderer.line_width(line)) / 2.0;
+			self.text_renderer.draw_line(&mut image, line, Vector2::new(x, y));
+			y += line_height;
+		}
+
+		DynamicImage::ImageRgba8(image)
+	}
+}
+
+/// Quote
+#[derive(Clone, Debug)]
+#[derive(serde::Deserialize)]
+struct Quote {
+	/// Text
+	text: String,
+
+	/// Author
+	#[serde(default)]
+	author: Option<String>,
+}
+
+/// Loads all quotes from `path`
+fn load_quotes(path: &Path) -> Result<Vec<Quote>, AppError> {
+	let contents = fs::read_to_string(path).context("Unable to read quotes file")?;
+	let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+	self::parse_quotes(&contents, is_toml)
+}
+
+/// Parses all quotes from `contents`.
+///
+/// If `is_toml`, parses them as a toml quotes file, else as a quote per line
+fn parse_quotes(contents: &str, is_toml: bool) -> Result<Vec<Quote>, AppError> {
+	/// Quotes file
+	#[derive(serde::Deserialize)]
+	struct QuotesFile {
+		quotes: Vec<Quote>,
+	}
+
+	let quotes = match is_toml {
+		true =>
+			toml::from_str::<QuotesFile>(contents)
+				.context("Unable to parse quotes file")?
+				.quotes,
+		false => contents
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty())
+			.map(|line| Quote {
+				text:   line.to_owned(),
+				author: None,
+			})
+			.collect(),
+	};
+
+	Ok(quotes)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn toml_quotes() {
+		let quotes_toml = "[[quotes]]\ntext = \"First\"\nauthor = \"Someone\"\n\n[[quotes]]\ntext = \"Second\"\n";
+		let quotes = self::parse_quotes(quotes_toml, true).expect("Unable to parse quotes");
+		let quotes = quotes
+			.iter()
+			.map(|quote| (quote.text.as_str(), quote.author.as_deref()))
+			.collect::<Vec<_>>();
+		assert_eq!(quotes, [("First", Some("Someone")), ("Second", None)], "Wrong quotes");
+	}
+
+	#[test]
+	fn line_quotes() {
+		let quotes = self::parse_quotes("First\n\n  Second  \n", false).expect("Unable to parse quotes");
+		let quotes = quotes.iter().map(|quote| quote.text.as_str()).collect::<Vec<_>>();
+		assert_eq!(quotes, ["First", "Second"], "Wrong quotes");
+	}
+
+	#[test]
+	fn invalid_toml_quotes() {
+		assert!(
+			self::parse_quotes("[[quotes]]\nauthor = \"Someone\"\n", true).is_err(),
+			"Quote without text was parsed"
+		);
+	}
+}
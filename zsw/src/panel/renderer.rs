@@ -1,24 +1,30 @@
 //! Panels renderer
 
 // Modules
+mod error;
 mod uniform;
 mod vertex;
 
 // Exports
-pub use self::{uniform::PanelUniforms, vertex::PanelVertex};
+pub use self::{
+	error::RenderError,
+	uniform::{PanelUniforms, MAX_UNIFORMS_SIZE},
+	vertex::PanelVertex,
+};
 
 // Imports
 use {
 	self::uniform::PanelImageUniforms,
-	super::{Panel, PanelImage},
-	crate::panel::PanelGeometry,
-	anyhow::Context,
-	cgmath::Point2,
-	std::path::{Path, PathBuf},
+	super::{Panel, PanelImage, PanelKind, PanelSamplers, PanelScaleMode},
+	crate::{panel::PanelGeometry, window::Monitor},
+	cgmath::{Point2, Vector2, Vector3},
+	std::{
+		path::{Path, PathBuf},
+		time::Duration,
+	},
 	wgpu::util::DeviceExt,
 	winit::dpi::PhysicalSize,
-	zsw_error::AppError,
-	zsw_util::Tpp,
+	zsw_util::{Rect, Tpp},
 	zsw_wgpu::{FrameRender, WgpuRenderer, WgpuShared},
 };
 
@@ -30,6 +36,12 @@ pub struct PanelsRendererLayouts {
 
 	/// Image bind group layout
 	pub image_bind_group_layout: wgpu::BindGroupLayout,
+
+	/// Noise bind group layout
+	pub noise_bind_group_layout: wgpu::BindGroupLayout,
+
+	/// Image samplers
+	pub samplers: PanelSamplers,
 }
 
 /// Panels renderer shader
@@ -40,6 +52,11 @@ pub struct PanelsRendererShader {
 
 	/// Shader path
 	pub shader_path: PathBuf,
+
+	/// Shader generation.
+	///
+	/// Incremented whenever the shader files change, to reload all shaders.
+	pub generation: u64,
 }
 
 /// Panels renderer
@@ -53,10 +70,19 @@ pub struct PanelsRendererShader {
 //       rendered as just a quad. Their position is determined by
 //       the matrix sent in the uniform. Their UVs are also determined
 //       via the uniforms.
+// Note: The pipelines and msaa framebuffer are only created once a panel
+//       needs them, so we don't waste any time on startup, or any memory
+//       while no panels are displayed.
 #[derive(Debug)]
 pub struct PanelsRenderer {
 	/// Render pipeline
-	render_pipeline: wgpu::RenderPipeline,
+	render_pipeline: Option<wgpu::RenderPipeline>,
+
+	/// Calibration render pipeline
+	calibration_render_pipeline: Option<wgpu::RenderPipeline>,
+
+	/// Calibration shader path
+	calibration_shader_path: PathBuf,
 
 	/// Vertex buffer
 	vertices: wgpu::Buffer,
@@ -64,62 +90,126 @@ pub struct PanelsRenderer {
 	/// Index buffer
 	indices: wgpu::Buffer,
 
+	/// Noise bind group.
+	///
+	/// Shared by all panels, for shaders that need noise.
+	noise_bind_group: wgpu::BindGroup,
+
 	/// Msaa frame-buffer
-	msaa_framebuffer: wgpu::TextureView,
+	msaa_framebuffer: Option<wgpu::TextureView>,
 
 	/// Current shader
 	cur_shader: PanelShader,
+
+	/// Current shader generation
+	cur_shader_generation: u64,
 }
 
 impl PanelsRenderer {
 	/// Creates a new renderer for the panels
 	pub fn new(
-		wgpu_renderer: &WgpuRenderer,
 		wgpu_shared: &WgpuShared,
 		shader_path: PathBuf,
-	) -> Result<(Self, PanelsRendererLayouts, PanelsRendererShader), AppError> {
+		calibration_shader_path: PathBuf,
+	) -> (Self, PanelsRendererLayouts, PanelsRendererShader) {
 		// Create the index / vertex buffer
 		let indices = self::create_indices(wgpu_shared);
 		let vertices = self::create_vertices(wgpu_shared);
 
-		// Create the framebuffer
-		let msaa_framebuffer = self::create_msaa_framebuffer(wgpu_renderer, wgpu_shared, wgpu_renderer.surface_size());
-
 		// Create the group layouts
 		let uniforms_bind_group_layout = self::create_uniforms_bind_group_layout(wgpu_shared);
 		let image_bind_group_layout = self::create_image_bind_group_layout(wgpu_shared);
+		let noise_bind_group_layout = self::create_noise_bind_group_layout(wgpu_shared);
+
+		// Create the noise
+		let noise_bind_group = self::create_noise_bind_group(wgpu_shared, &noise_bind_group_layout);
 
 		// By default use the empty shader
 		let shader = PanelShader::None;
 
-		Ok((
+		(
 			Self {
-				render_pipeline: self::create_render_pipeline(
-					wgpu_renderer,
-					wgpu_shared,
-					&uniforms_bind_group_layout,
-					&image_bind_group_layout,
-					shader,
-					&shader_path,
-				)
-				.context("Unable to create render pipeline")?,
+				render_pipeline: None,
+				calibration_render_pipeline: None,
+				calibration_shader_path,
 				vertices,
 				indices,
-				msaa_framebuffer,
+				noise_bind_group,
+				msaa_framebuffer: None,
 				cur_shader: shader,
+				cur_shader_generation: 0,
 			},
 			PanelsRendererLayouts {
 				uniforms_bind_group_layout,
 				image_bind_group_layout,
+				noise_bind_group_layout,
+				samplers: PanelSamplers::new(),
 			},
-			PanelsRendererShader { shader, shader_path },
-		))
+			PanelsRendererShader {
+				shader,
+				shader_path,
+				generation: 0,
+			},
+		)
 	}
 
 	/// Resizes the buffer
-	pub fn resize(&mut self, wgpu_renderer: &WgpuRenderer, wgpu_shared: &WgpuShared, size: PhysicalSize<u32>) {
+	pub fn resize(&mut self, size: PhysicalSize<u32>) {
+		// Note: It'll be re-created with the new size once it's needed
 		tracing::debug!("Resizing msaa framebuffer to {}x{}", size.width, size.height);
-		self.msaa_framebuffer = self::create_msaa_framebuffer(wgpu_renderer, wgpu_shared, size);
+		self.msaa_framebuffer = None;
+	}
+
+	/// Creates all resources needed to render `panels`, if not yet created
+	fn create_resources(
+		&mut self,
+		wgpu_renderer: &WgpuRenderer,
+		wgpu_shared: &WgpuShared,
+		layouts: &PanelsRendererLayouts,
+		panels: &[&Panel],
+		shader: &PanelsRendererShader,
+	) -> Result<(), RenderError> {
+		if self.render_pipeline.is_none() &&
+			panels
+				.iter()
+				.any(|panel| matches!(panel.kind, PanelKind::Images | PanelKind::Quotes | PanelKind::Ticker))
+		{
+			tracing::debug!(shader = ?self.cur_shader, "Creating render pipeline");
+			self.render_pipeline = Some(self::create_render_pipeline(
+				wgpu_renderer,
+				wgpu_shared,
+				&layouts.uniforms_bind_group_layout,
+				&layouts.image_bind_group_layout,
+				&layouts.noise_bind_group_layout,
+				self::shader_define(self.cur_shader),
+				&shader.shader_path,
+			)?);
+		}
+
+		if self.calibration_render_pipeline.is_none() &&
+			panels
+				.iter()
+				.any(|panel| matches!(panel.kind, PanelKind::Calibration { .. }))
+		{
+			tracing::debug!("Creating calibration render pipeline");
+			self.calibration_render_pipeline = Some(self::create_render_pipeline(
+				wgpu_renderer,
+				wgpu_shared,
+				&layouts.uniforms_bind_group_layout,
+				&layouts.image_bind_group_layout,
+				&layouts.noise_bind_group_layout,
+				"calibration",
+				&self.calibration_shader_path,
+			)?);
+		}
+
+		if self.msaa_framebuffer.is_none() && MSAA_SAMPLES != 1 && !panels.is_empty() {
+			let size = wgpu_renderer.surface_size();
+			tracing::debug!("Creating msaa framebuffer with size {}x{}", size.width, size.height);
+			self.msaa_framebuffer = Some(self::create_msaa_framebuffer(wgpu_renderer, wgpu_shared, size));
+		}
+
+		Ok(())
 	}
 
 	/// Updates the shader.
@@ -131,7 +221,13 @@ impl PanelsRenderer {
 			(PanelShader::Fade, PanelShader::Fade) |
 			(PanelShader::FadeWhite { .. }, PanelShader::FadeWhite { .. }) |
 			(PanelShader::FadeOut { .. }, PanelShader::FadeOut { .. }) |
-			(PanelShader::FadeIn { .. }, PanelShader::FadeIn { .. }) => false,
+			(PanelShader::FadeIn { .. }, PanelShader::FadeIn { .. }) |
+			(PanelShader::Dissolve { .. }, PanelShader::Dissolve { .. }) |
+			(PanelShader::Pixelate { .. }, PanelShader::Pixelate { .. }) |
+			(PanelShader::PageCurl { .. }, PanelShader::PageCurl { .. }) |
+			(PanelShader::Cube { .. }, PanelShader::Cube { .. }) |
+			(PanelShader::Slide { .. }, PanelShader::Slide { .. }) => false,
+			(PanelShader::Wipe { kind: prev_kind, .. }, PanelShader::Wipe { kind, .. }) if prev_kind == kind => false,
 
 			// Else reload it
 			_ => true,
@@ -142,34 +238,82 @@ impl PanelsRenderer {
 		needs_reload
 	}
 
-	/// Renders a panel
-	#[expect(clippy::too_many_arguments)] // TODO: Refactor
+	/// Returns the minimum interval between frames the visible panels need.
+	///
+	/// Returns `None` if any of them needs the maximum frame rate, or there are none.
+	pub fn frame_interval_hint(panels: &[Panel]) -> Option<Duration> {
+		self::visible_panels(panels)
+			.into_iter()
+			.map(Panel::frame_interval_hint)
+			// Note: `None` orders before any interval, so it wins over all of them
+			.reduce(Option::min)
+			.flatten()
+	}
+
+	/// Clears the whole surface to `color`, instead of rendering any panels
+	pub fn render_blank(frame: &mut FrameRender, color: Vector3<f32>) {
+		let render_pass_descriptor = wgpu::RenderPassDescriptor {
+			label:                    Some("[zsw::panel_renderer] Blank render pass"),
+			color_attachments:        &[Some(wgpu::RenderPassColorAttachment {
+				view:           &frame.surface_view,
+				resolve_target: None,
+				ops:            wgpu::Operations {
+					load:  wgpu::LoadOp::Clear(wgpu::Color {
+						r: f64::from(color.x),
+						g: f64::from(color.y),
+						b: f64::from(color.z),
+						a: 1.0,
+					}),
+					store: wgpu::StoreOp::Store,
+				},
+			})],
+			depth_stencil_attachment: None,
+			timestamp_writes:         None,
+			occlusion_query_set:      None,
+		};
+		let _ = frame.encoder.begin_render_pass(&render_pass_descriptor);
+	}
+
+	/// Renders all panels.
+	///
+	/// Returns the number of draws
+	#[expect(clippy::too_many_arguments, clippy::too_many_lines)] // TODO: Refactor
 	pub fn render(
 		&mut self,
 		frame: &mut FrameRender,
 		wgpu_renderer: &WgpuRenderer,
 		wgpu_shared: &WgpuShared,
 		layouts: &PanelsRendererLayouts,
+		surface_size: PhysicalSize<u32>,
 		cursor_pos: Point2<i32>,
-		panels: impl IntoIterator<Item = &'_ Panel>,
+		shift: Vector2<i32>,
+		tint: Vector3<f32>,
+		panels: &[Panel],
+		monitors: &[Monitor],
 		shader: &PanelsRendererShader,
-	) -> Result<(), AppError> {
+	) -> Result<usize, RenderError> {
 		// Update the shader, if requested
-		if self.update_shader(shader.shader) {
-			self.render_pipeline = self::create_render_pipeline(
-				wgpu_renderer,
-				wgpu_shared,
-				&layouts.uniforms_bind_group_layout,
-				&layouts.image_bind_group_layout,
-				shader.shader,
-				&shader.shader_path,
-			)
-			.context("Unable to create render pipeline")?;
+		// Note: We clamp the parameters here, so out of range values never reach the shaders
+		if self.update_shader(shader.shader.clamped()) {
+			self.render_pipeline = None;
 		}
 
+		// Reload all shaders, if they changed
+		if self.cur_shader_generation != shader.generation {
+			self.cur_shader_generation = shader.generation;
+			self.render_pipeline = None;
+			self.calibration_render_pipeline = None;
+		}
+
+		// Create any resources we need for the visible panels
+		let panels = self::visible_panels(panels);
+		self.create_resources(wgpu_renderer, wgpu_shared, layouts, &panels, shader)?;
+
 		// Create the render pass for all panels
-		let render_pass_color_attachment = match MSAA_SAMPLES {
-			1 => wgpu::RenderPassColorAttachment {
+		// Note: If we don't have an msaa framebuffer, there's nothing to draw, so we
+		//       can just clear the surface directly.
+		let render_pass_color_attachment = match &self.msaa_framebuffer {
+			None => wgpu::RenderPassColorAttachment {
 				view:           &frame.surface_view,
 				resolve_target: None,
 				ops:            wgpu::Operations {
@@ -182,8 +326,8 @@ impl PanelsRenderer {
 					store: wgpu::StoreOp::Store,
 				},
 			},
-			_ => wgpu::RenderPassColorAttachment {
-				view:           &self.msaa_framebuffer,
+			Some(msaa_framebuffer) => wgpu::RenderPassColorAttachment {
+				view:           msaa_framebuffer,
 				resolve_target: Some(&frame.surface_view),
 				ops:            wgpu::Operations {
 					load:  wgpu::LoadOp::Clear(wgpu::Color {
@@ -203,38 +347,73 @@ impl PanelsRenderer {
 			timestamp_writes:         None,
 			occlusion_query_set:      None,
 		};
-		let surface_size = frame.surface_size();
+		let framebuffer_size = frame.surface_size();
 		let mut render_pass = frame.encoder.begin_render_pass(&render_pass_descriptor);
 
-		// Set our shared pipeline, indices, vertices and uniform bind group
-		render_pass.set_pipeline(&self.render_pipeline);
+		// Set our shared indices and vertices
 		render_pass.set_index_buffer(self.indices.slice(..), wgpu::IndexFormat::Uint32);
 		render_pass.set_vertex_buffer(0, self.vertices.slice(..));
+		render_pass.set_bind_group(2, &self.noise_bind_group, &[]);
 
 		// And draw each panel
+		let surface_rect = Rect {
+			pos:  Point2::new(0, 0),
+			size: Vector2::new(surface_size.width, surface_size.height),
+		};
+		let mut draws = 0;
 		for panel in panels {
-			// Bind the panel-shared image bind group
-			render_pass.set_bind_group(1, panel.images.image_bind_group(), &[]);
+			// Set the pipeline
+			// Note: The image bind group is set per-geometry, since they may show distinct images.
+			let render_pipeline = match panel.kind {
+				PanelKind::Images | PanelKind::Quotes | PanelKind::Ticker => &self.render_pipeline,
+				PanelKind::Calibration { .. } => &self.calibration_render_pipeline,
+			};
+			render_pass.set_pipeline(
+				render_pipeline
+					.as_ref()
+					.expect("Render pipeline should have been created"),
+			);
+
+			// If the panel is pinned to a monitor, only render it within that monitor
+			// Note: If the monitor isn't available, we render it everywhere, so the panel isn't lost
+			let monitor_geometry = panel
+				.state
+				.monitor
+				.as_ref()
+				.and_then(|monitor_name| monitors.iter().find(|monitor| monitor.name == *monitor_name))
+				.map(|monitor| monitor.geometry);
+			let clip_rect = match monitor_geometry {
+				Some(monitor_geometry) => match monitor_geometry.intersection(surface_rect) {
+					Some(clip_rect) => clip_rect,
+					None => continue,
+				},
+				None => surface_rect,
+			};
+			let scissor_rect = self::scissor_rect(clip_rect, surface_rect.size, framebuffer_size);
+			render_pass.set_scissor_rect(
+				scissor_rect.pos.x,
+				scissor_rect.pos.y,
+				scissor_rect.size.x,
+				scissor_rect.size.y,
+			);
+
+			for (geometry_idx, geometry) in panel.geometries.iter().enumerate() {
+				// Skip any geometries outside of the surface, or monitor
+				if geometry.geometry.intersection(clip_rect).is_none() {
+					continue;
+				}
+
+				// Note: Calibration panels don't use any images, but the bind group
+				//       is still part of their pipeline layout.
+				let images = panel.images_of(geometry_idx);
+				render_pass.set_bind_group(1, images.image_bind_group(), &[]);
 
-			for geometry in &panel.geometries {
 				// Calculate the position matrix for the panel
-				let pos_matrix = geometry.pos_matrix(surface_size);
-
-				let create_uniforms = |image: &PanelImage| {
-					let ratio = PanelGeometry::image_ratio(geometry.geometry.size, image.size());
-					let (parallax_ratio, parallax_offset) = geometry.parallax_ratio_offset(
-						ratio,
-						cursor_pos,
-						panel.state.parallax.ratio,
-						panel.state.parallax.exp,
-						panel.state.parallax.reverse,
-					);
-					PanelImageUniforms::new(ratio, parallax_ratio, parallax_offset, image.swap_dir())
-				};
+				let pos_matrix = geometry.geometry.pos_matrix(surface_rect.size, shift);
 
-				let uniforms_prev = create_uniforms(panel.images.prev());
-				let uniforms_cur = create_uniforms(panel.images.cur());
-				let uniforms_next = create_uniforms(panel.images.next());
+				let uniforms_prev = self::create_image_uniforms(panel, geometry, images.prev(), cursor_pos);
+				let uniforms_cur = self::create_image_uniforms(panel, geometry, images.cur(), cursor_pos);
+				let uniforms_next = self::create_image_uniforms(panel, geometry, images.next(), cursor_pos);
 
 				/// Writes uniforms with `$extra` into `panel.uniforms`
 				macro write_uniforms($extra:expr) {{
@@ -243,31 +422,168 @@ impl PanelsRenderer {
 						uniforms_prev,
 						uniforms_cur,
 						uniforms_next,
-						panel.state.fade_point_norm(),
-						panel.state.progress_norm(),
+						panel.state.fade.fade_point_norm(),
+						panel.state.fade.progress_norm(),
+						tint,
 						$extra,
 					);
-					wgpu_shared
-						.queue
-						.write_buffer(&geometry.uniforms, 0, uniforms.as_bytes())
+					uniforms.write(wgpu_shared, &geometry.uniforms)
 				}}
 
 				// Update the uniforms
-				match self.cur_shader {
-					PanelShader::None => write_uniforms!(uniform::NoneExtra {}),
-					PanelShader::Fade => write_uniforms!(uniform::FadeExtra {}),
-					PanelShader::FadeWhite { strength } => write_uniforms!(uniform::FadeWhiteExtra { strength }),
-					PanelShader::FadeOut { strength } => write_uniforms!(uniform::FadeOutExtra { strength }),
-					PanelShader::FadeIn { strength } => write_uniforms!(uniform::FadeInExtra { strength }),
+				match (panel.kind, self.cur_shader) {
+					(PanelKind::Calibration { pattern }, _) => write_uniforms!(uniform::CalibrationExtra {
+						size: geometry
+							.geometry
+							.size
+							.cast::<f32>()
+							.expect("Panel size didn't fit into an `f32`")
+							.into(),
+						pattern,
+					}),
+					(_, PanelShader::None) => write_uniforms!(uniform::NoneExtra {}),
+					(_, PanelShader::Fade) => write_uniforms!(uniform::FadeExtra {}),
+					(_, PanelShader::FadeWhite { strength }) => write_uniforms!(uniform::FadeWhiteExtra { strength }),
+					(_, PanelShader::FadeOut { strength }) => write_uniforms!(uniform::FadeOutExtra { strength }),
+					(_, PanelShader::FadeIn { strength }) => write_uniforms!(uniform::FadeInExtra { strength }),
+					(_, PanelShader::Dissolve { grain, feather }) => write_uniforms!(uniform::DissolveExtra {
+						feather,
+						size: geometry
+							.geometry
+							.size
+							.cast::<f32>()
+							.expect("Panel size didn't fit into an `f32`")
+							.into(),
+						grain,
+					}),
+					(_, PanelShader::Pixelate { block_size }) => write_uniforms!(uniform::PixelateExtra {
+						block_size,
+						size: geometry
+							.geometry
+							.size
+							.cast::<f32>()
+							.expect("Panel size didn't fit into an `f32`")
+							.into(),
+					}),
+					(_, PanelShader::PageCurl { radius }) => write_uniforms!(uniform::PageCurlExtra { radius }),
+					(_, PanelShader::Cube { distance }) => write_uniforms!(uniform::CubeExtra {
+						distance,
+						size: geometry
+							.geometry
+							.size
+							.cast::<f32>()
+							.expect("Panel size didn't fit into an `f32`")
+							.into(),
+					}),
+					(_, PanelShader::Slide { direction }) => write_uniforms!(uniform::SlideExtra {
+						blur:      panel.state.transition_blur,
+						direction: direction.uvs_direction(),
+					}),
+					(_, PanelShader::Wipe { angle, feather, .. }) => write_uniforms!(uniform::WipeExtra {
+						feather,
+						size: geometry
+							.geometry
+							.size
+							.cast::<f32>()
+							.expect("Panel size didn't fit into an `f32`")
+							.into(),
+						angle: angle.to_radians(),
+					}),
 				};
 
 				// Then bind the geometry uniforms and draw
 				render_pass.set_bind_group(0, &geometry.uniforms_bind_group, &[]);
 				render_pass.draw_indexed(0..6, 0, 0..1);
+				draws += 1;
 			}
 		}
 
-		Ok(())
+		Ok(draws)
+	}
+}
+
+/// Returns all visible panels.
+///
+/// If any panel is soloed, only those are visible.
+fn visible_panels(panels: &[Panel]) -> Vec<&Panel> {
+	let any_soloed = panels.iter().any(|panel| panel.state.soloed);
+	panels
+		.iter()
+		.filter(|panel| !panel.state.muted && (!any_soloed || panel.state.soloed))
+		.collect()
+}
+
+/// Creates the uniforms of an image of a panel's geometry
+fn create_image_uniforms(
+	panel: &Panel,
+	geometry: &PanelGeometry,
+	image: &PanelImage,
+	cursor_pos: Point2<i32>,
+) -> PanelImageUniforms {
+	let mut ratio = PanelGeometry::image_ratio(geometry.geometry.size, image.size(), panel.state.scale_mode);
+	if panel.state.scale_mode == PanelScaleMode::Tile {
+		ratio /= panel.state.tile.scale;
+	}
+	let (parallax_ratio, parallax_offset) = geometry.parallax_ratio_offset(
+		ratio,
+		cursor_pos,
+		panel.state.parallax.ratio,
+		panel.state.parallax.exp,
+		panel.state.parallax.reverse,
+	);
+
+	let alignment = panel.state.alignment.offset();
+	let scroll = panel.state.scale_mode.scrolls() && alignment.is_none();
+	let alignment = alignment.unwrap_or(Vector2::new(0.5, 0.5));
+	let tile = panel.state.tiles();
+	let tile_scroll = match panel.state.scale_mode {
+		PanelScaleMode::Tile => panel.state.tile.scroll,
+		_ => [0.0; 2],
+	};
+
+	PanelImageUniforms::new(
+		ratio,
+		parallax_ratio,
+		parallax_offset,
+		image.swap_dir(),
+		scroll,
+		alignment,
+		tile,
+		tile_scroll,
+	)
+}
+
+/// Returns the scissor rect, in framebuffer pixels, for `clip_rect`, in `surface_size` pixels.
+///
+/// `clip_rect` must be within the surface.
+fn scissor_rect(
+	clip_rect: Rect<i32, u32>,
+	surface_size: Vector2<u32>,
+	framebuffer_size: PhysicalSize<u32>,
+) -> Rect<u32, u32> {
+	/// Scales `value` from the surface to the framebuffer, rounding with `round`
+	#[expect(clippy::cast_sign_loss, clippy::cast_possible_truncation)] // It's clamped to the framebuffer
+	fn scale(value: i32, surface_len: u32, framebuffer_len: u32, round: fn(f64) -> f64) -> u32 {
+		let value = f64::from(value) * f64::from(framebuffer_len) / f64::from(surface_len.max(1));
+		round(value).clamp(0.0, f64::from(framebuffer_len)) as u32
+	}
+
+	// Note: We round outwards, so no pixel of the clip rect is left out
+
+	let min = clip_rect.min();
+	let max = clip_rect.max();
+	let min = Point2::new(
+		scale(min.x, surface_size.x, framebuffer_size.width, f64::floor),
+		scale(min.y, surface_size.y, framebuffer_size.height, f64::floor),
+	);
+	let max = Point2::new(
+		scale(max.x, surface_size.x, framebuffer_size.width, f64::ceil),
+		scale(max.y, surface_size.y, framebuffer_size.height, f64::ceil),
+	);
+
+	Rect {
+		pos:  min,
+		size: max - min,
 	}
 }
 
@@ -300,23 +616,19 @@ fn create_render_pipeline(
 	wgpu_shared: &WgpuShared,
 	uniforms_bind_group_layout: &wgpu::BindGroupLayout,
 	image_bind_group_layout: &wgpu::BindGroupLayout,
-	shader: PanelShader,
+	noise_bind_group_layout: &wgpu::BindGroupLayout,
+	shader_define: &str,
 	shader_path: &Path,
-) -> Result<wgpu::RenderPipeline, AppError> {
-	tracing::debug!(?shader, ?shader_path, "Creating render pipeline for shader");
+) -> Result<wgpu::RenderPipeline, RenderError> {
+	tracing::debug!(?shader_define, ?shader_path, "Creating render pipeline for shader");
 
 	// Parse the shader
 	let mut tpp = Tpp::new();
-	match shader {
-		PanelShader::None => tpp.define("SHADER", "none"),
-		PanelShader::Fade => tpp.define("SHADER", "fade"),
-		PanelShader::FadeWhite { .. } => tpp.define("SHADER", "fade-white"),
-		PanelShader::FadeOut { .. } => tpp.define("SHADER", "fade-out"),
-		PanelShader::FadeIn { .. } => tpp.define("SHADER", "fade-in"),
-	};
-	let shader_contents = tpp
-		.process(shader_path)
-		.with_context(|| format!("Unable to preprocess shader {shader_path:?}"))?;
+	tpp.define("SHADER", shader_define);
+	let shader_contents = tpp.process(shader_path).map_err(|err| RenderError::Shader {
+		shader_path: shader_path.to_path_buf(),
+		err,
+	})?;
 
 	// Load the shader
 	let shader_descriptor = wgpu::ShaderModuleDescriptor {
@@ -328,7 +640,11 @@ fn create_render_pipeline(
 	// Create the pipeline layout
 	let render_pipeline_layout_descriptor = wgpu::PipelineLayoutDescriptor {
 		label:                Some("[zsw::panel_renderer] Render pipeline layout"),
-		bind_group_layouts:   &[uniforms_bind_group_layout, image_bind_group_layout],
+		bind_group_layouts:   &[
+			uniforms_bind_group_layout,
+			image_bind_group_layout,
+			noise_bind_group_layout,
+		],
 		push_constant_ranges: &[],
 	};
 	let render_pipeline_layout = wgpu_shared
@@ -372,10 +688,39 @@ fn create_render_pipeline(
 			compilation_options: wgpu::PipelineCompilationOptions::default(),
 		}),
 		multiview:     None,
-		cache:         None,
+		cache:         wgpu_shared.pipeline_cache.as_ref().map(zsw_wgpu::PipelineCache::cache),
 	};
+	let render_pipeline = wgpu_shared.device.create_render_pipeline(&render_pipeline_descriptor);
 
-	Ok(wgpu_shared.device.create_render_pipeline(&render_pipeline_descriptor))
+	// Save the pipeline cache, so the next run can reuse this pipeline
+	if let Some(pipeline_cache) = &wgpu_shared.pipeline_cache {
+		if let Err(err) = pipeline_cache.save() {
+			tracing::warn!(?err, "Unable to save pipeline cache");
+		}
+	}
+
+	Ok(render_pipeline)
+}
+
+/// Returns the `SHADER` define for a shader
+fn shader_define(shader: PanelShader) -> &'static str {
+	match shader {
+		PanelShader::None => "none",
+		PanelShader::Fade => "fade",
+		PanelShader::FadeWhite { .. } => "fade-white",
+		PanelShader::FadeOut { .. } => "fade-out",
+		PanelShader::FadeIn { .. } => "fade-in",
+		PanelShader::Dissolve { .. } => "dissolve",
+		PanelShader::Pixelate { .. } => "pixelate",
+		PanelShader::PageCurl { .. } => "page-curl",
+		PanelShader::Cube { .. } => "cube",
+		PanelShader::Slide { .. } => "slide",
+		PanelShader::Wipe { kind, .. } => match kind {
+			PanelWipeKind::Linear => "wipe-linear",
+			PanelWipeKind::Iris => "wipe-iris",
+			PanelWipeKind::Diagonal => "wipe-diagonal",
+		},
+	}
 }
 
 /// Creates the msaa framebuffer
@@ -411,6 +756,9 @@ fn create_msaa_framebuffer(
 /// MSAA samples
 const MSAA_SAMPLES: u32 = 4;
 
+/// Noise texture size
+const NOISE_SIZE: u32 = 256;
+
 /// Creates the uniforms bind group layout
 fn create_uniforms_bind_group_layout(wgpu_shared: &WgpuShared) -> wgpu::BindGroupLayout {
 	let descriptor = wgpu::BindGroupLayoutDescriptor {
@@ -477,17 +825,130 @@ fn create_image_bind_group_layout(wgpu_shared: &WgpuShared) -> wgpu::BindGroupLa
 	wgpu_shared.device.create_bind_group_layout(&descriptor)
 }
 
+/// Creates the noise bind group layout
+fn create_noise_bind_group_layout(wgpu_shared: &WgpuShared) -> wgpu::BindGroupLayout {
+	let descriptor = wgpu::BindGroupLayoutDescriptor {
+		label:   Some("[zsw::panel_renderer] Noise bind group layout"),
+		entries: &[
+			wgpu::BindGroupLayoutEntry {
+				binding:    0,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty:         wgpu::BindingType::Texture {
+					multisampled:   false,
+					view_dimension: wgpu::TextureViewDimension::D2,
+					sample_type:    wgpu::TextureSampleType::Float { filterable: true },
+				},
+				count:      None,
+			},
+			wgpu::BindGroupLayoutEntry {
+				binding:    1,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty:         wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+				count:      None,
+			},
+		],
+	};
+
+	wgpu_shared.device.create_bind_group_layout(&descriptor)
+}
+
+/// Creates the noise bind group.
+///
+/// The noise is random on each run, and tiles seamlessly.
+fn create_noise_bind_group(wgpu_shared: &WgpuShared, layout: &wgpu::BindGroupLayout) -> wgpu::BindGroup {
+	let noise = (0..NOISE_SIZE * NOISE_SIZE)
+		.map(|_| rand::random::<u8>())
+		.collect::<Vec<_>>();
+	let texture_descriptor = wgpu::TextureDescriptor {
+		label:           Some("[zsw::panel_renderer] Noise texture"),
+		size:            wgpu::Extent3d {
+			width:                 NOISE_SIZE,
+			height:                NOISE_SIZE,
+			depth_or_array_layers: 1,
+		},
+		mip_level_count: 1,
+		sample_count:    1,
+		dimension:       wgpu::TextureDimension::D2,
+		format:          wgpu::TextureFormat::R8Unorm,
+		usage:           wgpu::TextureUsages::TEXTURE_BINDING,
+		view_formats:    &[],
+	};
+	let texture = wgpu_shared.device.create_texture_with_data(
+		&wgpu_shared.queue,
+		&texture_descriptor,
+		wgpu::util::TextureDataOrder::LayerMajor,
+		&noise,
+	);
+	let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+	// Note: We interpolate the noise, so it's made of smooth blobs, instead of single texels
+	let sampler_descriptor = wgpu::SamplerDescriptor {
+		label: Some("[zsw::panel_renderer] Noise sampler"),
+		address_mode_u: wgpu::AddressMode::Repeat,
+		address_mode_v: wgpu::AddressMode::Repeat,
+		mag_filter: wgpu::FilterMode::Linear,
+		min_filter: wgpu::FilterMode::Linear,
+		..wgpu::SamplerDescriptor::default()
+	};
+	let sampler = wgpu_shared.device.create_sampler(&sampler_descriptor);
+
+	let descriptor = wgpu::BindGroupDescriptor {
+		label: Some("[zsw::panel_renderer] Noise bind group"),
+		layout,
+		entries: &[
+			wgpu::BindGroupEntry {
+				binding:  0,
+				resource: wgpu::BindingResource::TextureView(&texture_view),
+			},
+			wgpu::BindGroupEntry {
+				binding:  1,
+				resource: wgpu::BindingResource::Sampler(&sampler),
+			},
+		],
+	};
+	wgpu_shared.device.create_bind_group(&descriptor)
+}
+
 /// Shader
 #[derive(PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only constructed by the settings menu
 pub enum PanelShader {
 	None,
 	Fade,
-	FadeWhite { strength: f32 },
-	FadeOut { strength: f32 },
-	FadeIn { strength: f32 },
+	FadeWhite {
+		strength: f32,
+	},
+	FadeOut {
+		strength: f32,
+	},
+	FadeIn {
+		strength: f32,
+	},
+	Dissolve {
+		grain:   f32,
+		feather: f32,
+	},
+	Pixelate {
+		block_size: f32,
+	},
+	PageCurl {
+		radius: f32,
+	},
+	Cube {
+		distance: f32,
+	},
+	Slide {
+		direction: PanelSlideDirection,
+	},
+	Wipe {
+		kind:    PanelWipeKind,
+		angle:   f32,
+		feather: f32,
+	},
 }
 impl PanelShader {
 	/// Returns this shader's name
+	#[cfg(feature = "menu")]
 	pub fn name(self) -> &'static str {
 		match self {
 			Self::None => "None",
@@ -495,6 +956,245 @@ impl PanelShader {
 			Self::FadeWhite { .. } => "Fade white",
 			Self::FadeOut { .. } => "Fade out",
 			Self::FadeIn { .. } => "Fade in",
+			Self::Dissolve { .. } => "Dissolve",
+			Self::Pixelate { .. } => "Pixelate",
+			Self::PageCurl { .. } => "Page curl",
+			Self::Cube { .. } => "Cube",
+			Self::Slide { direction } => match direction {
+				PanelSlideDirection::Left => "Slide left",
+				PanelSlideDirection::Right => "Slide right",
+				PanelSlideDirection::Up => "Slide up",
+				PanelSlideDirection::Down => "Slide down",
+			},
+			Self::Wipe { kind, .. } => match kind {
+				PanelWipeKind::Linear => "Wipe",
+				PanelWipeKind::Iris => "Iris",
+				PanelWipeKind::Diagonal => "Diagonal split",
+			},
 		}
 	}
+
+	/// Returns all shaders, with their default parameters
+	#[cfg(feature = "menu")]
+	pub fn all() -> [Self; 16] {
+		// Note: The parameters here are replaced by their defaults
+		[
+			Self::None,
+			Self::Fade,
+			Self::FadeWhite { strength: 0.0 },
+			Self::FadeOut { strength: 0.0 },
+			Self::FadeIn { strength: 0.0 },
+			Self::Dissolve {
+				grain:   0.0,
+				feather: 0.0,
+			},
+			Self::Pixelate { block_size: 0.0 },
+			Self::PageCurl { radius: 0.0 },
+			Self::Cube { distance: 0.0 },
+			Self::Slide {
+				direction: PanelSlideDirection::Left,
+			},
+			Self::Slide {
+				direction: PanelSlideDirection::Right,
+			},
+			Self::Slide {
+				direction: PanelSlideDirection::Up,
+			},
+			Self::Slide {
+				direction: PanelSlideDirection::Down,
+			},
+			Self::Wipe {
+				kind:    PanelWipeKind::Linear,
+				angle:   0.0,
+				feather: 0.0,
+			},
+			Self::Wipe {
+				kind:    PanelWipeKind::Iris,
+				angle:   0.0,
+				feather: 0.0,
+			},
+			Self::Wipe {
+				kind:    PanelWipeKind::Diagonal,
+				angle:   0.0,
+				feather: 0.0,
+			},
+		]
+		.map(Self::with_default_param)
+	}
+
+	/// Returns this shader's parameters, along with their values
+	pub fn params_mut(&mut self) -> Vec<(PanelShaderParam, &mut f32)> {
+		/// Feather parameter of all wipes and the dissolve
+		const FEATHER: PanelShaderParam = PanelShaderParam {
+			name:    "Feather",
+			min:     0.0,
+			max:     0.5,
+			default: 0.05,
+		};
+
+		match self {
+			Self::None | Self::Fade | Self::Slide { .. } => vec![],
+			Self::FadeWhite { strength } => vec![(
+				PanelShaderParam {
+					name:    "Strength",
+					min:     0.0,
+					max:     20.0,
+					default: 1.0,
+				},
+				strength,
+			)],
+			Self::FadeOut { strength } | Self::FadeIn { strength } => vec![(
+				PanelShaderParam {
+					name:    "Strength",
+					min:     0.0,
+					max:     2.0,
+					default: 0.2,
+				},
+				strength,
+			)],
+			Self::Dissolve { grain, feather } => vec![
+				(
+					PanelShaderParam {
+						name:    "Grain",
+						min:     1.0,
+						max:     64.0,
+						default: 8.0,
+					},
+					grain,
+				),
+				(FEATHER, feather),
+			],
+			Self::Pixelate { block_size } => vec![(
+				PanelShaderParam {
+					name:    "Block size",
+					min:     2.0,
+					max:     256.0,
+					default: 64.0,
+				},
+				block_size,
+			)],
+			Self::PageCurl { radius } => vec![(
+				PanelShaderParam {
+					name:    "Radius",
+					min:     0.01,
+					max:     0.5,
+					default: 0.1,
+				},
+				radius,
+			)],
+			Self::Cube { distance } => vec![(
+				PanelShaderParam {
+					name:    "Distance",
+					min:     0.5,
+					max:     10.0,
+					default: 2.0,
+				},
+				distance,
+			)],
+			// Note: The iris is always centered, so it has no direction
+			Self::Wipe {
+				kind: PanelWipeKind::Iris,
+				feather,
+				..
+			} => vec![(FEATHER, feather)],
+			Self::Wipe { kind, angle, feather } => vec![
+				(
+					PanelShaderParam {
+						name:    "Direction",
+						min:     0.0,
+						max:     360.0,
+						default: match kind {
+							PanelWipeKind::Diagonal => 45.0,
+							_ => 0.0,
+						},
+					},
+					angle,
+				),
+				(FEATHER, feather),
+			],
+		}
+	}
+
+	/// Returns this shader with it's parameters set to their defaults
+	#[cfg(feature = "menu")]
+	#[must_use]
+	pub fn with_default_param(mut self) -> Self {
+		for (param, value) in self.params_mut() {
+			*value = param.default;
+		}
+
+		self
+	}
+
+	/// Returns this shader with it's parameters clamped to their range
+	#[must_use]
+	pub fn clamped(mut self) -> Self {
+		for (param, value) in self.params_mut() {
+			*value = value.clamp(param.min, param.max);
+		}
+
+		self
+	}
+}
+
+/// Wipe kind
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only constructed by the settings menu
+pub enum PanelWipeKind {
+	/// Straight edge, sweeping across the panel
+	Linear,
+
+	/// Circle, growing from the center of the panel
+	Iris,
+
+	/// Band through the center of the panel, widening outwards
+	Diagonal,
+}
+
+/// Slide direction
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only constructed by the settings menu
+pub enum PanelSlideDirection {
+	/// Images move left, with the new image entering from the right
+	Left,
+
+	/// Images move right, with the new image entering from the left
+	Right,
+
+	/// Images move up, with the new image entering from the bottom
+	Up,
+
+	/// Images move down, with the new image entering from the top
+	Down,
+}
+
+impl PanelSlideDirection {
+	/// Returns the direction the images move in, in uvs
+	// Note: In uvs, the y axis points down
+	pub fn uvs_direction(self) -> [f32; 2] {
+		match self {
+			Self::Left => [-1.0, 0.0],
+			Self::Right => [1.0, 0.0],
+			Self::Up => [0.0, -1.0],
+			Self::Down => [0.0, 1.0],
+		}
+	}
+}
+
+/// Shader parameter
+#[derive(Clone, Copy, Debug)]
+pub struct PanelShaderParam {
+	/// Name
+	#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only read by the settings menu
+	pub name: &'static str,
+
+	/// Minimum value
+	pub min: f32,
+
+	/// Maximum value
+	pub max: f32,
+
+	/// Default value
+	#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only read by the settings menu
+	pub default: f32,
 }
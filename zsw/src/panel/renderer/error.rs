@@ -0,0 +1,25 @@
+//! Panels renderer errors
+
+// Imports
+use {std::path::PathBuf, zsw_error::AppError};
+
+/// Render error
+#[derive(Debug, thiserror::Error)]
+pub enum RenderError {
+	/// Unable to preprocess shader
+	#[error("Unable to preprocess shader {shader_path:?}")]
+	Shader {
+		/// Shader path
+		shader_path: PathBuf,
+
+		/// Error
+		#[source]
+		err: anyhow::Error,
+	},
+}
+
+impl From<RenderError> for AppError {
+	fn from(err: RenderError) -> Self {
+		Self::Other(anyhow::Error::new(err))
+	}
+}
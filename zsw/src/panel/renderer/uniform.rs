@@ -8,8 +8,30 @@
 use {
 	bytemuck::{Pod, Zeroable},
 	std::{mem, ptr, slice},
+	zsw_wgpu::WgpuShared,
 };
 
+/// Maximum size of the panel uniforms, for any extra.
+///
+/// Panel uniform buffers are created with this size, so uniforms with any extra fit in them.
+// Note: The shader's uniforms struct is aligned to 16 bytes, so it's size is rounded up
+//       to it, and the buffer must be at least that big.
+pub const MAX_UNIFORMS_SIZE: usize = self::max_size(&[
+	mem::size_of::<PanelUniforms<NoneExtra>>(),
+	mem::size_of::<PanelUniforms<FadeExtra>>(),
+	mem::size_of::<PanelUniforms<FadeWhiteExtra>>(),
+	mem::size_of::<PanelUniforms<FadeOutExtra>>(),
+	mem::size_of::<PanelUniforms<FadeInExtra>>(),
+	mem::size_of::<PanelUniforms<DissolveExtra>>(),
+	mem::size_of::<PanelUniforms<PixelateExtra>>(),
+	mem::size_of::<PanelUniforms<PageCurlExtra>>(),
+	mem::size_of::<PanelUniforms<CubeExtra>>(),
+	mem::size_of::<PanelUniforms<SlideExtra>>(),
+	mem::size_of::<PanelUniforms<WipeExtra>>(),
+	mem::size_of::<PanelUniforms<CalibrationExtra>>(),
+])
+.next_multiple_of(16);
+
 /// `vec2<f32>`
 #[derive(PartialEq, Clone, Copy, Default, Debug)]
 #[repr(C, align(8))]
@@ -35,20 +57,46 @@ pub struct PanelImageUniforms {
 
 	/// Swap direction
 	swap_dir: u32,
+
+	/// Whether the image scrolls
+	scroll: u32,
+
+	/// Alignment offset, if not scrolling
+	alignment: Vec2,
+
+	/// Tile scroll, if tiling
+	tile_scroll: Vec2,
+
+	/// Whether the image is tiled
+	tile: u32,
+
+	/// Padding
+	// Note: Uniform structs used as members must have a size that's a multiple of 16
+	_padding: [u32; 3],
 }
 
 impl PanelImageUniforms {
+	#[expect(clippy::too_many_arguments)] // TODO: Refactor
 	pub fn new(
 		ratio: impl Into<[f32; 2]>,
 		parallax_ratio: impl Into<[f32; 2]>,
 		parallax_offset: impl Into<[f32; 2]>,
 		swap_dir: bool,
+		scroll: bool,
+		alignment: impl Into<[f32; 2]>,
+		tile: bool,
+		tile_scroll: impl Into<[f32; 2]>,
 	) -> Self {
 		Self {
 			ratio:           Vec2(ratio.into()),
 			parallax_ratio:  Vec2(parallax_ratio.into()),
 			parallax_offset: Vec2(parallax_offset.into()),
 			swap_dir:        swap_dir.into(),
+			scroll:          scroll.into(),
+			alignment:       Vec2(alignment.into()),
+			tile_scroll:     Vec2(tile_scroll.into()),
+			tile:            tile.into(),
+			_padding:        [0; 3],
 		}
 	}
 }
@@ -76,12 +124,26 @@ pub struct PanelUniforms<X: UniformsExtra> {
 	/// Progress
 	progress: f32,
 
+	/// Padding
+	// Note: The tint is aligned to 16 bytes
+	_padding: [u32; 2],
+
+	/// Tint, multiplied with the output color
+	tint: [f32; 3],
+
 	/// Extra
 	extra: X,
 }
 
 impl<X: UniformsExtra> PanelUniforms<X> {
+	/// Asserts that these uniforms fit within [`MAX_UNIFORMS_SIZE`]
+	const FITS_MAX_SIZE: () = assert!(
+		mem::size_of::<Self>() <= MAX_UNIFORMS_SIZE,
+		"Panel uniforms are bigger than the maximum uniforms size"
+	);
+
 	/// Creates new panel uniforms
+	#[expect(clippy::too_many_arguments)] // TODO: Refactor
 	pub fn new(
 		pos_matrix: impl Into<[[f32; 4]; 4]>,
 		prev: PanelImageUniforms,
@@ -89,6 +151,7 @@ impl<X: UniformsExtra> PanelUniforms<X> {
 		next: PanelImageUniforms,
 		fade_point: f32,
 		progress: f32,
+		tint: impl Into<[f32; 3]>,
 		extra: X,
 	) -> Self {
 		Self {
@@ -98,6 +161,8 @@ impl<X: UniformsExtra> PanelUniforms<X> {
 			next,
 			fade_point,
 			progress,
+			_padding: [0; 2],
+			tint: tint.into(),
 			extra,
 		}
 	}
@@ -108,6 +173,30 @@ impl<X: UniformsExtra> PanelUniforms<X> {
 		//         We also guarantee `X` is Pod,
 		unsafe { slice::from_raw_parts(ptr::from_ref(self).cast(), mem::size_of::<Self>()) }
 	}
+
+	/// Writes these uniforms to `buffer`.
+	///
+	/// `buffer` must have been created with a size of [`MAX_UNIFORMS_SIZE`].
+	pub fn write(&self, wgpu_shared: &WgpuShared, buffer: &wgpu::Buffer) {
+		// Note: This fails to compile if `X` wasn't included in `MAX_UNIFORMS_SIZE` and is bigger than it.
+		let () = Self::FITS_MAX_SIZE;
+
+		wgpu_shared.queue.write_buffer(buffer, 0, self.as_bytes());
+	}
+}
+
+/// Returns the maximum of `sizes`
+const fn max_size(sizes: &[usize]) -> usize {
+	let mut max = 0;
+	let mut idx = 0;
+	while idx < sizes.len() {
+		if sizes[idx] > max {
+			max = sizes[idx];
+		}
+		idx += 1;
+	}
+
+	max
 }
 
 pub trait UniformsExtra: Pod {}
@@ -161,3 +250,107 @@ pub struct FadeInExtra {
 }
 
 impl UniformsExtra for FadeInExtra {}
+
+/// Dissolve extra
+// Note: The fields are ordered so that `size` is aligned to 8 bytes, like in the shader.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+#[derive(Zeroable, Pod)]
+#[repr(C)]
+pub struct DissolveExtra {
+	/// Feather, as a fraction of the transition
+	pub feather: f32,
+
+	/// Panel size
+	pub size: [f32; 2],
+
+	/// Noise grain size, in pixels
+	pub grain: f32,
+}
+
+impl UniformsExtra for DissolveExtra {}
+
+/// Pixelate extra
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+#[derive(Zeroable, Pod)]
+#[repr(C)]
+pub struct PixelateExtra {
+	/// Maximum block size, in pixels
+	pub block_size: f32,
+
+	/// Panel size
+	pub size: [f32; 2],
+}
+
+impl UniformsExtra for PixelateExtra {}
+
+/// Page curl extra
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+#[derive(Zeroable, Pod)]
+#[repr(C)]
+pub struct PageCurlExtra {
+	/// Curl radius, as a fraction of the panel width
+	pub radius: f32,
+}
+
+impl UniformsExtra for PageCurlExtra {}
+
+/// Cube extra
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+#[derive(Zeroable, Pod)]
+#[repr(C)]
+pub struct CubeExtra {
+	/// Viewing distance, as a multiple of the panel width
+	pub distance: f32,
+
+	/// Panel size
+	pub size: [f32; 2],
+}
+
+impl UniformsExtra for CubeExtra {}
+
+/// Slide extra
+// Note: The fields are ordered so that `direction` is aligned to 8 bytes, like in the shader.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+#[derive(Zeroable, Pod)]
+#[repr(C)]
+pub struct SlideExtra {
+	/// Motion blur strength, 0.0 .. 1.0
+	pub blur: f32,
+
+	/// Direction the images move in, in uvs
+	pub direction: [f32; 2],
+}
+
+impl UniformsExtra for SlideExtra {}
+
+/// Wipe extra
+// Note: The fields are ordered so that `size` is aligned to 8 bytes, like in the shader.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+#[derive(Zeroable, Pod)]
+#[repr(C)]
+pub struct WipeExtra {
+	/// Feather, as a fraction of the panel
+	pub feather: f32,
+
+	/// Panel size
+	pub size: [f32; 2],
+
+	/// Angle, in radians
+	pub angle: f32,
+}
+
+impl UniformsExtra for WipeExtra {}
+
+/// Calibration extra
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+#[derive(Zeroable, Pod)]
+#[repr(C)]
+pub struct CalibrationExtra {
+	/// Pattern
+	pub pattern: u32,
+
+	/// Panel size
+	pub size: [f32; 2],
+}
+
+impl UniformsExtra for CalibrationExtra {}
@@ -1,7 +1,22 @@
 //! Panel serialization / deserialization
 
 // Imports
-use zsw_util::Rect;
+use {
+	super::{PanelAlignment, PanelQuietHours, PanelSampler, PanelScaleMode, PanelTile, PANEL_FRAME_RATE},
+	crate::playlist::PlaylistPlayer,
+	anyhow::Context,
+	std::path::PathBuf,
+	zsw_error::AppError,
+	zsw_util::Rect,
+};
+
+/// Serialized snapshot
+#[cfg(any(feature = "menu", test))]
+#[derive(Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+	pub panels: Vec<Panel>,
+}
 
 /// Serialized panel
 #[derive(Debug)]
@@ -9,7 +24,168 @@ use zsw_util::Rect;
 pub struct Panel {
 	pub geometries: Vec<PanelGeometry>,
 	pub state:      PanelState,
-	pub playlist:   String,
+
+	// Note: Calibration panels don't need a playlist
+	#[serde(default)]
+	pub playlist: String,
+
+	#[serde(default)]
+	pub kind: PanelKind,
+
+	// Note: Only quotes / ticker panels need these
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub quotes: Option<PanelQuotes>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub ticker: Option<PanelTicker>,
+
+	/// Whether to coordinate with other panels of the same playlist.
+	///
+	/// Coordinated panels share a playlist player, and never show the same image at once.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub coordinate: bool,
+
+	/// Offset to cascade with other panels of the same playlist, if any.
+	///
+	/// Cascading panels share a playlist player, each showing what
+	/// the panel with offset `0` showed `offset` steps ago.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cascade_offset: Option<usize>,
+}
+
+impl Panel {
+	/// Validates this panel
+	pub fn validate(&self) -> Result<(), AppError> {
+		if self.geometries.is_empty() {
+			return Err(anyhow::anyhow!("Panel has no geometries").into());
+		}
+		for geometry in &self.geometries {
+			geometry
+				.validate()
+				.with_context(|| format!("Invalid geometry {}", geometry.geometry))?;
+		}
+		self.state.validate().context("Invalid state")?;
+
+		match self.kind {
+			PanelKind::Images | PanelKind::Calibration => (),
+			PanelKind::Quotes => {
+				let quotes = self.quotes.as_ref().context("Quotes panel has no quotes")?;
+				quotes.style.validate().context("Invalid quotes style")?;
+			},
+			PanelKind::Ticker => {
+				let ticker = self.ticker.as_ref().context("Ticker panel has no ticker")?;
+				ticker.validate().context("Invalid ticker")?;
+			},
+		}
+
+		if self.coordinate && self.cascade_offset.is_some() {
+			return Err(anyhow::anyhow!("Panel can't both coordinate and cascade").into());
+		}
+		if let Some(offset) = self.cascade_offset {
+			if offset >= PlaylistPlayer::MAX_CURSOR_ITEMS {
+				return Err(anyhow::anyhow!(
+					"Cascade offset {offset} must be less than {}",
+					PlaylistPlayer::MAX_CURSOR_ITEMS
+				)
+				.into());
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Serialized panel kind
+#[derive(Clone, Copy, Default, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PanelKind {
+	/// Images from the playlist
+	#[default]
+	Images,
+
+	/// Calibration test patterns
+	Calibration,
+
+	/// Quotes from a file
+	Quotes,
+
+	/// Headlines from news feeds
+	Ticker,
+}
+
+/// Serialized panel quotes
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PanelQuotes {
+	/// Quotes file.
+	///
+	/// Either a `toml` file with `[[quotes]]` tables, each with a `text`
+	/// and optional `author`, or a text file with a quote per line.
+	pub path: PathBuf,
+
+	/// Text style
+	#[serde(flatten)]
+	pub style: PanelTextStyle,
+}
+
+/// Serialized panel ticker
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PanelTicker {
+	/// Feed urls, either RSS or Atom
+	pub feeds: Vec<String>,
+
+	/// Interval between fetching the feeds, in seconds
+	#[serde(default = "default_panel_ticker_interval")]
+	pub interval: u64,
+
+	/// Text style
+	#[serde(flatten)]
+	pub style: PanelTextStyle,
+}
+
+impl PanelTicker {
+	/// Validates this ticker
+	pub fn validate(&self) -> Result<(), AppError> {
+		if self.interval == 0 {
+			return Err(anyhow::anyhow!("Interval must not be zero").into());
+		}
+		self.style.validate().context("Invalid style")?;
+
+		Ok(())
+	}
+}
+
+/// Serialized panel text style
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PanelTextStyle {
+	/// Font file, if not using the default font
+	#[serde(default)]
+	pub font: Option<PathBuf>,
+
+	/// Font size, in pixels
+	#[serde(default = "default_panel_text_font_size")]
+	pub font_size: f32,
+
+	/// Text color, as RGBA
+	#[serde(default = "default_panel_text_color")]
+	pub color: [u8; 4],
+
+	/// Background color, as RGBA
+	#[serde(default = "default_panel_text_background")]
+	pub background: [u8; 4],
+}
+
+impl PanelTextStyle {
+	/// Validates this text style
+	pub fn validate(&self) -> Result<(), AppError> {
+		if !self.font_size.is_finite() || self.font_size <= 0.0 {
+			return Err(anyhow::anyhow!("Font size must be positive, found {}", self.font_size).into());
+		}
+
+		Ok(())
+	}
 }
 
 /// Serialized panel geometry
@@ -21,13 +197,47 @@ pub struct PanelGeometry {
 	pub geometry: Rect<i32, u32>,
 }
 
+impl PanelGeometry {
+	/// Validates this geometry
+	pub fn validate(&self) -> Result<(), AppError> {
+		let Rect { pos, size } = self.geometry;
+		if size.x == 0 || size.y == 0 {
+			return Err(anyhow::anyhow!("Size must not be zero").into());
+		}
+
+		// Note: The end of the geometry must fit within the position's type
+		if pos.x.checked_add_unsigned(size.x).is_none() || pos.y.checked_add_unsigned(size.y).is_none() {
+			return Err(anyhow::anyhow!("Geometry must not overflow").into());
+		}
+
+		Ok(())
+	}
+}
+
 
 /// Serialized panel state
 #[derive(Debug)]
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct PanelState {
-	pub duration:   u64,
-	pub fade_point: u64,
+	/// Duration, in seconds
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub duration_secs: Option<f64>,
+
+	/// Fade point, in seconds
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub fade_point_secs: Option<f64>,
+
+	/// Duration, in panel frames.
+	///
+	/// Alternative to `duration_secs`, kept for older panels.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub duration: Option<u64>,
+
+	/// Fade point, in panel frames.
+	///
+	/// Alternative to `fade_point_secs`, kept for older panels.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub fade_point: Option<u64>,
 
 	// TODO: Move these to a sub-struct?
 	#[serde(default = "default_panel_parallax_ratio")]
@@ -36,14 +246,272 @@ pub struct PanelState {
 	pub parallax_exp:     f32,
 	#[serde(default = "default_panel_parallax_reverse")]
 	pub reverse_parallax: bool,
+
+	#[serde(default)]
+	pub scale_mode: PanelScaleMode,
+	#[serde(default)]
+	pub alignment:  PanelAlignment,
+	#[serde(default)]
+	pub sampler:    PanelSampler,
+	#[serde(default)]
+	pub tile:       PanelTile,
+
+	/// Whether to play the transition sound, if any, when transitioning
+	#[serde(default = "default_panel_transition_sound")]
+	pub transition_sound: bool,
+
+	/// Motion blur strength of slide transitions, 0.0 .. 1.0.
+	///
+	/// If zero, slides aren't blurred
+	#[serde(default)]
+	pub transition_blur: f32,
+
+	/// Whether each geometry shows a different image, instead of all of them showing the same one
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub distinct_images: bool,
+
+	/// Monitor to pin the panel to, by name
+	#[serde(default)]
+	pub monitor: Option<String>,
+
+	/// Quiet hours, during which the panel is frozen
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub quiet_hours: Vec<PanelQuietHours>,
+
+	/// Frame rate the panel needs while not transitioning.
+	///
+	/// The window renders at the highest frame rate any visible panel needs.
+	/// If unset, the panel always needs the maximum frame rate.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub fps: Option<f32>,
+}
+
+impl PanelState {
+	/// Validates this state
+	pub fn validate(&self) -> Result<(), AppError> {
+		let duration = self.duration_frames()?;
+		let fade_point = self.fade_point_frames()?;
+		if duration == 0 {
+			return Err(anyhow::anyhow!("Duration must not be zero").into());
+		}
+		if fade_point > duration {
+			return Err(anyhow::anyhow!(
+				"Fade point ({fade_point} frames) must not be past the duration ({duration} frames)"
+			)
+			.into());
+		}
+		if !(0.0..=1.0).contains(&self.parallax_ratio) {
+			return Err(anyhow::anyhow!("Parallax ratio must be within 0..1, found {}", self.parallax_ratio).into());
+		}
+		if !self.parallax_exp.is_finite() {
+			return Err(anyhow::anyhow!("Parallax exponent must be finite, found {}", self.parallax_exp).into());
+		}
+		if !(0.0..=1.0).contains(&self.transition_blur) {
+			return Err(anyhow::anyhow!("Transition blur must be within 0..1, found {}", self.transition_blur).into());
+		}
+		if !(1..=PanelSampler::MAX_ANISOTROPY).contains(&self.sampler.anisotropy) {
+			return Err(anyhow::anyhow!(
+				"Sampler anisotropy must be within 1..={}, found {}",
+				PanelSampler::MAX_ANISOTROPY,
+				self.sampler.anisotropy
+			)
+			.into());
+		}
+		if !(self.tile.scale.is_finite() && self.tile.scale > 0.0) {
+			return Err(anyhow::anyhow!("Tile scale must be positive, found {}", self.tile.scale).into());
+		}
+		if !self.tile.scroll.iter().all(|scroll| scroll.is_finite()) {
+			return Err(anyhow::anyhow!("Tile scroll must be finite, found {:?}", self.tile.scroll).into());
+		}
+		if let Some(fps) = self.fps {
+			// Note: `Duration::from_secs_f32` panics if the interval is too long
+			if !(fps.is_finite() && fps >= super::PanelState::MIN_FPS) {
+				return Err(anyhow::anyhow!("Fps must be at least {}, found {fps}", super::PanelState::MIN_FPS).into());
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Returns the duration, in panel frames
+	pub fn duration_frames(&self) -> Result<u64, AppError> {
+		let frames = self::frames_or_secs(self.duration, self.duration_secs).context("Invalid duration")?;
+		Ok(frames)
+	}
+
+	/// Returns the fade point, in panel frames
+	pub fn fade_point_frames(&self) -> Result<u64, AppError> {
+		let frames = self::frames_or_secs(self.fade_point, self.fade_point_secs).context("Invalid fade point")?;
+		Ok(frames)
+	}
+}
+
+/// Returns a time given either in panel frames or in seconds, in panel frames
+fn frames_or_secs(frames: Option<u64>, secs: Option<f64>) -> Result<u64, AppError> {
+	match (frames, secs) {
+		(Some(frames), None) => Ok(frames),
+		(None, Some(secs)) => {
+			let frames = secs * f64::from(PANEL_FRAME_RATE);
+			if !frames.is_finite() || frames < 0.0 || frames >= u64::MAX as f64 {
+				return Err(
+					anyhow::anyhow!("Seconds must be finite, not negative and not too large, found {secs}").into(),
+				);
+			}
+
+			#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // We checked it fits above
+			Ok(frames.round() as u64)
+		},
+		(Some(_), Some(_)) => Err(anyhow::anyhow!("Must be in either frames or seconds, not both").into()),
+		(None, None) => Err(anyhow::anyhow!("Must be in either frames or seconds").into()),
+	}
+}
+
+/// Returns `frames` panel frames in seconds
+#[cfg(feature = "menu")]
+pub fn frames_to_secs(frames: u64) -> f64 {
+	frames as f64 / f64::from(PANEL_FRAME_RATE)
 }
 
-fn default_panel_parallax_ratio() -> f32 {
+pub fn default_panel_parallax_ratio() -> f32 {
 	0.998
 }
-fn default_panel_parallax_exp() -> f32 {
+pub fn default_panel_parallax_exp() -> f32 {
 	2.0
 }
-fn default_panel_parallax_reverse() -> bool {
+pub fn default_panel_parallax_reverse() -> bool {
 	false
 }
+pub fn default_panel_transition_sound() -> bool {
+	true
+}
+pub fn default_panel_text_font_size() -> f32 {
+	48.0
+}
+pub fn default_panel_text_color() -> [u8; 4] {
+	[255, 255, 255, 255]
+}
+pub fn default_panel_text_background() -> [u8; 4] {
+	[0, 0, 0, 255]
+}
+pub fn default_panel_ticker_interval() -> u64 {
+	15 * 60
+}
+
+#[cfg(test)]
+mod test {
+	use {
+		super::*,
+		crate::panel::{PanelFade, PanelFadeImages},
+		proptest::prelude::*,
+	};
+
+	#[test]
+	fn snapshot_roundtrip() {
+		let panel_toml = "playlist = \"a\"\n\n[state]\nduration = 60\nfade_point = 50\n\n[[geometries]]\ngeometry = \
+		                  \"1920x1080+0+0\"\n";
+		let panel = || toml::from_str::<Panel>(panel_toml).expect("Unable to parse panel");
+		let snapshot = Snapshot {
+			panels: vec![panel(), panel()],
+		};
+
+		let snapshot_toml = toml::to_string(&snapshot).expect("Unable to serialize snapshot");
+		let snapshot = toml::from_str::<Snapshot>(&snapshot_toml).expect("Unable to parse snapshot");
+		assert_eq!(snapshot.panels.len(), 2, "Wrong number of panels");
+		for panel in &snapshot.panels {
+			assert_eq!(panel.playlist, "a", "Wrong playlist");
+			assert_eq!(panel.geometries.len(), 1, "Wrong number of geometries");
+		}
+	}
+
+	#[test]
+	fn durations_in_secs() {
+		let panel = |state_toml: &str| {
+			let panel_toml = format!("[state]\n{state_toml}\n\n[[geometries]]\ngeometry = \"1920x1080+0+0\"\n");
+			toml::from_str::<Panel>(&panel_toml).expect("Unable to parse panel")
+		};
+
+		let secs_panel = panel("duration_secs = 2.5\nfade_point_secs = 2");
+		secs_panel.validate().expect("Panel in seconds was invalid");
+		assert_eq!(secs_panel.state.duration_frames().ok(), Some(150), "Wrong duration");
+		assert_eq!(secs_panel.state.fade_point_frames().ok(), Some(120), "Wrong fade point");
+
+		let frames_panel = panel("duration = 150\nfade_point = 120");
+		frames_panel.validate().expect("Panel in frames was invalid");
+		assert_eq!(frames_panel.state.duration_frames().ok(), Some(150), "Wrong duration");
+
+		assert!(
+			panel("duration = 150\nduration_secs = 2.5\nfade_point = 120")
+				.validate()
+				.is_err(),
+			"Duration in both frames and seconds was valid"
+		);
+		assert!(
+			panel("fade_point = 120").validate().is_err(),
+			"Missing duration was valid"
+		);
+	}
+
+	#[test]
+	fn transition_blur_range() {
+		let panel = |transition_blur: f32| {
+			let panel_toml = format!(
+				"[state]\nduration = 60\nfade_point = 50\ntransition_blur = \
+				 {transition_blur:?}\n\n[[geometries]]\ngeometry = \"1920x1080+0+0\"\n"
+			);
+			toml::from_str::<Panel>(&panel_toml).expect("Unable to parse panel")
+		};
+
+		panel(0.5).validate().expect("Transition blur within range was invalid");
+		assert!(panel(-0.5).validate().is_err(), "Negative transition blur was valid");
+		assert!(panel(1.5).validate().is_err(), "Transition blur above 1 was valid");
+	}
+
+	proptest! {
+		#[test]
+		fn parse_never_panics(panel_toml in "\\PC*") {
+			if let Ok(panel) = toml::from_str::<Panel>(&panel_toml) {
+				let _ = panel.validate();
+			}
+		}
+
+		#[test]
+		fn valid_panels_never_panic(
+			duration in any::<u64>(),
+			fade_point in any::<u64>(),
+			x in any::<i64>(),
+			y in any::<i64>(),
+			width in any::<i64>(),
+			height in any::<i64>(),
+			frames in any::<i64>(),
+			cur in any::<bool>(),
+			next in any::<bool>(),
+		) {
+			let panel_toml = format!(
+				"[state]\nduration = {duration}\nfade_point = {fade_point}\n\n[[geometries]]\ngeometry = \"{width}x{height}+{x}+{y}\"\n"
+			);
+			let Ok(panel) = toml::from_str::<Panel>(&panel_toml) else {
+				return Ok(());
+			};
+			if panel.validate().is_err() {
+				return Ok(());
+			}
+
+			for geometry in &panel.geometries {
+				let Rect { pos, size } = geometry.geometry;
+				prop_assert!(pos.x.checked_add_unsigned(size.x).is_some(), "Geometry overflowed");
+				prop_assert!(pos.y.checked_add_unsigned(size.y).is_some(), "Geometry overflowed");
+			}
+
+			let duration = panel.state.duration_frames().expect("Validated panel had no duration");
+			let fade_point = panel.state.fade_point_frames().expect("Validated panel had no fade point");
+			let mut fade = PanelFade::new(duration, fade_point);
+			let _ = fade.step(frames, PanelFadeImages { cur, next });
+			let _ = fade.skip();
+			prop_assert!(fade.progress_norm().is_finite(), "Progress wasn't finite");
+			prop_assert!(fade.fade_point_norm().is_finite(), "Fade point wasn't finite");
+
+			let _ = fade.step_looping(frames);
+			prop_assert!(fade.progress < fade.duration, "Looping progress wasn't within the duration");
+		}
+	}
+}
@@ -1,36 +1,113 @@
 //! Panel state
 
+// Imports
+use {
+	super::PanelFade,
+	cgmath::Vector2,
+	chrono::{Datelike, Days, NaiveDateTime, NaiveTime, Weekday},
+	std::time::{Duration, SystemTime},
+};
+
 /// Panel state
 #[derive(Debug)]
+#[expect(clippy::struct_excessive_bools)] // They're all independent flags
 pub struct PanelState {
 	/// If paused
 	pub paused: bool,
 
-	/// Current progress (in frames)
-	pub progress: u64,
+	/// If muted.
+	///
+	/// Muted panels aren't rendered
+	pub muted: bool,
 
-	/// Duration (in frames)
-	pub duration: u64,
+	/// If soloed.
+	///
+	/// If any panel is soloed, only soloed panels are rendered
+	pub soloed: bool,
 
-	/// Fade point (in frames)
-	pub fade_point: u64,
+	/// Fade
+	pub fade: PanelFade,
 
 	/// Parallax
 	pub parallax: PanelParallaxState,
+
+	/// Scale mode
+	pub scale_mode: PanelScaleMode,
+
+	/// Alignment
+	pub alignment: PanelAlignment,
+
+	/// Image sampler
+	pub sampler: PanelSampler,
+
+	/// Tiling, when using the tile scale mode
+	pub tile: PanelTile,
+
+	/// Whether to play the transition sound, if any, when transitioning
+	pub transition_sound: bool,
+
+	/// Motion blur strength of slide transitions, 0.0 .. 1.0
+	pub transition_blur: f32,
+
+	/// Whether each geometry shows a different image.
+	///
+	/// All geometries still transition at the same time.
+	pub distinct_images: bool,
+
+	/// Monitor the panel is pinned to, by name.
+	///
+	/// Pinned panels are only rendered within that monitor
+	pub monitor: Option<String>,
+
+	/// Quiet hours.
+	///
+	/// Within these, the panel is frozen, as if paused
+	pub quiet_hours: Vec<PanelQuietHours>,
+
+	/// If currently within any quiet hours
+	pub quiet: bool,
+
+	/// Frame rate needed while not transitioning.
+	///
+	/// If `None`, the maximum frame rate is always needed
+	pub fps: Option<f32>,
 }
 
 impl PanelState {
-	/// Returns the normalized image progress
-	#[must_use]
-	pub fn progress_norm(&self) -> f32 {
-		// Note: Image progress is linear throughout the full cycle
-		self.progress as f32 / self.duration as f32
+	/// Minimum frame rate
+	pub const MIN_FPS: f32 = 0.1;
+
+	/// Returns when the next image will be switched to, given the duration of each frame.
+	///
+	/// Returns `None` if paused or within quiet hours.
+	pub fn next_switch(&self, now: SystemTime, frame_duration: Duration) -> Option<SystemTime> {
+		if self.paused || self.quiet {
+			return None;
+		}
+
+		now.checked_add(self.fade.remaining(frame_duration))
+	}
+
+	/// Returns the image sampler to use.
+	///
+	/// When using the tile scale mode, the sampler always tiles.
+	pub fn sampler(&self) -> PanelSampler {
+		let mut sampler = self.sampler;
+		if self.scale_mode == PanelScaleMode::Tile && !sampler.address_mode.tiles() {
+			sampler.address_mode = PanelSamplerAddressMode::Repeat;
+		}
+
+		sampler
+	}
+
+	/// Returns if images are tiled across the panel
+	pub fn tiles(&self) -> bool {
+		self.sampler().address_mode.tiles()
 	}
 
-	/// Returns the fade point normalized
-	pub fn fade_point_norm(&self) -> f32 {
-		// Note: Image progress is linear throughout the full cycle
-		self.fade_point as f32 / self.duration as f32
+	/// Updates whether we're within any quiet hours at `now`
+	pub fn update_quiet(&mut self, now: NaiveDateTime) {
+		self.quiet = self.quiet_hours.iter().any(|quiet_hours| quiet_hours.contains(now));
 	}
 }
 
@@ -47,3 +124,341 @@ pub struct PanelParallaxState {
 	/// Reverse parallax
 	pub reverse: bool,
 }
+
+/// Panel quiet hours
+#[serde_with::serde_as]
+#[derive(Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PanelQuietHours {
+	/// Start time
+	#[serde_as(as = "serde_with::DisplayFromStr")]
+	pub start: NaiveTime,
+
+	/// End time.
+	///
+	/// If before the start time, the quiet hours span midnight
+	#[serde_as(as = "serde_with::DisplayFromStr")]
+	pub end: NaiveTime,
+
+	/// Days the quiet hours start on.
+	///
+	/// If empty, they start every day
+	#[serde_as(as = "Vec<serde_with::DisplayFromStr>")]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub days: Vec<Weekday>,
+}
+
+impl PanelQuietHours {
+	/// Returns if `time` is within these quiet hours
+	pub fn contains(&self, time: NaiveDateTime) -> bool {
+		let starts_on = |date: Option<NaiveDateTime>| {
+			date.is_some_and(|date| self.days.is_empty() || self.days.contains(&date.weekday()))
+		};
+
+		match self.start <= self.end {
+			true => starts_on(Some(time)) && (self.start..self.end).contains(&time.time()),
+			// Note: When spanning midnight, the hours after midnight belong to the previous day
+			false =>
+				(starts_on(Some(time)) && time.time() >= self.start) ||
+					(starts_on(time.checked_sub_days(Days::new(1))) && time.time() < self.end),
+		}
+	}
+}
+
+/// Panel scale mode
+///
+/// Determines how images are scaled onto the panel
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum PanelScaleMode {
+	/// Fills the panel, cropping the image and scrolling along the cropped axis
+	#[default]
+	Fill,
+
+	/// Fits the whole image within the panel, letterboxing the remaining space
+	Fit,
+
+	/// Stretches the image onto the panel, ignoring it's aspect ratio
+	Stretch,
+
+	/// Centers the image on the panel, without scaling it
+	Center,
+
+	/// Repeats the image across the panel, for seamless textures
+	Tile,
+}
+
+impl PanelScaleMode {
+	/// All scale modes
+	#[cfg(feature = "menu")]
+	pub const ALL: [Self; 5] = [Self::Fill, Self::Fit, Self::Stretch, Self::Center, Self::Tile];
+
+	/// Returns this scale mode's name
+	#[cfg(feature = "menu")]
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Fill => "Fill",
+			Self::Fit => "Fit",
+			Self::Stretch => "Stretch",
+			Self::Center => "Center",
+			Self::Tile => "Tile",
+		}
+	}
+
+	/// Returns if images scroll along the panel with this scale mode
+	pub fn scrolls(self) -> bool {
+		match self {
+			Self::Fill => true,
+			Self::Fit | Self::Stretch | Self::Center | Self::Tile => false,
+		}
+	}
+
+	/// Returns if images are stretched to cover the panel with this scale mode.
+	///
+	/// Only images for these scale modes are upscaled.
+	pub fn stretches(self) -> bool {
+		match self {
+			Self::Fill | Self::Stretch => true,
+			Self::Fit | Self::Center | Self::Tile => false,
+		}
+	}
+}
+
+/// Panel tiling
+#[derive(PartialEq, Clone, Copy, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PanelTile {
+	/// Scale of each tile, relative to the image size
+	#[serde(default = "PanelTile::default_scale")]
+	pub scale: f32,
+
+	/// Scrolling, in tiles per image duration
+	#[serde(default)]
+	pub scroll: [f32; 2],
+}
+
+impl PanelTile {
+	/// Default scale
+	fn default_scale() -> f32 {
+		1.0
+	}
+}
+
+impl Default for PanelTile {
+	fn default() -> Self {
+		Self {
+			scale:  Self::default_scale(),
+			scroll: [0.0; 2],
+		}
+	}
+}
+
+/// Panel alignment
+///
+/// Determines which part of the image is shown when it doesn't
+/// exactly cover the panel.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum PanelAlignment {
+	/// Scrolls the image along the panel if the scale mode allows it, else centers it
+	#[default]
+	Scroll,
+
+	/// Center
+	Center,
+
+	/// Top
+	Top,
+
+	/// Bottom
+	Bottom,
+
+	/// Left
+	Left,
+
+	/// Right
+	Right,
+
+	/// Custom offset, where `0.0` aligns to the left / top and `1.0` to the right / bottom.
+	Offset { x: f32, y: f32 },
+}
+
+impl PanelAlignment {
+	/// All alignments
+	#[cfg(feature = "menu")]
+	pub const ALL: [Self; 7] = [
+		Self::Scroll,
+		Self::Center,
+		Self::Top,
+		Self::Bottom,
+		Self::Left,
+		Self::Right,
+		Self::Offset { x: 0.5, y: 0.5 },
+	];
+
+	/// Returns this alignment's name
+	#[cfg(feature = "menu")]
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Scroll => "Scroll",
+			Self::Center => "Center",
+			Self::Top => "Top",
+			Self::Bottom => "Bottom",
+			Self::Left => "Left",
+			Self::Right => "Right",
+			Self::Offset { .. } => "Offset",
+		}
+	}
+
+	/// Returns the offset of this alignment, if fixed.
+	///
+	/// Returns `None` if the image should scroll instead.
+	pub fn offset(self) -> Option<Vector2<f32>> {
+		let (x, y) = match self {
+			Self::Scroll => return None,
+			Self::Center => (0.5, 0.5),
+			Self::Top => (0.5, 0.0),
+			Self::Bottom => (0.5, 1.0),
+			Self::Left => (0.0, 0.5),
+			Self::Right => (1.0, 0.5),
+			Self::Offset { x, y } => (x.clamp(0.0, 1.0), y.clamp(0.0, 1.0)),
+		};
+
+		Some(Vector2::new(x, y))
+	}
+}
+
+/// Panel image sampler
+///
+/// Determines how images are sampled when drawn onto the panel
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PanelSampler {
+	/// Filter
+	#[serde(default)]
+	pub filter: PanelSamplerFilter,
+
+	/// Address mode
+	#[serde(default)]
+	pub address_mode: PanelSamplerAddressMode,
+
+	/// Maximum anisotropy, 1 ..= 16.
+	///
+	/// Only used with linear filtering
+	#[serde(default = "PanelSampler::default_anisotropy")]
+	pub anisotropy: u16,
+}
+
+impl PanelSampler {
+	/// Maximum anisotropy
+	pub const MAX_ANISOTROPY: u16 = 16;
+
+	/// Default anisotropy
+	fn default_anisotropy() -> u16 {
+		1
+	}
+
+	/// Returns the anisotropy clamp to create the sampler with
+	pub fn anisotropy_clamp(self) -> u16 {
+		// Note: Anisotropic filtering requires all filters to be linear
+		match self.filter {
+			PanelSamplerFilter::Linear => self.anisotropy.clamp(1, Self::MAX_ANISOTROPY),
+			PanelSamplerFilter::Nearest => 1,
+		}
+	}
+}
+
+impl Default for PanelSampler {
+	fn default() -> Self {
+		Self {
+			filter:       PanelSamplerFilter::default(),
+			address_mode: PanelSamplerAddressMode::default(),
+			anisotropy:   Self::default_anisotropy(),
+		}
+	}
+}
+
+/// Panel image sampler filter
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum PanelSamplerFilter {
+	/// Linear, smoothly interpolating between pixels
+	#[default]
+	Linear,
+
+	/// Nearest, keeping pixels sharp (e.g. for pixel art)
+	Nearest,
+}
+
+impl PanelSamplerFilter {
+	/// All filters
+	#[cfg(feature = "menu")]
+	pub const ALL: [Self; 2] = [Self::Linear, Self::Nearest];
+
+	/// Returns this filter's name
+	#[cfg(feature = "menu")]
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Linear => "Linear",
+			Self::Nearest => "Nearest",
+		}
+	}
+
+	/// Returns the wgpu filter mode of this filter
+	pub fn filter_mode(self) -> wgpu::FilterMode {
+		match self {
+			Self::Linear => wgpu::FilterMode::Linear,
+			Self::Nearest => wgpu::FilterMode::Nearest,
+		}
+	}
+}
+
+/// Panel image sampler address mode
+///
+/// Determines what's shown outside of the image
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum PanelSamplerAddressMode {
+	/// Leaves the area outside of the image black
+	#[default]
+	ClampToEdge,
+
+	/// Tiles the image
+	Repeat,
+
+	/// Tiles the image, mirroring every other tile
+	MirrorRepeat,
+}
+
+impl PanelSamplerAddressMode {
+	/// All address modes
+	#[cfg(feature = "menu")]
+	pub const ALL: [Self; 3] = [Self::ClampToEdge, Self::Repeat, Self::MirrorRepeat];
+
+	/// Returns this address mode's name
+	#[cfg(feature = "menu")]
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::ClampToEdge => "Clamp to edge",
+			Self::Repeat => "Repeat",
+			Self::MirrorRepeat => "Mirror repeat",
+		}
+	}
+
+	/// Returns the wgpu address mode of this address mode
+	pub fn address_mode(self) -> wgpu::AddressMode {
+		match self {
+			Self::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+			Self::Repeat => wgpu::AddressMode::Repeat,
+			Self::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+		}
+	}
+
+	/// Returns if images are tiled with this address mode
+	pub fn tiles(self) -> bool {
+		match self {
+			Self::ClampToEdge => false,
+			Self::Repeat | Self::MirrorRepeat => true,
+		}
+	}
+}
@@ -0,0 +1,148 @@
+//! Panel text rendering
+//!
+//! Renders text into images on the cpu, for panels that display text.
+
+// Imports
+use {
+	super::ser,
+	ab_glyph::{Font, FontArc, GlyphId, PxScaleFont, ScaleFont},
+	anyhow::Context,
+	cgmath::Vector2,
+	image::{Rgba, RgbaImage},
+	std::{fs, mem},
+	zsw_error::AppError,
+};
+
+/// Text renderer
+#[derive(Debug)]
+pub struct TextRenderer {
+	/// Style
+	style: ser::PanelTextStyle,
+
+	/// Font
+	font: FontArc,
+}
+
+impl TextRenderer {
+	/// Creates a text renderer, loading the font of `style`
+	pub fn new(style: ser::PanelTextStyle) -> Result<Self, AppError> {
+		let font = match &style.font {
+			Some(font_path) => {
+				let font = fs::read(font_path).context("Unable to read font")?;
+				FontArc::try_from_vec(font).context("Unable to parse font")?
+			},
+			None => {
+				// Note: We use the same font egui uses by default
+				let font = egui::FontDefinitions::default()
+					.font_data
+					.remove("Ubuntu-Light")
+					.context("Default font was missing")?;
+				FontArc::try_from_vec(font.font.into_owned()).context("Unable to parse default font")?
+			},
+		};
+
+		Ok(Self { style, font })
+	}
+
+	/// Returns the style
+	pub fn style(&self) -> &ser::PanelTextStyle {
+		&self.style
+	}
+
+	/// Returns the height of each line
+	pub fn line_height(&self) -> f32 {
+		let font = self.scaled_font();
+		font.height() + font.line_gap()
+	}
+
+	/// Returns the width of `line`
+	pub fn line_width(&self, line: &str) -> f32 {
+		let font = self.scaled_font();
+		self::line_glyphs(&font, line)
+			.last()
+			.map_or(0.0, |(glyph_id, x)| x + font.h_advance(glyph_id))
+	}
+
+	/// Wraps `text` into lines of at most `max_width`.
+	///
+	/// Words wider than `max_width` are kept in their own line.
+	pub fn wrap(&self, text: &str, max_width: f32) -> Vec<String> {
+		let mut lines = vec![];
+		for paragraph in text.lines() {
+			let mut line = String::new();
+			for word in paragraph.split_whitespace() {
+				let prev_len = line.len();
+				if !line.is_empty() {
+					line.push(' ');
+				}
+				line.push_str(word);
+
+				if prev_len != 0 && self.line_width(&line) > max_width {
+					line.truncate(prev_len);
+					lines.push(mem::replace(&mut line, word.to_owned()));
+				}
+			}
+			lines.push(line);
+		}
+
+		lines
+	}
+
+	/// Creates an image of `size` filled with the background
+	pub fn background(&self, size: Vector2<u32>) -> RgbaImage {
+		RgbaImage::from_pixel(size.x, size.y, Rgba(self.style.background))
+	}
+
+	/// Draws `line` onto `image`, with it's top left at `pos`
+	#[expect(clippy::cast_sign_loss)] // Blended channels are always within `0..=255`
+	pub fn draw_line(&self, image: &mut RgbaImage, line: &str, pos: Vector2<f32>) {
+		let font = self.scaled_font();
+		let color = self.style.color;
+		for (glyph_id, x) in self::line_glyphs(&font, line) {
+			let glyph =
+				glyph_id.with_scale_and_position(font.scale(), ab_glyph::point(pos.x + x, pos.y + font.ascent()));
+			let Some(glyph) = font.outline_glyph(glyph) else {
+				continue;
+			};
+
+			let bounds = glyph.px_bounds();
+			glyph.draw(|glyph_x, glyph_y, coverage| {
+				let x = bounds.min.x as i64 + i64::from(glyph_x);
+				let y = bounds.min.y as i64 + i64::from(glyph_y);
+				let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) else {
+					return;
+				};
+				let Some(pixel) = image.get_pixel_mut_checked(x, y) else {
+					return;
+				};
+
+				// Note: We blend the text over the background, by it's coverage and alpha
+				let alpha = coverage.clamp(0.0, 1.0) * f32::from(color[3]) / 255.0;
+				for (channel, &color_channel) in pixel.0.iter_mut().zip(&color).take(3) {
+					let blended = f32::from(*channel) * (1.0 - alpha) + f32::from(color_channel) * alpha;
+					*channel = blended.round() as u8;
+				}
+			});
+		}
+	}
+
+	/// Returns the font, scaled to the font size
+	fn scaled_font(&self) -> PxScaleFont<&FontArc> {
+		self.font.as_scaled(self.style.font_size)
+	}
+}
+
+/// Returns the glyphs of `line`, along with their horizontal offsets
+fn line_glyphs<'a>(font: &'a PxScaleFont<&FontArc>, line: &'a str) -> impl Iterator<Item = (GlyphId, f32)> + 'a {
+	line.chars().scan((None, 0.0), |(prev_glyph_id, x), ch| {
+		let glyph_id = font.glyph_id(ch);
+		if let Some(prev_glyph_id) = *prev_glyph_id {
+			*x += font.kern(prev_glyph_id, glyph_id);
+		}
+		let glyph_x = *x;
+		*x += font.h_advance(glyph_id);
+		*prev_glyph_id = Some(glyph_id);
+
+		Some((glyph_id, glyph_x))
+	})
+}
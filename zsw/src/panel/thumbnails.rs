@@ -0,0 +1,81 @@
+//! Panel thumbnails
+
+// Imports
+use {
+	super::{Panel, PanelImage},
+	crate::AppError,
+	std::collections::HashMap,
+	zsw_egui::EguiRenderer,
+	zsw_wgpu::WgpuShared,
+};
+
+/// Panel thumbnails.
+///
+/// Keeps the egui textures of all panel image thumbnails.
+#[derive(Debug)]
+pub struct PanelThumbnails {
+	/// Registered textures, by their texture view
+	textures: HashMap<wgpu::Id<wgpu::TextureView>, egui::TextureId>,
+
+	/// Unused textures
+	// Note: We never free textures, since egui paint jobs might still
+	//       reference them, so instead we re-use them for other thumbnails.
+	free_textures: Vec<egui::TextureId>,
+}
+
+impl PanelThumbnails {
+	/// Creates new, empty, panel thumbnails
+	pub fn new() -> Self {
+		Self {
+			textures:      HashMap::new(),
+			free_textures: vec![],
+		}
+	}
+
+	/// Returns the egui texture of a thumbnail texture view, if registered
+	pub fn get(&self, texture_view: &wgpu::TextureView) -> Option<egui::TextureId> {
+		self.textures.get(&texture_view.global_id()).copied()
+	}
+
+	/// Updates the thumbnails for all panels
+	pub fn update(
+		&mut self,
+		egui_renderer: &mut EguiRenderer,
+		wgpu_shared: &WgpuShared,
+		panels: &[Panel],
+	) -> Result<(), AppError> {
+		let texture_views = panels
+			.iter()
+			.flat_map(|panel| [panel.images.prev(), panel.images.cur(), panel.images.next()])
+			.map(PanelImage::thumbnail_texture_view)
+			.collect::<Vec<_>>();
+
+		// Free any textures no longer in use
+		self.textures.retain(|id, texture_id| {
+			let in_use = texture_views.iter().any(|texture_view| texture_view.global_id() == *id);
+			if !in_use {
+				self.free_textures.push(*texture_id);
+			}
+			in_use
+		});
+
+		// Then register any new ones
+		for texture_view in texture_views {
+			let id = texture_view.global_id();
+			if self.textures.contains_key(&id) {
+				continue;
+			}
+
+			let texture_id = match self.free_textures.pop() {
+				Some(texture_id) => {
+					egui_renderer.update_texture(wgpu_shared, texture_view, texture_id)?;
+					texture_id
+				},
+				None => egui_renderer.register_texture(wgpu_shared, texture_view),
+			};
+			let _ = self.textures.insert(id, texture_id);
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,289 @@
+//! Panel ticker
+//!
+//! Headlines are fetched from news feeds periodically, and rendered into
+//! a wide strip, which the panel then scrolls along.
+
+// Imports
+use {
+	super::{ser, text::TextRenderer},
+	anyhow::Context,
+	cgmath::Vector2,
+	image::DynamicImage,
+	std::{
+		sync::{Arc, Mutex, PoisonError, Weak},
+		time::Duration,
+	},
+	zsw_error::AppError,
+};
+
+/// Panel ticker
+#[derive(Debug)]
+pub struct PanelTicker {
+	/// Feed urls
+	feeds: Vec<String>,
+
+	/// Interval between fetching the feeds
+	interval: Duration,
+
+	/// Text renderer
+	text_renderer: TextRenderer,
+
+	/// Latest headlines of each feed
+	headlines: Arc<Mutex<Vec<Vec<String>>>>,
+}
+
+impl PanelTicker {
+	/// Creates a ticker from `config`.
+	///
+	/// No headlines are fetched until [`run_fetcher`] is run
+	pub fn new(config: ser::PanelTicker) -> Result<Self, AppError> {
+		let text_renderer = TextRenderer::new(config.style).context("Unable to create text renderer")?;
+
+		Ok(Self {
+			headlines: Arc::new(Mutex::new(vec![vec![]; config.feeds.len()])),
+			feeds: config.feeds,
+			interval: Duration::from_secs(config.interval),
+			text_renderer,
+		})
+	}
+
+	/// Returns the configuration
+	#[cfg(feature = "menu")]
+	pub fn config(&self) -> ser::PanelTicker {
+		ser::PanelTicker {
+			feeds:    self.feeds.clone(),
+			interval: self.interval.as_secs(),
+			style:    self.text_renderer.style().clone(),
+		}
+	}
+
+	/// Returns the feed urls
+	pub fn feeds(&self) -> &[String] {
+		&self.feeds
+	}
+
+	/// Returns the interval between fetching the feeds
+	pub fn interval(&self) -> Duration {
+		self.interval
+	}
+
+	/// Returns the latest headlines of each feed
+	pub fn headlines(&self) -> &Arc<Mutex<Vec<Vec<String>>>> {
+		&self.headlines
+	}
+
+	/// Renders the latest headlines into a strip with the height of `size`.
+	///
+	/// The strip is padded with `size`'s width on both sides, so the headlines
+	/// scroll fully into and out of the panel.
+	/// Returns `None` if no headlines have been fetched yet.
+	pub fn render(&self, size: Vector2<u32>, max_image_size: u32) -> Option<DynamicImage> {
+		/// Separator between each headline
+		const SEPARATOR: &str = "   •   ";
+
+		let text = {
+			let headlines = self.headlines.lock().unwrap_or_else(PoisonError::into_inner);
+			headlines
+				.iter()
+				.flatten()
+				.map(String::as_str)
+				.collect::<Vec<_>>()
+				.join(SEPARATOR)
+		};
+		if text.is_empty() {
+			return None;
+		}
+
+		// Note: If the strip doesn't fit in a texture, we cut off the last headlines.
+		#[expect(clippy::cast_sign_loss, clippy::cast_possible_truncation)] // Widths are never negative
+		let text_width = self.text_renderer.line_width(&text).ceil() as u32;
+		let width = text_width.saturating_add(2 * size.x).min(max_image_size);
+
+		let mut image = self.text_renderer.background(Vector2::new(width, size.y));
+		let y = (size.y as f32 - self.text_renderer.line_height()) / 2.0;
+		self.text_renderer
+			.draw_line(&mut image, &text, Vector2::new(size.x as f32, y));
+
+		Some(DynamicImage::ImageRgba8(image))
+	}
+}
+
+/// Fetches the headlines of all `feeds` into `headlines` every `interval`.
+///
+/// Returns once `headlines` is dropped.
+#[cfg(feature = "online")]
+pub async fn run_fetcher(
+	feeds: Vec<String>,
+	interval: Duration,
+	headlines: Weak<Mutex<Vec<Vec<String>>>>,
+) -> Result<(), AppError> {
+	loop {
+		for (feed_idx, feed) in feeds.iter().enumerate() {
+			let feed_headlines = tokio::task::spawn_blocking({
+				let feed = feed.clone();
+				move || self::fetch_headlines(&feed)
+			})
+			.await
+			.context("Unable to join feed fetch task")?;
+
+			// Note: If we're offline, or the feed is unavailable, we keep the previous headlines
+			let feed_headlines = match feed_headlines {
+				Ok(feed_headlines) => feed_headlines,
+				Err(err) => {
+					tracing::warn!(feed = ?self::redact_feed(feed), ?err, "Unable to fetch feed headlines");
+					continue;
+				},
+			};
+
+			let Some(headlines) = headlines.upgrade() else {
+				return Ok(());
+			};
+			let mut headlines = headlines.lock().unwrap_or_else(PoisonError::into_inner);
+			if let Some(headlines) = headlines.get_mut(feed_idx) {
+				*headlines = feed_headlines;
+			}
+		}
+
+		if headlines.strong_count() == 0 {
+			return Ok(());
+		}
+		tokio::time::sleep(interval).await;
+	}
+}
+
+/// Fetches the headlines of all `feeds` into `headlines` every `interval`.
+///
+/// Returns an error immediately, since fetching feeds requires the `online` feature.
+#[cfg(not(feature = "online"))]
+pub async fn run_fetcher(
+	_feeds: Vec<String>,
+	_interval: Duration,
+	_headlines: Weak<Mutex<Vec<Vec<String>>>>,
+) -> Result<(), AppError> {
+	Err(anyhow::anyhow!("Fetching feeds requires the `online` feature").into())
+}
+
+/// Redacts `feed`, keeping only it's scheme and host.
+///
+/// Feed urls often include api tokens, so only this is logged or shown.
+pub fn redact_feed(feed: &str) -> String {
+	let Some((scheme, rest)) = feed.split_once("://") else {
+		return "<redacted>".to_owned();
+	};
+	let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+	let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+
+	format!("{scheme}://{host}/<redacted>")
+}
+
+/// Fetches the headlines of `feed`
+#[cfg(feature = "online")]
+fn fetch_headlines(feed: &str) -> Result<Vec<String>, AppError> {
+	use std::io::Read;
+
+	/// Timeout for fetching a feed
+	const TIMEOUT: Duration = Duration::from_secs(30);
+
+	/// Maximum feed size
+	const MAX_SIZE: u64 = 16 * 1024 * 1024;
+
+	// Note: `ureq`'s errors include the url, which may contain secrets, so we only keep their kind
+	let response = ureq::get(feed).timeout(TIMEOUT).call().map_err(|err| match err {
+		ureq::Error::Status(status, _) => anyhow::anyhow!("Feed returned status {status}"),
+		ureq::Error::Transport(err) => anyhow::anyhow!("Unable to fetch feed: {}", err.kind()),
+	})?;
+	let mut contents = vec![];
+	let _ = response
+		.into_reader()
+		.take(MAX_SIZE)
+		.read_to_end(&mut contents)
+		.context("Unable to read feed")?;
+
+	self::parse_headlines(&contents)
+}
+
+/// Parses all headlines from a feed.
+///
+/// Supports both RSS (`<item>`) and Atom (`<entry>`) feeds.
+#[cfg(feature = "online")]
+fn parse_headlines(contents: &[u8]) -> Result<Vec<String>, AppError> {
+	use xml::reader::{EventReader, XmlEvent};
+
+	let mut headlines = vec![];
+	let mut in_item = false;
+	let mut title = None::<String>;
+	for event in EventReader::new(contents) {
+		match event.context("Unable to parse feed")? {
+			XmlEvent::StartElement { name, .. } => match name.local_name.as_str() {
+				"item" | "entry" => in_item = true,
+				"title" if in_item => title = Some(String::new()),
+				_ => (),
+			},
+			XmlEvent::EndElement { name } => match name.local_name.as_str() {
+				"item" | "entry" => in_item = false,
+				"title" =>
+					if let Some(title) = title.take() {
+						let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+						if !title.is_empty() {
+							headlines.push(title);
+						}
+					},
+				_ => (),
+			},
+			XmlEvent::Characters(text) | XmlEvent::CData(text) =>
+				if let Some(title) = &mut title {
+					title.push_str(&text);
+				},
+			_ => (),
+		}
+	}
+
+	Ok(headlines)
+}
+
+#[cfg(test)]
+#[cfg(feature = "online")]
+mod test {
+	use super::*;
+
+	#[test]
+	fn rss_headlines() {
+		let feed = r#"<?xml version="1.0"?>
+<rss version="2.0">
+	<channel>
+		<title>Feed title</title>
+		<item>
+			<title>First
+				headline</title>
+		</item>
+		<item>
+			<title><![CDATA[Second <b>headline</b>]]></title>
+		</item>
+		<item>
+			<title>   </title>
+		</item>
+	</channel>
+</rss>"#;
+
+		let headlines = self::parse_headlines(feed.as_bytes()).expect("Unable to parse feed");
+		assert_eq!(
+			headlines,
+			["First headline", "Second <b>headline</b>"],
+			"Wrong headlines"
+		);
+	}
+
+	#[test]
+	fn atom_headlines() {
+		let feed = r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+	<title>Feed title</title>
+	<entry>
+		<title>  Only   headline  </title>
+	</entry>
+</feed>"#;
+
+		let headlines = self::parse_headlines(feed.as_bytes()).expect("Unable to parse feed");
+		assert_eq!(headlines, ["Only headline"], "Wrong headlines");
+	}
+}
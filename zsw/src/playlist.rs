@@ -3,9 +3,13 @@
 // Modules
 mod player;
 mod ser;
+mod watcher;
 
 // Exports
-pub use self::player::PlaylistPlayer;
+pub use self::{
+	player::{PlaylistCursor, PlaylistLease, PlaylistPlayer},
+	watcher::PlaylistWatcher,
+};
 
 // Imports
 use {
@@ -119,37 +123,47 @@ impl Playlists {
 	/// Adds a playlist.
 	///
 	/// Saves the playlist to disk.
+	#[cfg(feature = "menu")]
 	pub async fn add(&mut self, path: &Path) -> Result<(PlaylistName, Arc<RwLock<Playlist>>), anyhow::Error> {
-		// Create the playlist path, ensuring we don't overwrite an existing path
-		let mut playlist_name = path
+		let playlist_name = path
 			.file_name()
 			.context("Path has no file name")?
 			.to_os_string()
 			.into_string()
 			.map_err(|file_name| anyhow!("Playlist file name was non-utf8: {file_name:?}"))?;
+
+		// Load the playlist
+		// Note: Since the playlist is moving directories, we save all paths as absolute
+		let playlist = self::load_playlist(path).await?;
+		for item in &playlist.items {
+			item.write().await.ser_path = None;
+		}
+
+		let playlist_name = self.create(&playlist_name, playlist).await?;
+		let playlist = self.get(&playlist_name).expect("Playlist was just created");
+
+		Ok((playlist_name, playlist))
+	}
+
+	/// Creates a new playlist.
+	///
+	/// If a playlist with the same name exists, appends `-new` to the name.
+	/// Saves the playlist to disk.
+	pub async fn create(&mut self, name: &str, playlist: Playlist) -> Result<PlaylistName, anyhow::Error> {
+		// Create the playlist name, ensuring we don't overwrite an existing playlist
+		let mut playlist_name = name.to_owned();
 		while self.playlists.contains_key(playlist_name.as_str()) {
 			playlist_name.push_str("-new");
 		}
 		let playlist_name = PlaylistName::from(playlist_name);
-
-		// Load the playlist
-		let playlist = self::load_playlist(path).await?;
-		let playlist = self
+		let _ = self
 			.playlists
-			.entry(playlist_name.clone())
-			.insert_entry(Arc::new(RwLock::new(playlist)))
-			.into_mut();
-		let playlist = Arc::clone(playlist);
+			.insert(playlist_name.clone(), Arc::new(RwLock::new(playlist)));
 
-		// Save it to disk
-		let ser_playlist = self::serialize_playlist(&playlist).await;
-		let playlist_toml = toml::to_string(&ser_playlist).context("Unable to serialize playlist")?;
-		let playlist_path = self.playlist_path(&playlist_name);
-		tokio::fs::write(playlist_path, playlist_toml)
-			.await
-			.context("Unable to write playlist to file")?;
+		// Then save it to disk
+		self.save(&playlist_name).await?;
 
-		Ok((playlist_name, playlist))
+		Ok(playlist_name)
 	}
 
 	/// Saves a loaded playlist by name.
@@ -164,8 +178,18 @@ impl Playlists {
 
 		// And save it
 		let playlist = self::serialize_playlist(&playlist).await;
-		let playlist_toml = toml::to_string(&playlist).context("Unable to serialize playlist")?;
+		let mut playlist_toml = toml::to_string(&playlist).context("Unable to serialize playlist")?;
 		let playlist_path = self.playlist_path(name);
+
+		// Keep any comments the user wrote in the previous file
+		// Note: If the file doesn't exist yet, there's nothing to keep
+		if let Ok(prev_playlist_toml) = tokio::fs::read_to_string(&playlist_path).await {
+			match zsw_util::preserve_toml_decor(&prev_playlist_toml, &playlist_toml) {
+				Ok(merged_playlist_toml) => playlist_toml = merged_playlist_toml,
+				Err(err) => tracing::warn!(?playlist_path, ?err, "Unable to preserve playlist comments"),
+			}
+		}
+
 		tokio::fs::write(playlist_path, playlist_toml)
 			.await
 			.context("Unable to write playlist to file")?;
@@ -173,7 +197,32 @@ impl Playlists {
 		Ok(())
 	}
 
+	/// Adds a transient playlist.
+	///
+	/// The playlist isn't saved to disk.
+	pub fn add_transient(&mut self, name: PlaylistName, playlist: Playlist) -> Arc<RwLock<Playlist>> {
+		let playlist = self
+			.playlists
+			.entry(name)
+			.insert_entry(Arc::new(RwLock::new(playlist)))
+			.into_mut();
+
+		Arc::clone(playlist)
+	}
+
+	/// Reloads all playlists from the playlists directory.
+	///
+	/// Playlists that no longer exist on disk, or are transient, are kept.
+	#[cfg(feature = "menu")]
+	pub async fn reload_all(&mut self) -> Result<(), AppError> {
+		let reloaded = Self::load(self.root.clone()).await?;
+		self.playlists.extend(reloaded.playlists);
+
+		Ok(())
+	}
+
 	/// Reloads a playlist by name.
+	#[cfg(feature = "menu")]
 	pub async fn reload(&mut self, name: PlaylistName) -> Result<Arc<RwLock<Playlist>>, AppError> {
 		let playlist_path = self.playlist_path(&name);
 		let playlist = self::load_playlist(&playlist_path).await?;
@@ -192,13 +241,38 @@ impl Playlists {
 pub struct Playlist {
 	/// All items
 	items: Vec<Arc<RwLock<PlaylistItem>>>,
+
+	/// Whether to play items in order
+	sequential: bool,
+
+	/// Whether this playlist is private
+	private: bool,
 }
 
 impl Playlist {
+	/// Creates a shuffled playlist from it's items
+	pub fn new(items: Vec<PlaylistItem>) -> Self {
+		Self {
+			items:      items.into_iter().map(RwLock::new).map(Arc::new).collect(),
+			sequential: false,
+			private:    false,
+		}
+	}
+
 	/// Returns all items
 	pub fn items(&self) -> Vec<Arc<RwLock<PlaylistItem>>> {
 		self.items.clone()
 	}
+
+	/// Returns whether to play items in order, instead of shuffled
+	pub fn sequential(&self) -> bool {
+		self.sequential
+	}
+
+	/// Returns whether this playlist is private, and hidden while in privacy mode
+	pub fn private(&self) -> bool {
+		self.private
+	}
 }
 
 /// Playlist item
@@ -209,6 +283,11 @@ pub struct PlaylistItem {
 
 	/// Kind
 	pub kind: PlaylistItemKind,
+
+	/// Path, as written in the playlist file.
+	///
+	/// Used when saving, so relative paths and variables aren't lost.
+	pub ser_path: Option<PathBuf>,
 }
 
 /// Playlist item kind
@@ -219,16 +298,65 @@ pub enum PlaylistItemKind {
 		path: Arc<Path>,
 
 		recursive: bool,
+
+		/// Extensions to include, if any
+		extensions: Option<Vec<String>>,
 	},
 
 	/// File
 	File { path: Arc<Path> },
+
+	/// Encrypted bundle
+	Bundle { path: Arc<Path> },
+
+	/// Comic book archive
+	Comic { path: Arc<Path> },
+}
+
+impl PlaylistItemKind {
+	/// Returns the path of this item
+	pub fn path(&self) -> &Arc<Path> {
+		match self {
+			Self::Directory { path, .. } | Self::File { path } | Self::Bundle { path } | Self::Comic { path } => path,
+		}
+	}
+}
+
+/// Returns if `path` has one of `extensions`, ignoring case.
+///
+/// If there are no `extensions`, all paths match.
+pub fn has_extension(path: &Path, extensions: Option<&[String]>) -> bool {
+	let Some(extensions) = extensions else {
+		return true;
+	};
+
+	path.extension()
+		.and_then(OsStr::to_str)
+		.is_some_and(|path_ext| extensions.iter().any(|ext| ext.eq_ignore_ascii_case(path_ext)))
 }
 
 /// Playlist name
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Debug)]
 pub struct PlaylistName(Arc<str>);
 
+impl PlaylistName {
+	/// Prefix of the names of transient playlists showing a path
+	const SHOW_PREFIX: &'static str = "show: ";
+
+	/// Returns the name of the transient playlist showing `path`
+	pub fn show(path: &Path) -> Self {
+		Self::from(format!("{}{}", Self::SHOW_PREFIX, path.display()))
+	}
+
+	/// Returns if this is the name of a transient playlist showing a path.
+	///
+	/// These playlists only exist while running, so they can't be saved.
+	#[cfg(feature = "menu")]
+	pub fn is_show(&self) -> bool {
+		self.0.starts_with(Self::SHOW_PREFIX)
+	}
+}
+
 impl From<String> for PlaylistName {
 	fn from(s: String) -> Self {
 		Self(s.into())
@@ -257,59 +385,102 @@ async fn load_playlist(path: &Path) -> Result<Playlist, AppError> {
 	tracing::trace!(?path, ?playlist_toml, "Parsing playlist file");
 	let playlist = toml::from_str::<ser::Playlist>(&playlist_toml).context("Unable to parse playlist")?;
 	tracing::trace!(?path, ?playlist, "Parsed playlist file");
-	let playlist = self::deserialize_playlist(playlist);
+	playlist.validate().context("Invalid playlist")?;
+	let playlist_dir = path.parent().context("Playlist path has no parent directory")?;
+	let playlist = self::deserialize_playlist(playlist, playlist_dir);
 
 	Ok(playlist)
 }
 
 /// Serializes a playlist to it's serialized format
 async fn serialize_playlist(playlist: &RwLock<Playlist>) -> ser::Playlist {
+	let playlist = playlist.read().await;
 	ser::Playlist {
-		items: {
-			let playlist = playlist.read().await;
-			playlist
-				.items
-				.iter()
-				.map(|item| async move {
-					let item = item.read().await;
-					ser::PlaylistItem {
-						enabled: item.enabled,
-						kind:    match &item.kind {
-							PlaylistItemKind::Directory { path, recursive } => ser::PlaylistItemKind::Directory {
-								path:      path.to_path_buf(),
-								recursive: *recursive,
-							},
-							PlaylistItemKind::File { path } => ser::PlaylistItemKind::File {
-								path: path.to_path_buf(),
-							},
+		items:      playlist
+			.items
+			.iter()
+			.map(|item| async move {
+				let item = item.read().await;
+				let path = item.ser_path.clone().unwrap_or_else(|| item.kind.path().to_path_buf());
+				ser::PlaylistItem {
+					enabled: item.enabled,
+					kind:    match item.kind {
+						PlaylistItemKind::Directory {
+							recursive,
+							ref extensions,
+							..
+						} => ser::PlaylistItemKind::Directory {
+							path,
+							recursive,
+							extensions: extensions.clone(),
 						},
-					}
-				})
-				.collect::<FuturesUnordered<_>>()
-				.collect()
-				.await
-		},
+						PlaylistItemKind::File { .. } => ser::PlaylistItemKind::File { path },
+						PlaylistItemKind::Bundle { .. } => ser::PlaylistItemKind::Bundle { path },
+						PlaylistItemKind::Comic { .. } => ser::PlaylistItemKind::Comic { path },
+					},
+				}
+			})
+			.collect::<FuturesUnordered<_>>()
+			.collect()
+			.await,
+		sequential: playlist.sequential,
+		private:    playlist.private,
 	}
 }
 
-/// Deserializes a playlist from it's serialized format
-fn deserialize_playlist(playlist: ser::Playlist) -> Playlist {
+/// Deserializes a playlist from it's serialized format.
+///
+/// Relative paths are resolved against `playlist_dir`.
+fn deserialize_playlist(playlist: ser::Playlist, playlist_dir: &Path) -> Playlist {
 	Playlist {
-		items: playlist
+		items:      playlist
 			.items
 			.into_iter()
-			.map(|item| PlaylistItem {
-				enabled: item.enabled,
-				kind:    match item.kind {
-					ser::PlaylistItemKind::Directory { path, recursive } => PlaylistItemKind::Directory {
-						path: path.into(),
+			.map(|item| {
+				let resolve_path = |path: &Path| Arc::<Path>::from(playlist_dir.join(zsw_util::expand_path(path)));
+				let (kind, ser_path) = match item.kind {
+					ser::PlaylistItemKind::Directory {
+						path,
 						recursive,
-					},
-					ser::PlaylistItemKind::File { path } => PlaylistItemKind::File { path: path.into() },
-				},
+						extensions,
+					} => (
+						PlaylistItemKind::Directory {
+							path: resolve_path(&path),
+							recursive,
+							extensions,
+						},
+						path,
+					),
+					ser::PlaylistItemKind::File { path } => (
+						PlaylistItemKind::File {
+							path: resolve_path(&path),
+						},
+						path,
+					),
+					ser::PlaylistItemKind::Bundle { path } => (
+						PlaylistItemKind::Bundle {
+							path: resolve_path(&path),
+						},
+						path,
+					),
+					ser::PlaylistItemKind::Comic { path } => (
+						PlaylistItemKind::Comic {
+							path: resolve_path(&path),
+						},
+						path,
+					),
+				};
+
+				PlaylistItem {
+					enabled: item.enabled,
+					kind,
+					ser_path: Some(ser_path),
+				}
 			})
 			.map(RwLock::new)
 			.map(Arc::new)
 			.collect(),
+		sequential: playlist.sequential,
+		private:    playlist.private,
 	}
 }
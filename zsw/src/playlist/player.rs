@@ -2,12 +2,17 @@
 
 // Imports
 use {
-	rand::{rngs::StdRng, seq::SliceRandom, SeedableRng},
+	crate::ratings::{RatingSelection, Ratings, DEFAULT_RATING},
+	crossbeam::atomic::AtomicCell,
+	rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng},
 	std::{
-		collections::{HashSet, VecDeque},
+		cmp::Ordering,
+		collections::{HashMap, HashSet, VecDeque},
+		iter,
 		path::Path,
-		sync::Arc,
+		sync::{Arc, Mutex, PoisonError, Weak},
 	},
+	tokio::sync::watch,
 };
 
 /// Playlist player
@@ -16,6 +21,11 @@ pub struct PlaylistPlayer {
 	/// All items
 	items: HashSet<Arc<Path>>,
 
+	/// Roots
+	///
+	/// Directories / files the items were found in
+	roots: HashSet<Arc<Path>>,
+
 	/// Previous items
 	///
 	/// Last item is newest item
@@ -31,24 +41,97 @@ pub struct PlaylistPlayer {
 
 	/// Rng
 	rng: StdRng,
+
+	/// Rating selection, if any
+	rating_selection: Option<(Arc<Ratings>, RatingSelection)>,
+
+	/// Whether to play items in order, instead of shuffled
+	sequential: bool,
+
+	/// Whether to skip leased items
+	coordinated: bool,
+
+	/// Number of leases of each leased item
+	leases: Arc<Mutex<HashMap<Arc<Path>, usize>>>,
+
+	/// Cursors
+	cursors: Vec<Weak<PlaylistCursorInner>>,
+
+	/// Items returned to cursors.
+	///
+	/// Kept until all cursors have moved past them.
+	cursor_items: VecDeque<Arc<Path>>,
+
+	/// Position of the first cursor item
+	cursor_items_start: i64,
+
+	/// Generation
+	///
+	/// Incremented each time all items are removed
+	generation: watch::Sender<u64>,
 }
 
 impl PlaylistPlayer {
+	/// Maximum number of items kept for cursors.
+	///
+	/// Cursors lagging further behind than this skip ahead.
+	/// Cursor offsets must be less than this, so cursors that are
+	/// only behind by their offset never skip ahead.
+	pub const MAX_CURSOR_ITEMS: usize = 256;
+
 	/// Creates a new, empty, player
 	pub fn new() -> Self {
 		Self {
-			items:          HashSet::new(),
-			prev_items:     VecDeque::new(),
-			max_prev_items: 64,
-			next_items:     vec![],
-			rng:            StdRng::from_entropy(),
+			items:              HashSet::new(),
+			roots:              HashSet::new(),
+			prev_items:         VecDeque::new(),
+			max_prev_items:     64,
+			next_items:         vec![],
+			rng:                StdRng::from_entropy(),
+			rating_selection:   None,
+			sequential:         false,
+			coordinated:        false,
+			leases:             Arc::new(Mutex::new(HashMap::new())),
+			cursors:            vec![],
+			cursor_items:       VecDeque::new(),
+			cursor_items_start: 0,
+			generation:         watch::Sender::new(0),
 		}
 	}
 
 	/// Adds an item to the playlist
 	pub fn add(&mut self, path: Arc<Path>) {
-		// TODO: Should we care if the item was already in?
-		let _ = self.items.insert(path);
+		if !self.items.insert(Arc::clone(&path)) {
+			return;
+		}
+
+		// If we're in the middle of a loop, add it to a random position of it,
+		// or it's sorted position, if sequential.
+		// Note: Otherwise, the item will be added once the next loop starts
+		if !self.next_items.is_empty() && self.is_selectable(&path) {
+			let idx = match self.sequential {
+				true => self
+					.next_items
+					.partition_point(|item| self::natural_cmp(item, &path).is_gt()),
+				false => self.rng.gen_range(0..=self.next_items.len()),
+			};
+			self.next_items.insert(idx, path);
+		}
+	}
+
+	/// Adds a root to the playlist
+	pub fn add_root(&mut self, root: Arc<Path>) {
+		let _ = self.roots.insert(root);
+	}
+
+	/// Returns the root `path` was found in, if any
+	pub fn root_of(&self, path: &Path) -> Option<Arc<Path>> {
+		// Note: Roots may be nested, so we pick the innermost one
+		self.roots
+			.iter()
+			.filter(|root| path.starts_with(root))
+			.max_by_key(|root| root.components().count())
+			.cloned()
 	}
 
 	/// Removes an item from the playlist
@@ -58,13 +141,86 @@ impl PlaylistPlayer {
 		let _ = self.items.remove(path);
 		self.prev_items.retain(|item| &**item != path);
 		self.next_items.retain(|item| &**item != path);
+
+		// Then remove it from the cursor items, moving back any cursors after it,
+		// so they keep pointing at the same items.
+		while let Some(idx) = self.cursor_items.iter().rposition(|item| &**item == path) {
+			let _ = self.cursor_items.remove(idx);
+
+			#[expect(clippy::cast_possible_wrap)] // We never have that many items
+			let position = self.cursor_items_start + idx as i64;
+			for cursor in self.cursors.iter().filter_map(Weak::upgrade) {
+				let _ = cursor
+					.position
+					.fetch_update(|cursor_position| (cursor_position > position).then_some(cursor_position - 1));
+			}
+		}
 	}
 
 	/// Removes all paths from the playlist
+	#[cfg(feature = "menu")]
 	pub fn remove_all(&mut self) {
 		self.items.clear();
+		self.roots.clear();
 		self.prev_items.clear();
 		self.next_items.clear();
+		self.cursor_items.clear();
+		self.generation.send_modify(|generation| *generation += 1);
+	}
+
+	/// Subscribes to changes of the generation.
+	///
+	/// The generation changes whenever all items are removed.
+	/// The receiver is also notified once the player is dropped.
+	pub fn subscribe_generation(&self) -> watch::Receiver<u64> {
+		self.generation.subscribe()
+	}
+
+	/// Jumps to `path`, so it's the next item returned.
+	///
+	/// If `path` was upcoming in this loop, it's moved, else it's shown an extra time.
+	/// Returns `false` if `path` isn't in the playlist.
+	// Note: Cursors may have already buffered the items before the jump.
+	#[cfg(any(feature = "menu", test))]
+	pub fn jump_to(&mut self, path: &Path) -> bool {
+		let Some(item) = self.items.get(path).cloned() else {
+			return false;
+		};
+
+		// TODO: Not have `O(N)` complexity
+		if let Some(item_idx) = self.next_items.iter().rposition(|next_item| *next_item == item) {
+			let _ = self.next_items.remove(item_idx);
+		}
+		self.next_items.push(item);
+
+		true
+	}
+
+	/// Jumps to the upcoming item at `idx`, so it's the next item returned.
+	///
+	/// Items are indexed as ordered by [`Self::peek_next_items`].
+	/// Returns `false` if there's no item at `idx`.
+	#[cfg(any(feature = "menu", test))]
+	pub fn jump_to_index(&mut self, idx: usize) -> bool {
+		if idx >= self.next_items.len() {
+			return false;
+		}
+		let item_idx = self.next_items.len() - idx - 1;
+
+		let item = self.next_items.remove(item_idx);
+		self.next_items.push(item);
+
+		true
+	}
+
+	/// Returns an iterator over all items whose path contains `query`, case insensitively
+	#[cfg(any(feature = "menu", test))]
+	pub fn search(&self, query: &str) -> impl Iterator<Item = &Arc<Path>> {
+		// TODO: Not use lossy conversion to search it?
+		let query = query.to_lowercase();
+		self.items
+			.iter()
+			.filter(move |item| item.to_string_lossy().to_lowercase().contains(&query))
 	}
 
 	/// Clears the current backlog
@@ -73,7 +229,17 @@ impl PlaylistPlayer {
 		self.next_items.clear();
 	}
 
+	/// Reshuffles the remaining items in this loop, with a new seed.
+	///
+	/// If sequential, they're instead kept sorted by path
+	#[cfg(any(feature = "menu", test))]
+	pub fn reshuffle(&mut self) {
+		self.rng = StdRng::from_entropy();
+		self.order_next_items();
+	}
+
 	/// Returns an iterator over all items in the playlist
+	#[cfg(any(feature = "menu", test))]
 	pub fn all_items(&self) -> impl ExactSizeIterator<Item = &Arc<Path>> {
 		self.items.iter()
 	}
@@ -81,6 +247,7 @@ impl PlaylistPlayer {
 	/// Returns an iterator over all consumed items
 	///
 	/// They are ordered from newest to oldest
+	#[cfg(feature = "menu")]
 	pub fn prev_items(&self) -> impl ExactSizeIterator<Item = &Arc<Path>> {
 		self.prev_items.iter().rev()
 	}
@@ -88,21 +255,207 @@ impl PlaylistPlayer {
 	/// Returns an iterator that peeks over the remaining items in this loop.
 	///
 	/// They are ordered from next to last
+	#[cfg(any(feature = "menu", test))]
 	pub fn peek_next_items(&self) -> impl ExactSizeIterator<Item = &Arc<Path>> {
 		self.next_items.iter().rev()
 	}
 
-	/// Returns the next image to load
-	pub fn next(&mut self) -> Option<Arc<Path>> {
+	/// Sets the rating selection.
+	///
+	/// Only takes effect once the next loop starts
+	pub fn set_rating_selection(&mut self, ratings: Arc<Ratings>, selection: RatingSelection) {
+		self.rating_selection = Some((ratings, selection));
+	}
+
+	/// Sets whether to play items in order, instead of shuffled.
+	///
+	/// Only takes effect once the next loop starts
+	pub fn set_sequential(&mut self, sequential: bool) {
+		self.sequential = sequential;
+	}
+
+	/// Sets whether to skip leased items.
+	///
+	/// When the player is shared between panels, this ensures the same item
+	/// is never shown on two panels at once.
+	pub fn set_coordinated(&mut self, coordinated: bool) {
+		self.coordinated = coordinated;
+	}
+
+	/// Returns if `path` may be selected under the current rating selection
+	fn is_selectable(&self, path: &Path) -> bool {
+		match &self.rating_selection {
+			Some((ratings, RatingSelection::Threshold { min_rating })) =>
+				ratings.get(path).unwrap_or(DEFAULT_RATING) >= *min_rating,
+			Some((_, RatingSelection::Weighted)) | None => true,
+		}
+	}
+
+	/// Shuffles all items into the next items.
+	///
+	/// If sequential, they're instead sorted naturally by path
+	fn shuffle_items(&mut self) {
+		let items = self
+			.items
+			.iter()
+			.filter(|item| self.is_selectable(item))
+			.cloned()
+			.collect::<Vec<_>>();
+		self.next_items.extend(items);
+		self.order_next_items();
+	}
+
+	/// Orders the next items randomly, or naturally by path, if sequential
+	fn order_next_items(&mut self) {
+		// Note: Since the last item is the next, we sort them in reverse
+		if self.sequential {
+			self.next_items.sort_by(|lhs, rhs| self::natural_cmp(rhs, lhs));
+			return;
+		}
+
+		match &self.rating_selection {
+			// Note: We use a weighted random sort, where each item gets the key `u ^ (1 / weight)`,
+			//       for `u` uniform in `0.0 .. 1.0`. Since the last item is the next, higher
+			//       rated items tend to be picked earlier in the loop.
+			Some((ratings, RatingSelection::Weighted)) => {
+				let mut keyed_items = self
+					.next_items
+					.drain(..)
+					.map(|item| {
+						let weight = f64::from(ratings.get(&item).unwrap_or(DEFAULT_RATING));
+						let key = self.rng.gen::<f64>().powf(weight.recip());
+						(key, item)
+					})
+					.collect::<Vec<_>>();
+				keyed_items.sort_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs));
+				self.next_items.extend(keyed_items.into_iter().map(|(_, item)| item));
+			},
+			Some((_, RatingSelection::Threshold { .. })) | None => self.next_items.shuffle(&mut self.rng),
+		}
+	}
+
+	/// Adds a cursor, `offset` steps behind the other cursors.
+	///
+	/// Each cursor walks the same sequence of items, so a cursor with offset `1`
+	/// returns the items a cursor with offset `0` returned one step earlier.
+	///
+	/// # Panics
+	/// Panics if `offset` isn't less than [`Self::MAX_CURSOR_ITEMS`].
+	pub fn add_cursor(&mut self, offset: usize) -> PlaylistCursor {
+		assert!(
+			offset < Self::MAX_CURSOR_ITEMS,
+			"Cursor offset must be less than {}",
+			Self::MAX_CURSOR_ITEMS
+		);
+
+		// Note: Cursors that already advanced stay ahead, so we place the new
+		//       cursor relative to the most advanced one.
+		#[expect(clippy::cast_possible_wrap)] // We checked it's less than `MAX_CURSOR_ITEMS` above
+		let offset = offset as i64;
+		let base = self
+			.cursors
+			.iter()
+			.filter_map(Weak::upgrade)
+			.map(|cursor| cursor.position.load() + cursor.offset)
+			.max()
+			.unwrap_or(0);
+		let cursor = Arc::new(PlaylistCursorInner {
+			offset,
+			position: AtomicCell::new(base - offset),
+		});
+		self.cursors.push(Arc::downgrade(&cursor));
+
+		PlaylistCursor { inner: cursor }
+	}
+
+	/// Returns the next image to load at `cursor`, leased until the lease is dropped.
+	pub fn next_at(&mut self, cursor: &PlaylistCursor) -> Option<PlaylistLease> {
+		let position = cursor.inner.position.load();
+
+		// If the cursor is before all items, add items before them
+		while position < self.cursor_items_start {
+			let item = self.next_item()?;
+			self.cursor_items.push_front(item);
+			self.cursor_items_start -= 1;
+		}
+
+		// Then add items until we reach the cursor
+		#[expect(clippy::cast_possible_wrap)] // We never have that many items
+		let cursor_items_end = self.cursor_items_start + self.cursor_items.len() as i64;
+		for _ in cursor_items_end..=position {
+			let item = self.next_item()?;
+			self.cursor_items.push_back(item);
+		}
+
+		#[expect(clippy::cast_sign_loss)] // We ensure the position is after the start above
+		let item = Arc::clone(&self.cursor_items[(position - self.cursor_items_start) as usize]);
+		cursor.inner.position.store(position + 1);
+
+		// Finally remove any items all cursors have moved past
+		self.cursors.retain(|cursor| cursor.strong_count() != 0);
+		let min_position = self
+			.cursors
+			.iter()
+			.filter_map(Weak::upgrade)
+			.map(|cursor| cursor.position.load())
+			.min()
+			.unwrap_or(position + 1);
+		while self.cursor_items_start < min_position && self.cursor_items.pop_front().is_some() {
+			self.cursor_items_start += 1;
+		}
+
+		// If a cursor lags too far behind (e.g. its panel is paused), skip it ahead,
+		// instead of keeping all items for it until it catches up.
+		if self.cursor_items.len() > Self::MAX_CURSOR_ITEMS {
+			let excess = self.cursor_items.len() - Self::MAX_CURSOR_ITEMS;
+			let _ = self.cursor_items.drain(..excess);
+			#[expect(clippy::cast_possible_wrap)] // We never have that many items
+			let excess = excess as i64;
+			self.cursor_items_start += excess;
+
+			for cursor in self.cursors.iter().filter_map(Weak::upgrade) {
+				let _ = cursor
+					.position
+					.fetch_update(|position| (position < self.cursor_items_start).then_some(self.cursor_items_start));
+			}
+		}
+
+		Some(self.lease(item))
+	}
+
+	/// Returns the next image to load, leased until the lease is dropped.
+	///
+	/// If coordinated, leased items are skipped. If all items are leased, returns `None`.
+	pub fn next(&mut self) -> Option<PlaylistLease> {
+		let item = self.next_item()?;
+		Some(self.lease(item))
+	}
+
+	/// Returns the next item
+	fn next_item(&mut self) -> Option<Arc<Path>> {
 		// If we're out of current items, shuffle the items in
 		// Note: If we don't actually have any items, this is essentially a no-op
 		if self.next_items.is_empty() {
-			self.next_items.extend(self.items.iter().cloned());
-			self.next_items.shuffle(&mut self.rng);
+			self.shuffle_items();
 		}
 
-		// Then pop the last item
-		let item = self.next_items.pop()?;
+		// Then pop the last item, or the last not leased, if coordinated
+		let item = match self.coordinated {
+			true => {
+				let mut item_idx = self.next_unleased_item_idx();
+
+				// Note: If all items left in this loop are leased, start the next loop early,
+				//       since the leased items may only be released after we return one.
+				if item_idx.is_none() {
+					self.next_items.clear();
+					self.shuffle_items();
+					item_idx = self.next_unleased_item_idx();
+				}
+
+				self.next_items.remove(item_idx?)
+			},
+			false => self.next_items.pop()?,
+		};
 		self.prev_items.push_back(Arc::clone(&item));
 		if self.prev_items.len() > self.max_prev_items {
 			let _ = self.prev_items.pop_front();
@@ -110,4 +463,383 @@ impl PlaylistPlayer {
 
 		Some(item)
 	}
+
+	/// Returns the index of the next item that isn't leased
+	fn next_unleased_item_idx(&self) -> Option<usize> {
+		let leases = self.leases.lock().unwrap_or_else(PoisonError::into_inner);
+		self.next_items.iter().rposition(|item| !leases.contains_key(item))
+	}
+
+	/// Leases `path`
+	fn lease(&self, path: Arc<Path>) -> PlaylistLease {
+		let mut leases = self.leases.lock().unwrap_or_else(PoisonError::into_inner);
+		*leases.entry(Arc::clone(&path)).or_default() += 1;
+
+		PlaylistLease {
+			path,
+			leases: Arc::downgrade(&self.leases),
+		}
+	}
+}
+
+/// Playlist cursor.
+///
+/// A position within the items of a player shared by several panels.
+#[derive(Debug)]
+pub struct PlaylistCursor {
+	/// Inner
+	inner: Arc<PlaylistCursorInner>,
+}
+
+/// Playlist cursor inner
+#[derive(Debug)]
+struct PlaylistCursorInner {
+	/// Offset behind the other cursors
+	offset: i64,
+
+	/// Position of the next item
+	position: AtomicCell<i64>,
+}
+
+/// Playlist item lease.
+///
+/// While alive, coordinated players won't return the item again.
+#[derive(Debug)]
+pub struct PlaylistLease {
+	/// Path
+	path: Arc<Path>,
+
+	/// Leases of the player
+	leases: Weak<Mutex<HashMap<Arc<Path>, usize>>>,
+}
+
+impl PlaylistLease {
+	/// Returns the leased path
+	pub fn path(&self) -> &Arc<Path> {
+		&self.path
+	}
+}
+
+impl Drop for PlaylistLease {
+	fn drop(&mut self) {
+		// Note: If the player was dropped, there's nothing to release
+		let Some(leases) = self.leases.upgrade() else {
+			return;
+		};
+
+		let mut leases = leases.lock().unwrap_or_else(PoisonError::into_inner);
+		if let Some(count) = leases.get_mut(&self.path) {
+			*count -= 1;
+			if *count == 0 {
+				let _ = leases.remove(&self.path);
+			}
+		}
+	}
+}
+
+/// Compares two paths naturally.
+///
+/// Each component is compared with any runs of digits compared by their
+/// numeric value, so that `page2` is ordered before `page10`.
+fn natural_cmp(lhs: &Path, rhs: &Path) -> Ordering {
+	let mut lhs = lhs.components();
+	let mut rhs = rhs.components();
+	loop {
+		let (lhs, rhs) = match (lhs.next(), rhs.next()) {
+			(None, None) => return Ordering::Equal,
+			(None, Some(_)) => return Ordering::Less,
+			(Some(_), None) => return Ordering::Greater,
+			(Some(lhs), Some(rhs)) => (lhs, rhs),
+		};
+
+		let ordering = self::natural_cmp_str(&lhs.as_os_str().to_string_lossy(), &rhs.as_os_str().to_string_lossy());
+		if ordering.is_ne() {
+			return ordering;
+		}
+	}
+}
+
+/// Compares two strings naturally.
+///
+/// See [`natural_cmp`] for details.
+fn natural_cmp_str(lhs: &str, rhs: &str) -> Ordering {
+	let mut lhs = lhs.chars().peekable();
+	let mut rhs = rhs.chars().peekable();
+	loop {
+		let (lhs_char, rhs_char) = match (lhs.peek(), rhs.peek()) {
+			(None, None) => return Ordering::Equal,
+			(None, Some(_)) => return Ordering::Less,
+			(Some(_), None) => return Ordering::Greater,
+			(Some(&lhs_char), Some(&rhs_char)) => (lhs_char, rhs_char),
+		};
+
+		// If both are numbers, compare them by their value, ignoring any leading zeros
+		if lhs_char.is_ascii_digit() && rhs_char.is_ascii_digit() {
+			let lhs_digits = iter::from_fn(|| lhs.next_if(char::is_ascii_digit)).collect::<String>();
+			let rhs_digits = iter::from_fn(|| rhs.next_if(char::is_ascii_digit)).collect::<String>();
+			let lhs_digits = lhs_digits.trim_start_matches('0');
+			let rhs_digits = rhs_digits.trim_start_matches('0');
+			match lhs_digits
+				.len()
+				.cmp(&rhs_digits.len())
+				.then_with(|| lhs_digits.cmp(rhs_digits))
+			{
+				Ordering::Equal => continue,
+				ordering => return ordering,
+			}
+		}
+
+		match lhs_char.cmp(&rhs_char) {
+			Ordering::Equal => {
+				let _ = lhs.next();
+				let _ = rhs.next();
+			},
+			ordering => return ordering,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use {super::*, std::path::PathBuf};
+
+	#[test]
+	fn reshuffle_keeps_remaining_items() {
+		let mut player = PlaylistPlayer::new();
+		for idx in 0..16 {
+			player.add(Arc::from(Path::new(&format!("{idx}"))));
+		}
+		for _ in 0..4 {
+			let _ = player.next().expect("Player had no items");
+		}
+
+		let mut remaining = player.peek_next_items().cloned().collect::<Vec<_>>();
+		player.reshuffle();
+		let mut reshuffled = player.peek_next_items().cloned().collect::<Vec<_>>();
+		remaining.sort();
+		reshuffled.sort();
+		assert_eq!(remaining, reshuffled, "Reshuffling changed the remaining items");
+	}
+
+	#[test]
+	fn sequential_natural_order() {
+		let mut player = PlaylistPlayer::new();
+		for path in ["page10.png", "page2.png", "page1.png"] {
+			player.add(Arc::from(Path::new(path)));
+		}
+		player.set_sequential(true);
+
+		let items = (0..3)
+			.map(|_| player.next().expect("Player had no items").path().to_path_buf())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			items,
+			["page1.png", "page2.png", "page10.png"].map(PathBuf::from),
+			"Items weren't played in natural order"
+		);
+	}
+
+	#[test]
+	fn jump_to_path() {
+		let mut player = PlaylistPlayer::new();
+		for idx in 0..8 {
+			player.add(Arc::from(Path::new(&format!("{idx}"))));
+		}
+		let _ = player.next().expect("Player had no items");
+
+		let path = Arc::clone(player.peek_next_items().last().expect("Player had no upcoming items"));
+		assert!(player.jump_to(&path), "Unable to jump to an item in the playlist");
+		assert_eq!(
+			*player.next().expect("Player had no items").path(),
+			path,
+			"Jumped item wasn't returned next"
+		);
+		assert!(
+			!player.peek_next_items().any(|item| *item == path),
+			"Jumped item was still upcoming"
+		);
+
+		let upcoming = player.peek_next_items().cloned().collect::<Vec<_>>();
+		assert!(
+			!player.jump_to(Path::new("missing")),
+			"Jumped to an item not in the playlist"
+		);
+		assert!(
+			player.peek_next_items().eq(&upcoming),
+			"Jumping to a missing item changed the upcoming items"
+		);
+	}
+
+	#[test]
+	fn jump_to_index() {
+		let mut player = PlaylistPlayer::new();
+		for idx in 0..8 {
+			player.add(Arc::from(Path::new(&format!("{idx}"))));
+		}
+		let _ = player.next().expect("Player had no items");
+
+		let upcoming = player.peek_next_items().cloned().collect::<Vec<_>>();
+		assert!(player.jump_to_index(2), "Unable to jump to an upcoming item");
+		assert_eq!(
+			*player.next().expect("Player had no items").path(),
+			upcoming[2],
+			"Jumped item wasn't returned next"
+		);
+
+		let upcoming = player.peek_next_items().cloned().collect::<Vec<_>>();
+		for idx in [upcoming.len(), usize::MAX] {
+			assert!(!player.jump_to_index(idx), "Jumped to out of range index {idx}");
+		}
+		assert!(
+			player.peek_next_items().eq(&upcoming),
+			"Jumping to an out of range index changed the upcoming items"
+		);
+	}
+
+	#[test]
+	fn search() {
+		let mut player = PlaylistPlayer::new();
+		for path in ["Photos/Beach.png", "photos/mountain.jpg", "other/beach2.png"] {
+			player.add(Arc::from(Path::new(path)));
+		}
+
+		let mut found = player
+			.search("BEACH")
+			.map(|path| path.to_path_buf())
+			.collect::<Vec<_>>();
+		found.sort();
+		assert_eq!(
+			found,
+			["Photos/Beach.png", "other/beach2.png"].map(PathBuf::from),
+			"Search didn't match case insensitively"
+		);
+		assert_eq!(player.search("photos/").count(), 2, "Search didn't match directories");
+		assert_eq!(player.search("missing").count(), 0, "Search matched a missing item");
+	}
+
+	#[test]
+	fn cursor_offsets() {
+		let mut player = PlaylistPlayer::new();
+		for idx in 0..8 {
+			player.add(Arc::from(Path::new(&format!("{idx}"))));
+		}
+
+		let cursor = player.add_cursor(0);
+		let offset_cursor = player.add_cursor(2);
+		let items = (0..6)
+			.map(|_| Arc::clone(player.next_at(&cursor).expect("Player had no items").path()))
+			.collect::<Vec<_>>();
+		let offset_items = (0..6)
+			.map(|_| Arc::clone(player.next_at(&offset_cursor).expect("Player had no items").path()))
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			offset_items[2..],
+			items[..4],
+			"Offset cursor didn't return the items 2 steps behind"
+		);
+	}
+
+	#[test]
+	fn remove_lagging_cursor_item() {
+		let mut player = PlaylistPlayer::new();
+		for idx in 0..8 {
+			player.add(Arc::from(Path::new(&format!("{idx}"))));
+		}
+
+		let cursor = player.add_cursor(0);
+		let lagging_cursor = player.add_cursor(0);
+		let items = (0..4)
+			.map(|_| Arc::clone(player.next_at(&cursor).expect("Player had no items").path()))
+			.collect::<Vec<_>>();
+
+		player.remove(&items[1]);
+		assert!(
+			!player.cursor_items.contains(&items[1]),
+			"Removed item was kept for the lagging cursor"
+		);
+
+		let lagging_items = (0..3)
+			.map(|_| Arc::clone(player.next_at(&lagging_cursor).expect("Player had no items").path()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			lagging_items,
+			[&items[0], &items[2], &items[3]].map(Arc::clone),
+			"Lagging cursor didn't skip the removed item"
+		);
+
+		let item = player.next_at(&cursor).expect("Player had no items");
+		assert!(!items.contains(item.path()), "Cursor returned an item twice");
+	}
+
+	#[test]
+	fn coordinated_leases() {
+		let mut player = PlaylistPlayer::new();
+		for idx in 0..2 {
+			player.add(Arc::from(Path::new(&format!("{idx}"))));
+		}
+		player.set_coordinated(true);
+
+		let lease0 = player.next().expect("Player had no items");
+		let lease1 = player.next().expect("Player had no items");
+		assert_ne!(lease0.path(), lease1.path(), "Leased item was returned twice");
+		assert!(player.next().is_none(), "Returned an item while all were leased");
+
+		let path0 = Arc::clone(lease0.path());
+		drop(lease0);
+		let lease = player.next().expect("Released item wasn't returned");
+		assert_eq!(*lease.path(), path0, "Returned a leased item");
+	}
+
+	#[test]
+	fn max_offset_cursor_keeps_offset() {
+		let mut player = PlaylistPlayer::new();
+		for idx in 0..8 {
+			player.add(Arc::from(Path::new(&format!("{idx}"))));
+		}
+
+		let offset = PlaylistPlayer::MAX_CURSOR_ITEMS - 1;
+		let cursor = player.add_cursor(0);
+		let offset_cursor = player.add_cursor(offset);
+		let mut items = vec![];
+		for idx in 0..2 * PlaylistPlayer::MAX_CURSOR_ITEMS {
+			items.push(Arc::clone(player.next_at(&cursor).expect("Player had no items").path()));
+			let offset_item = player.next_at(&offset_cursor).expect("Player had no items");
+			if let Some(item_idx) = idx.checked_sub(offset) {
+				assert_eq!(
+					*offset_item.path(),
+					items[item_idx],
+					"Cursor at the maximum offset skipped ahead"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn lagging_cursor_skips_ahead() {
+		let mut player = PlaylistPlayer::new();
+		for idx in 0..8 {
+			player.add(Arc::from(Path::new(&format!("{idx}"))));
+		}
+
+		let cursor = player.add_cursor(0);
+		let lagging_cursor = player.add_cursor(0);
+		for _ in 0..2 * PlaylistPlayer::MAX_CURSOR_ITEMS {
+			let _ = player.next_at(&cursor).expect("Player had no items");
+		}
+		assert_eq!(
+			player.cursor_items.len(),
+			PlaylistPlayer::MAX_CURSOR_ITEMS,
+			"Items were kept for the lagging cursor"
+		);
+
+		// Note: The lagging cursor should skip to the oldest item kept
+		let _ = player.next_at(&lagging_cursor).expect("Lagging cursor had no items");
+		let max_cursor_items = i64::try_from(PlaylistPlayer::MAX_CURSOR_ITEMS).expect("Maximum didn't fit");
+		assert_eq!(
+			lagging_cursor.inner.position.load(),
+			max_cursor_items + 1,
+			"Lagging cursor didn't skip ahead"
+		);
+	}
 }
@@ -1,7 +1,14 @@
 //! Serialized playlist
 
 // Imports
-use std::{collections::HashMap, path::PathBuf};
+use {
+	anyhow::Context,
+	std::{
+		collections::HashMap,
+		path::{Path, PathBuf},
+	},
+	zsw_error::AppError,
+};
 
 /// Playlists
 #[derive(Debug)]
@@ -14,6 +21,27 @@ pub struct _Playlists {
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Playlist {
 	pub items: Vec<PlaylistItem>,
+
+	/// Whether to play items in order, instead of shuffled
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub sequential: bool,
+
+	/// Whether this playlist is private, and hidden while in privacy mode
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub private: bool,
+}
+
+impl Playlist {
+	/// Validates this playlist
+	pub fn validate(&self) -> Result<(), AppError> {
+		for (item_idx, item) in self.items.iter().enumerate() {
+			item.kind
+				.validate()
+				.with_context(|| format!("Invalid item #{item_idx}"))?;
+		}
+
+		Ok(())
+	}
 }
 
 /// Playlist item
@@ -46,14 +74,83 @@ pub enum PlaylistItemKind {
 
 		#[serde(default = "PlaylistItemKind::default_directory_recursive")]
 		recursive: bool,
+
+		/// Extensions to include, case insensitively.
+		///
+		/// If unset, includes all files.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		extensions: Option<Vec<String>>,
 	},
 
 	/// File
 	File { path: PathBuf },
+
+	/// Encrypted bundle
+	Bundle { path: PathBuf },
+
+	/// Comic book archive
+	Comic { path: PathBuf },
 }
 
 impl PlaylistItemKind {
 	fn default_directory_recursive() -> bool {
 		true
 	}
+
+	/// Returns the path of this item
+	pub fn path(&self) -> &Path {
+		match self {
+			Self::Directory { path, .. } | Self::File { path } | Self::Bundle { path } | Self::Comic { path } => path,
+		}
+	}
+
+	/// Validates this item
+	pub fn validate(&self) -> Result<(), AppError> {
+		if self.path().as_os_str().is_empty() {
+			return Err(anyhow::anyhow!("Path must not be empty").into());
+		}
+
+		if let Self::Directory {
+			extensions: Some(extensions),
+			..
+		} = self
+		{
+			if extensions.iter().any(String::is_empty) {
+				return Err(anyhow::anyhow!("Extensions must not be empty").into());
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use {super::*, proptest::prelude::*};
+
+	proptest! {
+		#[test]
+		fn parse_never_panics(playlist_toml in "\\PC*") {
+			if let Ok(playlist) = toml::from_str::<Playlist>(&playlist_toml) {
+				let _ = playlist.validate();
+			}
+		}
+
+		#[test]
+		fn valid_items_have_paths(path in "\\PC*", extension in "\\PC*") {
+			let playlist_toml = format!(
+				"[[items]]\ntype = \"Directory\"\npath = {path:?}\nextensions = [{extension:?}]\n"
+			);
+			let Ok(playlist) = toml::from_str::<Playlist>(&playlist_toml) else {
+				return Ok(());
+			};
+			if playlist.validate().is_err() {
+				return Ok(());
+			}
+
+			for item in &playlist.items {
+				prop_assert!(!item.kind.path().as_os_str().is_empty(), "Item path was empty");
+			}
+		}
+	}
 }
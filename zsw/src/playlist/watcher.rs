@@ -0,0 +1,247 @@
+//! Playlist watcher
+
+// Imports
+use {
+	super::{self as playlist, PlaylistItem, PlaylistItemKind, PlaylistPlayer},
+	crate::AppError,
+	anyhow::Context,
+	notify::{
+		event::{AccessKind, AccessMode, ModifyKind, RenameMode},
+		EventKind,
+		RecursiveMode,
+		Watcher,
+	},
+	std::{
+		path::{Path, PathBuf},
+		sync::{Arc, Weak},
+	},
+	tokio::sync::{mpsc, RwLock},
+};
+
+/// Playlist watcher.
+///
+/// Watches all items of a playlist for added / removed files.
+#[derive(Debug)]
+pub struct PlaylistWatcher {
+	/// Watcher
+	///
+	/// Note: Kept alive, since dropping it stops watching.
+	_watcher: notify::RecommendedWatcher,
+
+	/// Event receiver
+	event_rx: mpsc::UnboundedReceiver<notify::Result<notify::Event>>,
+
+	/// All watched paths
+	watched_paths: Vec<WatchedPath>,
+}
+
+impl PlaylistWatcher {
+	/// Creates a new watcher for all enabled `items`.
+	pub async fn new(items: &[Arc<RwLock<PlaylistItem>>]) -> Result<Self, AppError> {
+		let (event_tx, event_rx) = mpsc::unbounded_channel();
+		let mut watcher = notify::recommended_watcher(move |event| {
+			// Note: If the receiver was dropped, we're no longer being watched, so we can ignore it
+			let _ = event_tx.send(event);
+		})
+		.context("Unable to create watcher")?;
+
+		let mut watched_paths = vec![];
+		for item in items {
+			let item = item.read().await;
+			if !item.enabled {
+				continue;
+			}
+
+			let (path, recursive_mode, extensions) = match item.kind {
+				PlaylistItemKind::Directory {
+					ref path,
+					recursive,
+					ref extensions,
+				} => (
+					path,
+					match recursive {
+						true => RecursiveMode::Recursive,
+						false => RecursiveMode::NonRecursive,
+					},
+					extensions.clone(),
+				),
+				PlaylistItemKind::File { ref path } => (path, RecursiveMode::NonRecursive, None),
+				// Note: Bundles and comics are loaded once, so there's nothing to watch
+				PlaylistItemKind::Bundle { .. } | PlaylistItemKind::Comic { .. } => continue,
+			};
+
+			// Note: We watch the canonical path, since that's what we add to the player
+			let path = match tokio::fs::canonicalize(path).await {
+				Ok(path) => path,
+				Err(err) => {
+					tracing::warn!(?path, ?err, "Unable to canonicalize path to watch");
+					continue;
+				},
+			};
+			if let Err(err) = watcher.watch(&path, recursive_mode) {
+				tracing::warn!(?path, ?err, "Unable to watch path");
+				continue;
+			}
+			watched_paths.push(WatchedPath { path, extensions });
+		}
+
+		Ok(Self {
+			_watcher: watcher,
+			event_rx,
+			watched_paths,
+		})
+	}
+
+	/// Runs this watcher, updating `playlist_player` with any changes.
+	///
+	/// Returns once the player is dropped, or it's items are all removed.
+	pub async fn run(mut self, playlist_player: Weak<RwLock<PlaylistPlayer>>) {
+		// Note: If the player's items are all removed, it's being replaced
+		//       by another playlist, so we should stop
+		let mut generation_rx = match playlist_player.upgrade() {
+			Some(playlist_player) => playlist_player.read().await.subscribe_generation(),
+			None => return,
+		};
+
+		loop {
+			// Note: We stop as soon as the player is dropped or replaced, instead of
+			//       waiting for the next event, which might never come.
+			let event = tokio::select! {
+				event = self.event_rx.recv() => event,
+				_ = generation_rx.changed() => None,
+			};
+			let Some(event) = event else {
+				break;
+			};
+			let event = match event {
+				Ok(event) => event,
+				Err(err) => {
+					tracing::warn!(?err, "Unable to watch playlist");
+					continue;
+				},
+			};
+			tracing::trace!(?event, "Received playlist watcher event");
+
+			let Some(playlist_player) = playlist_player.upgrade() else {
+				break;
+			};
+			if generation_rx.has_changed().unwrap_or(true) {
+				break;
+			}
+
+			match event.kind {
+				// Note: We wait until the file is closed, so we don't add partially written files
+				EventKind::Access(AccessKind::Close(AccessMode::Write)) |
+				EventKind::Modify(ModifyKind::Name(RenameMode::To)) =>
+					for path in &event.paths {
+						self::add_path(&playlist_player, &self.watched_paths, path).await;
+					},
+				EventKind::Modify(ModifyKind::Name(RenameMode::Both)) =>
+					if let [from, to] = &*event.paths {
+						self::remove_path(&playlist_player, from).await;
+						self::add_path(&playlist_player, &self.watched_paths, to).await;
+					},
+				EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) =>
+					for path in &event.paths {
+						self::remove_path(&playlist_player, path).await;
+					},
+				_ => (),
+			}
+		}
+	}
+}
+
+/// Watched path
+#[derive(Debug)]
+struct WatchedPath {
+	/// Canonical path
+	path: PathBuf,
+
+	/// Extensions to include, if any
+	extensions: Option<Vec<String>>,
+}
+
+/// Adds `path` to `playlist_player`, if it's a file with the extensions of a watched path
+async fn add_path(playlist_player: &RwLock<PlaylistPlayer>, watched_paths: &[WatchedPath], path: &Path) {
+	let path = match tokio::fs::canonicalize(path).await {
+		Ok(path) => path,
+		Err(err) => {
+			tracing::warn!(?path, ?err, "Unable to canonicalize added path");
+			return;
+		},
+	};
+
+	// TODO: Add the contents of directories moved into a recursive directory?
+	match tokio::fs::metadata(&path).await {
+		Ok(metadata) if metadata.is_dir() => return,
+		Ok(_) => (),
+		Err(err) => {
+			tracing::warn!(?path, ?err, "Unable to get added path metadata");
+			return;
+		},
+	}
+
+	// Note: Paths may be within several watched paths, so we add it if any of them include it
+	let is_included = watched_paths
+		.iter()
+		.filter(|watched| path.starts_with(&watched.path))
+		.any(|watched| playlist::has_extension(&path, watched.extensions.as_deref()));
+	if !is_included {
+		tracing::trace!(?path, "Skipping added path without a watched extension");
+		return;
+	}
+
+	tracing::debug!(?path, "Adding new playlist path");
+	playlist_player.write().await.add(path.into());
+}
+
+/// Removes `path` from `playlist_player`
+async fn remove_path(playlist_player: &RwLock<PlaylistPlayer>, path: &Path) {
+	// Note: We can't canonicalize the path, since it no longer exists, but
+	//       the watched paths are canonical, so this path already is too.
+	tracing::debug!(?path, "Removing playlist path");
+	playlist_player.write().await.remove(path);
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[tokio::test]
+	async fn add_path_filtered() {
+		let dir = std::env::temp_dir().join(format!("zsw-watcher-test-{}", std::process::id()));
+		tokio::fs::create_dir_all(&dir)
+			.await
+			.expect("Unable to create test directory");
+		let dir = tokio::fs::canonicalize(&dir)
+			.await
+			.expect("Unable to canonicalize test directory");
+		for file in ["a.png", "b.PNG", "c.txt"] {
+			tokio::fs::write(dir.join(file), [])
+				.await
+				.expect("Unable to create test file");
+		}
+
+		let watched_paths = [WatchedPath {
+			path:       dir.clone(),
+			extensions: Some(vec!["png".to_owned()]),
+		}];
+		let playlist_player = RwLock::new(PlaylistPlayer::new());
+		for file in ["a.png", "b.PNG", "c.txt"] {
+			self::add_path(&playlist_player, &watched_paths, &dir.join(file)).await;
+		}
+
+		let mut items = playlist_player
+			.read()
+			.await
+			.all_items()
+			.map(|path| path.to_path_buf())
+			.collect::<Vec<_>>();
+		items.sort();
+		assert_eq!(items, [dir.join("a.png"), dir.join("b.PNG")]);
+
+		tokio::fs::remove_dir_all(&dir)
+			.await
+			.expect("Unable to remove test directory");
+	}
+}
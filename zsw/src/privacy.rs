@@ -0,0 +1,268 @@
+//! Privacy mode
+//!
+//! While active, all panels showing a playlist flagged as `private` are
+//! switched to a fallback playlist, and restored once it's inactive.
+//!
+//! Privacy mode is activated manually from the settings menu, or automatically
+//! whenever a screen capture is detected.
+
+// Imports
+use {
+	crate::{
+		config::PrivacyConfig,
+		panel::{Panel, PanelCommand, PanelPlaylistOverride, PanelsManager},
+		playlist::{PlaylistName, PlaylistPlayer},
+		shared::Shared,
+	},
+	anyhow::Context,
+	crossbeam::atomic::AtomicCell,
+	std::{
+		collections::HashSet,
+		process::Command,
+		sync::{Arc, Mutex, PoisonError},
+	},
+	tokio::sync::{Notify, RwLock},
+	zsw_error::AppError,
+};
+
+/// Privacy mode
+#[derive(Debug)]
+pub struct Privacy {
+	/// Config
+	config: PrivacyConfig,
+
+	/// Fallback playlist
+	fallback_playlist: PlaylistName,
+
+	/// Whether privacy mode was manually enabled
+	manual: AtomicCell<bool>,
+
+	/// Whether a screen capture was detected
+	screen_capture: AtomicCell<bool>,
+
+	/// Whether privacy mode is currently applied to the panels
+	applied: AtomicCell<bool>,
+
+	/// Private playlists, as of when privacy mode was last applied
+	private_playlists: Mutex<HashSet<PlaylistName>>,
+
+	/// Notified whenever privacy mode is manually toggled
+	toggled: Notify,
+}
+
+impl Privacy {
+	/// Creates a new, inactive, privacy mode
+	pub fn new(config: PrivacyConfig) -> Self {
+		Self {
+			fallback_playlist: PlaylistName::from(config.fallback_playlist.clone()),
+			config,
+			manual: AtomicCell::new(false),
+			screen_capture: AtomicCell::new(false),
+			applied: AtomicCell::new(false),
+			private_playlists: Mutex::new(HashSet::new()),
+			toggled: Notify::new(),
+		}
+	}
+
+	/// Returns whether privacy mode was manually enabled
+	pub fn manual(&self) -> bool {
+		self.manual.load()
+	}
+
+	/// Sets whether privacy mode is manually enabled
+	#[cfg(feature = "menu")]
+	pub fn set_manual(&self, manual: bool) {
+		self.manual.store(manual);
+		self.toggled.notify_one();
+	}
+
+	/// Returns whether a screen capture was detected
+	pub fn screen_capture(&self) -> bool {
+		self.screen_capture.load()
+	}
+
+	/// Returns whether privacy mode is currently applied to the panels
+	pub fn applied(&self) -> bool {
+		self.applied.load()
+	}
+
+	/// Switches `panel` to the fallback playlist, if privacy mode is applied and it's showing a private playlist.
+	///
+	/// Must be called whenever a panel's playlist changes, so private playlists are never shown while applied.
+	pub fn apply(&self, panel: &mut Panel, shared: &Arc<Shared>) {
+		if !self.applied() || panel.is_overridden(PanelPlaylistOverride::Privacy) {
+			return;
+		}
+
+		let is_private = self
+			.private_playlists
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.contains(&panel.playlist_name);
+		if !is_private {
+			return;
+		}
+
+		let playlist_player = Arc::new(RwLock::new(PlaylistPlayer::new()));
+		crate::spawn_task(format!("Load privacy playlist {:?}", self.fallback_playlist), {
+			let playlist_player = Arc::clone(&playlist_player);
+			let fallback_playlist = self.fallback_playlist.clone();
+			let shared = Arc::clone(shared);
+			|| async move {
+				PanelsManager::load_playlist_into(&playlist_player, &fallback_playlist, &shared)
+					.await
+					.context("Unable to load playlist")?;
+
+				Ok(())
+			}
+		});
+
+		// Note: Private images shouldn't be shown any longer than necessary, so we switch instantly,
+		//       or, if the fallback playlist isn't loaded by then, transition as soon as possible.
+		panel.override_playlist(
+			PanelPlaylistOverride::Privacy,
+			self.fallback_playlist.clone(),
+			playlist_player,
+		);
+		panel.state.fade.progress = panel.state.fade.progress.min(panel.state.fade.fade_point);
+		shared.panel_commands.push(panel.id, PanelCommand::SwitchInstant);
+	}
+}
+
+/// Runs privacy mode
+pub async fn run(shared: Arc<Shared>) -> Result<!, AppError> {
+	let privacy = shared.privacy.as_ref().context("Privacy mode isn't configured")?;
+
+	loop {
+		if privacy.config.detect_screen_capture() {
+			let screen_capture = tokio::task::spawn_blocking(self::detect_screen_capture)
+				.await
+				.context("Unable to join screen capture detector task")?
+				.unwrap_or_else(|err| {
+					tracing::debug!(?err, "Unable to detect screen capture");
+					false
+				});
+			if screen_capture != privacy.screen_capture.swap(screen_capture) {
+				tracing::info!(?screen_capture, "Screen capture changed");
+			}
+		}
+
+		let active = privacy.manual() || privacy.screen_capture();
+		match (active, privacy.applied()) {
+			(true, applied) => {
+				if !applied {
+					tracing::info!("Entering privacy mode");
+				}
+
+				// Note: We re-apply it while active, in case any playlists were made private since
+				self::start(&shared, privacy).await;
+			},
+			(false, true) => {
+				tracing::info!("Leaving privacy mode");
+				self::stop(&shared, privacy).await;
+			},
+			(false, false) => (),
+		}
+
+		// Note: We wake up early if toggled, so the menu feels responsive
+		tokio::select! {
+			() = tokio::time::sleep(privacy.config.poll_interval()) => (),
+			() = privacy.toggled.notified() => (),
+		}
+	}
+}
+
+/// Applies privacy mode to all panels
+async fn start(shared: &Arc<Shared>, privacy: &Privacy) {
+	// Note: We get the private playlists before locking the panels, so we don't hold both locks
+	let playlists = shared.playlists.read().await.get_all();
+	let mut private_playlists = HashSet::new();
+	for (playlist_name, playlist) in playlists {
+		if playlist.read().await.private() {
+			let _ = private_playlists.insert(playlist_name);
+		}
+	}
+	*privacy.private_playlists.lock().unwrap_or_else(PoisonError::into_inner) = private_playlists;
+	privacy.applied.store(true);
+
+	let mut cur_panels = shared.cur_panels.lock().await;
+	for panel in &mut *cur_panels {
+		privacy.apply(panel, shared);
+	}
+}
+
+/// Restores all panels switched by [`start`]
+async fn stop(shared: &Shared, privacy: &Privacy) {
+	let mut cur_panels = shared.cur_panels.lock().await;
+	privacy.applied.store(false);
+	for panel in &mut *cur_panels {
+		let _ = panel.restore_playlist(PanelPlaylistOverride::Privacy);
+	}
+}
+
+/// Returns whether a screen capture is active.
+///
+/// Screen captures through the desktop portal are exposed as pipewire video
+/// nodes, so we look for any running video node that isn't a device (e.g. a webcam).
+// TODO: Detect X11 captures, which don't go through pipewire.
+fn detect_screen_capture() -> Result<bool, AppError> {
+	let output = Command::new("pw-dump").output().context("Unable to run `pw-dump`")?;
+	if !output.status.success() {
+		return Err(anyhow::anyhow!("`pw-dump` failed: {}", output.status).into());
+	}
+
+	let objects =
+		serde_json::from_slice::<Vec<serde_json::Value>>(&output.stdout).context("Unable to parse `pw-dump` output")?;
+	Ok(objects.iter().any(self::is_screen_capture_node))
+}
+
+/// Returns whether `object`, from `pw-dump`, is a running screen capture node
+fn is_screen_capture_node(object: &serde_json::Value) -> bool {
+	let is_node = object["type"] == "PipeWire:Interface:Node";
+	let info = &object["info"];
+	let props = &info["props"];
+	let is_video = matches!(
+		props["media.class"].as_str(),
+		Some("Video/Source" | "Stream/Output/Video")
+	);
+	let is_device = props.get("device.api").is_some();
+	let is_running = info["state"] == "running";
+
+	is_node && is_video && !is_device && is_running
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn screen_capture_nodes() {
+		let node = |media_class: &str, device_api: Option<&str>, state: &str| {
+			let mut props = serde_json::json!({ "media.class": media_class });
+			if let Some(device_api) = device_api {
+				props["device.api"] = device_api.into();
+			}
+			serde_json::json!({
+				"type": "PipeWire:Interface:Node",
+				"info": { "state": state, "props": props },
+			})
+		};
+
+		assert!(
+			is_screen_capture_node(&node("Video/Source", None, "running")),
+			"Running portal node wasn't detected"
+		);
+		assert!(
+			!is_screen_capture_node(&node("Video/Source", None, "suspended")),
+			"Suspended portal node was detected"
+		);
+		assert!(
+			!is_screen_capture_node(&node("Video/Source", Some("v4l2"), "running")),
+			"Webcam was detected"
+		);
+		assert!(
+			!is_screen_capture_node(&node("Audio/Sink", None, "running")),
+			"Audio node was detected"
+		);
+	}
+}
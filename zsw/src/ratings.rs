@@ -0,0 +1,91 @@
+//! Image ratings
+//!
+//! Images may be rated from 1 to 5 stars, which playlist players
+//! may then use to weight or filter their selection.
+
+// Imports
+use {
+	anyhow::Context,
+	std::{
+		collections::HashMap,
+		path::{Path, PathBuf},
+		sync::{PoisonError, RwLock},
+	},
+	zsw_error::AppError,
+};
+
+/// Maximum rating
+#[cfg(feature = "menu")]
+pub const MAX_RATING: u8 = 5;
+
+/// Rating assumed for unrated images
+pub const DEFAULT_RATING: u8 = 3;
+
+/// Image ratings
+#[derive(Debug)]
+pub struct Ratings {
+	/// Path to the ratings database
+	#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only read by the settings menu
+	path: PathBuf,
+
+	/// All ratings, by image path
+	ratings: RwLock<HashMap<PathBuf, u8>>,
+}
+
+impl Ratings {
+	/// Loads the ratings from `path`.
+	///
+	/// If the file doesn't exist, starts with no ratings.
+	pub fn load(path: PathBuf) -> Result<Self, AppError> {
+		let ratings = match path.exists() {
+			true => zsw_util::parse_json_from_file(&path).context("Unable to load ratings")?,
+			false => HashMap::new(),
+		};
+
+		Ok(Self {
+			path,
+			ratings: RwLock::new(ratings),
+		})
+	}
+
+	/// Returns the rating of `path`, if rated
+	pub fn get(&self, path: &Path) -> Option<u8> {
+		let ratings = self.ratings.read().unwrap_or_else(PoisonError::into_inner);
+		ratings.get(path).copied()
+	}
+
+	/// Sets the rating of `path` and saves all ratings.
+	///
+	/// A rating of `None` removes the rating.
+	#[cfg(feature = "menu")]
+	pub fn set(&self, path: &Path, rating: Option<u8>) -> Result<(), AppError> {
+		let mut ratings = self.ratings.write().unwrap_or_else(PoisonError::into_inner);
+		match rating {
+			Some(rating) => {
+				let _ = ratings.insert(path.to_path_buf(), rating.clamp(1, MAX_RATING));
+			},
+			None => {
+				let _ = ratings.remove(path);
+			},
+		}
+
+		zsw_util::serialize_json_to_file(&self.path, &*ratings).context("Unable to save ratings")?;
+
+		Ok(())
+	}
+}
+
+/// Rating selection
+#[derive(Clone, Copy, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum RatingSelection {
+	/// Weights each image's selection probability by its rating
+	Weighted,
+
+	/// Skips images below a minimum rating
+	Threshold {
+		/// Minimum rating
+		min_rating: u8,
+	},
+}
@@ -0,0 +1,122 @@
+//! Sandbox
+//!
+//! When running sandboxed (e.g. under flatpak), we can't assume access to
+//! playlist items on the host. Instead, the user must grant access to them
+//! through the document portal, which exposes them under a different path.
+//!
+//! We keep track of items we couldn't access, so the user may grant access
+//! to them, and persist the granted paths.
+
+// Imports
+use {
+	anyhow::Context,
+	std::{
+		collections::{BTreeMap, HashMap},
+		io,
+		path::{Path, PathBuf},
+		sync::{Mutex, PoisonError},
+	},
+	zsw_error::AppError,
+};
+
+/// Sandbox
+#[derive(Debug)]
+pub struct Sandbox {
+	/// Whether we're sandboxed
+	sandboxed: bool,
+
+	/// Path to the granted paths database
+	#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only read by the settings menu
+	path: PathBuf,
+
+	/// All granted paths, by their original path
+	granted: Mutex<HashMap<PathBuf, PathBuf>>,
+
+	/// All paths that need permission
+	needs_permission: Mutex<BTreeMap<PathBuf, SandboxPathKind>>,
+}
+
+impl Sandbox {
+	/// Loads the sandbox, with the granted paths from `path`.
+	///
+	/// If the file doesn't exist, starts with no granted paths.
+	pub fn load(path: PathBuf) -> Result<Self, AppError> {
+		let granted = match path.exists() {
+			true => zsw_util::parse_json_from_file(&path).context("Unable to load sandbox granted paths")?,
+			false => HashMap::new(),
+		};
+
+		// Note: Flatpak always mounts this file within the sandbox
+		let sandboxed = Path::new("/.flatpak-info").exists();
+		tracing::debug!(?sandboxed, "Detected sandbox");
+
+		Ok(Self {
+			sandboxed,
+			path,
+			granted: Mutex::new(granted),
+			needs_permission: Mutex::new(BTreeMap::new()),
+		})
+	}
+
+	/// Returns the path we should access `path` through
+	pub fn resolve(&self, path: &Path) -> PathBuf {
+		let granted = self.granted.lock().unwrap_or_else(PoisonError::into_inner);
+		granted.get(path).cloned().unwrap_or_else(|| path.to_path_buf())
+	}
+
+	/// Checks if we can access `path` (resolved through [`Self::resolve`]).
+	///
+	/// If we can't, registers `path` as needing permission.
+	pub async fn check_access(&self, path: &Path, kind: SandboxPathKind) -> bool {
+		let Err(err) = tokio::fs::metadata(self.resolve(path)).await else {
+			return true;
+		};
+
+		// Note: When sandboxed, host paths we weren't granted access to simply don't exist.
+		//       Other errors are left for whoever accesses the path to report.
+		let needs_permission = match err.kind() {
+			io::ErrorKind::PermissionDenied => true,
+			io::ErrorKind::NotFound => self.sandboxed,
+			_ => false,
+		};
+		if !needs_permission {
+			return true;
+		}
+
+		tracing::warn!(?path, ?err, "Path needs permission to be accessed");
+		let mut paths = self.needs_permission.lock().unwrap_or_else(PoisonError::into_inner);
+		let _ = paths.insert(path.to_path_buf(), kind);
+
+		false
+	}
+
+	/// Returns all paths that need permission, sorted
+	#[cfg(feature = "menu")]
+	pub fn needs_permission(&self) -> Vec<(PathBuf, SandboxPathKind)> {
+		let paths = self.needs_permission.lock().unwrap_or_else(PoisonError::into_inner);
+		paths.iter().map(|(path, &kind)| (path.clone(), kind)).collect()
+	}
+
+	/// Grants access to `path` through `granted_path` and saves all granted paths
+	#[cfg(feature = "menu")]
+	pub fn grant(&self, path: &Path, granted_path: PathBuf) -> Result<(), AppError> {
+		let mut granted = self.granted.lock().unwrap_or_else(PoisonError::into_inner);
+		let _ = granted.insert(path.to_path_buf(), granted_path);
+		zsw_util::serialize_json_to_file(&self.path, &*granted).context("Unable to save sandbox granted paths")?;
+
+		let _ = self
+			.needs_permission
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.remove(path);
+
+		Ok(())
+	}
+}
+
+/// Sandbox path kind
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SandboxPathKind {
+	File,
+	Directory,
+}
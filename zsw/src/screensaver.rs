@@ -0,0 +1,187 @@
+//! Screensaver
+//!
+//! Switches all panels to a screensaver playlist after the user has been idle
+//! for a while, and restores them on activity.
+
+// Imports
+#[cfg(target_os = "linux")]
+use x11rb::{
+	connection::Connection,
+	protocol::{screensaver::ConnectionExt, xproto},
+	rust_connection::RustConnection,
+};
+use {
+	crate::{
+		config::ScreensaverConfig,
+		panel::{PanelId, PanelPlaylistOverride, PanelsManager},
+		playlist::{PlaylistName, PlaylistPlayer},
+		shared::Shared,
+	},
+	anyhow::Context,
+	std::{collections::HashMap, mem, sync::Arc, time::Duration},
+	tokio::sync::RwLock,
+	zsw_error::AppError,
+};
+
+/// Idle detector.
+///
+/// Uses the X11 screensaver extension.
+// TODO: Support wayland's `ext-idle-notify` protocol.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct IdleDetector {
+	/// Connection
+	conn: RustConnection,
+
+	/// Root window
+	root: xproto::Window,
+}
+
+#[cfg(target_os = "linux")]
+impl IdleDetector {
+	/// Creates a new idle detector
+	fn new() -> Result<Self, AppError> {
+		let (conn, screen_idx) = x11rb::connect(None).context("Unable to connect to X11 server")?;
+		let root = conn.setup().roots[screen_idx].root;
+
+		Ok(Self { conn, root })
+	}
+
+	/// Returns the time since the last user input
+	fn idle_time(&self) -> Result<Duration, AppError> {
+		let info = self
+			.conn
+			.screensaver_query_info(self.root)
+			.context("Unable to query screensaver info")?
+			.reply()
+			.context("Unable to get screensaver info reply")?;
+
+		Ok(Duration::from_millis(info.ms_since_user_input.into()))
+	}
+}
+
+/// Idle detector.
+///
+/// Unsupported outside of X11.
+// TODO: Support windows' `GetLastInputInfo`.
+#[cfg(not(target_os = "linux"))]
+#[derive(Debug)]
+enum IdleDetector {}
+
+#[cfg(not(target_os = "linux"))]
+impl IdleDetector {
+	/// Creates a new idle detector
+	fn new() -> Result<Self, AppError> {
+		Err(anyhow::anyhow!("Idle detection is only supported on X11").into())
+	}
+
+	/// Returns the time since the last user input
+	fn idle_time(&self) -> Result<Duration, AppError> {
+		match *self {}
+	}
+}
+
+/// Panel timings saved while the screensaver is active
+#[derive(Debug)]
+struct SavedTimings {
+	/// Duration
+	duration: u64,
+
+	/// Fade point
+	fade_point: u64,
+}
+
+/// Runs the screensaver
+pub async fn run(shared: Arc<Shared>, config: ScreensaverConfig) -> Result<!, AppError> {
+	/// Interval between idle checks
+	const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+	let idle_detector = Arc::new(IdleDetector::new().context("Unable to create idle detector")?);
+	let idle_timeout = Duration::from_secs_f32(config.idle_secs);
+	let playlist_name = PlaylistName::from(config.playlist.clone());
+
+	let mut saved_timings = None;
+	loop {
+		tokio::time::sleep(POLL_INTERVAL).await;
+
+		let idle_time = tokio::task::spawn_blocking({
+			let idle_detector = Arc::clone(&idle_detector);
+			move || idle_detector.idle_time()
+		})
+		.await
+		.context("Unable to join idle detector task")??;
+
+		match (idle_time >= idle_timeout, saved_timings.take()) {
+			(true, None) => {
+				tracing::debug!(?idle_time, "Starting screensaver");
+				saved_timings = Some(self::start(&shared, &config, &playlist_name).await);
+			},
+			(false, Some(saved)) => {
+				tracing::debug!(?idle_time, "Stopping screensaver");
+				self::stop(&shared, saved).await;
+			},
+			(_, saved) => saved_timings = saved,
+		}
+	}
+}
+
+/// Starts the screensaver, returning the timings of all panels before it
+async fn start(
+	shared: &Arc<Shared>,
+	config: &ScreensaverConfig,
+	playlist_name: &PlaylistName,
+) -> HashMap<PanelId, SavedTimings> {
+	let mut cur_panels = shared.cur_panels.lock().await;
+	cur_panels
+		.iter_mut()
+		.map(|panel| {
+			let playlist_player = Arc::new(RwLock::new(PlaylistPlayer::new()));
+			crate::spawn_task(format!("Load screensaver playlist {playlist_name:?}"), {
+				let playlist_player = Arc::clone(&playlist_player);
+				let playlist_name = playlist_name.clone();
+				let shared = Arc::clone(shared);
+				|| async move {
+					PanelsManager::load_playlist_into(&playlist_player, &playlist_name, &shared)
+						.await
+						.context("Unable to load playlist")?;
+
+					Ok(())
+				}
+			});
+
+			// Note: We clamp the progress so the panel transitions soon with the new duration
+			panel.state.fade.progress = panel.state.fade.progress.min(config.fade_point);
+			panel.override_playlist(
+				PanelPlaylistOverride::Screensaver,
+				playlist_name.clone(),
+				playlist_player,
+			);
+			let saved = SavedTimings {
+				duration:   mem::replace(&mut panel.state.fade.duration, config.duration),
+				fade_point: mem::replace(&mut panel.state.fade.fade_point, config.fade_point),
+			};
+			(panel.id, saved)
+		})
+		.collect()
+}
+
+/// Stops the screensaver, restoring the state of all panels
+async fn stop(shared: &Arc<Shared>, mut saved_timings: HashMap<PanelId, SavedTimings>) {
+	// Note: Any panels added since we started never showed the screensaver, so they have nothing to restore
+	let mut cur_panels = shared.cur_panels.lock().await;
+	for panel in &mut *cur_panels {
+		let _ = panel.restore_playlist(PanelPlaylistOverride::Screensaver);
+
+		// Note: The restored playlist may be private, if it was made so, or privacy mode
+		//       was applied, while we were active.
+		if let Some(privacy) = &shared.privacy {
+			privacy.apply(panel, shared);
+		}
+		let Some(saved) = saved_timings.remove(&panel.id) else {
+			continue;
+		};
+		panel.state.fade.duration = saved.duration;
+		panel.state.fade.fade_point = saved.fade_point;
+		panel.state.fade.progress = panel.state.fade.progress.min(saved.fade_point);
+	}
+}
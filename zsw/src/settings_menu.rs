@@ -4,18 +4,58 @@
 #![allow(unused_results)] // Egui produces a lot of results we don't need to use
 #![expect(clippy::too_many_lines)] // TODO: Refactor
 
+// Modules
+mod chart;
+mod geometry_editor;
+
+// Exports
+pub use self::chart::ChartPalette;
+
 // Imports
 use {
+	self::{
+		chart::{ChartSeries, LineChart},
+		geometry_editor::GeometryEditor,
+	},
 	crate::{
-		panel::{PanelImage, PanelShader, PanelsManager},
-		playlist::{Playlist, PlaylistItemKind, PlaylistName},
+		bug_report::{self, BugReport},
+		desktop,
+		image_loader::{self, ImageHistogram, ImageMetadata},
+		panel::{
+			Panel,
+			PanelAlignment,
+			PanelCommand,
+			PanelImage,
+			PanelImages,
+			PanelKind,
+			PanelPlayerSharing,
+			PanelSampler,
+			PanelSamplerAddressMode,
+			PanelSamplerFilter,
+			PanelScaleMode,
+			PanelShader,
+			PanelState,
+			PanelsManager,
+			PANEL_FRAME_DURATION,
+		},
+		playlist::{Playlist, PlaylistItemKind, PlaylistName, PlaylistPlayer},
+		privacy::Privacy,
+		ratings,
+		sandbox::SandboxPathKind,
 		shared::Shared,
+		tasks::{TaskStatus, TASKS},
 	},
 	anyhow::Context,
+	chrono::Weekday,
 	egui::Widget,
-	std::{path::Path, sync::Arc},
+	std::{
+		ops::RangeInclusive,
+		path::Path,
+		sync::{Arc, PoisonError},
+		time::{Duration, Instant},
+	},
 	tokio::sync::RwLock,
-	zsw_util::{Rect, TokioTaskBlockOn},
+	zsw_util::TokioTaskBlockOn,
 };
 
 /// Settings menu
@@ -29,20 +69,68 @@ pub struct SettingsMenu {
 
 	/// Add playlist state
 	add_playlist_state: AddPlaylistState,
+
+	/// If using the high contrast theme
+	high_contrast: bool,
+
+	/// Until when to identify the monitors, if identifying
+	identify_monitors_until: Option<Instant>,
+
+	/// If showing the frame statistics
+	show_frame_stats: bool,
+
+	/// Palette of the frame statistics chart
+	chart_palette: ChartPalette,
+
+	/// Image being shown in the "about this image" popup, if any
+	image_about: Option<ImageMetadata>,
+
+	/// Name of the snapshot to save
+	snapshot_name: String,
 }
 
 impl SettingsMenu {
 	/// Creates the settings menu
-	pub fn new() -> Self {
+	pub fn new(chart_palette: ChartPalette) -> Self {
 		Self {
-			open:               false,
-			cur_tab:            Tab::Panels,
+			open: false,
+			cur_tab: Tab::Panels,
 			add_playlist_state: AddPlaylistState::default(),
+			high_contrast: false,
+			identify_monitors_until: None,
+			show_frame_stats: false,
+			chart_palette,
+			image_about: None,
+			snapshot_name: String::new(),
 		}
 	}
 
 	/// Draws the settings menu
 	pub fn draw(&mut self, ctx: &egui::Context, shared: &Arc<Shared>) {
+		// Prompt for any bundle passphrases, even if we're closed
+		self::draw_passphrase_prompts(ctx, shared);
+
+		// Show the image popup, even if we're closed
+		self::draw_image_about(ctx, &mut self.image_about, shared);
+
+		// Show the progress of loading the default panels, even if we're closed
+		if let Some((loaded, total)) = shared.panels_loading.load() {
+			self::draw_panels_loading(ctx, loaded, total);
+		}
+
+		// Show the frame statistics, if requested
+		if self.show_frame_stats {
+			self::draw_frame_stats(ctx, &mut self.chart_palette, shared);
+		}
+
+		// Identify the monitors, if requested
+		if let Some(until) = self.identify_monitors_until {
+			match Instant::now() < until {
+				true => self::draw_monitor_identification(ctx, shared),
+				false => self.identify_monitors_until = None,
+			}
+		}
+
 		// Adjust cursor pos to account for the scale factor
 		let scale_factor = shared.window.scale_factor();
 		let cursor_pos = shared.cursor_pos.load().cast::<f32>().to_logical(scale_factor);
@@ -60,28 +148,438 @@ impl SettingsMenu {
 
 		// Then render it
 		egui_window.open(&mut self.open).show(ctx, |ui| {
+			let offline_roots = shared.image_requester.offline_roots().block_on();
+			if !offline_roots.is_empty() {
+				for root in offline_roots {
+					ui.colored_label(
+						egui::Color32::YELLOW,
+						format!("⚠ Offline, image loading paused: {root:?}"),
+					);
+				}
+				ui.separator();
+			}
+
+			let needs_permission = shared.sandbox.needs_permission();
+			if !needs_permission.is_empty() {
+				for (path, kind) in needs_permission {
+					ui.horizontal(|ui| {
+						ui.colored_label(egui::Color32::YELLOW, format!("⚠ Needs permission: {path:?}"));
+						if ui.button("Grant access").clicked() {
+							self::grant_access(&path, kind, shared);
+						}
+					});
+				}
+				ui.separator();
+			}
+
+			// Note: Images larger than the texture limit are downscaled, so warn if the panels could be larger
+			let max_texture_size = shared.wgpu.device.limits().max_texture_dimension_2d;
+			let surface_size = shared.panels_surface_size(shared.window.inner_size());
+			if surface_size.width > max_texture_size || surface_size.height > max_texture_size {
+				ui.colored_label(
+					egui::Color32::YELLOW,
+					format!(
+						"⚠ Gpu max texture size is {max_texture_size}px, larger images will be downscaled to it and \
+						 may look blurry"
+					),
+				);
+				ui.separator();
+			}
+
 			ui.horizontal(|ui| {
 				ui.selectable_value(&mut self.cur_tab, Tab::Panels, "Panels");
 				ui.selectable_value(&mut self.cur_tab, Tab::Playlists, "Playlists");
+				ui.selectable_value(&mut self.cur_tab, Tab::Tasks, "Tasks");
 			});
 			ui.separator();
 
 			match self.cur_tab {
-				Tab::Panels => self::draw_panels_tab(&mut self.add_playlist_state, ui, shared),
+				Tab::Panels => self::draw_panels_tab(
+					&mut self.add_playlist_state,
+					&mut self.image_about,
+					&mut self.snapshot_name,
+					ui,
+					shared,
+				),
 				Tab::Playlists => self::draw_playlists(&mut self.add_playlist_state, ui, shared),
+				Tab::Tasks => self::draw_tasks_tab(ui),
 			}
+
+			ui.separator();
+			ui.horizontal(|ui| {
+				if ui.checkbox(&mut self.high_contrast, "High contrast").changed() {
+					ctx.set_visuals(match self.high_contrast {
+						true => self::high_contrast_visuals(),
+						false => egui::Visuals::default(),
+					});
+				}
+
+				if ui.button("🖵 (Identify monitors)").clicked() {
+					self.identify_monitors_until = Some(Instant::now() + IDENTIFY_MONITORS_DURATION);
+				}
+
+				ui.checkbox(&mut self.show_frame_stats, "Frame statistics");
+				if ui.button("💾 (Export frame times)").clicked() {
+					self::export_frame_times(shared);
+				}
+				if ui.button("🐞 (Bug report)").clicked() {
+					self::create_bug_report(shared);
+				}
+
+				let blanked = shared.blank.color().is_some();
+				let response = ui
+					.button(match blanked {
+						true => "▶ (Restore)",
+						false => "⬛ (Blank)",
+					})
+					.on_hover_text("Ctrl+B");
+				if response.clicked() {
+					let mut cur_panels = shared.cur_panels.lock().block_on();
+					shared.blank.toggle(&mut cur_panels);
+				}
+
+				if let Some(timelapse) = &shared.timelapse {
+					let recording = timelapse.is_recording();
+					let response = ui.button(match recording {
+						true => "⏹ (Stop timelapse)",
+						false => "⏺ (Record timelapse)",
+					});
+					if response.clicked() {
+						timelapse.set_recording(!recording);
+					}
+				}
+
+				if let Some(privacy) = &shared.privacy {
+					self::draw_privacy(ui, privacy);
+				}
+			});
+		});
+	}
+}
+/// Draws the progress of loading the default panels
+fn draw_panels_loading(ctx: &egui::Context, loaded: usize, total: usize) {
+	egui::Area::new(egui::Id::new("Panels loading"))
+		.anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+		.interactable(false)
+		.show(ctx, |ui| {
+			egui::Frame::popup(ui.style()).show(ui, |ui| {
+				let progress = match total {
+					0 => 1.0,
+					_ => loaded as f32 / total as f32,
+				};
+				egui::ProgressBar::new(progress)
+					.text(format!("Loading panels ({loaded}/{total})"))
+					.desired_width(200.0)
+					.ui(ui);
+			});
 		});
+}
+
+/// Draws the privacy mode toggle
+fn draw_privacy(ui: &mut egui::Ui, privacy: &Privacy) {
+	let mut manual = privacy.manual();
+	if ui.checkbox(&mut manual, "🔒 Privacy mode").changed() {
+		privacy.set_manual(manual);
+	}
+
+	if privacy.screen_capture() {
+		ui.colored_label(egui::Color32::YELLOW, "Screen capture detected")
+			.on_hover_text("Privacy mode is enabled while the screen is being captured");
+	}
+	if privacy.applied() {
+		ui.label("(Active)");
 	}
 }
+
+/// Draws the frame statistics overlay
+fn draw_frame_stats(ctx: &egui::Context, chart_palette: &mut ChartPalette, shared: &Shared) {
+	/// Number of frames to average the fps over
+	const FPS_FRAMES: usize = 60;
+
+	/// Number of frames in the frame time graph
+	const GRAPH_FRAMES: usize = 240;
+
+	/// Maximum frame time in the frame time graph
+	// Note: Anything higher is clipped
+	const GRAPH_MAX_FRAME_TIME: Duration = Duration::from_millis(50);
+
+	let frame_times = shared.frame_times.lock().unwrap_or_else(PoisonError::into_inner);
+	egui::Area::new(egui::Id::new("Frame statistics"))
+		.anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+		.show(ctx, |ui| {
+			egui::Frame::popup(ui.style()).show(ui, |ui| {
+				ui.label(format!("FPS: {:.1}", frame_times.fps(FPS_FRAMES)));
+				if let Some(frame) = frame_times.frames().next_back() {
+					ui.label(format!("Frame time: {:.2}ms", frame.duration.as_secs_f32() * 1000.0));
+					ui.label(format!("Panels: {}, Draws: {}", frame.panels, frame.draws));
+					if let Some(gpu) = frame.gpu {
+						ui.label(format!(
+							"Gpu: {:.2}ms (Panels: {:.2}ms, Egui: {:.2}ms)",
+							gpu.total().as_secs_f32() * 1000.0,
+							gpu.panels.as_secs_f32() * 1000.0,
+							gpu.egui.as_secs_f32() * 1000.0
+						));
+					}
+				}
+				if let Some(startup_duration) = frame_times.startup_duration() {
+					ui.label(format!("Startup: {:.2}s", startup_duration.as_secs_f32()));
+				}
+
+				let memory_metrics = shared.memory_metrics.load();
+				match memory_metrics.rss {
+					Some(rss) => ui.label(format!("RSS: {:.1} MiB", rss as f64 / (1024.0 * 1024.0))),
+					None => ui.label("RSS: Unknown"),
+				};
+				ui.label(format!(
+					"Tasks: {}, Textures: {}",
+					memory_metrics.tasks, memory_metrics.textures
+				));
+
+				// Note: Gpu timings are only available on some devices, and always lag behind a few frames
+				let frames = || frame_times.frames().rev().take(GRAPH_FRAMES);
+				let ms = |duration: Duration| duration.as_secs_f32() * 1000.0;
+				let series = [
+					ChartSeries {
+						name:   "Frame",
+						values: frames().map(|frame| Some(ms(frame.duration))).collect(),
+					},
+					ChartSeries {
+						name:   "Gpu",
+						values: frames().map(|frame| frame.gpu.map(|gpu| ms(gpu.total()))).collect(),
+					},
+				];
+				LineChart {
+					id:      ui.make_persistent_id("Frame time chart"),
+					palette: *chart_palette,
+					series:  &series,
+					len:     GRAPH_FRAMES,
+					max:     ms(GRAPH_MAX_FRAME_TIME),
+					unit:    "ms",
+				}
+				.show(ui, egui::vec2(GRAPH_FRAMES as f32, 48.0));
+
+				egui::ComboBox::from_label("Palette")
+					.selected_text(chart_palette.name())
+					.show_ui(ui, |ui| {
+						for palette in ChartPalette::ALL {
+							ui.selectable_value(chart_palette, palette, palette.name());
+						}
+					});
+			});
+		});
+}
+
+/// Exports all frame times to a file chosen by the user
+fn export_frame_times(shared: &Shared) {
+	let file_dialog = self::file_dialog(shared)
+		.set_file_name("frame-times.csv")
+		.add_filter("Csv", &["csv"])
+		.add_filter("Json", &["json"]);
+	let Some(path) = file_dialog.save_file() else {
+		tracing::debug!("User cancelled export frame times");
+		return;
+	};
+
+	let frame_times = shared.frame_times.lock().unwrap_or_else(PoisonError::into_inner);
+	match frame_times.export(&path) {
+		Ok(()) => tracing::info!(?path, "Exported frame times"),
+		Err(err) => tracing::warn!(?path, ?err, "Unable to export frame times"),
+	}
+}
+
+/// Creates a bug report, saved to a file chosen by the user
+fn create_bug_report(shared: &Arc<Shared>) {
+	let file_dialog = self::file_dialog(shared)
+		.set_file_name(bug_report::default_file_name().to_string_lossy())
+		.add_filter("Tar gz", &["gz"]);
+	let Some(path) = file_dialog.save_file() else {
+		tracing::debug!("User cancelled bug report");
+		return;
+	};
+
+	let shared = Arc::clone(shared);
+	crate::spawn_task(format!("Bug report {path:?}"), || async move {
+		// Note: Gathering and writing the report blocks (e.g. enumerating adapters), so we do it on another thread
+		let mut bug_report = tokio::task::spawn_blocking({
+			let shared = Arc::clone(&shared);
+			move || BugReport::new(&shared.config_path, shared.log_file.as_deref())
+		})
+		.await
+		.context("Unable to join bug report task")?;
+		bug_report.add_app_state(&shared).await;
+		tokio::task::spawn_blocking({
+			let path = path.clone();
+			move || bug_report.write(&path)
+		})
+		.await
+		.context("Unable to join bug report task")?
+		.context("Unable to write bug report")?;
+		tracing::info!(?path, "Created bug report");
+
+		Ok(())
+	});
+}
+
+/// Asks the user to grant access to `path`
+fn grant_access(path: &Path, kind: SandboxPathKind, shared: &Shared) {
+	// Note: When sandboxed, the dialog goes through the document portal, which
+	//       returns a path within the sandbox that we may access.
+	let mut file_dialog = self::file_dialog(shared).set_title(format!("Grant access to {}", path.display()));
+	if let Some(parent) = path.parent() {
+		file_dialog = file_dialog.set_directory(parent);
+	}
+	let granted_path = match kind {
+		SandboxPathKind::File => file_dialog.pick_file(),
+		SandboxPathKind::Directory => file_dialog.pick_folder(),
+	};
+	let Some(granted_path) = granted_path else {
+		tracing::debug!(?path, "User cancelled grant access");
+		return;
+	};
+
+	match shared.sandbox.grant(path, granted_path) {
+		Ok(()) => tracing::info!(?path, "Granted access, reload the playlist to load it"),
+		Err(err) => tracing::warn!(?path, ?err, "Unable to grant access"),
+	}
+}
+
+/// Creates a file dialog, parented to our window.
+///
+/// On linux, this uses the xdg desktop portal when available (e.g. under
+/// wayland or flatpak), falling back to `zenity` otherwise.
+fn file_dialog(shared: &Shared) -> rfd::FileDialog {
+	// Note: Portals need the parent window to show the dialog on top of it
+	rfd::FileDialog::new().set_parent(shared.window)
+}
+
+/// Duration to identify the monitors for
+const IDENTIFY_MONITORS_DURATION: Duration = Duration::from_secs(5);
+
+/// Draws each monitor's number and name at it's center
+fn draw_monitor_identification(ctx: &egui::Context, shared: &Shared) {
+	// Note: Monitor geometries are relative to the desktop, which may
+	//       be scaled down onto the window if previewing.
+	let window_size = shared.window.inner_size();
+	let surface_size = shared.panels_surface_size(window_size);
+	let scale_factor = shared.window.scale_factor() as f32;
+	let x_scale = window_size.width as f32 / surface_size.width as f32 / scale_factor;
+	let y_scale = window_size.height as f32 / surface_size.height as f32 / scale_factor;
+
+	for (monitor_idx, monitor) in shared.monitors.iter().enumerate() {
+		let center = monitor.geometry.center();
+		egui::Area::new(egui::Id::new(("Monitor identification", monitor_idx)))
+			.fixed_pos(egui::pos2(center.x as f32 * x_scale, center.y as f32 * y_scale))
+			.pivot(egui::Align2::CENTER_CENTER)
+			.interactable(false)
+			.show(ctx, |ui| {
+				egui::Frame::popup(ui.style()).show(ui, |ui| {
+					ui.vertical_centered(|ui| {
+						ui.label(egui::RichText::new(format!("{}", monitor_idx + 1)).size(96.0).strong());
+						ui.label(egui::RichText::new(&monitor.name).size(24.0));
+						ui.label(monitor.geometry.to_string());
+						if let Some(refresh_rate) = monitor.refresh_rate {
+							ui.label(format!("{refresh_rate:.2} hz"));
+						}
+					});
+				});
+			});
+	}
+}
+
+/// Draws all pending bundle passphrase prompts
+fn draw_passphrase_prompts(ctx: &egui::Context, shared: &Shared) {
+	let mut requests = shared.bundles.passphrase_requests().lock().block_on();
+
+	// Note: `true` submits the request, while `false` dismisses it
+	let mut finished = vec![];
+	for (request_idx, request) in requests.iter_mut().enumerate() {
+		egui::Window::new(format!("Bundle passphrase: {:?}", request.path())).show(ctx, |ui| {
+			ui.label(format!("Enter the passphrase for {:?}", request.path()));
+			let response = egui::TextEdit::singleline(request.passphrase_mut())
+				.password(true)
+				.ui(ui);
+
+			ui.horizontal(|ui| {
+				if ui.button("Decrypt").clicked() ||
+					(response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)))
+				{
+					finished.push((request_idx, true));
+				}
+				if ui.button("Cancel").clicked() {
+					finished.push((request_idx, false));
+				}
+			});
+		});
+	}
+
+	for (request_idx, submit) in finished.into_iter().rev() {
+		let request = requests.remove(request_idx);
+		if submit {
+			request.submit();
+		}
+	}
+}
+
 /// Draws the panels tab
-fn draw_panels_tab(add_playlist_state: &mut AddPlaylistState, ui: &mut egui::Ui, shared: &Arc<Shared>) {
-	self::draw_panels_editor(add_playlist_state, ui, shared);
+fn draw_panels_tab(
+	add_playlist_state: &mut AddPlaylistState,
+	image_about: &mut Option<ImageMetadata>,
+	snapshot_name: &mut String,
+	ui: &mut egui::Ui,
+	shared: &Arc<Shared>,
+) {
+	self::draw_panels_editor(add_playlist_state, image_about, ui, shared);
 	ui.separator();
 	self::draw_shader_select(ui, shared);
+	ui.separator();
+	ui.collapsing("Snapshots", |ui| self::draw_snapshots(snapshot_name, ui, shared));
+}
+
+/// Draws the tasks tab
+fn draw_tasks_tab(ui: &mut egui::Ui) {
+	let tasks = TASKS.all();
+	let running = tasks.iter().filter(|task| task.status == TaskStatus::Running).count();
+	ui.label(format!(
+		"Running: {running}, Recently finished: {}",
+		tasks.len() - running
+	));
+
+	egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+		egui::Grid::new("tasks").striped(true).show(ui, |ui| {
+			ui.strong("Name");
+			ui.strong("Status");
+			ui.strong("Runtime");
+			ui.end_row();
+
+			// Note: We show the newest tasks first
+			for task in tasks.iter().rev() {
+				ui.label(&task.name);
+				match &task.status {
+					TaskStatus::Running => ui.label("Running"),
+					TaskStatus::Finished => ui.label("Finished"),
+					TaskStatus::Failed { err } => ui.colored_label(egui::Color32::RED, "Failed").on_hover_text(err),
+					TaskStatus::Cancelled => ui.colored_label(egui::Color32::YELLOW, "Cancelled"),
+				};
+				ui.label(format!("{:.1?}", task.runtime()));
+				ui.end_row();
+			}
+		});
+	});
 }
 
 /// Draws the playlists tab
 fn draw_playlists(add_playlist_state: &mut AddPlaylistState, ui: &mut egui::Ui, shared: &Arc<Shared>) {
+	let response = ui
+		.button("↻ (Reload all)")
+		.on_hover_text("Reloads all playlists from disk, without interrupting the panels");
+	if response.clicked() {
+		let shared = Arc::clone(shared);
+		crate::spawn_task("Reload all playlists", || async move {
+			PanelsManager::reload_playlists(&shared).await
+		});
+	}
+	ui.separator();
+
 	let playlists = shared.playlists.blocking_read().get_all();
 
 	for (playlist_name, playlist) in playlists {
@@ -94,13 +592,20 @@ fn draw_playlists(add_playlist_state: &mut AddPlaylistState, ui: &mut egui::Ui,
 
 				ui.checkbox(&mut item.enabled, "Enabled");
 				match &mut item.kind {
-					PlaylistItemKind::Directory { path, recursive } => {
+					PlaylistItemKind::Directory {
+						path,
+						recursive,
+						extensions,
+					} => {
 						ui.horizontal(|ui| {
 							ui.label("Dir: ");
 							self::draw_openable_path(ui, path);
 						});
 
 						ui.checkbox(recursive, "Recursive");
+						if let Some(extensions) = extensions {
+							ui.label(format!("Extensions: {}", extensions.join(", ")));
+						}
 					},
 					PlaylistItemKind::File { path } => {
 						ui.horizontal(|ui| {
@@ -108,6 +613,18 @@ fn draw_playlists(add_playlist_state: &mut AddPlaylistState, ui: &mut egui::Ui,
 							self::draw_openable_path(ui, path);
 						});
 					},
+					PlaylistItemKind::Bundle { path } => {
+						ui.horizontal(|ui| {
+							ui.label("Bundle: ");
+							self::draw_openable_path(ui, path);
+						});
+					},
+					PlaylistItemKind::Comic { path } => {
+						ui.horizontal(|ui| {
+							ui.label("Comic: ");
+							self::draw_openable_path(ui, path);
+						});
+					},
 				}
 
 				if ui.button("↻ (Reload)").clicked() {
@@ -159,7 +676,7 @@ fn choose_load_playlist_from_file(
 	shared: &Arc<Shared>,
 ) -> Option<(PlaylistName, Arc<RwLock<Playlist>>)> {
 	// TODO: Not have this toml filter here? Or at least allow files other than `.toml`
-	let file_dialog = rfd::FileDialog::new().add_filter("Playlist file", &["toml"]);
+	let file_dialog = self::file_dialog(shared).add_filter("Playlist file", &["toml"]);
 
 	// Ask the user for a playlist file
 	match file_dialog.pick_file() {
@@ -185,9 +702,18 @@ fn choose_load_playlist_from_file(
 	None
 }
 
-/// Draws the panels editor
+/// Draws the panels editor.
+///
+/// The panels are locked for the whole editor, including thumbnails and image popups,
+/// so anything that may wait, such as skipping, is queued as a [`PanelCommand`] instead.
 // TODO: Not edit the values as-is, as that breaks some invariants of panels (such as duration versus image states)
-fn draw_panels_editor(add_playlist_state: &mut AddPlaylistState, ui: &mut egui::Ui, shared: &Arc<Shared>) {
+// TODO: Draw from a snapshot of the panels, so we don't hold the lock while drawing.
+fn draw_panels_editor(
+	add_playlist_state: &mut AddPlaylistState,
+	image_about: &mut Option<ImageMetadata>,
+	ui: &mut egui::Ui,
+	shared: &Arc<Shared>,
+) {
 	let mut cur_panels = shared.cur_panels.lock().block_on();
 
 	if cur_panels.is_empty() {
@@ -195,187 +721,762 @@ fn draw_panels_editor(add_playlist_state: &mut AddPlaylistState, ui: &mut egui::
 		return;
 	}
 
+	let mut panel_op = None;
 	for (panel_idx, panel) in cur_panels.iter_mut().enumerate() {
-		ui.collapsing(format!("Panel {panel_idx}"), |ui| {
-			ui.checkbox(&mut panel.state.paused, "Paused");
+		let id = ui.make_persistent_id(("Panel", panel_idx));
+		let collapsing_state = egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false);
+		let (_, header, _) = collapsing_state
+			.show_header(ui, |ui| {
+				let handle = ui.add(egui::Label::new("☰").sense(egui::Sense::drag()));
+				handle.dnd_set_drag_payload(PanelDragPayload { panel_idx });
+				self::set_accessible_name(ui, handle, "Drag to reorder");
+				ui.label(format!("Panel {panel_idx}"));
+				if panel.state.quiet {
+					ui.label("🌙").on_hover_text("Frozen by quiet hours");
+				}
+			})
+			.body(|ui| {
+				ui.horizontal(|ui| {
+					ui.checkbox(&mut panel.state.paused, "Paused");
+					ui.checkbox(&mut panel.state.muted, "Mute");
+					ui.checkbox(&mut panel.state.soloed, "Solo");
+					if ui.button("⧉ (Duplicate)").clicked() {
+						panel_op = Some(PanelOp::Duplicate { panel_idx });
+					}
+				});
 
-			ui.collapsing("Geometries", |ui| {
-				for (geometry_idx, geometry) in panel.geometries.iter_mut().enumerate() {
-					ui.horizontal(|ui| {
-						ui.label(format!("#{}: ", geometry_idx + 1));
-						self::draw_rect(ui, &mut geometry.geometry);
+				if !panel.state.quiet_hours.is_empty() {
+					ui.collapsing("Quiet hours", |ui| {
+						for quiet_hours in &panel.state.quiet_hours {
+							let days = match quiet_hours.days.is_empty() {
+								true => "Every day".to_owned(),
+								false => quiet_hours
+									.days
+									.iter()
+									.map(Weekday::to_string)
+									.collect::<Vec<_>>()
+									.join(", "),
+							};
+							ui.label(format!(
+								"{} - {} ({days})",
+								quiet_hours.start.format("%H:%M"),
+								quiet_hours.end.format("%H:%M")
+							));
+						}
 					});
 				}
-			});
 
-			ui.horizontal(|ui| {
-				// Note: We only allow up until the duration - 1 so that you don't get stuck
-				//       skipping images when you hold it at the max value
-				ui.label("Cur progress");
-				egui::Slider::new(&mut panel.state.progress, 0..=panel.state.duration.saturating_sub(1))
+				ui.collapsing("Geometries", |ui| {
+					let surface_size = shared.panels_surface_size(shared.window.inner_size());
+					for (geometry_idx, geometry) in panel.geometries.iter_mut().enumerate() {
+						ui.horizontal(|ui| {
+							ui.label(format!("#{}: ", geometry_idx + 1));
+							GeometryEditor::new(&mut geometry.geometry, surface_size, (panel_idx, geometry_idx)).ui(ui);
+						});
+					}
+				});
+
+				ui.horizontal(|ui| {
+					// Note: We only allow up until the duration - 1 so that you don't get stuck
+					//       skipping images when you hold it at the max value
+					ui.label("Cur progress");
+					self::frames_slider(
+						&mut panel.state.fade.progress,
+						0..=panel.state.fade.duration.saturating_sub(1),
+					)
 					.clamp_to_range(true)
 					.ui(ui);
 
-				// Then clamp to the current max
-				// Note: We don't just use this max above so the slider doesn't jitter when the max changes
-				let cur_max = match (panel.images.cur().is_loaded(), panel.images.next().is_loaded()) {
-					(false, false) => 0,
-					(true, false) => panel.state.fade_point,
-					(_, true) => panel.state.duration,
-				};
-				panel.state.progress = panel.state.progress.clamp(0, cur_max);
-			});
+					// Then clamp to the current max
+					// Note: We don't just use this max above so the slider doesn't jitter when the max changes
+					let cur_max = panel.state.fade.max_progress(panel.images.fade_images());
+					panel.state.fade.progress = panel.state.fade.progress.clamp(0, cur_max);
 
-			ui.horizontal(|ui| {
-				ui.label("Fade Point");
-				let min = panel.state.duration / 2;
-				let max = panel.state.duration.saturating_sub(1);
-				egui::Slider::new(&mut panel.state.fade_point, min..=max).ui(ui);
-			});
+					let remaining = panel.state.fade.remaining(PANEL_FRAME_DURATION);
+					ui.label(format!("Next in {:.1}s", remaining.as_secs_f32()));
+				});
 
-			ui.horizontal(|ui| {
-				ui.label("Duration");
-				egui::Slider::new(&mut panel.state.duration, 0..=10800).ui(ui);
-			});
+				ui.horizontal(|ui| {
+					ui.label("Fade Point");
+					let min = panel.state.fade.duration / 2;
+					let max = panel.state.fade.duration.saturating_sub(1);
+					self::frames_slider(&mut panel.state.fade.fade_point, min..=max).ui(ui);
+				});
 
-			ui.horizontal(|ui| {
-				ui.label("Parallax ratio");
-				egui::Slider::new(&mut panel.state.parallax.ratio, 0.0..=1.0).ui(ui);
-			});
+				ui.horizontal(|ui| {
+					ui.label("Duration");
+					self::frames_slider(&mut panel.state.fade.duration, 0..=10800).ui(ui);
+				});
 
-			ui.horizontal(|ui| {
-				ui.label("Parallax exp");
-				egui::Slider::new(&mut panel.state.parallax.exp, 0.0..=4.0).ui(ui);
-			});
+				ui.horizontal(|ui| {
+					ui.label("Parallax ratio");
+					egui::Slider::new(&mut panel.state.parallax.ratio, 0.0..=1.0).ui(ui);
+				});
 
+				ui.horizontal(|ui| {
+					ui.label("Parallax exp");
+					egui::Slider::new(&mut panel.state.parallax.exp, 0.0..=4.0).ui(ui);
+				});
 
-			ui.horizontal(|ui| {
-				ui.checkbox(&mut panel.state.parallax.reverse, "Reverse parallax");
-			});
 
-			ui.horizontal(|ui| {
-				ui.label("Skip");
-				if ui.button("🔄").clicked() {
-					panel
-						.skip(&shared.wgpu, &shared.panels_renderer_layout, &shared.image_requester)
-						.block_on();
+				ui.horizontal(|ui| {
+					ui.checkbox(&mut panel.state.parallax.reverse, "Reverse parallax");
+				});
+
+				if shared.transition_sound.is_some() {
+					ui.horizontal(|ui| {
+						ui.checkbox(&mut panel.state.transition_sound, "Transition sound");
+					});
 				}
-			});
 
-			ui.collapsing("Images", |ui| {
-				ui.collapsing("Previous", |ui| match panel.images.prev().is_loaded() {
-					true => self::draw_panel_image(ui, panel.images.prev_mut()),
-					false => {
-						ui.label("[Unloaded]");
-					},
-				});
-				ui.collapsing("Current", |ui| match panel.images.cur().is_loaded() {
-					true => self::draw_panel_image(ui, panel.images.cur_mut()),
-					false => {
-						ui.label("[Unloaded]");
-					},
+				ui.horizontal(|ui| {
+					ui.label("Transition blur");
+					egui::Slider::new(&mut panel.state.transition_blur, 0.0..=1.0)
+						.ui(ui)
+						.on_hover_text("Motion blur of slide transitions");
 				});
-				ui.collapsing("Next", |ui| match panel.images.next().is_loaded() {
-					true => self::draw_panel_image(ui, panel.images.next_mut()),
-					false => {
-						ui.label("[Unloaded]");
-					},
+
+				if panel.kind == PanelKind::Images && panel.geometries.len() > 1 {
+					ui.horizontal(|ui| {
+						ui.checkbox(&mut panel.state.distinct_images, "Distinct image per geometry")
+							.on_hover_text("Shows a different image on each geometry, all transitioning together");
+					});
+				}
+
+				ui.horizontal(|ui| {
+					ui.label("Scale mode");
+					egui::ComboBox::from_id_source(("Scale mode selection menu", panel_idx))
+						.selected_text(panel.state.scale_mode.name())
+						.show_ui(ui, |ui| {
+							for scale_mode in PanelScaleMode::ALL {
+								ui.selectable_value(&mut panel.state.scale_mode, scale_mode, scale_mode.name());
+							}
+						});
 				});
-			});
 
-			ui.collapsing("Playlist player", |ui| {
-				let playlist_player = panel.playlist_player.write().block_on();
+				if panel.state.scale_mode == PanelScaleMode::Tile {
+					ui.horizontal(|ui| {
+						ui.label("Tile scale");
+						egui::Slider::new(&mut panel.state.tile.scale, 0.05..=4.0)
+							.logarithmic(true)
+							.ui(ui);
+					});
 
-				let row_height = ui.text_style_height(&egui::TextStyle::Body);
+					ui.horizontal(|ui| {
+						ui.label("Tile scroll");
+						let [x, y] = &mut panel.state.tile.scroll;
+						egui::Slider::new(x, -4.0..=4.0).ui(ui);
+						egui::Slider::new(y, -4.0..=4.0).ui(ui);
+					});
+				}
 
-				if ui.button("↹ (Replace)").clicked() {
-					// TODO: Stop everything that could be inserting items still?
-					if let Some((playlist_name, playlist)) =
-						self::choose_load_playlist_from_file(add_playlist_state, shared)
-					{
-						crate::spawn_task(format!("Replace playlist {playlist:?}"), {
-							let playlist_player = Arc::clone(&panel.playlist_player);
-							let shared = Arc::clone(shared);
-							|| async move {
-								{
-									let mut playlist_player = playlist_player.write().await;
-									playlist_player.remove_all();
+				ui.horizontal(|ui| {
+					ui.label("Alignment");
+					egui::ComboBox::from_id_source(("Alignment selection menu", panel_idx))
+						.selected_text(panel.state.alignment.name())
+						.show_ui(ui, |ui| {
+							for alignment in PanelAlignment::ALL {
+								// Note: We compare by name so that custom offsets show as selected
+								let selected = panel.state.alignment.name() == alignment.name();
+								if ui.selectable_label(selected, alignment.name()).clicked() && !selected {
+									panel.state.alignment = alignment;
 								}
+							}
+						});
+				});
 
-								PanelsManager::load_playlist_into(&playlist_player, &playlist_name, &shared)
-									.await
-									.context("Unable to load playlist")?;
+				if let PanelAlignment::Offset { x, y } = &mut panel.state.alignment {
+					ui.horizontal(|ui| {
+						ui.label("Alignment offset");
+						egui::Slider::new(x, 0.0..=1.0).ui(ui);
+						egui::Slider::new(y, 0.0..=1.0).ui(ui);
+					});
+				}
 
-								Ok(())
+				ui.horizontal(|ui| {
+					ui.label("Sampler filter");
+					egui::ComboBox::from_id_source(("Sampler filter selection menu", panel_idx))
+						.selected_text(panel.state.sampler.filter.name())
+						.show_ui(ui, |ui| {
+							for filter in PanelSamplerFilter::ALL {
+								ui.selectable_value(&mut panel.state.sampler.filter, filter, filter.name());
 							}
 						});
-					}
-				};
+				});
 
-				ui.collapsing("Prev", |ui| {
-					egui::ScrollArea::new([false, true])
-						.auto_shrink([false, true])
-						.stick_to_right(true)
-						.max_height(row_height * 10.0)
-						.show_rows(ui, row_height, playlist_player.prev_items().len(), |ui, idx| {
-							for item in playlist_player.prev_items().take(idx.end).skip(idx.start) {
-								self::draw_openable_path(ui, item);
+				ui.horizontal(|ui| {
+					ui.label("Sampler address mode");
+					egui::ComboBox::from_id_source(("Sampler address mode selection menu", panel_idx))
+						.selected_text(panel.state.sampler.address_mode.name())
+						.show_ui(ui, |ui| {
+							for address_mode in PanelSamplerAddressMode::ALL {
+								ui.selectable_value(
+									&mut panel.state.sampler.address_mode,
+									address_mode,
+									address_mode.name(),
+								);
 							}
 						});
 				});
 
-				ui.collapsing("Next", |ui| {
-					egui::ScrollArea::new([false, true])
-						.auto_shrink([false, true])
-						.stick_to_right(true)
-						.max_height(row_height * 10.0)
-						.show_rows(ui, row_height, playlist_player.peek_next_items().len(), |ui, idx| {
-							for item in playlist_player.peek_next_items().take(idx.end).skip(idx.start) {
-								self::draw_openable_path(ui, item);
+				// Note: Anisotropic filtering is only available with linear filtering
+				if panel.state.sampler.filter == PanelSamplerFilter::Linear {
+					ui.horizontal(|ui| {
+						ui.label("Sampler anisotropy");
+						egui::Slider::new(&mut panel.state.sampler.anisotropy, 1..=PanelSampler::MAX_ANISOTROPY).ui(ui);
+					});
+				}
+
+				ui.horizontal(|ui| {
+					ui.label("Monitor");
+					egui::ComboBox::from_id_source(("Monitor selection menu", panel_idx))
+						.selected_text(panel.state.monitor.as_deref().unwrap_or("All"))
+						.show_ui(ui, |ui| {
+							ui.selectable_value(&mut panel.state.monitor, None, "All");
+							for monitor in &shared.monitors {
+								ui.selectable_value(
+									&mut panel.state.monitor,
+									Some(monitor.name.clone()),
+									&monitor.name,
+								);
 							}
 						});
 				});
 
-				ui.collapsing("All", |ui| {
-					egui::ScrollArea::new([false, true])
-						.auto_shrink([false, true])
-						.stick_to_right(true)
-						.max_height(row_height * 10.0)
-						.show_rows(ui, row_height, playlist_player.all_items().len(), |ui, idx| {
-							for item in playlist_player.all_items().take(idx.end).skip(idx.start) {
-								self::draw_openable_path(ui, item);
+				ui.horizontal(|ui| {
+					let mut has_fps = panel.state.fps.is_some();
+					ui.checkbox(&mut has_fps, "Frame rate")
+						.on_hover_text("Frame rate the panel needs while not transitioning");
+					match has_fps {
+						true => {
+							let fps = panel.state.fps.get_or_insert(30.0);
+							egui::Slider::new(fps, PanelState::MIN_FPS..=240.0)
+								.logarithmic(true)
+								.suffix(" fps")
+								.ui(ui);
+						},
+						false => panel.state.fps = None,
+					}
+				});
+
+				ui.horizontal(|ui| {
+					ui.label("Skip");
+					if ui.button("🔄").clicked() {
+						shared.panel_commands.push(panel.id, PanelCommand::Skip);
+					}
+
+					let response = ui.button("⏭");
+					if response.clicked() {
+						shared.panel_commands.push(panel.id, PanelCommand::SwitchInstant);
+					}
+					self::set_accessible_name(ui, response, "Switch instantly (Right arrow over the panel)");
+				});
+
+				ui.collapsing("Images", |ui| {
+					self::draw_panel_thumbnails(ui, &panel.images, shared);
+
+					ui.collapsing("Previous", |ui| match panel.images.prev().is_loaded() {
+						true => self::draw_panel_image(ui, panel.images.prev_mut(), image_about, shared),
+						false => {
+							ui.label("[Unloaded]");
+						},
+					});
+					ui.collapsing("Current", |ui| match panel.images.cur().is_loaded() {
+						true => self::draw_panel_image(ui, panel.images.cur_mut(), image_about, shared),
+						false => {
+							ui.label("[Unloaded]");
+						},
+					});
+					ui.collapsing("Next", |ui| match panel.images.next().is_loaded() {
+						true => self::draw_panel_image(ui, panel.images.next_mut(), image_about, shared),
+						false => {
+							ui.label("[Unloaded]");
+						},
+					});
+				});
+
+				ui.collapsing("Playlist player", |ui| {
+					// Note: We clone the player, so we may still modify the panel while it's locked
+					let panel_playlist_player = Arc::clone(&panel.playlist_player);
+					let mut playlist_player = panel_playlist_player.write().block_on();
+
+					let row_height = ui.text_style_height(&egui::TextStyle::Body);
+
+					match panel.player_sharing {
+						Some(PanelPlayerSharing::Coordinated) => {
+							ui.label("Coordinated: Shared with other panels of the same playlist");
+						},
+						Some(PanelPlayerSharing::Cascade { offset }) => {
+							ui.label(format!(
+								"Cascading: Shared with other panels of the same playlist, offset {offset}"
+							));
+						},
+						None => (),
+					}
+
+					ui.horizontal(|ui| {
+						// Note: We show the playlist under any overrides, since that's what the user chose
+						ui.label("Playlist");
+						let base_playlist_name = panel.base_playlist().0.clone();
+						let mut playlist_name = base_playlist_name.clone();
+						let playlists = shared.playlists.blocking_read().get_all();
+						egui::ComboBox::from_id_source(("panel-playlist", panel_idx))
+							.selected_text(playlist_name.to_string())
+							.show_ui(ui, |ui| {
+								for (name, _) in playlists {
+									let text = name.to_string();
+									ui.selectable_value(&mut playlist_name, name, text);
+								}
+							});
+						if playlist_name != base_playlist_name {
+							self::set_panel_playlist(panel, playlist_name, shared);
+						}
+
+						if ui.button("↹ (Replace)").clicked() {
+							if let Some((playlist_name, _)) =
+								self::choose_load_playlist_from_file(add_playlist_state, shared)
+							{
+								self::set_panel_playlist(panel, playlist_name, shared);
+							}
+						};
+					});
+
+					ui.collapsing("Prev", |ui| {
+						egui::ScrollArea::new([false, true])
+							.auto_shrink([false, true])
+							.stick_to_right(true)
+							.max_height(row_height * 10.0)
+							.show_rows(ui, row_height, playlist_player.prev_items().len(), |ui, idx| {
+								for item in playlist_player.prev_items().take(idx.end).skip(idx.start) {
+									self::draw_openable_path(ui, item);
+								}
+							});
+					});
+
+					// Note: Jumping only affects images that weren't scheduled yet, so we
+					//       clear the scheduled images to show the jumped to image next.
+					let mut jump_to = None;
+					ui.collapsing("Next", |ui| {
+						/// Default number of upcoming items to show
+						const DEFAULT_LOOKAHEAD: usize = 100;
+
+						// Note: A loop may have as many items as the whole playlist, so we
+						//       only show the first few by default.
+						let next_items_len = playlist_player.peek_next_items().len();
+						let lookahead_id = ui.make_persistent_id("playlist-lookahead");
+						let mut lookahead =
+							ui.data(|data| data.get_temp::<usize>(lookahead_id).unwrap_or(DEFAULT_LOOKAHEAD));
+						ui.horizontal(|ui| {
+							ui.label("Show");
+							egui::DragValue::new(&mut lookahead).range(1..=usize::MAX).ui(ui);
+							ui.label(format!("of {next_items_len}"));
+
+							// Note: Like jumping, we clear the scheduled images, so the reshuffle is seen right away
+							if ui
+								.button("🔀 (Reshuffle)")
+								.on_hover_text("Reshuffles the remaining items in this loop")
+								.clicked()
+							{
+								playlist_player.reshuffle();
+								panel.clear_scheduled();
 							}
 						});
+						ui.data_mut(|data| data.insert_temp(lookahead_id, lookahead));
+
+						egui::ScrollArea::new([false, true])
+							.auto_shrink([false, true])
+							.stick_to_right(true)
+							.max_height(row_height * 10.0)
+							.show_rows(ui, row_height, next_items_len.min(lookahead), |ui, idx| {
+								for (item_idx, item) in playlist_player
+									.peek_next_items()
+									.enumerate()
+									.take(idx.end)
+									.skip(idx.start)
+								{
+									ui.horizontal(|ui| {
+										if ui.small_button("⤴").on_hover_text("Jump to").clicked() {
+											jump_to = Some(PlaylistJump::Index(item_idx));
+										}
+										self::draw_openable_path(ui, item);
+									});
+								}
+							});
+					});
+
+					ui.collapsing("Search", |ui| {
+						let query_id = ui.make_persistent_id("playlist-search");
+						let mut query = ui.data(|data| data.get_temp::<String>(query_id).unwrap_or_default());
+						ui.text_edit_singleline(&mut query);
+
+						if !query.is_empty() {
+							let results = playlist_player.search(&query).collect::<Vec<_>>();
+							egui::ScrollArea::new([false, true])
+								.auto_shrink([false, true])
+								.stick_to_right(true)
+								.max_height(row_height * 10.0)
+								.show_rows(ui, row_height, results.len(), |ui, idx| {
+									for item in &results[idx] {
+										ui.horizontal(|ui| {
+											if ui.small_button("⤴").on_hover_text("Jump to").clicked() {
+												jump_to = Some(PlaylistJump::Path(Arc::clone(item)));
+											}
+											self::draw_openable_path(ui, item);
+										});
+									}
+								});
+						}
+
+						ui.data_mut(|data| data.insert_temp(query_id, query));
+					});
+
+					if let Some(jump_to) = jump_to {
+						let jumped = match &jump_to {
+							PlaylistJump::Index(idx) => playlist_player.jump_to_index(*idx),
+							PlaylistJump::Path(path) => playlist_player.jump_to(path),
+						};
+						match jumped {
+							true => panel.clear_scheduled(),
+							false => tracing::warn!(?jump_to, "Unable to jump to playlist item"),
+						}
+					}
+
+					ui.collapsing("All", |ui| {
+						egui::ScrollArea::new([false, true])
+							.auto_shrink([false, true])
+							.stick_to_right(true)
+							.max_height(row_height * 10.0)
+							.show_rows(ui, row_height, playlist_player.all_items().len(), |ui, idx| {
+								for item in playlist_player.all_items().take(idx.end).skip(idx.start) {
+									self::draw_openable_path(ui, item);
+								}
+							});
+					});
+
+					// TODO: Allow a "Go back" button. Or even a full playlist solution
 				});
+			});
 
-				// TODO: Allow a "Go back" button. Or even a full playlist solution
+		// Reorder any panels dropped onto this one.
+		// Note: Panels are rendered in order, so this also changes which panels are drawn on top
+		if let Some(payload) = header.response.dnd_hover_payload::<PanelDragPayload>() {
+			let rect = header.response.rect;
+			let y = match payload.panel_idx > panel_idx {
+				true => rect.top(),
+				false => rect.bottom(),
+			};
+			let stroke = ui.visuals().selection.stroke;
+			ui.painter().hline(rect.x_range(), y, stroke);
+		}
+		if let Some(payload) = header.response.dnd_release_payload::<PanelDragPayload>() {
+			panel_op = Some(PanelOp::Move {
+				from_idx: payload.panel_idx,
+				to_idx:   panel_idx,
 			});
-		});
+		}
+	}
+
+	match panel_op {
+		// Note: The duplicate is placed right after the original, so it's rendered just above it
+		Some(PanelOp::Duplicate { panel_idx }) => match PanelsManager::duplicate(&cur_panels[panel_idx], shared) {
+			Ok(panel) => cur_panels.insert(panel_idx + 1, panel),
+			Err(err) => tracing::warn!(?panel_idx, ?err, "Unable to duplicate panel"),
+		},
+		Some(PanelOp::Move { from_idx, to_idx }) => {
+			let panel = cur_panels.remove(from_idx);
+			cur_panels.insert(to_idx, panel);
+		},
+		None => (),
 	}
 }
 
 
+/// Copies `text` to the clipboard, in the background
+fn copy_to_clipboard(text: String) {
+	crate::spawn_task("Copy to clipboard", || async move {
+		desktop::copy_to_clipboard(&text)
+			.await
+			.context("Unable to copy to clipboard")?;
+
+		Ok(())
+	});
+}
+
 /// Draws an openable path
 fn draw_openable_path(ui: &mut egui::Ui, path: &Path) {
 	ui.horizontal(|ui| {
 		ui.label("Path: ");
 		// TODO: Not use lossy conversion to display it?
 		if ui.link(path.to_string_lossy()).clicked() {
-			if let Err(err) = opener::open(path) {
+			if let Err(err) = desktop::open(path) {
 				tracing::warn!(?path, ?err, "Unable to open file");
 			}
 		}
 	});
 }
 
+/// Switches `panel` to the playlist `playlist_name`.
+///
+/// If the panel shared it's playlist player with other panels, it's detached
+/// from them first, so they keep their playlist.
+///
+/// Any overrides of the panel's playlist are discarded, and privacy mode re-applied.
+fn set_panel_playlist(panel: &mut Panel, playlist_name: PlaylistName, shared: &Arc<Shared>) {
+	// TODO: Stop everything that could be inserting items still?
+	panel.clear_overrides();
+	panel.playlist_name = playlist_name.clone();
+	panel.clear_scheduled();
+	if panel.player_sharing.take().is_some() {
+		panel.playlist_player = Arc::new(RwLock::new(PlaylistPlayer::new()));
+		panel.images.set_cascade_offset(None);
+	}
+
+	crate::spawn_task(format!("Replace playlist {playlist_name:?}"), {
+		let playlist_player = Arc::clone(&panel.playlist_player);
+		let shared = Arc::clone(shared);
+		|| async move {
+			{
+				let mut playlist_player = playlist_player.write().await;
+				playlist_player.remove_all();
+			}
+
+			PanelsManager::load_playlist_into(&playlist_player, &playlist_name, &shared)
+				.await
+				.context("Unable to load playlist")?;
+
+			Ok(())
+		}
+	});
+
+	if let Some(privacy) = &shared.privacy {
+		privacy.apply(panel, shared);
+	}
+}
+
+/// Draws the thumbnails of all panel images
+fn draw_panel_thumbnails(ui: &mut egui::Ui, images: &PanelImages, shared: &Shared) {
+	let panel_thumbnails = shared.panel_thumbnails.lock().unwrap_or_else(PoisonError::into_inner);
+	let thumbnail_size = egui::Vec2::splat(image_loader::THUMBNAIL_SIZE as f32);
+
+	ui.horizontal(|ui| {
+		for (name, image) in [
+			("Previous", images.prev()),
+			("Current", images.cur()),
+			("Next", images.next()),
+		] {
+			ui.vertical(|ui| {
+				ui.label(name);
+
+				// Note: The texture is only registered on the next frame, so
+				//       we might not have it yet.
+				let texture_id = panel_thumbnails.get(image.thumbnail_texture_view());
+				match texture_id.filter(|_| image.is_loaded()) {
+					Some(texture_id) => {
+						let size = image.thumbnail_size();
+						let size = egui::Vec2::new(size.x as f32, size.y as f32);
+						ui.add(egui::Image::new((texture_id, size)).max_size(thumbnail_size));
+					},
+					None => {
+						ui.allocate_ui(thumbnail_size, |ui| ui.label("[Unloaded]"));
+					},
+				}
+			});
+		}
+	});
+}
+
 /// Draws a panel image
-fn draw_panel_image(ui: &mut egui::Ui, image: &mut PanelImage) {
+fn draw_panel_image(
+	ui: &mut egui::Ui,
+	image: &mut PanelImage,
+	image_about: &mut Option<ImageMetadata>,
+	shared: &Arc<Shared>,
+) {
 	let size = image.size();
 	if let Some(path) = image.path() {
-		self::draw_openable_path(ui, path);
+		// Note: Virtual images, such as those within bundles, aren't files on disk,
+		//       so there's nothing to open, reveal or copy the path of.
+		let is_virtual = image.is_virtual();
+		match is_virtual {
+			true => {
+				// TODO: Not use lossy conversion to display it?
+				ui.label(format!("Path: {}", path.to_string_lossy()));
+			},
+			false => self::draw_openable_path(ui, path),
+		}
+		ui.horizontal(|ui| {
+			let disabled_hover_text = "Only available for images on disk";
+			let copy_button = ui
+				.add_enabled(!is_virtual, egui::Button::new("📋 (Copy path)"))
+				.on_disabled_hover_text(disabled_hover_text);
+			if copy_button.clicked() {
+				// TODO: Not use lossy conversion to copy it?
+				self::copy_to_clipboard(path.to_string_lossy().into_owned());
+			}
+			let open_button = ui
+				.add_enabled(!is_virtual, egui::Button::new("🖼 (Open)"))
+				.on_disabled_hover_text(disabled_hover_text);
+			if open_button.clicked() {
+				if let Err(err) = desktop::open(path) {
+					tracing::warn!(?path, ?err, "Unable to open file");
+				}
+			}
+			let reveal_button = ui
+				.add_enabled(!is_virtual, egui::Button::new("📁 (Reveal)"))
+				.on_disabled_hover_text(disabled_hover_text);
+			if reveal_button.clicked() {
+				if let Err(err) = desktop::reveal(path) {
+					tracing::warn!(?path, ?err, "Unable to reveal file");
+				}
+			}
+			let about_button = ui
+				.add_enabled(!is_virtual, egui::Button::new("ℹ (About)"))
+				.on_disabled_hover_text(disabled_hover_text);
+			if about_button.clicked() {
+				*image_about = Some(ImageMetadata::read(path));
+			}
+			let wallpaper_button = ui
+				.add_enabled(!is_virtual, egui::Button::new("🖵 (Set as wallpaper)"))
+				.on_disabled_hover_text(disabled_hover_text);
+			if wallpaper_button.clicked() {
+				let path = path.to_path_buf();
+				let shared = Arc::clone(shared);
+				crate::spawn_task(format!("Export wallpaper {path:?}"), || async move {
+					shared
+						.wallpaper_export_backend
+						.export(&path)
+						.await
+						.context("Unable to export wallpaper")?;
+
+					Ok(())
+				});
+			}
+		});
+		self::draw_rating(ui, path, shared);
 	}
 	ui.label(format!("Size: {}x{}", size.x, size.y));
 	ui.checkbox(image.swap_dir_mut(), "Swap direction");
+	if let Some(histogram) = image.histogram() {
+		ui.collapsing("Histogram", |ui| self::draw_histogram(ui, histogram));
+	}
+}
+
+/// Draws an image histogram, with clipping indicators
+fn draw_histogram(ui: &mut egui::Ui, histogram: &ImageHistogram) {
+	/// Width of each bin
+	const BIN_WIDTH: f32 = 4.0;
+
+	/// Fraction of clipped pixels above which we warn
+	const CLIPPED_WARN_RATIO: f32 = 0.01;
+
+	let width = ImageHistogram::BINS as f32 * BIN_WIDTH;
+	let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 64.0), egui::Sense::hover());
+	let visuals = &ui.visuals().widgets.noninteractive;
+	ui.painter().rect_stroke(rect, 0.0, visuals.bg_stroke);
+
+	// Note: We scale by the largest bin, so the shape is visible even if most pixels are in a few bins
+	let max_count = histogram.luminance.iter().copied().max().unwrap_or(0).max(1);
+	for (bin_idx, &count) in histogram.luminance.iter().enumerate() {
+		let height = count as f32 / max_count as f32 * rect.height();
+		let left = rect.left() + bin_idx as f32 * BIN_WIDTH;
+		let bin_rect = egui::Rect::from_min_max(
+			egui::pos2(left, rect.bottom() - height),
+			egui::pos2(left + BIN_WIDTH, rect.bottom()),
+		);
+		ui.painter().rect_filled(bin_rect, 0.0, visuals.fg_stroke.color);
+	}
+
+	for (name, ratio) in [
+		("Clipped shadows", histogram.clipped_black_ratio()),
+		("Clipped highlights", histogram.clipped_white_ratio()),
+	] {
+		let text = format!("{name}: {:.1}%", ratio * 100.0);
+		match ratio > CLIPPED_WARN_RATIO {
+			true => ui.colored_label(egui::Color32::YELLOW, format!("⚠ {text}")),
+			false => ui.label(text),
+		};
+	}
+}
+
+/// Draws the "about this image" popup, if open
+fn draw_image_about(ctx: &egui::Context, image_about: &mut Option<ImageMetadata>, shared: &Shared) {
+	let Some(metadata) = image_about else {
+		return;
+	};
+
+	let mut open = true;
+	egui::Window::new("About this image").open(&mut open).show(ctx, |ui| {
+		self::draw_openable_path(ui, &metadata.path);
+		match metadata.resolution {
+			Some((width, height)) => ui.label(format!("Resolution: {width}x{height}")),
+			None => ui.label("Resolution: Unknown"),
+		};
+		match metadata.file_size {
+			Some(file_size) => ui.label(format!("File size: {:.2} MiB", file_size as f64 / (1024.0 * 1024.0))),
+			None => ui.label("File size: Unknown"),
+		};
+
+		ui.collapsing("Exif", |ui| {
+			if metadata.exif.is_empty() {
+				ui.label("None");
+				return;
+			}
+
+			egui::ScrollArea::vertical().max_height(256.0).show(ui, |ui| {
+				egui::Grid::new("About this image exif").striped(true).show(ui, |ui| {
+					for (tag, value) in &metadata.exif {
+						ui.label(tag);
+						ui.label(value);
+						ui.end_row();
+					}
+				});
+			});
+		});
+
+		ui.horizontal(|ui| {
+			if ui.button("📋 (Copy path)").clicked() {
+				// TODO: Not use lossy conversion to copy it?
+				self::copy_to_clipboard(metadata.path.to_string_lossy().into_owned());
+			}
+			let reverse_search_button = ui
+				.add_enabled(
+					shared.reverse_image_search_url.is_some(),
+					egui::Button::new("🔍 (Copy reverse image search url)"),
+				)
+				.on_disabled_hover_text("Set `reverse_image_search_url` in the config to enable this");
+			if reverse_search_button.clicked() {
+				if let Some(reverse_image_search_url) = &shared.reverse_image_search_url {
+					self::copy_to_clipboard(metadata.reverse_search_url(reverse_image_search_url));
+				}
+			}
+		});
+	});
+
+	if !open {
+		*image_about = None;
+	}
+}
+
+/// Draws the rating of an image
+fn draw_rating(ui: &mut egui::Ui, path: &Path, shared: &Shared) {
+	ui.horizontal(|ui| {
+		ui.label("Rating: ");
+		let rating = shared.ratings.get(path);
+		for star in 1..=ratings::MAX_RATING {
+			let text = match rating.is_some_and(|rating| rating >= star) {
+				true => "★",
+				false => "☆",
+			};
+
+			// Note: Clicking the current rating clears it
+			if ui.button(text).on_hover_text(format!("{star} star(s)")).clicked() {
+				let rating = (rating != Some(star)).then_some(star);
+				if let Err(err) = shared.ratings.set(path, rating) {
+					tracing::warn!(?path, ?err, "Unable to set rating");
+				}
+			}
+		}
+	});
 }
 
 /// Draws the shader select
@@ -387,53 +1488,110 @@ fn draw_shader_select(ui: &mut egui::Ui, shared: &Shared) {
 	egui::ComboBox::from_id_source("Shader selection menu")
 		.selected_text(cur_shader.name())
 		.show_ui(ui, |ui| {
-			// TODO: Not have default values here?
-			let shaders = [
-				PanelShader::None,
-				PanelShader::Fade,
-				PanelShader::FadeWhite { strength: 1.0 },
-				PanelShader::FadeOut { strength: 0.2 },
-				PanelShader::FadeIn { strength: 0.2 },
-			];
-			for shader in shaders {
+			for shader in PanelShader::all() {
 				ui.selectable_value(cur_shader, shader, shader.name());
 			}
 		});
 
-	match cur_shader {
-		PanelShader::None | PanelShader::Fade => (),
-		PanelShader::FadeWhite { strength } => {
-			ui.horizontal(|ui| {
-				ui.label("Strength");
-				egui::Slider::new(strength, 0.0..=20.0).ui(ui);
-			});
-		},
-		PanelShader::FadeOut { strength } => {
-			ui.horizontal(|ui| {
-				ui.label("Strength");
-				egui::Slider::new(strength, 0.0..=2.0).ui(ui);
-			});
-		},
-		PanelShader::FadeIn { strength } => {
-			ui.horizontal(|ui| {
-				ui.label("Strength");
-				egui::Slider::new(strength, 0.0..=2.0).ui(ui);
-			});
-		},
+	for (param, value) in cur_shader.params_mut() {
+		ui.horizontal(|ui| {
+			ui.label(param.name);
+			egui::Slider::new(value, param.min..=param.max).ui(ui);
+		});
 	}
 }
 
-/// Draws a geometry rectangle
-fn draw_rect(ui: &mut egui::Ui, geometry: &mut Rect<i32, u32>) {
+/// Draws the snapshots
+fn draw_snapshots(snapshot_name: &mut String, ui: &mut egui::Ui, shared: &Arc<Shared>) {
 	ui.horizontal(|ui| {
-		egui::DragValue::new(&mut geometry.size.x).speed(10).ui(ui);
-		ui.label("x");
-		egui::DragValue::new(&mut geometry.size.y).speed(10).ui(ui);
-		ui.label("+");
-		egui::DragValue::new(&mut geometry.pos.x).speed(10).ui(ui);
-		ui.label("+");
-		egui::DragValue::new(&mut geometry.pos.y).speed(10).ui(ui);
+		let response = ui.text_edit_singleline(snapshot_name);
+		self::set_accessible_name(ui, response, "Snapshot name");
+
+		if ui.button("💾 (Save)").clicked() {
+			let cur_panels = shared.cur_panels.lock().block_on();
+			match shared.snapshots.save(snapshot_name, &cur_panels) {
+				Ok(()) => snapshot_name.clear(),
+				Err(err) => tracing::warn!(?snapshot_name, ?err, "Unable to save snapshot"),
+			}
+		}
 	});
+
+	let names = match shared.snapshots.names() {
+		Ok(names) => names,
+		Err(err) => {
+			ui.colored_label(egui::Color32::RED, format!("Unable to list snapshots: {err:?}"));
+			return;
+		},
+	};
+	for name in names {
+		ui.horizontal(|ui| {
+			ui.label(&name);
+
+			if ui.button("Restore").clicked() {
+				match shared.snapshots.load(&name, shared) {
+					Ok(panels) => *shared.cur_panels.lock().block_on() = panels,
+					Err(err) => tracing::warn!(?name, ?err, "Unable to restore snapshot"),
+				}
+			}
+
+			if ui.button("🗑").clicked() {
+				if let Err(err) = shared.snapshots.remove(&name) {
+					tracing::warn!(?name, ?err, "Unable to remove snapshot");
+				}
+			}
+		});
+	}
+}
+
+/// Returns a slider over a number of panel frames, shown in seconds
+fn frames_slider(frames: &mut u64, range: RangeInclusive<u64>) -> egui::Slider<'_> {
+	let frame_secs = PANEL_FRAME_DURATION.as_secs_f64();
+	egui::Slider::new(frames, range)
+		.custom_formatter(move |frames, _| format!("{:.2}", frames * frame_secs))
+		.custom_parser(move |secs| secs.parse::<f64>().ok().map(|secs| (secs / frame_secs).round()))
+		.suffix("s")
+}
+
+/// Sets the name of an unlabeled widget for screen readers.
+///
+/// Also shows the name when hovering.
+fn set_accessible_name(ui: &egui::Ui, response: egui::Response, name: &str) {
+	ui.ctx()
+		.accesskit_node_builder(response.id, |builder| builder.set_name(name));
+	response.on_hover_text(name);
+}
+
+/// Returns the high contrast theme
+fn high_contrast_visuals() -> egui::Visuals {
+	let stroke = |width: f32, color| egui::Stroke::new(width, color);
+
+	let mut visuals = egui::Visuals::dark();
+	visuals.override_text_color = Some(egui::Color32::WHITE);
+	visuals.hyperlink_color = egui::Color32::YELLOW;
+	visuals.window_fill = egui::Color32::BLACK;
+	visuals.panel_fill = egui::Color32::BLACK;
+	visuals.extreme_bg_color = egui::Color32::BLACK;
+	visuals.window_stroke = stroke(2.0, egui::Color32::WHITE);
+	visuals.selection.bg_fill = egui::Color32::from_rgb(0, 0, 192);
+	visuals.selection.stroke = stroke(2.0, egui::Color32::YELLOW);
+
+	let widgets = &mut visuals.widgets;
+	for widget in [
+		&mut widgets.noninteractive,
+		&mut widgets.inactive,
+		&mut widgets.hovered,
+		&mut widgets.active,
+		&mut widgets.open,
+	] {
+		widget.bg_fill = egui::Color32::BLACK;
+		widget.weak_bg_fill = egui::Color32::BLACK;
+		widget.bg_stroke = stroke(1.0, egui::Color32::WHITE);
+		widget.fg_stroke = stroke(1.5, egui::Color32::WHITE);
+	}
+	widgets.hovered.bg_stroke = stroke(2.0, egui::Color32::YELLOW);
+	widgets.active.bg_stroke = stroke(2.0, egui::Color32::YELLOW);
+
+	visuals
 }
 
 
@@ -442,6 +1600,34 @@ fn draw_rect(ui: &mut egui::Ui, geometry: &mut Rect<i32, u32>) {
 enum Tab {
 	Panels,
 	Playlists,
+	Tasks,
+}
+
+/// Panel editor operation
+#[derive(Clone, Copy, Debug)]
+enum PanelOp {
+	/// Duplicate a panel
+	Duplicate { panel_idx: usize },
+
+	/// Move a panel to another index
+	Move { from_idx: usize, to_idx: usize },
+}
+
+/// Playlist item to jump to
+#[derive(Clone, Debug)]
+enum PlaylistJump {
+	/// Upcoming item, by index
+	Index(usize),
+
+	/// Item by path
+	Path(Arc<Path>),
+}
+
+/// Drag and drop payload for reordering panels
+#[derive(Clone, Copy, Debug)]
+struct PanelDragPayload {
+	/// Index of the dragged panel
+	panel_idx: usize,
 }
 
 /// State for adding a playlist
@@ -0,0 +1,176 @@
+//! Chart
+
+// Imports
+use std::{collections::HashSet, mem};
+
+/// Chart palette
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChartPalette {
+	/// Default palette
+	#[default]
+	Default,
+
+	/// Colour-blind safe palette.
+	///
+	/// Uses the Okabe-Ito colors, which remain distinguishable under all common forms of colour blindness.
+	ColorBlind,
+}
+
+impl ChartPalette {
+	/// All palettes
+	pub const ALL: [Self; 2] = [Self::Default, Self::ColorBlind];
+
+	/// Returns the name of this palette
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Default => "Default",
+			Self::ColorBlind => "Colour-blind safe",
+		}
+	}
+
+	/// Returns the color of the series at `series_idx`
+	pub fn color(self, series_idx: usize) -> egui::Color32 {
+		let colors: &[egui::Color32] = match self {
+			Self::Default => &[
+				egui::Color32::LIGHT_GRAY,
+				egui::Color32::GREEN,
+				egui::Color32::LIGHT_BLUE,
+				egui::Color32::YELLOW,
+			],
+			Self::ColorBlind => &[
+				egui::Color32::from_rgb(230, 159, 0),
+				egui::Color32::from_rgb(86, 180, 233),
+				egui::Color32::from_rgb(0, 158, 115),
+				egui::Color32::from_rgb(240, 228, 66),
+				egui::Color32::from_rgb(0, 114, 178),
+				egui::Color32::from_rgb(213, 94, 0),
+				egui::Color32::from_rgb(204, 121, 167),
+			],
+		};
+
+		colors[series_idx % colors.len()]
+	}
+}
+
+/// Chart series
+#[derive(Debug)]
+pub struct ChartSeries<'a> {
+	/// Name
+	pub name: &'a str,
+
+	/// Values, from newest to oldest.
+	///
+	/// Missing values aren't drawn.
+	pub values: Vec<Option<f32>>,
+}
+
+/// Line chart
+#[derive(Debug)]
+pub struct LineChart<'a> {
+	/// Id, used to store which series are hidden
+	pub id: egui::Id,
+
+	/// Palette
+	pub palette: ChartPalette,
+
+	/// All series
+	pub series: &'a [ChartSeries<'a>],
+
+	/// Number of values shown, across the whole width
+	pub len: usize,
+
+	/// Maximum value.
+	///
+	/// Anything higher is clipped
+	pub max: f32,
+
+	/// Unit, shown after values
+	pub unit: &'a str,
+}
+
+impl LineChart<'_> {
+	/// Draws this chart, with a legend below it to toggle each series.
+	///
+	/// Hovering over the chart shows the exact values at that point.
+	pub fn show(self, ui: &mut egui::Ui, size: egui::Vec2) {
+		let mut hidden = ui.data(|data| data.get_temp::<HashSet<String>>(self.id).unwrap_or_default());
+
+		let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+		let step = rect.width() / self.len.max(1) as f32;
+		let visuals = &ui.visuals().widgets.noninteractive;
+		ui.painter().rect_stroke(rect, 0.0, visuals.bg_stroke);
+
+		for (series_idx, series) in self.series.iter().enumerate() {
+			if hidden.contains(series.name) {
+				continue;
+			}
+
+			// Note: We split the line on missing values, so we don't connect across gaps
+			let color = self.palette.color(series_idx);
+			let mut points = vec![];
+			for (value_idx, value) in series.values.iter().take(self.len).enumerate() {
+				match value {
+					Some(value) => {
+						let height = (value / self.max).clamp(0.0, 1.0);
+						points.push(egui::pos2(
+							rect.right() - value_idx as f32 * step,
+							rect.bottom() - height * rect.height(),
+						));
+					},
+					None => {
+						ui.painter()
+							.add(egui::Shape::line(mem::take(&mut points), (1.5, color)));
+					},
+				}
+			}
+			ui.painter().add(egui::Shape::line(points, (1.5, color)));
+		}
+
+		// Show the values at the hovered point
+		if let Some(hover_pos) = response.hover_pos() {
+			#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Positive and small
+			let value_idx = ((rect.right() - hover_pos.x) / step).round().max(0.0) as usize;
+			let x = rect.right() - value_idx as f32 * step;
+			ui.painter().vline(x, rect.y_range(), visuals.fg_stroke);
+
+			response.on_hover_ui_at_pointer(|ui| {
+				for (series_idx, series) in self.series.iter().enumerate() {
+					if hidden.contains(series.name) {
+						continue;
+					}
+
+					let value = series.values.get(value_idx).copied().flatten();
+					let text = match value {
+						Some(value) => format!("{}: {value:.2}{}", series.name, self.unit),
+						None => format!("{}: -", series.name),
+					};
+					ui.colored_label(self.palette.color(series_idx), text);
+				}
+			});
+		}
+
+		// Finally draw the legend
+		ui.horizontal(|ui| {
+			for (series_idx, series) in self.series.iter().enumerate() {
+				let is_hidden = hidden.contains(series.name);
+				let color = match is_hidden {
+					true => ui.visuals().weak_text_color(),
+					false => self.palette.color(series_idx),
+				};
+				let response = ui
+					.add(
+						egui::Label::new(egui::RichText::new(format!("■ {}", series.name)).color(color))
+							.sense(egui::Sense::click()),
+					)
+					.on_hover_text("Click to toggle");
+				if response.clicked() && !hidden.remove(series.name) {
+					hidden.insert(series.name.to_owned());
+				}
+			}
+		});
+
+		ui.data_mut(|data| data.insert_temp(self.id, hidden));
+	}
+}
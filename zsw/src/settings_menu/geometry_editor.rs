@@ -0,0 +1,112 @@
+//! Geometry editor
+
+// Imports
+use {egui::Widget, winit::dpi::PhysicalSize, zsw_util::Rect};
+
+/// Geometry editor.
+///
+/// Edits a copy of the geometry, which is only clamped and written
+/// back once all fields have been edited, so no field can go out of
+/// range due to another.
+#[derive(Debug)]
+pub struct GeometryEditor<'a> {
+	/// Geometry
+	geometry: &'a mut Rect<i32, u32>,
+
+	/// Size of the surface the geometry is relative to
+	surface_size: PhysicalSize<u32>,
+
+	/// Id source
+	id_source: egui::Id,
+}
+
+impl<'a> GeometryEditor<'a> {
+	/// Creates a new geometry editor
+	pub fn new(
+		geometry: &'a mut Rect<i32, u32>,
+		surface_size: PhysicalSize<u32>,
+		id_source: impl std::hash::Hash,
+	) -> Self {
+		Self {
+			geometry,
+			surface_size,
+			id_source: egui::Id::new(id_source),
+		}
+	}
+}
+
+impl egui::Widget for GeometryEditor<'_> {
+	fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+		// Note: The aspect ratio is stored while locked, else any rounding would make it drift
+		let aspect_ratio_id = ui.make_persistent_id(self.id_source);
+		let mut aspect_ratio = ui.data(|data| data.get_temp::<f64>(aspect_ratio_id));
+
+		// Note: We edit with wider integers so that nothing overflows before clamping
+		let mut size = self.geometry.size.map(i64::from);
+		let mut pos = self.geometry.pos.map(i64::from);
+
+		let response = ui
+			.horizontal(|ui| {
+				let width_response = self::drag_length(ui, &mut size[0], self.surface_size.width, "Width");
+				ui.label("x");
+				let height_response = self::drag_length(ui, &mut size[1], self.surface_size.height, "Height");
+				ui.label("+");
+				self::drag_length(ui, &mut pos[0], self.surface_size.width, "X position");
+				ui.label("+");
+				self::drag_length(ui, &mut pos[1], self.surface_size.height, "Y position");
+
+				let mut locked = aspect_ratio.is_some();
+				if ui
+					.checkbox(&mut locked, "🔒")
+					.on_hover_text("Lock aspect ratio")
+					.changed()
+				{
+					aspect_ratio =
+						locked.then(|| f64::from(self.geometry.size.x) / f64::from(self.geometry.size.y.max(1)));
+				}
+
+				if let Some(aspect_ratio) = aspect_ratio {
+					if width_response.changed() {
+						size[1] = (size[0] as f64 / aspect_ratio).round() as i64;
+					} else if height_response.changed() {
+						size[0] = (size[1] as f64 * aspect_ratio).round() as i64;
+					}
+				}
+			})
+			.response;
+
+		ui.data_mut(|data| match aspect_ratio {
+			Some(aspect_ratio) => data.insert_temp(aspect_ratio_id, aspect_ratio),
+			None => data.remove::<f64>(aspect_ratio_id),
+		});
+
+		*self.geometry = Rect::clamped(pos, size);
+		response
+	}
+}
+
+/// Draws a drag value for a length, in pixels.
+///
+/// Values may also be typed as a percentage of `surface_len`, such as `50%`.
+fn drag_length(ui: &mut egui::Ui, value: &mut i64, surface_len: u32, name: &str) -> egui::Response {
+	let response = egui::DragValue::new(value)
+		.speed(10)
+		.suffix(" px")
+		.custom_parser(|text| self::parse_length(text, surface_len))
+		.ui(ui);
+	super::set_accessible_name(ui, response.clone(), name);
+
+	response
+}
+
+/// Parses a length, either in pixels or as a percentage of `surface_len`
+fn parse_length(text: &str, surface_len: u32) -> Option<f64> {
+	let text = text.trim();
+	match text.strip_suffix('%') {
+		Some(percentage) => {
+			let percentage = percentage.trim().parse::<f64>().ok()?;
+			Some((percentage / 100.0 * f64::from(surface_len)).round())
+		},
+		None => text.strip_suffix("px").unwrap_or(text).trim().parse::<f64>().ok(),
+	}
+}
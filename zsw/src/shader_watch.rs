@@ -0,0 +1,158 @@
+//! Shader watching
+//!
+//! Reloads all shaders whenever any of them change on disk, so shaders
+//! may be edited without restarting.
+//!
+//! When running in shader watch mode, a screenshot is also saved after
+//! each reload, so that changes may be compared.
+
+// Imports
+use {
+	crate::{frame_capture::FrameCapture, panel::PanelsRendererShader},
+	anyhow::Context,
+	notify::{
+		event::{AccessKind, AccessMode},
+		EventKind,
+		RecursiveMode,
+		Watcher,
+	},
+	std::{
+		path::{Path, PathBuf},
+		time::Duration,
+	},
+	tokio::sync::{mpsc, RwLock},
+	zsw_error::AppError,
+	zsw_wgpu::{FrameRender, WgpuShared},
+};
+
+/// Duration to wait for more changes before reloading.
+///
+/// Editors usually write several times when saving (e.g. to a temporary
+/// file, then renaming it), so we only reload once they're done.
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(100);
+
+/// Watches `shaders_dir`, reloading all shaders whenever any of them change
+pub async fn watch_shaders(shaders_dir: &Path, shader: &RwLock<PanelsRendererShader>) -> Result<(), AppError> {
+	let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+	let mut watcher = notify::recommended_watcher(move |event| {
+		// Note: If the receiver was dropped, we're no longer being watched, so we can ignore it
+		let _ = event_tx.send(event);
+	})
+	.context("Unable to create watcher")?;
+	watcher
+		.watch(shaders_dir, RecursiveMode::Recursive)
+		.context("Unable to watch shaders directory")?;
+
+	while let Some(event) = event_rx.recv().await {
+		if !self::is_shader_change(event) {
+			continue;
+		}
+
+		// Wait until no more changes happen before reloading
+		tokio::time::sleep(DEBOUNCE_DURATION).await;
+		while event_rx.try_recv().is_ok() {}
+
+		let mut shader = shader.write().await;
+		shader.generation += 1;
+		tracing::info!(generation = shader.generation, "Reloading shaders");
+	}
+
+	Ok(())
+}
+
+/// Returns if `event` changed any shader
+fn is_shader_change(event: notify::Result<notify::Event>) -> bool {
+	let event = match event {
+		Ok(event) => event,
+		Err(err) => {
+			tracing::warn!(?err, "Unable to watch shaders");
+			return false;
+		},
+	};
+	tracing::trace!(?event, "Received shader watcher event");
+
+	let is_change = matches!(
+		event.kind,
+		EventKind::Create(_) | EventKind::Modify(_) | EventKind::Access(AccessKind::Close(AccessMode::Write))
+	);
+	is_change &&
+		event
+			.paths
+			.iter()
+			.any(|path| path.extension().is_some_and(|ext| ext == "wgsl"))
+}
+
+/// Shader screenshotter.
+///
+/// Saves a numbered screenshot whenever the shaders are reloaded.
+#[derive(Debug)]
+pub struct ShaderScreenshotter {
+	/// Directory to save screenshots to
+	output_dir: PathBuf,
+
+	/// Index of the next screenshot
+	next_idx: usize,
+
+	/// Shader generation of the last screenshot
+	generation: u64,
+
+	/// Capture
+	capture: FrameCapture,
+}
+
+impl ShaderScreenshotter {
+	/// Creates a new screenshotter, saving to `output_dir`.
+	///
+	/// Screenshots are numbered after any existing ones, so previous runs aren't overwritten.
+	pub fn new(output_dir: PathBuf) -> Result<Self, AppError> {
+		std::fs::create_dir_all(&output_dir).context("Unable to create screenshots directory")?;
+		let mut next_idx = 0;
+		while self::screenshot_path(&output_dir, next_idx).exists() {
+			next_idx += 1;
+		}
+
+		Ok(Self {
+			output_dir,
+			next_idx,
+			generation: 0,
+			capture: FrameCapture::new(),
+		})
+	}
+
+	/// Records a screenshot of `frame`, if the shaders were reloaded since the last one.
+	///
+	/// Must be called after everything has been rendered onto `frame`, and before it's finished.
+	pub fn record(&mut self, wgpu_shared: &WgpuShared, frame: &mut FrameRender, shader_generation: u64) {
+		// Note: If we're still capturing the previous screenshot, we'll try again next frame
+		if self.generation == shader_generation || self.capture.is_pending() {
+			return;
+		}
+
+		match self.capture.record(wgpu_shared, frame) {
+			Ok(()) => self.generation = shader_generation,
+			Err(err) => tracing::warn!(?err, "Unable to capture shader screenshot"),
+		}
+	}
+
+	/// Polls the pending screenshot, if any.
+	///
+	/// Must be called after the frame passed to [`Self::record`] has been finished.
+	pub fn poll(&mut self, wgpu_shared: &WgpuShared) {
+		let Some(frame) = self.capture.poll(wgpu_shared) else {
+			return;
+		};
+
+		let path = self::screenshot_path(&self.output_dir, self.next_idx);
+		self.next_idx += 1;
+		#[expect(clippy::let_underscore_future)] // We don't need to wait for it to be saved
+		let _ = tokio::task::spawn_blocking(move || match frame.into_rgba().save(&path) {
+			Ok(()) => tracing::info!(?path, "Saved shader screenshot"),
+			Err(err) => tracing::warn!(?path, ?err, "Unable to save shader screenshot"),
+		});
+	}
+}
+
+/// Returns the path of the screenshot at `idx`
+fn screenshot_path(output_dir: &Path, idx: usize) -> PathBuf {
+	output_dir.join(format!("{idx:04}.png"))
+}
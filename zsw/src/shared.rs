@@ -3,16 +3,34 @@
 // Imports
 use {
 	crate::{
+		blank::Blank,
+		bundle::Bundles,
+		burn_in::BurnInProtection,
 		image_loader::ImageRequester,
-		panel::{Panel, PanelsManager, PanelsRendererLayouts, PanelsRendererShader},
+		metrics::{FrameTimes, MemoryMetrics},
+		night_light::NightLight,
+		panel::{Panel, PanelCommands, PanelsManager, PanelsRendererLayouts, PanelsRendererShader, RgbConverter},
 		playlist::Playlists,
+		privacy::Privacy,
+		ratings::{RatingSelection, Ratings},
+		sandbox::Sandbox,
+		sound::TransitionSound,
+		timelapse::Timelapse,
+		window::Monitor,
 		Resize,
 	},
+	cgmath::Point2,
 	crossbeam::atomic::AtomicCell,
+	std::sync::Arc,
 	tokio::sync::{Mutex, RwLock},
-	winit::dpi::PhysicalPosition,
+	winit::dpi::{PhysicalPosition, PhysicalSize},
 	zsw_wgpu::WgpuShared,
 };
+#[cfg(feature = "menu")]
+use {
+	crate::{snapshots::Snapshots, wallpaper_export::WallpaperExportBackend},
+	std::path::PathBuf,
+};
 
 /// Shared data
 #[derive(Debug)]
@@ -20,13 +38,102 @@ pub struct Shared {
 	pub window:                 &'static winit::window::Window,
 	pub wgpu:                   WgpuShared,
 	pub panels_renderer_layout: PanelsRendererLayouts,
+	pub rgb_converter:          RgbConverter,
 	pub last_resize:            AtomicCell<Option<Resize>>,
 	pub cursor_pos:             AtomicCell<PhysicalPosition<f64>>,
 
+	/// Whether the window is fully occluded
+	pub occluded: AtomicCell<bool>,
+
+	/// Whether the window is minimized (i.e. has no size)
+	pub minimized: AtomicCell<bool>,
+
+	/// Whether the monitors are asleep
+	pub monitors_asleep: AtomicCell<bool>,
+
+	/// If previewing, the size of the desktop being previewed
+	pub preview_desktop_size: Option<PhysicalSize<u32>>,
+
+	/// All monitors
+	pub monitors: Vec<Monitor>,
+
 	pub panels_manager:  PanelsManager,
 	pub image_requester: ImageRequester,
+	pub bundles:         Arc<Bundles>,
+
+	pub ratings:          Arc<Ratings>,
+	pub rating_selection: Option<RatingSelection>,
+
+	/// Snapshots of all panels
+	#[cfg(feature = "menu")]
+	pub snapshots: Snapshots,
+
+	/// Sandbox
+	pub sandbox: Sandbox,
+
+	#[cfg(feature = "menu")]
+	pub wallpaper_export_backend: WallpaperExportBackend,
+	pub burn_in_protection:       Option<BurnInProtection>,
+	pub night_light:              Option<NightLight>,
+
+	/// Blank
+	pub blank: Blank,
+
+	/// Privacy mode, if configured
+	pub privacy: Option<Privacy>,
+
+	/// Timelapse, if configured
+	pub timelapse: Option<Timelapse>,
+
+	/// Reverse image search url template, if configured
+	#[cfg(feature = "menu")]
+	pub reverse_image_search_url: Option<String>,
+
+	/// Transition sound, if any
+	pub transition_sound: Option<TransitionSound>,
+
+	pub frame_times:    std::sync::Mutex<FrameTimes>,
+	pub memory_metrics: AtomicCell<MemoryMetrics>,
+
+	/// Egui textures of all panel thumbnails
+	#[cfg(feature = "menu")]
+	pub panel_thumbnails: std::sync::Mutex<crate::panel::PanelThumbnails>,
 
 	pub cur_panels:             Mutex<Vec<Panel>>,
 	pub panels_renderer_shader: RwLock<PanelsRendererShader>,
 	pub playlists:              RwLock<Playlists>,
+
+	/// Commands queued for the panels updater
+	pub panel_commands: PanelCommands,
+
+	/// Config path
+	#[cfg(feature = "menu")]
+	pub config_path: PathBuf,
+
+	/// Log file, if any
+	#[cfg(feature = "menu")]
+	pub log_file: Option<PathBuf>,
+
+	/// Progress of loading the default panels, as `(loaded, total)`, while loading
+	pub panels_loading: AtomicCell<Option<(usize, usize)>>,
+}
+
+impl Shared {
+	/// Returns the size panel geometries are relative to, given the current surface size.
+	///
+	/// When previewing, this is the desktop's size, else it's just the surface size.
+	pub fn panels_surface_size(&self, surface_size: PhysicalSize<u32>) -> PhysicalSize<u32> {
+		self.preview_desktop_size.unwrap_or(surface_size)
+	}
+
+	/// Returns the cursor position, relative to the panel geometries
+	pub fn panels_cursor_pos(&self) -> Point2<i32> {
+		let cursor_pos = self.cursor_pos.load();
+		let window_size = self.window.inner_size();
+		let surface_size = self.panels_surface_size(window_size);
+		let x_scale = f64::from(surface_size.width) / f64::from(window_size.width);
+		let y_scale = f64::from(surface_size.height) / f64::from(window_size.height);
+
+		Point2::new((cursor_pos.x * x_scale) as i32, (cursor_pos.y * y_scale) as i32)
+	}
 }
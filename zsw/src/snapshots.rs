@@ -0,0 +1,102 @@
+//! Snapshots
+//!
+//! Snapshots capture all current panels, exactly as they're currently
+//! configured, so they may be quickly restored later.
+
+// Imports
+use {
+	crate::{
+		panel::{ser, Panel, PanelsManager},
+		shared::Shared,
+	},
+	anyhow::Context,
+	std::{fs, path::PathBuf, sync::Arc},
+	zsw_error::AppError,
+};
+
+/// Snapshots
+#[derive(Debug)]
+pub struct Snapshots {
+	/// Directory the snapshots are stored in
+	dir: PathBuf,
+}
+
+impl Snapshots {
+	/// Creates the snapshots, stored within `dir`
+	pub fn new(dir: PathBuf) -> Self {
+		Self { dir }
+	}
+
+	/// Returns the names of all snapshots, sorted
+	pub fn names(&self) -> Result<Vec<String>, AppError> {
+		// Note: The directory is only created once the first snapshot is saved
+		if !self.dir.exists() {
+			return Ok(vec![]);
+		}
+
+		let mut names = vec![];
+		for entry in fs::read_dir(&self.dir).context("Unable to read snapshots directory")? {
+			let entry = entry.context("Unable to read snapshots directory entry")?;
+			let path = entry.path();
+			if path.extension().is_some_and(|extension| extension == "toml") {
+				if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+					names.push(name.to_owned());
+				}
+			}
+		}
+		names.sort();
+
+		Ok(names)
+	}
+
+	/// Saves `panels` as the snapshot `name`, replacing it if it exists.
+	///
+	/// Panels showing a path can't be saved, since their playlist is transient.
+	pub fn save(&self, name: &str, panels: &[Panel]) -> Result<(), AppError> {
+		let path = self.path(name)?;
+		if panels.iter().any(|panel| panel.base_playlist().0.is_show()) {
+			return Err(anyhow::anyhow!("Snapshots can't include panels showing a path").into());
+		}
+		let snapshot = ser::Snapshot {
+			panels: panels.iter().map(PanelsManager::serialize).collect(),
+		};
+
+		let snapshot_toml = toml::to_string(&snapshot).context("Unable to serialize snapshot")?;
+		fs::create_dir_all(&self.dir).context("Unable to create snapshots directory")?;
+		fs::write(&path, snapshot_toml).context("Unable to write snapshot")?;
+
+		Ok(())
+	}
+
+	/// Loads all panels of the snapshot `name`
+	pub fn load(&self, name: &str, shared: &Arc<Shared>) -> Result<Vec<Panel>, AppError> {
+		let path = self.path(name)?;
+		tracing::debug!(?path, "Loading snapshot");
+		let snapshot_toml = fs::read_to_string(&path).context("Unable to read snapshot")?;
+		let snapshot = toml::from_str::<ser::Snapshot>(&snapshot_toml).context("Unable to parse snapshot")?;
+
+		snapshot
+			.panels
+			.into_iter()
+			.map(|panel| PanelsManager::create(panel, shared))
+			.collect::<Result<_, _>>()
+	}
+
+	/// Removes the snapshot `name`
+	pub fn remove(&self, name: &str) -> Result<(), AppError> {
+		let path = self.path(name)?;
+		fs::remove_file(path).context("Unable to remove snapshot")?;
+
+		Ok(())
+	}
+
+	/// Returns the path of the snapshot `name`
+	fn path(&self, name: &str) -> Result<PathBuf, AppError> {
+		// Note: Names are used as file names, so they can't escape the snapshots directory
+		if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+			return Err(anyhow::anyhow!("Invalid snapshot name: {name:?}").into());
+		}
+
+		Ok(self.dir.join(format!("{name}.toml")))
+	}
+}
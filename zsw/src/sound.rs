@@ -0,0 +1,107 @@
+//! Sound
+//!
+//! Plays a sound whenever a panel starts transitioning into it's next image.
+//!
+//! Playback requires the `sound` feature, else the sound is never played.
+
+// Imports
+use {
+	crate::config::TransitionSoundConfig,
+	anyhow::Context,
+	std::{
+		fs,
+		sync::{mpsc, Mutex, PoisonError},
+		thread,
+		time::{Duration, Instant},
+	},
+	zsw_error::AppError,
+};
+
+/// Transition sound
+#[derive(Debug)]
+pub struct TransitionSound {
+	/// Play request sender
+	play_tx: mpsc::Sender<()>,
+
+	/// Minimum interval between each time the sound is played
+	min_interval: Duration,
+
+	/// Last time the sound was played
+	last_played: Mutex<Option<Instant>>,
+}
+
+impl TransitionSound {
+	/// Creates a new transition sound.
+	///
+	/// Spawns a thread for playing the sound.
+	pub fn new(config: &TransitionSoundConfig) -> Result<Self, AppError> {
+		let sound = fs::read(&config.path).context("Unable to read sound file")?;
+		let volume = config.volume();
+
+		// Note: Audio output streams can't be sent across threads, so we keep it on it's own thread
+		let (play_tx, play_rx) = mpsc::channel();
+		let _ = thread::Builder::new()
+			.name("Transition sound".to_owned())
+			.spawn(move || self::run_player(sound, volume, &play_rx))
+			.context("Unable to spawn sound thread")?;
+
+		Ok(Self {
+			play_tx,
+			min_interval: config.min_interval(),
+			last_played: Mutex::new(None),
+		})
+	}
+
+	/// Plays the sound, unless it was played too recently.
+	///
+	/// This ensures multiple panels transitioning at once only play the sound once.
+	pub fn play(&self) {
+		let now = Instant::now();
+		let mut last_played = self.last_played.lock().unwrap_or_else(PoisonError::into_inner);
+		if last_played.is_some_and(|last_played| now.duration_since(last_played) < self.min_interval) {
+			return;
+		}
+		*last_played = Some(now);
+
+		// Note: If the player quit, it already logged why
+		let _ = self.play_tx.send(());
+	}
+}
+
+/// Runs the player, playing the sound for each request
+#[cfg(feature = "sound")]
+fn run_player(sound: Vec<u8>, volume: f32, play_rx: &mpsc::Receiver<()>) {
+	use {
+		rodio::Source,
+		std::{io::Cursor, sync::Arc},
+	};
+
+	let (_stream, stream_handle) = match rodio::OutputStream::try_default() {
+		Ok(stream) => stream,
+		Err(err) => {
+			tracing::warn!(?err, "Unable to open audio output, disabling transition sound");
+			return;
+		},
+	};
+
+	let sound = Arc::<[u8]>::from(sound);
+	for () in play_rx {
+		let source = match rodio::Decoder::new(Cursor::new(Arc::clone(&sound))) {
+			Ok(source) => source,
+			Err(err) => {
+				tracing::warn!(?err, "Unable to decode transition sound, disabling it");
+				return;
+			},
+		};
+
+		if let Err(err) = stream_handle.play_raw(source.convert_samples().amplify(volume)) {
+			tracing::warn!(?err, "Unable to play transition sound");
+		}
+	}
+}
+
+/// Runs the player, playing the sound for each request
+#[cfg(not(feature = "sound"))]
+fn run_player(_sound: Vec<u8>, _volume: f32, _play_rx: &mpsc::Receiver<()>) {
+	tracing::warn!("Transition sound requires the `sound` feature, disabling it");
+}
@@ -0,0 +1,106 @@
+//! Status
+//!
+//! Periodically writes the state of all panels to a json file, so
+//! external widgets may display it (for example, as a countdown to
+//! the next image).
+
+// Imports
+use {
+	crate::{panel::PANEL_FRAME_DURATION, shared::Shared},
+	anyhow::Context,
+	std::{
+		fs,
+		path::{Path, PathBuf},
+		sync::Arc,
+		time::{Duration, SystemTime},
+	},
+	zsw_error::AppError,
+};
+
+/// Interval between writes
+const WRITE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Writes the status to `path` periodically
+#[expect(clippy::infinite_loop, reason = "We need this type signature for `spawn_task`")]
+pub async fn run_writer(shared: Arc<Shared>, path: PathBuf) -> Result<!, AppError> {
+	loop {
+		let status = self::status(&shared).await;
+		if let Err(err) = self::write(&path, &status) {
+			tracing::warn!(?path, ?err, "Unable to write status");
+		}
+
+		tokio::time::sleep(WRITE_INTERVAL).await;
+	}
+}
+
+/// Returns the current status
+async fn status(shared: &Shared) -> ser::Status {
+	let now = SystemTime::now();
+	let cur_panels = shared.cur_panels.lock().await;
+	let panels = cur_panels
+		.iter()
+		.map(|panel| ser::Panel {
+			playlist:       panel.playlist_name.to_string(),
+			image:          panel.images.cur().path().map(Path::to_path_buf),
+			paused:         panel.state.paused,
+			quiet:          panel.state.quiet,
+			elapsed_ms:     Some(self::millis(panel.state.fade.elapsed(PANEL_FRAME_DURATION))),
+			remaining_ms:   Some(self::millis(panel.state.fade.remaining(PANEL_FRAME_DURATION))),
+			next_switch_ms: panel
+				.state
+				.next_switch(now, PANEL_FRAME_DURATION)
+				.and_then(self::unix_millis),
+		})
+		.collect();
+
+	ser::Status {
+		timestamp_ms: self::unix_millis(now),
+		panels,
+	}
+}
+
+/// Writes `status` to `path`
+fn write(path: &Path, status: &ser::Status) -> Result<(), AppError> {
+	// Note: We write to a temporary file first, so readers never see a partially written status
+	let tmp_path = path.with_extension("tmp");
+	zsw_util::serialize_json_to_file(&tmp_path, status).context("Unable to write status")?;
+	fs::rename(&tmp_path, path).context("Unable to replace previous status")?;
+
+	Ok(())
+}
+
+/// Returns a duration in milliseconds
+fn millis(duration: Duration) -> f64 {
+	duration.as_secs_f64() * 1000.0
+}
+
+/// Returns the milliseconds since the unix epoch of `time`, if after it
+fn unix_millis(time: SystemTime) -> Option<f64> {
+	time.duration_since(SystemTime::UNIX_EPOCH).ok().map(self::millis)
+}
+
+/// Serialized types
+mod ser {
+	use std::path::PathBuf;
+
+	/// Serialized status
+	#[derive(Debug)]
+	#[derive(serde::Serialize)]
+	pub struct Status {
+		pub timestamp_ms: Option<f64>,
+		pub panels:       Vec<Panel>,
+	}
+
+	/// Serialized panel status
+	#[derive(Debug)]
+	#[derive(serde::Serialize)]
+	pub struct Panel {
+		pub playlist:       String,
+		pub image:          Option<PathBuf>,
+		pub paused:         bool,
+		pub quiet:          bool,
+		pub elapsed_ms:     Option<f64>,
+		pub remaining_ms:   Option<f64>,
+		pub next_switch_ms: Option<f64>,
+	}
+}
@@ -0,0 +1,219 @@
+//! Tasks
+//!
+//! Keeps track of all tasks spawned through [`crate::spawn_task`], so they
+//! may be inspected from the settings menu.
+
+// Imports
+#[cfg(feature = "menu")]
+use std::time::Duration;
+use std::{
+	collections::BTreeMap,
+	sync::{
+		atomic::{self, AtomicUsize},
+		Mutex,
+		PoisonError,
+	},
+	time::Instant,
+};
+
+/// All tasks
+pub static TASKS: Tasks = Tasks::new();
+
+/// Tasks
+#[derive(Debug)]
+pub struct Tasks {
+	/// All tasks, by id
+	tasks: Mutex<BTreeMap<usize, TaskInfo>>,
+
+	/// Next task id
+	next_id: AtomicUsize,
+}
+
+impl Tasks {
+	/// Maximum number of finished tasks to keep
+	const MAX_FINISHED: usize = 32;
+
+	/// Creates new, empty, tasks
+	const fn new() -> Self {
+		Self {
+			tasks:   Mutex::new(BTreeMap::new()),
+			next_id: AtomicUsize::new(0),
+		}
+	}
+
+	/// Registers a new running task.
+	///
+	/// If the returned guard is dropped before finishing, the task is marked as cancelled.
+	pub fn register(&'static self, name: String) -> TaskGuard {
+		let id = self.next_id.fetch_add(1, atomic::Ordering::Relaxed);
+		let task = TaskInfo {
+			name,
+			status: TaskStatus::Running,
+			started: Instant::now(),
+			finished: None,
+		};
+		let _ = self
+			.tasks
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(id, task);
+
+		TaskGuard {
+			tasks: self,
+			id,
+			status: None,
+		}
+	}
+
+	/// Finishes task `id` with `status`
+	fn finish(&self, id: usize, status: TaskStatus) {
+		let mut tasks = self.tasks.lock().unwrap_or_else(PoisonError::into_inner);
+		if let Some(task) = tasks.get_mut(&id) {
+			task.status = status;
+			task.finished = Some(Instant::now());
+		}
+
+		// Note: We only keep the latest finished tasks, else we'd keep every task ever spawned.
+		let finished_ids = tasks
+			.iter()
+			.filter(|(_, task)| task.finished.is_some())
+			.map(|(&id, _)| id)
+			.collect::<Vec<_>>();
+		for id in finished_ids
+			.iter()
+			.take(finished_ids.len().saturating_sub(Self::MAX_FINISHED))
+		{
+			let _ = tasks.remove(id);
+		}
+	}
+
+	/// Returns all tasks, ordered from oldest to newest
+	#[cfg(any(feature = "menu", test))]
+	pub fn all(&self) -> Vec<TaskInfo> {
+		let tasks = self.tasks.lock().unwrap_or_else(PoisonError::into_inner);
+		tasks.values().cloned().collect()
+	}
+}
+
+/// Task guard
+#[derive(Debug)]
+pub struct TaskGuard {
+	/// Tasks
+	tasks: &'static Tasks,
+
+	/// Task id
+	id: usize,
+
+	/// Status to finish with
+	status: Option<TaskStatus>,
+}
+
+impl TaskGuard {
+	/// Finishes the task with `status`
+	pub fn finish(mut self, status: TaskStatus) {
+		self.status = Some(status);
+	}
+}
+
+impl Drop for TaskGuard {
+	fn drop(&mut self) {
+		let status = self.status.take().unwrap_or(TaskStatus::Cancelled);
+		self.tasks.finish(self.id, status);
+	}
+}
+
+/// Task info
+#[derive(Clone, Debug)]
+pub struct TaskInfo {
+	/// Name
+	#[cfg_attr(not(any(feature = "menu", test)), expect(dead_code))] // Only read by the settings menu and bug reports
+	pub name: String,
+
+	/// Status
+	pub status: TaskStatus,
+
+	/// When the task started
+	#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only read by the settings menu
+	pub started: Instant,
+
+	/// When the task finished, if it did
+	pub finished: Option<Instant>,
+}
+
+impl TaskInfo {
+	/// Returns how long the task has been running for, or ran for, if finished
+	#[cfg(feature = "menu")]
+	pub fn runtime(&self) -> Duration {
+		self.finished.unwrap_or_else(Instant::now).duration_since(self.started)
+	}
+}
+
+/// Task status
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum TaskStatus {
+	/// Running
+	Running,
+
+	/// Finished successfully
+	Finished,
+
+	/// Returned an error
+	Failed { err: String },
+
+	/// Dropped before finishing (e.g. panicked or aborted)
+	Cancelled,
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn finish() {
+		static TASKS: Tasks = Tasks::new();
+
+		let running = TASKS.register("running".to_owned());
+		TASKS.register("finished".to_owned()).finish(TaskStatus::Finished);
+		drop(TASKS.register("cancelled".to_owned()));
+
+		let statuses = TASKS
+			.all()
+			.into_iter()
+			.map(|task| (task.name, task.status))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			statuses,
+			[
+				("running".to_owned(), TaskStatus::Running),
+				("finished".to_owned(), TaskStatus::Finished),
+				("cancelled".to_owned(), TaskStatus::Cancelled),
+			],
+			"Task statuses were wrong"
+		);
+
+		drop(running);
+	}
+
+	#[test]
+	fn forgets_finished() {
+		static TASKS: Tasks = Tasks::new();
+
+		let _running = TASKS.register("running".to_owned());
+		for idx in 0..2 * Tasks::MAX_FINISHED {
+			TASKS.register(format!("finished{idx}")).finish(TaskStatus::Finished);
+		}
+
+		let tasks = TASKS.all();
+		assert_eq!(
+			tasks.len(),
+			1 + Tasks::MAX_FINISHED,
+			"Old finished tasks weren't forgotten"
+		);
+		assert_eq!(tasks[0].name, "running", "Running task was forgotten");
+		assert_eq!(
+			tasks[1].name,
+			format!("finished{}", Tasks::MAX_FINISHED),
+			"Newest finished tasks weren't kept"
+		);
+	}
+}
@@ -0,0 +1,240 @@
+//! Timelapse recording
+//!
+//! Captures the rendered frames at a low rate while recording, and encodes
+//! them into a timelapse video by running `ffmpeg`, which must be installed.
+//!
+//! Each recording is saved to a new file within the output directory.
+
+// Imports
+use {
+	crate::{
+		config::TimelapseConfig,
+		frame_capture::{CapturedFrame, CapturedFrameFormat, FrameCapture},
+	},
+	anyhow::Context,
+	crossbeam::atomic::AtomicCell,
+	std::{
+		io::Write,
+		path::{Path, PathBuf},
+		process::{Command, Stdio},
+		sync::mpsc,
+		time::{Duration, Instant},
+	},
+	winit::dpi::PhysicalSize,
+	zsw_error::AppError,
+	zsw_wgpu::{FrameRender, WgpuShared},
+};
+
+/// Timelapse.
+///
+/// Controls whether a timelapse is being recorded.
+#[derive(Debug)]
+pub struct Timelapse {
+	/// Whether we're recording
+	recording: AtomicCell<bool>,
+}
+
+impl Timelapse {
+	/// Creates a new timelapse, not recording
+	pub fn new() -> Self {
+		Self {
+			recording: AtomicCell::new(false),
+		}
+	}
+
+	/// Returns whether we're recording
+	pub fn is_recording(&self) -> bool {
+		self.recording.load()
+	}
+
+	/// Sets whether we're recording
+	pub fn set_recording(&self, recording: bool) {
+		self.recording.store(recording);
+	}
+}
+
+/// Timelapse recorder
+#[derive(Debug)]
+pub struct TimelapseRecorder {
+	/// Directory to save the timelapses to
+	output_dir: PathBuf,
+
+	/// Interval between each captured frame
+	interval: Duration,
+
+	/// Frames per second of the timelapse video
+	fps: f32,
+
+	/// Last capture
+	last_capture: Option<Instant>,
+
+	/// Capture
+	capture: FrameCapture,
+
+	/// Encoder, if recording
+	encoder: Option<TimelapseEncoder>,
+}
+
+impl TimelapseRecorder {
+	/// Creates a new timelapse recorder
+	pub fn new(config: &TimelapseConfig) -> Self {
+		Self {
+			output_dir:   config.output_dir.clone(),
+			interval:     config.interval(),
+			fps:          config.fps(),
+			last_capture: None,
+			capture:      FrameCapture::new(),
+			encoder:      None,
+		}
+	}
+
+	/// Records a copy of `frame`, if recording and it's time to capture a new frame.
+	///
+	/// Must be called after everything has been rendered onto `frame`, and before it's finished.
+	pub fn record(&mut self, timelapse: &Timelapse, wgpu_shared: &WgpuShared, frame: &mut FrameRender) {
+		// If we stopped recording, finish the video
+		// Note: The encoder finishes in the background once dropped
+		if !timelapse.is_recording() {
+			if self.encoder.take().is_some() {
+				tracing::info!("Stopped recording timelapse");
+			}
+			self.last_capture = None;
+			return;
+		}
+
+		if self.capture.is_pending() ||
+			self.last_capture
+				.is_some_and(|last_capture| last_capture.elapsed() < self.interval)
+		{
+			return;
+		}
+
+		if let Err(err) = self.capture.record(wgpu_shared, frame) {
+			tracing::warn!(?err, "Unable to capture frame, stopping timelapse");
+			timelapse.set_recording(false);
+			return;
+		}
+		self.last_capture = Some(Instant::now());
+	}
+
+	/// Polls the pending capture, if any, and sends it to the encoder.
+	///
+	/// Must be called after the frame passed to [`Self::record`] has been finished.
+	pub fn poll(&mut self, timelapse: &Timelapse, wgpu_shared: &WgpuShared) {
+		let Some(frame) = self.capture.poll(wgpu_shared) else {
+			return;
+		};
+		if !timelapse.is_recording() {
+			return;
+		}
+
+		// Start encoding, if this is the first frame
+		let encoder = match &mut self.encoder {
+			Some(encoder) => encoder,
+			None => match TimelapseEncoder::start(&self.output_dir, self.fps, frame.size, frame.format) {
+				Ok(encoder) => self.encoder.insert(encoder),
+				Err(err) => {
+					tracing::warn!(?err, "Unable to start timelapse encoder, stopping timelapse");
+					timelapse.set_recording(false);
+					return;
+				},
+			},
+		};
+
+		// Note: The encoder only accepts frames of it's initial size, so
+		//       we skip any while the window is resized.
+		if frame.size != encoder.size {
+			tracing::debug!(size = ?frame.size, encoder_size = ?encoder.size, "Skipping resized timelapse frame");
+			return;
+		}
+
+		if encoder.frame_tx.send(frame).is_err() {
+			tracing::warn!("Timelapse encoder quit, stopping timelapse");
+			self.encoder = None;
+			timelapse.set_recording(false);
+		}
+	}
+}
+
+/// Timelapse encoder.
+///
+/// Sends frames to `ffmpeg` on a separate thread, which finishes the video
+/// once this is dropped.
+#[derive(Debug)]
+struct TimelapseEncoder {
+	/// Frame size
+	size: PhysicalSize<u32>,
+
+	/// Frame sender
+	frame_tx: mpsc::Sender<CapturedFrame>,
+}
+
+impl TimelapseEncoder {
+	/// Starts a new timelapse video within `output_dir`
+	fn start(
+		output_dir: &Path,
+		fps: f32,
+		size: PhysicalSize<u32>,
+		format: CapturedFrameFormat,
+	) -> Result<Self, AppError> {
+		std::fs::create_dir_all(output_dir).context("Unable to create timelapse directory")?;
+		let path = output_dir.join(format!(
+			"zsw-timelapse-{}.mp4",
+			chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+		));
+
+		let pixel_format = match format {
+			CapturedFrameFormat::Rgba => "rgba",
+			CapturedFrameFormat::Bgra => "bgra",
+		};
+		// Note: `yuv420p` requires even dimensions, so we crop the last row / column, if necessary
+		let mut encoder = Command::new("ffmpeg")
+			.args([
+				"-nostdin",
+				"-loglevel",
+				"error",
+				"-y",
+				"-f",
+				"rawvideo",
+				"-pix_fmt",
+				pixel_format,
+			])
+			.arg("-s")
+			.arg(format!("{}x{}", size.width, size.height))
+			.arg("-r")
+			.arg(fps.to_string())
+			.args(["-i", "pipe:0", "-vf", "crop=trunc(iw/2)*2:trunc(ih/2)*2"])
+			.args(["-c:v", "libx264", "-pix_fmt", "yuv420p"])
+			.arg(&path)
+			.stdin(Stdio::piped())
+			.spawn()
+			.context("Unable to run `ffmpeg`")?;
+		let mut stdin = encoder.stdin.take().expect("Encoder stdin should be piped");
+
+		let (frame_tx, frame_rx) = mpsc::channel::<CapturedFrame>();
+		let res = std::thread::Builder::new()
+			.name("timelapse-encoder".to_owned())
+			.spawn(move || {
+				tracing::info!(?path, "Recording timelapse");
+				let mut frames = 0_usize;
+				for frame in frame_rx {
+					if let Err(err) = stdin.write_all(&frame.pixels) {
+						tracing::warn!(?path, ?err, "Unable to write timelapse frame");
+						break;
+					}
+					frames += 1;
+				}
+
+				// Note: Closing stdin lets the encoder finish the video
+				drop(stdin);
+				match encoder.wait() {
+					Ok(status) if status.success() => tracing::info!(?path, ?frames, "Saved timelapse"),
+					Ok(status) => tracing::warn!(?path, ?status, "Timelapse encoder failed"),
+					Err(err) => tracing::warn!(?path, ?err, "Unable to wait for timelapse encoder"),
+				}
+			});
+		let _ = res.context("Unable to spawn timelapse encoder thread")?;
+
+		Ok(Self { size, frame_tx })
+	}
+}
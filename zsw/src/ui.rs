@@ -0,0 +1,18 @@
+//! User interface
+//!
+//! With the `menu` feature, the settings menu is drawn with egui over the panels,
+//! and mouse / keyboard input is used to control them.
+//!
+//! Without it, nothing is drawn over the panels and all input is ignored.
+
+// Modules
+#[cfg(not(feature = "menu"))]
+mod headless;
+#[cfg(feature = "menu")]
+mod menu;
+
+// Exports
+#[cfg(not(feature = "menu"))]
+pub use headless::{create, UiRenderer};
+#[cfg(feature = "menu")]
+pub use menu::{create, UiRenderer};
@@ -0,0 +1,67 @@
+//! Headless user interface
+
+// Imports
+use {
+	crate::{config::Config, panel::Panel, shared::Shared},
+	std::sync::Arc,
+	winit::{event::WindowEvent, window::Window},
+	zsw_error::AppError,
+	zsw_wgpu::{FrameRender, WgpuRenderer, WgpuShared},
+};
+
+/// Ui renderer
+#[derive(Debug)]
+pub struct UiRenderer(());
+
+#[expect(clippy::unused_self, clippy::needless_pass_by_ref_mut)] // We keep the same api as the menu
+impl UiRenderer {
+	/// Receives the latest output of the painter, if any
+	pub fn update(&mut self) {}
+
+	/// Updates the thumbnails of `panels`
+	pub fn update_thumbnails(&mut self, _shared: &Shared, _panels: &[Panel]) -> Result<(), AppError> {
+		Ok(())
+	}
+
+	/// Renders the ui onto `frame`
+	pub fn render(&mut self, _frame: &mut FrameRender, _shared: &Shared) -> Result<(), AppError> {
+		Ok(())
+	}
+
+	/// Returns if the ui is drawing anything
+	pub fn is_drawing(&self) -> bool {
+		false
+	}
+}
+
+/// Ui painter
+#[derive(Debug)]
+pub struct UiPainter(());
+
+impl UiPainter {
+	/// Runs the painter.
+	///
+	/// Returns immediately, since there's nothing to paint.
+	pub async fn run(self, _shared: Arc<Shared>) -> Result<(), AppError> {
+		Ok(())
+	}
+}
+
+/// Ui event handler
+#[derive(Debug)]
+pub struct UiEventHandler(());
+
+impl UiEventHandler {
+	/// Handles an event
+	pub async fn handle_event(&self, _event: &WindowEvent) {}
+}
+
+/// Creates the ui
+pub fn create(
+	_window: &Window,
+	_wgpu_renderer: &WgpuRenderer,
+	_wgpu_shared: &WgpuShared,
+	_config: &Config,
+) -> (UiRenderer, UiPainter, UiEventHandler) {
+	(UiRenderer(()), UiPainter(()), UiEventHandler(()))
+}
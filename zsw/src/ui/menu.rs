@@ -0,0 +1,293 @@
+//! Egui user interface
+
+// Imports
+use {
+	crate::{
+		config::Config,
+		panel::{Panel, PanelCommand},
+		settings_menu::SettingsMenu,
+		shared::Shared,
+	},
+	anyhow::Context,
+	std::sync::{Arc, PoisonError},
+	winit::{event::WindowEvent, window::Window},
+	zsw_egui::{EguiEventHandler, EguiPainter, EguiRenderer},
+	zsw_error::AppError,
+	zsw_util::{meetup, TokioTaskBlockOn},
+	zsw_wgpu::{FrameRender, WgpuRenderer, WgpuShared},
+};
+
+/// Painter output
+type PainterOutput = (Vec<egui::ClippedPrimitive>, egui::TexturesDelta);
+
+/// Ui renderer
+#[derive(Debug)]
+pub struct UiRenderer {
+	/// Egui renderer
+	egui_renderer: EguiRenderer,
+
+	/// Painter output receiver
+	output_rx: meetup::Receiver<PainterOutput>,
+
+	/// Latest paint jobs
+	paint_jobs: Vec<egui::ClippedPrimitive>,
+
+	/// Latest textures delta, if not yet rendered
+	textures_delta: Option<egui::TexturesDelta>,
+}
+
+impl UiRenderer {
+	/// Receives the latest output of the painter, if any
+	pub fn update(&mut self) {
+		if let Some((paint_jobs, textures_delta)) = self.output_rx.try_recv() {
+			self.paint_jobs = paint_jobs;
+			self.textures_delta = Some(textures_delta);
+		}
+	}
+
+	/// Updates the thumbnails of `panels`
+	pub fn update_thumbnails(&mut self, shared: &Shared, panels: &[Panel]) -> Result<(), AppError> {
+		shared
+			.panel_thumbnails
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.update(&mut self.egui_renderer, &shared.wgpu, panels)
+			.context("Unable to update panel thumbnails")?;
+
+		Ok(())
+	}
+
+	/// Renders the ui onto `frame`
+	pub fn render(&mut self, frame: &mut FrameRender, shared: &Shared) -> Result<(), AppError> {
+		self.egui_renderer
+			.render_egui(
+				frame,
+				shared.window,
+				&shared.wgpu,
+				&self.paint_jobs,
+				self.textures_delta.take(),
+			)
+			.context("Unable to render egui")?;
+
+		Ok(())
+	}
+
+	/// Returns if the ui is drawing anything
+	pub fn is_drawing(&self) -> bool {
+		!self.paint_jobs.is_empty()
+	}
+}
+
+/// Ui painter
+#[derive(Debug)]
+pub struct UiPainter {
+	/// Egui painter
+	egui_painter: EguiPainter,
+
+	/// Settings menu
+	settings_menu: SettingsMenu,
+
+	/// Output sender
+	output_tx: meetup::Sender<PainterOutput>,
+}
+
+impl UiPainter {
+	/// Runs the painter
+	pub async fn run(self, shared: Arc<Shared>) -> Result<!, AppError> {
+		let Self {
+			egui_painter,
+			mut settings_menu,
+			output_tx,
+		} = self;
+
+		loop {
+			let full_output_fut = egui_painter.draw(shared.window, |ctx| {
+				// Draw the settings menu
+				tokio::task::block_in_place(|| settings_menu.draw(ctx, &shared));
+
+				// Pause any double-clicked panels
+				if !ctx.is_pointer_over_area() &&
+					ctx.input(|input| input.pointer.button_double_clicked(egui::PointerButton::Primary))
+				{
+					let cursor_pos = shared.panels_cursor_pos();
+					let mut cur_panels = shared.cur_panels.lock().block_on();
+					for panel in &mut *cur_panels {
+						for geometry in &panel.geometries {
+							if geometry.geometry.contains(cursor_pos) {
+								panel.state.paused ^= true;
+								break;
+							}
+						}
+					}
+				}
+
+				// Skip any ctrl-clicked panels
+				// TODO: Deduplicate this with the above and settings menu.
+				if !ctx.is_pointer_over_area() &&
+					ctx.input(|input| {
+						input.pointer.button_clicked(egui::PointerButton::Primary) && input.modifiers.ctrl
+					}) {
+					let cursor_pos = shared.panels_cursor_pos();
+					let cur_panels = shared.cur_panels.lock().block_on();
+					for panel in &*cur_panels {
+						if !panel
+							.geometries
+							.iter()
+							.any(|geometry| geometry.geometry.contains(cursor_pos))
+						{
+							continue;
+						}
+
+						shared.panel_commands.push(panel.id, PanelCommand::Skip);
+					}
+				}
+
+				// Blank or restore all panels
+				if !ctx.wants_keyboard_input() &&
+					ctx.input(|input| input.modifiers.command && input.key_pressed(egui::Key::B))
+				{
+					let mut cur_panels = shared.cur_panels.lock().block_on();
+					shared.blank.toggle(&mut cur_panels);
+				}
+
+				// Rate the current image of any hovered panels, or switch them instantly
+				if !ctx.is_pointer_over_area() && !ctx.wants_keyboard_input() {
+					self::rate_hovered_panels(ctx, &shared);
+					if ctx.input(|input| input.key_pressed(egui::Key::ArrowRight)) {
+						self::switch_hovered_panels_instant(&shared);
+					}
+				}
+
+				// Scroll panels
+				// TODO: Deduplicate this with the above and settings menu.
+				if !ctx.is_pointer_over_area() && ctx.input(|input| input.smooth_scroll_delta.y != 0.0) {
+					let delta = ctx.input(|input| input.smooth_scroll_delta.y);
+					let cursor_pos = shared.panels_cursor_pos();
+					let cur_panels = shared.cur_panels.lock().block_on();
+					for panel in &*cur_panels {
+						if !panel
+							.geometries
+							.iter()
+							.any(|geometry| geometry.geometry.contains(cursor_pos))
+						{
+							continue;
+						}
+
+						// TODO: Make this "speed" configurable
+						let speed = (panel.state.fade.duration as f32) / 1000.0;
+						let frames = (-delta * speed) as i64;
+						shared.panel_commands.push(panel.id, PanelCommand::Step { frames });
+					}
+				}
+
+				Ok::<_, !>(())
+			});
+			let full_output = full_output_fut.await?;
+			let paint_jobs = egui_painter
+				.tessellate_shapes(full_output.shapes, full_output.pixels_per_point)
+				.await;
+			let textures_delta = full_output.textures_delta;
+
+			output_tx.send((paint_jobs, textures_delta)).await;
+		}
+	}
+}
+
+/// Ui event handler
+#[derive(Debug)]
+pub struct UiEventHandler {
+	/// Egui event handler
+	egui_event_handler: EguiEventHandler,
+}
+
+impl UiEventHandler {
+	/// Handles an event
+	pub async fn handle_event(&self, event: &WindowEvent) {
+		self.egui_event_handler.handle_event(event).await;
+	}
+}
+
+/// Creates the ui
+pub fn create(
+	window: &Window,
+	wgpu_renderer: &WgpuRenderer,
+	wgpu_shared: &WgpuShared,
+	config: &Config,
+) -> (UiRenderer, UiPainter, UiEventHandler) {
+	let (egui_renderer, egui_painter, egui_event_handler) = zsw_egui::create(window, wgpu_renderer, wgpu_shared);
+	let settings_menu = SettingsMenu::new(config.chart_palette.unwrap_or_default());
+	let (output_tx, output_rx) = meetup::channel();
+
+	(
+		UiRenderer {
+			egui_renderer,
+			output_rx,
+			paint_jobs: vec![],
+			textures_delta: None,
+		},
+		UiPainter {
+			egui_painter,
+			settings_menu,
+			output_tx,
+		},
+		UiEventHandler { egui_event_handler },
+	)
+}
+
+/// Rates the current image of any hovered panels with the number keys.
+///
+/// `1` through `5` set the rating, while `0` clears it.
+fn rate_hovered_panels(ctx: &egui::Context, shared: &Shared) {
+	let keys = [
+		(egui::Key::Num0, None),
+		(egui::Key::Num1, Some(1)),
+		(egui::Key::Num2, Some(2)),
+		(egui::Key::Num3, Some(3)),
+		(egui::Key::Num4, Some(4)),
+		(egui::Key::Num5, Some(5)),
+	];
+	let Some(rating) = keys
+		.into_iter()
+		.find(|&(key, _)| ctx.input(|input| input.key_pressed(key)))
+		.map(|(_, rating)| rating)
+	else {
+		return;
+	};
+
+	let cursor_pos = shared.panels_cursor_pos();
+	let cur_panels = shared.cur_panels.lock().block_on();
+	for panel in &*cur_panels {
+		if !panel
+			.geometries
+			.iter()
+			.any(|geometry| geometry.geometry.contains(cursor_pos))
+		{
+			continue;
+		}
+
+		let Some(path) = panel.images.cur().path() else {
+			continue;
+		};
+		match shared.ratings.set(path, rating) {
+			Ok(()) => tracing::info!(?path, ?rating, "Rated image"),
+			Err(err) => tracing::warn!(?path, ?err, "Unable to set rating"),
+		}
+	}
+}
+
+/// Switches any hovered panels to their next image instantly
+fn switch_hovered_panels_instant(shared: &Shared) {
+	let cursor_pos = shared.panels_cursor_pos();
+	let cur_panels = shared.cur_panels.lock().block_on();
+	for panel in &*cur_panels {
+		if !panel
+			.geometries
+			.iter()
+			.any(|geometry| geometry.geometry.contains(cursor_pos))
+		{
+			continue;
+		}
+
+		shared.panel_commands.push(panel.id, PanelCommand::SwitchInstant);
+	}
+}
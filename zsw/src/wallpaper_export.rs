@@ -0,0 +1,80 @@
+//! Wallpaper export
+//!
+//! Exports an image as the desktop environment's static wallpaper.
+
+// Imports
+use std::path::PathBuf;
+#[cfg(feature = "menu")]
+use {
+	anyhow::Context,
+	std::{env, ffi::OsStr, path::Path},
+	tokio::process::Command,
+	url::Url,
+	zsw_error::AppError,
+};
+
+/// Wallpaper export backend
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum WallpaperExportBackend {
+	/// Gnome's `gsettings`
+	Gsettings,
+
+	/// `feh`
+	Feh,
+
+	/// Custom command.
+	///
+	/// Will be called with the image path as it's only argument
+	Custom { cmd: PathBuf },
+}
+
+impl WallpaperExportBackend {
+	/// Detects the backend to use from the current desktop environment
+	#[cfg(feature = "menu")]
+	pub fn detect() -> Self {
+		let is_gnome = env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop.contains("GNOME"));
+		match is_gnome {
+			true => Self::Gsettings,
+			false => Self::Feh,
+		}
+	}
+
+	/// Exports `path` as the wallpaper
+	#[cfg(feature = "menu")]
+	pub async fn export(&self, path: &Path) -> Result<(), AppError> {
+		tracing::debug!(backend = ?self, ?path, "Exporting wallpaper");
+		match self {
+			Self::Gsettings => {
+				let uri = Url::from_file_path(path)
+					.map_err(|()| anyhow::anyhow!("Wallpaper path {path:?} must be absolute"))?;
+				for key in ["picture-uri", "picture-uri-dark"] {
+					self::run_cmd("gsettings", ["set", "org.gnome.desktop.background", key, uri.as_str()]).await?;
+				}
+			},
+			Self::Feh => self::run_cmd("feh", [OsStr::new("--bg-fill"), path.as_os_str()]).await?,
+			Self::Custom { cmd } => self::run_cmd(cmd, [path]).await?,
+		}
+
+		Ok(())
+	}
+}
+
+/// Runs `cmd` with `args`, waiting for it to succeed
+#[cfg(feature = "menu")]
+async fn run_cmd(cmd: impl AsRef<OsStr>, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Result<(), AppError> {
+	let cmd = cmd.as_ref();
+	Command::new(cmd)
+		.args(args)
+		.kill_on_drop(true)
+		.spawn()
+		.with_context(|| format!("Unable to run {cmd:?}"))?
+		.wait()
+		.await
+		.with_context(|| format!("Unable to wait for {cmd:?}"))?
+		.exit_ok()
+		.with_context(|| format!("{cmd:?} returned error"))?;
+
+	Ok(())
+}
@@ -1,5 +1,9 @@
 //! Winit initialization
 
+// Modules
+#[cfg(windows)]
+mod win32;
+
 // Imports
 #[cfg(target_os = "linux")]
 use winit::platform::x11::{WindowAttributesExtX11, WindowType};
@@ -18,12 +22,7 @@ use {
 /// Creates the window, as well as the associated event loop
 pub fn create(event_loop: &ActiveEventLoop) -> Result<Window, AppError> {
 	// Find the window geometry
-	// Note: We just merge all monitors' geometry.
-	let window_geometry = event_loop
-		.available_monitors()
-		.map(|monitor| self::monitor_geometry(&monitor))
-		.reduce(Rect::merge)
-		.context("No monitors found")?;
+	let window_geometry = self::desktop_geometry(event_loop)?;
 	tracing::debug!(?window_geometry, "Found window geometry");
 
 	// Start building the window
@@ -40,7 +39,7 @@ pub fn create(event_loop: &ActiveEventLoop) -> Result<Window, AppError> {
 		.with_decorations(false);
 
 	// If on linux x11, add the `Desktop`
-	// TODO: Wayland, windows and macos?
+	// TODO: Wayland and macos?
 	#[cfg(target_os = "linux")]
 	let window_attrs = window_attrs.with_x11_window_type(vec![WindowType::Desktop]);
 
@@ -49,9 +48,88 @@ pub fn create(event_loop: &ActiveEventLoop) -> Result<Window, AppError> {
 		.create_window(window_attrs)
 		.context("Unable to build window")?;
 
+	// If on windows, attach it behind the desktop icons
+	#[cfg(windows)]
+	if let Err(err) = win32::attach_to_desktop(&window) {
+		tracing::warn!(?err, "Unable to attach window to the desktop");
+	}
+
 	Ok(window)
 }
 
+/// Creates a preview window.
+///
+/// The preview window is a normal window, with the desktop's aspect ratio, scaled down
+pub fn create_preview(event_loop: &ActiveEventLoop) -> Result<Window, AppError> {
+	/// Maximum preview window size
+	const MAX_SIZE: Vector2<u32> = Vector2::new(1280, 720);
+
+	let desktop_geometry = self::desktop_geometry(event_loop)?;
+	let size = desktop_geometry.size_fit_within(MAX_SIZE);
+	tracing::debug!(?desktop_geometry, ?size, "Creating preview window");
+
+	let window_attrs = WindowAttributes::default()
+		.with_title("zsw (preview)")
+		.with_inner_size(PhysicalSize {
+			width:  size.x,
+			height: size.y,
+		});
+
+	let window = event_loop
+		.create_window(window_attrs)
+		.context("Unable to build window")?;
+
+	Ok(window)
+}
+
+/// Returns the desktop's geometry
+pub fn desktop_geometry(event_loop: &ActiveEventLoop) -> Result<Rect<i32, u32>, AppError> {
+	// Note: We just merge all monitors' geometry.
+	let geometry = event_loop
+		.available_monitors()
+		.map(|monitor| self::monitor_geometry(&monitor))
+		.reduce(Rect::merge)
+		.context("No monitors found")?;
+
+	Ok(geometry)
+}
+
+/// Returns all monitors
+pub fn monitors(event_loop: &ActiveEventLoop) -> Result<Vec<Monitor>, AppError> {
+	let desktop_geometry = self::desktop_geometry(event_loop)?;
+	let monitors = event_loop
+		.available_monitors()
+		.enumerate()
+		.map(|(monitor_idx, monitor)| {
+			let geometry = self::monitor_geometry(&monitor);
+			Monitor {
+				name:         monitor.name().unwrap_or_else(|| format!("Monitor {}", monitor_idx + 1)),
+				geometry:     geometry.translated(Vector2::new(-desktop_geometry.pos.x, -desktop_geometry.pos.y)),
+				refresh_rate: monitor
+					.refresh_rate_millihertz()
+					.map(|refresh_rate| refresh_rate as f32 / 1000.0),
+			}
+		})
+		.collect::<Vec<_>>();
+	tracing::debug!(?monitors, "Found monitors");
+
+	Ok(monitors)
+}
+
+/// Monitor
+#[derive(Clone, Debug)]
+pub struct Monitor {
+	/// Name
+	pub name: String,
+
+	/// Geometry, relative to the desktop
+	pub geometry: Rect<i32, u32>,
+
+	/// Refresh rate (in hz), if known
+	#[cfg_attr(not(feature = "menu"), expect(dead_code))] // Only read by the settings menu
+	pub refresh_rate: Option<f32>,
+}
+
 /// Returns a monitor's geometry
 fn monitor_geometry(monitor: &winit::monitor::MonitorHandle) -> Rect<i32, u32> {
 	let monitor_pos = monitor.position();
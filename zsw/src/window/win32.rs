@@ -0,0 +1,97 @@
+//! Win32 desktop layer
+//!
+//! Attaches the window behind the desktop icons, by asking `Progman` to spawn a
+//! `WorkerW` window behind them, and then parenting our window to it.
+
+// Imports
+use {
+	anyhow::Context,
+	std::ptr,
+	windows_sys::Win32::{
+		Foundation::{BOOL, FALSE, HWND, LPARAM, TRUE},
+		UI::WindowsAndMessaging::{
+			EnumWindows,
+			FindWindowExW,
+			FindWindowW,
+			SendMessageTimeoutW,
+			SetParent,
+			SMTO_NORMAL,
+		},
+	},
+	winit::{
+		raw_window_handle::{HasWindowHandle, RawWindowHandle},
+		window::Window,
+	},
+	zsw_error::AppError,
+};
+
+/// Undocumented message that makes `Progman` spawn the `WorkerW` behind the desktop icons
+const SPAWN_WORKER_MSG: u32 = 0x052c;
+
+/// Timeout for `Progman` to respond, in milliseconds
+const SPAWN_WORKER_TIMEOUT_MS: u32 = 1000;
+
+/// Attaches `window` behind the desktop icons
+pub fn attach_to_desktop(window: &Window) -> Result<(), AppError> {
+	let window_handle = window.window_handle().context("Unable to get window handle")?;
+	let RawWindowHandle::Win32(window_handle) = window_handle.as_raw() else {
+		return Err(anyhow::anyhow!("Window handle wasn't a win32 handle").into());
+	};
+	let hwnd = window_handle.hwnd.get() as HWND;
+
+	// SAFETY: All window handles we pass are either null or valid windows,
+	//         and all strings are null terminated.
+	unsafe {
+		let progman = FindWindowW(windows_sys::w!("Progman"), ptr::null());
+		if progman.is_null() {
+			return Err(anyhow::anyhow!("Unable to find `Progman` window").into());
+		}
+
+		// Note: If `Progman` doesn't respond, the worker might already exist, so we keep going
+		let mut result = 0;
+		let _ = SendMessageTimeoutW(
+			progman,
+			SPAWN_WORKER_MSG,
+			0,
+			0,
+			SMTO_NORMAL,
+			SPAWN_WORKER_TIMEOUT_MS,
+			&mut result,
+		);
+
+		// Find the worker after the one with the desktop icons
+		// Note: On newer versions of windows, the worker is instead a child of `Progman`
+		let mut worker: HWND = ptr::null_mut();
+		let _ = EnumWindows(Some(self::find_worker), ptr::from_mut(&mut worker) as LPARAM);
+		if worker.is_null() {
+			worker = FindWindowExW(progman, ptr::null_mut(), windows_sys::w!("WorkerW"), ptr::null());
+		}
+		if worker.is_null() {
+			return Err(anyhow::anyhow!("Unable to find `WorkerW` window").into());
+		}
+
+		if SetParent(hwnd, worker).is_null() {
+			return Err(anyhow::anyhow!("Unable to parent window to `WorkerW`").into());
+		}
+	}
+
+	Ok(())
+}
+
+/// [`EnumWindows`] callback to find the worker behind the desktop icons.
+///
+/// Writes the worker to `lparam`, which must be a `*mut HWND`.
+unsafe extern "system" fn find_worker(hwnd: HWND, lparam: LPARAM) -> BOOL {
+	// SAFETY: `hwnd` is a valid window, and the caller ensures `lparam` is a `*mut HWND`
+	unsafe {
+		// Note: The desktop icons are within `SHELLDLL_DefView`
+		let shell_view = FindWindowExW(hwnd, ptr::null_mut(), windows_sys::w!("SHELLDLL_DefView"), ptr::null());
+		if shell_view.is_null() {
+			return TRUE;
+		}
+
+		let worker = FindWindowExW(ptr::null_mut(), hwnd, windows_sys::w!("WorkerW"), ptr::null());
+		*(lparam as *mut HWND) = worker;
+		FALSE
+	}
+}